@@ -1953,6 +1953,38 @@ impl AcpThread {
         })
     }
 
+    /// Restores the git working tree to the state at the given checkpoint (if one exists),
+    /// leaving this thread as it is. Used when the conversation continues from that point in
+    /// another thread.
+    pub fn restore_project_to_checkpoint(
+        &self,
+        id: &UserMessageId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(message) = self
+            .entries
+            .iter()
+            .filter_map(AgentThreadEntry::user_message)
+            .find(|message| message.id.as_ref() == Some(id))
+        else {
+            return Task::ready(Err(anyhow!("message not found")));
+        };
+        let Some(checkpoint) = message
+            .checkpoint
+            .as_ref()
+            .map(|c| c.git_checkpoint.clone())
+        else {
+            return Task::ready(Ok(()));
+        };
+
+        let git_store = self.project.read(cx).git_store().clone();
+        cx.spawn(async move |_, cx| {
+            git_store
+                .update(cx, |git, cx| git.restore_checkpoint(checkpoint, cx))?
+                .await
+        })
+    }
+
     /// Rewinds this thread to before the entry at `index`, removing it and all
     /// subsequent entries while rejecting any action_log changes made from that point.
     /// Unlike `restore_checkpoint`, this method does not restore from git.
@@ -39,12 +39,16 @@ use prompt_store::{
 use serde::{Deserialize, Serialize};
 use settings::{LanguageModelSelection, update_settings_file};
 use std::any::Any;
+use std::iter;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
 use util::ResultExt;
 use util::rel_path::RelPath;
 
+/// Built-in command that compacts the thread, see [`Thread::compact`].
+const COMPACT_COMMAND: &str = "compact";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ProjectSnapshot {
     pub worktree_snapshots: Vec<project::telemetry_snapshot::TelemetryWorktreeSnapshot>,
@@ -674,9 +678,12 @@ impl NativeAgent {
                 .or_insert(0) += 1;
         }
 
-        registry
-            .prompts()
-            .flat_map(|context_server_prompt| {
+        let compact_command = acp::AvailableCommand::new(
+            COMPACT_COMMAND,
+            "Summarize earlier messages to free up space in the context window",
+        );
+        iter::once(compact_command)
+            .chain(registry.prompts().flat_map(|context_server_prompt| {
                 let prompt = &context_server_prompt.prompt;
 
                 let should_prefix = prompt_name_counts
@@ -712,7 +719,7 @@ impl NativeAgent {
                 }
 
                 Some(command)
-            })
+            }))
             .collect()
     }
 
@@ -793,6 +800,42 @@ impl NativeAgent {
         })
     }
 
+    /// Saves a new thread to the history that continues the conversation of the session `id`
+    /// from before `message_id`, and returns its metadata so that it can be opened.
+    pub fn fork_thread(
+        &mut self,
+        id: &acp::SessionId,
+        message_id: &UserMessageId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DbThreadMetadata>> {
+        let Some(session) = self.sessions.get(id) else {
+            return Task::ready(Err(anyhow!("Session not found")));
+        };
+        let fork = match session
+            .thread
+            .update(cx, |thread, cx| thread.fork(message_id, cx))
+        {
+            Ok(fork) => fork,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        let database_future = ThreadsDatabase::connect(cx);
+        let (id, db_thread) = fork.update(cx, |fork, cx| (fork.id().clone(), fork.to_db(cx)));
+        let history = self.history.clone();
+        cx.spawn(async move |_, cx| {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            let db_thread = db_thread.await;
+            let metadata = DbThreadMetadata {
+                id: id.clone(),
+                title: db_thread.title.clone(),
+                updated_at: db_thread.updated_at,
+            };
+            database.save_thread(id, db_thread).await?;
+            history.update(cx, |history, cx| history.reload(cx))?;
+            Ok(metadata)
+        })
+    }
+
     fn save_thread(&mut self, thread: Entity<Thread>, cx: &mut Context<Self>) {
         if thread.read(cx).is_empty() {
             return;
@@ -1244,6 +1287,14 @@ impl acp_thread::AgentConnection for NativeAgentConnection {
         log::debug!("Prompt blocks count: {}", params.prompt.len());
 
         if let Some(parsed_command) = Command::parse(&params.prompt) {
+            if parsed_command.prompt_name == COMPACT_COMMAND
+                && parsed_command.explicit_server_id.is_none()
+            {
+                return self.run_turn(session_id, cx, |thread, cx| {
+                    thread.update(cx, |thread, cx| thread.compact(cx))
+                });
+            }
+
             let registry = self.0.read(cx).context_server_registry.read(cx);
 
             let explicit_server_id = parsed_command
@@ -833,6 +833,158 @@ async fn test_tool_hallucination(cx: &mut TestAppContext) {
     assert_eq!(update.fields.status, Some(acp::ToolCallStatus::Failed));
 }
 
+#[gpui::test]
+async fn test_subagent(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    let environment = Rc::new(FakeThreadEnvironment {
+        handle: Rc::new(cx.update(|cx| FakeTerminalHandle::new_never_exits(cx))),
+    });
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(SubagentTool::new(cx.weak_entity(), environment));
+            thread.send(UserMessageId::new(), ["Where is the answer?"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let input = json!({
+        "label": "Find the answer",
+        "task": "Find the answer to everything",
+        "profile": "test-profile",
+    });
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "subagent_1".into(),
+            name: SubagentTool::name().into(),
+            raw_input: input.to_string(),
+            input,
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // The sub-agent sends its own request, and can't create sub-agents itself.
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert!(
+        completion
+            .messages
+            .last()
+            .unwrap()
+            .string_contents()
+            .contains("Find the answer to everything")
+    );
+    assert!(
+        completion
+            .tools
+            .iter()
+            .all(|tool| tool.name != SubagentTool::name())
+    );
+    fake_model.send_last_completion_stream_text_chunk("The answer is in `answer.rs`.");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // Only the report is sent to the parent's model.
+    let completion = fake_model.pending_completions().pop().unwrap();
+    let tool_results = completion
+        .messages
+        .last()
+        .unwrap()
+        .content
+        .iter()
+        .filter_map(|content| match content {
+            MessageContent::ToolResult(result) => Some(result.content.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tool_results,
+        vec![language_model::LanguageModelToolResultContent::Text(
+            "The answer is in `answer.rs`.".into()
+        )]
+    );
+
+    let tool_call = expect_tool_call(&mut events).await;
+    assert_eq!(tool_call.title, "Sub-agent: Find the answer");
+}
+
+#[gpui::test]
+async fn test_subagent_tool_authorization(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    let environment = Rc::new(FakeThreadEnvironment {
+        handle: Rc::new(cx.update(|cx| FakeTerminalHandle::new_never_exits(cx))),
+    });
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(SubagentTool::new(cx.weak_entity(), environment));
+            thread.send(UserMessageId::new(), ["Do the tests pass?"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let input = json!({
+        "label": "Run the tests",
+        "task": "Run the tests and report failures",
+        "profile": "test-profile",
+    });
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "subagent_1".into(),
+            name: SubagentTool::name().into(),
+            raw_input: input.to_string(),
+            input,
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    let input = json!({ "command": "cargo test", "cd": "." });
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "terminal_1".into(),
+            name: TerminalTool::name().into(),
+            raw_input: input.to_string(),
+            input,
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+
+    // The sub-agent's tool calls offer the same options as the parent's own.
+    let authorization = loop {
+        let event = events
+            .next()
+            .await
+            .expect("no tool call authorization event received")
+            .unwrap();
+        if let ThreadEvent::ToolCallAuthorization(authorization) = event {
+            break authorization;
+        }
+    };
+    assert_eq!(
+        authorization
+            .options
+            .iter()
+            .map(|option| option.option_id.0.as_ref())
+            .collect::<Vec<_>>(),
+        vec!["always_allow", "always_allow_command", "allow", "deny"]
+    );
+    authorization
+        .response
+        .send(authorization.options[0].option_id.clone())
+        .unwrap();
+    cx.run_until_parked();
+    assert!(
+        cx.update(|cx| agent_settings::AgentSettings::get_global(cx).always_allow_tool_actions)
+    );
+}
+
 #[gpui::test]
 async fn test_resume_after_tool_use_limit(cx: &mut TestAppContext) {
     let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
@@ -1855,6 +2007,78 @@ async fn test_truncate_first_message(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_auto_compaction(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    let first_message_id = UserMessageId::new();
+    for (message_id, text) in [
+        (first_message_id.clone(), "Message 1"),
+        (UserMessageId::new(), "Message 2"),
+    ] {
+        thread
+            .update(cx, |thread, cx| thread.send(message_id, [text], cx))
+            .unwrap();
+        cx.run_until_parked();
+        fake_model.send_last_completion_stream_text_chunk(format!("Response to {text}"));
+        fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+            language_model::TokenUsage {
+                input_tokens: 850_000,
+                output_tokens: 1_000,
+                cache_creation_input_tokens: 0,
+                cache_read_input_tokens: 0,
+            },
+        ));
+        fake_model.end_last_completion_stream();
+        cx.run_until_parked();
+    }
+
+    // The thread uses more than 80% of the context window, so the first turn is summarized
+    // before the model sees the next message.
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 3"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.intent,
+        Some(CompletionIntent::ThreadContextSummarization)
+    );
+    assert_eq!(completion.messages.len(), 3);
+    fake_model.send_last_completion_stream_text_chunk("Summary of the first turn");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(completion.intent, Some(CompletionIntent::UserPrompt));
+    assert_eq!(
+        completion.messages[1..]
+            .iter()
+            .map(|message| message.string_contents())
+            .collect::<Vec<_>>(),
+        vec![
+            "Earlier messages in this conversation were replaced by the following summary.\n\
+            <summary>\nSummary of the first turn\n</summary>"
+                .to_string(),
+            "Message 2".to_string(),
+            "Response to Message 2".to_string(),
+            "Message 3".to_string(),
+        ]
+    );
+    fake_model.send_last_completion_stream_text_chunk("Response to Message 3");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // Truncating to a compacted message restores the messages that preceded it.
+    thread
+        .update(cx, |thread, cx| thread.truncate(first_message_id, cx))
+        .unwrap();
+    thread.read_with(cx, |thread, _| assert_eq!(thread.to_markdown(), ""));
+}
+
 #[gpui::test]
 async fn test_truncate_second_message(cx: &mut TestAppContext) {
     let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
@@ -1964,6 +2188,79 @@ async fn test_truncate_second_message(cx: &mut TestAppContext) {
     assert_first_message_state(cx);
 }
 
+#[gpui::test]
+async fn test_fork_thread(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 1"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_text_chunk("Message 1 response");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    let second_message_id = UserMessageId::new();
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(second_message_id.clone(), ["Message 2"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_text_chunk("Message 2 response");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    let fork = thread
+        .update(cx, |thread, cx| thread.fork(&second_message_id, cx))
+        .unwrap();
+    let expected_prefix = indoc! {"
+        ## User
+
+        Message 1
+
+        ## Assistant
+
+        Message 1 response
+    "};
+    fork.read_with(cx, |fork, _| {
+        assert_eq!(fork.to_markdown(), expected_prefix);
+    });
+    thread.read_with(cx, |thread, cx| {
+        assert_ne!(thread.id(), fork.read(cx).id());
+        assert_eq!(
+            thread.to_markdown(),
+            format!(
+                "{expected_prefix}\n## User\n\nMessage 2\n\n## Assistant\n\nMessage 2 response\n"
+            )
+        );
+    });
+
+    // The fork continues independently of the original thread.
+    fork.update(cx, |fork, cx| {
+        fork.send(UserMessageId::new(), ["Message 3"], cx)
+    })
+    .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_text_chunk("Message 3 response");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+    fork.read_with(cx, |fork, _| {
+        assert_eq!(
+            fork.to_markdown(),
+            format!(
+                "{expected_prefix}\n## User\n\nMessage 3\n\n## Assistant\n\nMessage 3 response\n"
+            )
+        );
+    });
+    thread.read_with(cx, |thread, _| {
+        assert!(!thread.to_markdown().contains("Message 3"));
+    });
+}
+
 #[gpui::test]
 async fn test_title_generation(cx: &mut TestAppContext) {
     let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
@@ -2612,6 +2909,8 @@ async fn setup(cx: &mut TestAppContext, model: TestModel) -> ThreadTest {
                             ToolRequiringPermission::name(): true,
                             InfiniteTool::name(): true,
                             ThinkingTool::name(): true,
+                            SubagentTool::name(): true,
+                            TerminalTool::name(): true,
                         }
                     }
                 }
@@ -1,17 +1,18 @@
 use crate::{
     ContextServerRegistry, CopyPathTool, CreateDirectoryTool, DbLanguageModel, DbThread,
-    DeletePathTool, DiagnosticsTool, EditFileTool, FetchTool, FindPathTool, GrepTool,
-    ListDirectoryTool, MovePathTool, NowTool, OpenTool, ProjectSnapshot, ReadFileTool,
-    RestoreFileFromDiskTool, SaveFileTool, SystemPromptTemplate, Template, Templates, TerminalTool,
-    ThinkingTool, WebSearchTool,
+    DeletePathTool, DiagnosticsTool, EditFileTool, FetchTool, FindPathTool, FindReferencesTool,
+    GoToDefinitionTool, GrepTool, HoverTool, ListDirectoryTool, MovePathTool, NowTool, OpenTool,
+    ProjectSnapshot, ReadFileTool, RenameSymbolTool, RestoreFileFromDiskTool, SaveFileTool,
+    SymbolSearchTool, SystemPromptTemplate, Template, Templates, TerminalTool, ThinkingTool,
+    WebSearchTool,
 };
 use acp_thread::{MentionUri, UserMessageId};
 use action_log::ActionLog;
 
 use agent_client_protocol as acp;
 use agent_settings::{
-    AgentProfileId, AgentProfileSettings, AgentSettings, CompletionMode,
-    SUMMARIZE_THREAD_DETAILED_PROMPT, SUMMARIZE_THREAD_PROMPT,
+    AgentProfileId, AgentProfileSettings, AgentSettings, COMPACT_THREAD_PROMPT, CompletionMode,
+    SUMMARIZE_THREAD_DETAILED_PROMPT, SUMMARIZE_THREAD_PROMPT, ToolPermissionAction,
 };
 use anyhow::{Context as _, Result, anyhow};
 use chrono::{DateTime, Utc};
@@ -41,7 +42,7 @@ use project::Project;
 use prompt_store::ProjectContext;
 use schemars::{JsonSchema, Schema};
 use serde::{Deserialize, Serialize};
-use settings::{LanguageModelSelection, Settings, update_settings_file};
+use settings::{LanguageModelSelection, Settings, ToolPermissionRuleContent, update_settings_file};
 use smol::stream::StreamExt;
 use std::{
     collections::BTreeMap,
@@ -96,6 +97,7 @@ pub enum Message {
     User(UserMessage),
     Agent(AgentMessage),
     Resume,
+    Compaction(CompactionMessage),
 }
 
 impl Message {
@@ -122,6 +124,7 @@ impl Message {
                 cache: false,
                 reasoning_details: None,
             }],
+            Message::Compaction(compaction) => vec![compaction.to_request()],
         }
     }
 
@@ -130,15 +133,27 @@ impl Message {
             Message::User(message) => message.to_markdown(),
             Message::Agent(message) => message.to_markdown(),
             Message::Resume => "[resume]\n".into(),
+            Message::Compaction(compaction) => compaction.to_markdown(),
         }
     }
 
     pub fn role(&self) -> Role {
         match self {
-            Message::User(_) | Message::Resume => Role::User,
+            Message::User(_) | Message::Resume | Message::Compaction(_) => Role::User,
             Message::Agent(_) => Role::Assistant,
         }
     }
+
+    fn contains_user_message(&self, id: &UserMessageId) -> bool {
+        match self {
+            Message::User(message) => &message.id == id,
+            Message::Compaction(compaction) => compaction
+                .compacted_messages
+                .iter()
+                .any(|message| message.contains_user_message(id)),
+            Message::Agent(_) | Message::Resume => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -533,6 +548,87 @@ pub enum AgentMessageContent {
     ToolUse(LanguageModelToolUse),
 }
 
+/// Replaces the older messages of a thread with a summary, to keep it within the model's context
+/// window. The replaced messages are kept so that they can still be displayed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionMessage {
+    pub summary: String,
+    /// Rules the user attached to the compacted messages, which keep being sent verbatim.
+    pub preserved_context: Vec<UserMessageContent>,
+    pub compacted_messages: Vec<Message>,
+}
+
+impl CompactionMessage {
+    fn new(summary: String, compacted_messages: Vec<Message>) -> Self {
+        let mut preserved_context = Vec::new();
+        for message in &compacted_messages {
+            let content = match message {
+                Message::User(message) => &message.content,
+                Message::Compaction(compaction) => &compaction.preserved_context,
+                Message::Agent(_) | Message::Resume => continue,
+            };
+            for content in content {
+                if let UserMessageContent::Mention {
+                    uri: MentionUri::Rule { .. },
+                    ..
+                } = content
+                    && !preserved_context.contains(content)
+                {
+                    preserved_context.push(content.clone());
+                }
+            }
+        }
+
+        Self {
+            summary,
+            preserved_context,
+            compacted_messages,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        fn count(messages: &[Message]) -> usize {
+            messages
+                .iter()
+                .map(|message| match message {
+                    Message::User(_) | Message::Agent(_) => 1,
+                    Message::Compaction(compaction) => count(&compaction.compacted_messages),
+                    Message::Resume => 0,
+                })
+                .sum()
+        }
+
+        format!(
+            "Compacted {} earlier messages",
+            count(&self.compacted_messages)
+        )
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        for message in &self.compacted_messages {
+            markdown.push_str(&message.to_markdown());
+            markdown.push('\n');
+        }
+        let _ = writeln!(&mut markdown, "## {}\n\n{}", self.title(), self.summary);
+        markdown
+    }
+
+    fn to_request(&self) -> LanguageModelRequestMessage {
+        let mut content = vec![UserMessageContent::Text(format!(
+            "Earlier messages in this conversation were replaced by the following summary.\n\
+            <summary>\n{}\n</summary>",
+            self.summary
+        ))];
+        content.extend(self.preserved_context.iter().cloned());
+        UserMessage {
+            id: UserMessageId::new(),
+            content,
+        }
+        .to_request()
+    }
+}
+
 pub trait TerminalHandle {
     fn id(&self, cx: &AsyncApp) -> Result<acp::TerminalId>;
     fn current_output(&self, cx: &AsyncApp) -> Result<acp::TerminalOutputResponse>;
@@ -612,6 +708,9 @@ pub struct Thread {
     initial_project_snapshot: Shared<Task<Option<Arc<ProjectSnapshot>>>>,
     context_server_registry: Entity<ContextServerRegistry>,
     profile_id: AgentProfileId,
+    /// For sub-agents, the profile of the thread that created them, whose disabled tools are
+    /// disabled for the sub-agent too.
+    parent_profile_id: Option<AgentProfileId>,
     project_context: Entity<ProjectContext>,
     templates: Arc<Templates>,
     model: Option<Arc<dyn LanguageModel>>,
@@ -669,6 +768,7 @@ impl Thread {
             },
             context_server_registry,
             profile_id,
+            parent_profile_id: None,
             project_context,
             templates,
             model,
@@ -692,31 +792,44 @@ impl Thread {
         let (tx, rx) = mpsc::unbounded();
         let stream = ThreadEventStream(tx);
         for message in &self.messages {
-            match message {
-                Message::User(user_message) => stream.send_user_message(user_message),
-                Message::Agent(assistant_message) => {
-                    for content in &assistant_message.content {
-                        match content {
-                            AgentMessageContent::Text(text) => stream.send_text(text),
-                            AgentMessageContent::Thinking { text, .. } => {
-                                stream.send_thinking(text)
-                            }
-                            AgentMessageContent::RedactedThinking(_) => {}
-                            AgentMessageContent::ToolUse(tool_use) => {
-                                self.replay_tool_call(
-                                    tool_use,
-                                    assistant_message.tool_results.get(&tool_use.id),
-                                    &stream,
-                                    cx,
-                                );
-                            }
+            self.replay_message(message, &stream, cx);
+        }
+        rx
+    }
+
+    fn replay_message(
+        &self,
+        message: &Message,
+        stream: &ThreadEventStream,
+        cx: &mut Context<Self>,
+    ) {
+        match message {
+            Message::User(user_message) => stream.send_user_message(user_message),
+            Message::Agent(assistant_message) => {
+                for content in &assistant_message.content {
+                    match content {
+                        AgentMessageContent::Text(text) => stream.send_text(text),
+                        AgentMessageContent::Thinking { text, .. } => stream.send_thinking(text),
+                        AgentMessageContent::RedactedThinking(_) => {}
+                        AgentMessageContent::ToolUse(tool_use) => {
+                            self.replay_tool_call(
+                                tool_use,
+                                assistant_message.tool_results.get(&tool_use.id),
+                                stream,
+                                cx,
+                            );
                         }
                     }
                 }
-                Message::Resume => {}
+            }
+            Message::Resume => {}
+            Message::Compaction(compaction) => {
+                for message in &compaction.compacted_messages {
+                    self.replay_message(message, stream, cx);
+                }
+                stream.send_compaction(compaction);
             }
         }
-        rx
     }
 
     fn replay_tool_call(
@@ -856,6 +969,7 @@ impl Thread {
             initial_project_snapshot: Task::ready(db_thread.initial_project_snapshot).shared(),
             context_server_registry,
             profile_id,
+            parent_profile_id: None,
             project_context,
             templates,
             model,
@@ -999,7 +1113,10 @@ impl Thread {
         ));
         self.add_tool(FetchTool::new(self.project.read(cx).client().http_client()));
         self.add_tool(FindPathTool::new(self.project.clone()));
+        self.add_tool(FindReferencesTool::new(self.project.clone()));
+        self.add_tool(GoToDefinitionTool::new(self.project.clone()));
         self.add_tool(GrepTool::new(self.project.clone()));
+        self.add_tool(HoverTool::new(self.project.clone()));
         self.add_tool(ListDirectoryTool::new(self.project.clone()));
         self.add_tool(MovePathTool::new(self.project.clone()));
         self.add_tool(NowTool);
@@ -1009,13 +1126,59 @@ impl Thread {
             self.project.clone(),
             self.action_log.clone(),
         ));
+        self.add_tool(RenameSymbolTool::new(
+            self.project.clone(),
+            self.action_log.clone(),
+        ));
         self.add_tool(SaveFileTool::new(self.project.clone()));
         self.add_tool(RestoreFileFromDiskTool::new(self.project.clone()));
+        self.add_tool(SubagentTool::new(cx.weak_entity(), environment.clone()));
+        self.add_tool(SymbolSearchTool::new(self.project.clone()));
         self.add_tool(TerminalTool::new(self.project.clone(), environment));
         self.add_tool(ThinkingTool);
         self.add_tool(WebSearchTool);
     }
 
+    /// Creates a thread for a sub-agent using the given profile, sharing this thread's project
+    /// and model. Sub-agents can't create sub-agents of their own.
+    pub(crate) fn new_subagent(
+        &self,
+        profile_id: AgentProfileId,
+        environment: Rc<dyn ThreadEnvironment>,
+        cx: &mut Context<Self>,
+    ) -> Result<Entity<Thread>> {
+        if !AgentSettings::get_global(cx)
+            .profiles
+            .contains_key(&profile_id)
+        {
+            return Err(anyhow!("Profile {} not found", profile_id.0));
+        }
+
+        let parent_profile_id = self.profile_id.clone();
+        let project = self.project.clone();
+        let project_context = self.project_context.clone();
+        let context_server_registry = self.context_server_registry.clone();
+        let templates = self.templates.clone();
+        let model = self.model.clone();
+        let completion_mode = self.completion_mode;
+        Ok(cx.new(|cx| {
+            let mut thread = Thread::new(
+                project,
+                project_context,
+                context_server_registry,
+                templates,
+                model,
+                cx,
+            );
+            thread.profile_id = profile_id;
+            thread.parent_profile_id = Some(parent_profile_id);
+            thread.completion_mode = completion_mode;
+            thread.add_default_tools(environment, cx);
+            thread.remove_tool(SubagentTool::name());
+            thread
+        }))
+    }
+
     pub fn add_tool<T: AgentTool>(&mut self, tool: T) {
         self.tools.insert(T::name().into(), tool.erase());
     }
@@ -1061,18 +1224,29 @@ impl Thread {
 
     pub fn truncate(&mut self, message_id: UserMessageId, cx: &mut Context<Self>) -> Result<()> {
         self.cancel(cx);
-        let Some(position) = self.messages.iter().position(
-            |msg| matches!(msg, Message::User(UserMessage { id, .. }) if id == &message_id),
-        ) else {
-            return Err(anyhow!("Message not found"));
-        };
+        loop {
+            let Some(position) = self
+                .messages
+                .iter()
+                .position(|message| message.contains_user_message(&message_id))
+            else {
+                return Err(anyhow!("Message not found"));
+            };
 
-        for message in self.messages.drain(position..) {
-            match message {
-                Message::User(message) => {
-                    self.request_token_usage.remove(&message.id);
+            let mut removed = self.messages.drain(position..).collect::<Vec<_>>();
+            for message in removed.drain(1..) {
+                self.forget_token_usage(&message);
+            }
+            match removed.pop() {
+                // Truncating to a compacted message restores the messages preceding it.
+                Some(Message::Compaction(compaction)) => {
+                    self.messages.extend(compaction.compacted_messages);
+                }
+                Some(message) => {
+                    self.forget_token_usage(&message);
+                    break;
                 }
-                Message::Agent(_) | Message::Resume => {}
+                None => break,
             }
         }
         self.clear_summary();
@@ -1080,6 +1254,53 @@ impl Thread {
         Ok(())
     }
 
+    /// Creates a thread containing the messages before `message_id`, so that the conversation
+    /// can continue differently from that point while this thread stays as it is.
+    pub fn fork(
+        &self,
+        message_id: &UserMessageId,
+        cx: &mut Context<Self>,
+    ) -> Result<Entity<Thread>> {
+        let fork = cx.new(|cx| {
+            let mut fork = Thread::new(
+                self.project.clone(),
+                self.project_context.clone(),
+                self.context_server_registry.clone(),
+                self.templates.clone(),
+                self.model.clone(),
+                cx,
+            );
+            fork.title = self
+                .title
+                .as_ref()
+                .map(|title| format!("{title} (fork)").into());
+            fork.messages = self.messages.clone();
+            fork.request_token_usage = self.request_token_usage.clone();
+            fork.cumulative_token_usage = self.cumulative_token_usage;
+            fork.initial_project_snapshot = self.initial_project_snapshot.clone();
+            fork.completion_mode = self.completion_mode;
+            fork.profile_id = self.profile_id.clone();
+            fork.summarization_model = self.summarization_model.clone();
+            fork
+        });
+        fork.update(cx, |fork, cx| fork.truncate(message_id.clone(), cx))?;
+        Ok(fork)
+    }
+
+    fn forget_token_usage(&mut self, message: &Message) {
+        match message {
+            Message::User(message) => {
+                self.request_token_usage.remove(&message.id);
+            }
+            Message::Compaction(compaction) => {
+                for message in &compaction.compacted_messages {
+                    self.forget_token_usage(message);
+                }
+            }
+            Message::Agent(_) | Message::Resume => {}
+        }
+    }
+
     pub fn latest_request_token_usage(&self) -> Option<language_model::TokenUsage> {
         let last_user_message = self.last_user_message()?;
         let tokens = self.request_token_usage.get(&last_user_message.id)?;
@@ -1299,6 +1520,13 @@ impl Thread {
         let mut attempt = 0;
         let mut intent = CompletionIntent::UserPrompt;
         loop {
+            if this.read_with(cx, |this, cx| this.should_auto_compact(cx))? {
+                Self::compact_messages(this, model.clone(), event_stream, cx)
+                    .await
+                    .context("failed to compact thread")
+                    .log_err();
+            }
+
             let request =
                 this.update(cx, |this, cx| this.build_completion_request(intent, cx))??;
 
@@ -1717,6 +1945,155 @@ impl Thread {
             });
     }
 
+    /// Replaces the messages preceding the last `compaction_preserved_turns` turns with a summary.
+    pub fn compact(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Result<mpsc::UnboundedReceiver<Result<ThreadEvent>>> {
+        self.cancel(cx);
+
+        let model = self.model.clone().context("No language model configured")?;
+        let (events_tx, events_rx) = mpsc::unbounded::<Result<ThreadEvent>>();
+        let event_stream = ThreadEventStream(events_tx);
+        self.running_turn = Some(RunningTurn {
+            event_stream: event_stream.clone(),
+            tools: BTreeMap::default(),
+            _task: cx.spawn(async move |this, cx| {
+                match Self::compact_messages(&this, model, &event_stream, cx).await {
+                    Ok(true) => event_stream.send_stop(acp::StopReason::EndTurn),
+                    Ok(false) => {
+                        event_stream.send_text("There are no earlier messages to compact.");
+                        event_stream.send_stop(acp::StopReason::EndTurn);
+                    }
+                    Err(error) => event_stream.send_error(error),
+                }
+                _ = this.update(cx, |this, _| this.running_turn.take());
+            }),
+        });
+        Ok(events_rx)
+    }
+
+    fn should_auto_compact(&self, cx: &App) -> bool {
+        let settings = AgentSettings::get_global(cx);
+        if !settings.auto_compact {
+            return false;
+        }
+        let Some(model) = self.model.as_ref() else {
+            return false;
+        };
+        // The last user message has no usage until the model responds to it, so use the usage
+        // of the last request that reported it.
+        let Some(usage) = self
+            .messages
+            .iter()
+            .rev()
+            .find_map(|message| match message {
+                Message::User(message) => self.request_token_usage.get(&message.id),
+                Message::Agent(_) | Message::Resume | Message::Compaction(_) => None,
+            })
+        else {
+            return false;
+        };
+        let max_tokens = model.max_token_count_for_mode(self.completion_mode.into());
+        max_tokens > 0
+            && usage.total_tokens() as f32 >= max_tokens as f32 * settings.auto_compact_threshold
+    }
+
+    /// Summarizes the messages preceding the preserved turns, returning `false` if there are none.
+    /// The thread's own model is used, since the messages are known to fit in its context window.
+    async fn compact_messages(
+        this: &WeakEntity<Self>,
+        model: Arc<dyn LanguageModel>,
+        event_stream: &ThreadEventStream,
+        cx: &mut AsyncApp,
+    ) -> Result<bool> {
+        let Some((request, compacted_len)) =
+            this.update(cx, |this, cx| this.build_compaction_request(&model, cx))?
+        else {
+            return Ok(false);
+        };
+
+        let mut summary = String::new();
+        let mut events = model.stream_completion(request, cx).await?;
+        while let Some(event) = events.next().await {
+            match event? {
+                LanguageModelCompletionEvent::Text(text) => summary.push_str(&text),
+                LanguageModelCompletionEvent::UsageUpdated { amount, limit } => {
+                    this.update(cx, |thread, cx| {
+                        thread.update_model_request_usage(amount, limit, cx);
+                    })?;
+                }
+                _ => {}
+            }
+        }
+        let summary = summary.trim();
+        anyhow::ensure!(!summary.is_empty(), "the model returned an empty summary");
+
+        this.update(cx, |this, cx| {
+            let compacted_messages = this.messages.drain(..compacted_len).collect();
+            let compaction = CompactionMessage::new(summary.to_string(), compacted_messages);
+            event_stream.send_compaction(&compaction);
+            this.messages.insert(0, Message::Compaction(compaction));
+
+            // The recorded usage predates the compaction, so forget it to avoid compacting again
+            // before the model reports the new usage.
+            for message in &this.messages {
+                if let Message::User(message) = message {
+                    this.request_token_usage.remove(&message.id);
+                }
+            }
+            cx.emit(TokenUsageUpdated(this.latest_token_usage()));
+            this.updated_at = Utc::now();
+            this.clear_summary();
+            cx.notify();
+        })?;
+        Ok(true)
+    }
+
+    fn build_compaction_request(
+        &self,
+        model: &Arc<dyn LanguageModel>,
+        cx: &App,
+    ) -> Option<(LanguageModelRequest, usize)> {
+        // Turns start with a user message, and at least the current one is always preserved.
+        let preserved_turns = AgentSettings::get_global(cx)
+            .compaction_preserved_turns
+            .max(1);
+        let compacted_len = self
+            .messages
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, message)| matches!(message, Message::User(_)))
+            .nth(preserved_turns - 1)
+            .map(|(ix, _)| ix)?;
+        let already_compacted = matches!(
+            self.messages[..compacted_len],
+            [] | [Message::Compaction(_)]
+        );
+        if already_compacted {
+            return None;
+        }
+
+        let mut request = LanguageModelRequest {
+            thread_id: Some(self.id.to_string()),
+            prompt_id: Some(self.prompt_id.to_string()),
+            intent: Some(CompletionIntent::ThreadContextSummarization),
+            temperature: AgentSettings::temperature_for_model(model, cx),
+            ..Default::default()
+        };
+        for message in &self.messages[..compacted_len] {
+            request.messages.extend(message.to_request());
+        }
+        request.messages.push(LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![COMPACT_THREAD_PROMPT.into()],
+            cache: false,
+            reasoning_details: None,
+        });
+        Some((request, compacted_len))
+    }
+
     pub fn title(&self) -> SharedString {
         self.title.clone().unwrap_or("New Thread".into())
     }
@@ -1871,14 +2248,32 @@ impl Thread {
         self.pending_summary_generation = None;
     }
 
+    /// The text of the agent's last message.
+    pub(crate) fn last_agent_message_text(&self) -> Option<String> {
+        let message = self
+            .messages
+            .iter()
+            .rev()
+            .find_map(Message::as_agent_message)?;
+        Some(
+            message
+                .content
+                .iter()
+                .filter_map(|content| match content {
+                    AgentMessageContent::Text(text) => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+
     fn last_user_message(&self) -> Option<&UserMessage> {
         self.messages
             .iter()
             .rev()
             .find_map(|message| match message {
                 Message::User(user_message) => Some(user_message),
-                Message::Agent(_) => None,
-                Message::Resume => None,
+                Message::Agent(_) | Message::Resume | Message::Compaction(_) => None,
             })
     }
 
@@ -1989,12 +2384,21 @@ impl Thread {
             }
         }
 
+        let parent_profile = self
+            .parent_profile_id
+            .as_ref()
+            .map(|profile_id| AgentSettings::get_global(cx).profiles.get(profile_id));
+        let enabled_for_parent = |is_enabled: &dyn Fn(&AgentProfileSettings) -> bool| {
+            parent_profile.is_none_or(|parent_profile| parent_profile.is_some_and(is_enabled))
+        };
+
         let mut tools = self
             .tools
             .iter()
             .filter_map(|(tool_name, tool)| {
                 if tool.supports_provider(&model.provider_id())
                     && profile.is_tool_enabled(tool_name)
+                    && enabled_for_parent(&|parent| parent.is_tool_enabled(tool_name))
                 {
                     Some((truncate(tool_name), tool.clone()))
                 } else {
@@ -2008,7 +2412,11 @@ impl Thread {
         let mut duplicate_tool_names = HashSet::default();
         for (server_id, server_tools) in self.context_server_registry.read(cx).servers() {
             for (tool_name, tool) in server_tools {
-                if profile.is_context_server_tool_enabled(&server_id.0, &tool_name) {
+                if profile.is_context_server_tool_enabled(&server_id.0, &tool_name)
+                    && enabled_for_parent(&|parent| {
+                        parent.is_context_server_tool_enabled(&server_id.0, &tool_name)
+                    })
+                {
                     let tool_name = truncate(tool_name);
                     if !seen_tools.insert(tool_name.clone()) {
                         duplicate_tool_names.insert(tool_name.clone());
@@ -2425,6 +2833,17 @@ impl ThreadEventStream {
             .ok();
     }
 
+    fn send_compaction(&self, compaction: &CompactionMessage) {
+        self.0
+            .unbounded_send(Ok(ThreadEvent::ToolCall(
+                acp::ToolCall::new(uuid::Uuid::new_v4().to_string(), compaction.title())
+                    .kind(acp::ToolKind::Think)
+                    .status(acp::ToolCallStatus::Completed)
+                    .content(vec![compaction.summary.clone().into()]),
+            )))
+            .ok();
+    }
+
     fn send_tool_call(
         &self,
         id: &LanguageModelToolUseId,
@@ -2542,37 +2961,94 @@ impl ToolCallEventStream {
         if agent_settings::AgentSettings::get_global(cx).always_allow_tool_actions {
             return Task::ready(Ok(()));
         }
+        self.request_authorization(title.into(), None, cx)
+    }
 
-        let (response_tx, response_rx) = oneshot::channel();
-        self.stream
-            .0
-            .unbounded_send(Ok(ThreadEvent::ToolCallAuthorization(
-                ToolCallAuthorization {
-                    tool_call: acp::ToolCallUpdate::new(
-                        self.tool_use_id.to_string(),
-                        acp::ToolCallUpdateFields::new().title(title.into()),
-                    ),
-                    options: vec![
-                        acp::PermissionOption::new(
-                            acp::PermissionOptionId::new("always_allow"),
-                            "Always Allow",
-                            acp::PermissionOptionKind::AllowAlways,
-                        ),
-                        acp::PermissionOption::new(
-                            acp::PermissionOptionId::new("allow"),
-                            "Allow",
-                            acp::PermissionOptionKind::AllowOnce,
-                        ),
-                        acp::PermissionOption::new(
-                            acp::PermissionOptionId::new("deny"),
-                            "Deny",
-                            acp::PermissionOptionKind::RejectOnce,
-                        ),
-                    ],
-                    response: response_tx,
-                },
-            )))
-            .ok();
+    /// Authorizes running a shell command, applying the `tool_permissions` rules configured
+    /// for `tool` before falling back to [`Self::authorize`].
+    pub fn authorize_command(
+        &self,
+        tool: &str,
+        command: &str,
+        title: impl Into<String>,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let settings = agent_settings::AgentSettings::get_global(cx);
+        match settings.tool_permissions.decide_for_command(tool, command) {
+            Some(ToolPermissionAction::Deny) => {
+                return Task::ready(Err(anyhow!(
+                    "Running `{command}` is denied by the tool permission rules"
+                )));
+            }
+            Some(ToolPermissionAction::Allow) => return Task::ready(Ok(())),
+            Some(ToolPermissionAction::Ask) => {}
+            None if settings.always_allow_tool_actions => return Task::ready(Ok(())),
+            None => {}
+        }
+        let rule = (
+            Arc::<str>::from(tool),
+            ToolPermissionRuleContent {
+                pattern: agent_settings::command_pattern(command),
+                action: ToolPermissionAction::Allow,
+            },
+        );
+        self.request_authorization(title.into(), Some(rule), cx)
+    }
+
+    /// Applies the `tool_permissions` rules configured for `tool` to the paths a tool call acts
+    /// on. Returns `None` when no rule matches, leaving the decision to the tool.
+    pub fn authorize_paths(
+        &self,
+        tool: &str,
+        paths: &[&str],
+        title: impl Into<String>,
+        cx: &mut App,
+    ) -> Option<Task<Result<()>>> {
+        let settings = agent_settings::AgentSettings::get_global(cx);
+        match settings.tool_permissions.decide_for_paths(tool, paths)? {
+            ToolPermissionAction::Deny => Some(Task::ready(Err(anyhow!(
+                "{} is denied by the tool permission rules",
+                paths.join(", ")
+            )))),
+            ToolPermissionAction::Allow => Some(Task::ready(Ok(()))),
+            ToolPermissionAction::Ask => Some(self.request_authorization(title.into(), None, cx)),
+        }
+    }
+
+    /// Asks the user to authorize the tool call. `command_rule` adds an option to always allow
+    /// the exact command by persisting it as a rule.
+    fn request_authorization(
+        &self,
+        title: String,
+        command_rule: Option<(Arc<str>, ToolPermissionRuleContent)>,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let mut options = vec![acp::PermissionOption::new(
+            acp::PermissionOptionId::new("always_allow"),
+            "Always Allow",
+            acp::PermissionOptionKind::AllowAlways,
+        )];
+        if command_rule.is_some() {
+            options.push(acp::PermissionOption::new(
+                acp::PermissionOptionId::new("always_allow_command"),
+                "Always Allow This Command",
+                acp::PermissionOptionKind::AllowAlways,
+            ));
+        }
+        options.extend([
+            acp::PermissionOption::new(
+                acp::PermissionOptionId::new("allow"),
+                "Allow",
+                acp::PermissionOptionKind::AllowOnce,
+            ),
+            acp::PermissionOption::new(
+                acp::PermissionOptionId::new("deny"),
+                "Deny",
+                acp::PermissionOptionKind::RejectOnce,
+            ),
+        ]);
+
+        let response_rx = self.send_authorization_request(title, options);
         let fs = self.fs.clone();
         cx.spawn(async move |cx| match response_rx.await?.0.as_ref() {
             "always_allow" => {
@@ -2589,10 +3065,56 @@ impl ToolCallEventStream {
 
                 Ok(())
             }
+            "always_allow_command" => {
+                if let Some((fs, (tool, rule))) = fs.clone().zip(command_rule) {
+                    cx.update(|cx| {
+                        update_settings_file(fs, cx, |settings, _| {
+                            settings
+                                .agent
+                                .get_or_insert_default()
+                                .add_tool_permission_rule(tool, rule);
+                        });
+                    })?;
+                }
+
+                Ok(())
+            }
             "allow" => Ok(()),
             _ => Err(anyhow!("Permission to run tool denied by user")),
         })
     }
+
+    /// Asks the user to authorize a tool call of a sub-agent, offering the options of the
+    /// sub-agent's own request, and returns the chosen one so the sub-agent can act on it.
+    pub(crate) fn authorize_subagent_tool_call(
+        &self,
+        title: impl Into<String>,
+        options: Vec<acp::PermissionOption>,
+    ) -> oneshot::Receiver<acp::PermissionOptionId> {
+        self.send_authorization_request(title.into(), options)
+    }
+
+    fn send_authorization_request(
+        &self,
+        title: String,
+        options: Vec<acp::PermissionOption>,
+    ) -> oneshot::Receiver<acp::PermissionOptionId> {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.stream
+            .0
+            .unbounded_send(Ok(ThreadEvent::ToolCallAuthorization(
+                ToolCallAuthorization {
+                    tool_call: acp::ToolCallUpdate::new(
+                        self.tool_use_id.to_string(),
+                        acp::ToolCallUpdateFields::new().title(title),
+                    ),
+                    options,
+                    response: response_tx,
+                },
+            )))
+            .ok();
+        response_rx
+    }
 }
 
 #[cfg(any(test, feature = "test-support"))]
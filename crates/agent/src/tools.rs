@@ -1,3 +1,4 @@
+mod code_navigation;
 mod context_server_registry;
 mod copy_path_tool;
 mod create_directory_tool;
@@ -6,14 +7,20 @@ mod diagnostics_tool;
 mod edit_file_tool;
 mod fetch_tool;
 mod find_path_tool;
+mod find_references_tool;
+mod go_to_definition_tool;
 mod grep_tool;
+mod hover_tool;
 mod list_directory_tool;
 mod move_path_tool;
 mod now_tool;
 mod open_tool;
 mod read_file_tool;
+mod rename_symbol_tool;
 mod restore_file_from_disk_tool;
 mod save_file_tool;
+mod subagent_tool;
+mod symbol_search_tool;
 
 mod terminal_tool;
 mod thinking_tool;
@@ -30,14 +37,20 @@ pub use diagnostics_tool::*;
 pub use edit_file_tool::*;
 pub use fetch_tool::*;
 pub use find_path_tool::*;
+pub use find_references_tool::*;
+pub use go_to_definition_tool::*;
 pub use grep_tool::*;
+pub use hover_tool::*;
 pub use list_directory_tool::*;
 pub use move_path_tool::*;
 pub use now_tool::*;
 pub use open_tool::*;
 pub use read_file_tool::*;
+pub use rename_symbol_tool::*;
 pub use restore_file_from_disk_tool::*;
 pub use save_file_tool::*;
+pub use subagent_tool::*;
+pub use symbol_search_tool::*;
 
 pub use terminal_tool::*;
 pub use thinking_tool::*;
@@ -88,14 +101,20 @@ tools! {
     EditFileTool,
     FetchTool,
     FindPathTool,
+    FindReferencesTool,
+    GoToDefinitionTool,
     GrepTool,
+    HoverTool,
     ListDirectoryTool,
     MovePathTool,
     NowTool,
     OpenTool,
     ReadFileTool,
+    RenameSymbolTool,
     RestoreFileFromDiskTool,
     SaveFileTool,
+    SubagentTool,
+    SymbolSearchTool,
     TerminalTool,
     ThinkingTool,
     WebSearchTool,
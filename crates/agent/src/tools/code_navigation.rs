@@ -0,0 +1,110 @@
+//! Helpers shared by the tools that query language servers about a symbol.
+
+use anyhow::{Context as _, Result, anyhow};
+use gpui::{App, AsyncApp, Entity, Task};
+use language::{Anchor, Buffer, Point, ToPoint as _};
+use project::{Project, lsp_store::OpenLspBufferHandle};
+use std::ops::Range;
+
+/// A symbol in a buffer that is registered with its language servers.
+pub(crate) struct SymbolPosition {
+    pub buffer: Entity<Buffer>,
+    pub position: Point,
+    _lsp_handle: OpenLspBufferHandle,
+}
+
+/// Opens the file at `path` and finds where `symbol` appears on `line`, which is 1-based.
+pub(crate) fn resolve_symbol_position(
+    project: &Entity<Project>,
+    path: &str,
+    line: u32,
+    symbol: &str,
+    cx: &mut App,
+) -> Task<Result<SymbolPosition>> {
+    let Some(project_path) = project.read(cx).find_project_path(path, cx) else {
+        return Task::ready(Err(anyhow!("Could not find path {path} in project")));
+    };
+    let open_buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+    let project = project.clone();
+    let path = path.to_string();
+    let symbol = symbol.to_string();
+    cx.spawn(async move |cx| {
+        let buffer = open_buffer.await?;
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+        let max_row = snapshot.max_point().row;
+        let row = line
+            .checked_sub(1)
+            .filter(|row| *row <= max_row)
+            .with_context(|| {
+                format!(
+                    "Line {line} doesn't exist in {path}, which has {} lines",
+                    max_row + 1
+                )
+            })?;
+        let line_text = snapshot
+            .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+            .collect::<String>();
+        let column = find_symbol(&line_text, &symbol)
+            .with_context(|| format!("`{symbol}` doesn't appear on line {line} of {path}"))?;
+        let lsp_handle = project.update(cx, |project, cx| {
+            project.register_buffer_with_language_servers(&buffer, cx)
+        })?;
+        Ok(SymbolPosition {
+            buffer,
+            position: Point::new(row, column as u32),
+            _lsp_handle: lsp_handle,
+        })
+    })
+}
+
+/// Describes a location as `path:line`, followed by the text of that line.
+pub(crate) fn describe_location(
+    buffer: &Entity<Buffer>,
+    range: &Range<Anchor>,
+    cx: &AsyncApp,
+) -> Result<String> {
+    buffer.read_with(cx, |buffer, cx| {
+        let path = buffer
+            .file()
+            .map(|file| file.full_path(cx).display().to_string())
+            .unwrap_or_else(|| "untitled".to_string());
+        let row = range.start.to_point(buffer).row;
+        let line_text = buffer
+            .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+            .collect::<String>();
+        format!("{path}:{}: {}", row + 1, line_text.trim())
+    })
+}
+
+/// Finds the byte offset of `symbol` in `line`, preferring an occurrence that isn't part of a
+/// longer identifier.
+fn find_symbol(line: &str, symbol: &str) -> Option<usize> {
+    if symbol.is_empty() {
+        return None;
+    }
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(symbol)
+        .map(|(offset, _)| offset)
+        .find(|&offset| {
+            let before = line[..offset].chars().next_back();
+            let after = line[offset + symbol.len()..].chars().next();
+            !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+        })
+        .or_else(|| line.find(symbol))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_symbol() {
+        let line = "    let item = items.iter().map(|item| item.len());";
+        assert_eq!(find_symbol(line, "item"), Some(8));
+        assert_eq!(find_symbol(line, "items"), Some(15));
+        assert_eq!(find_symbol(line, "len"), Some(44));
+        assert_eq!(find_symbol("fn render_item()", "render"), Some(3));
+        assert_eq!(find_symbol(line, "missing"), None);
+        assert_eq!(find_symbol(line, ""), None);
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
 };
 use acp_thread::Diff;
 use agent_client_protocol::{self as acp, ToolCallLocation, ToolCallUpdateFields};
+use agent_settings::ToolPermissionAction;
 use anyhow::{Context as _, Result, anyhow};
 use cloud_llm_client::CompletionIntent;
 use collections::HashSet;
@@ -149,7 +150,23 @@ impl EditFileTool {
         event_stream: &ToolCallEventStream,
         cx: &mut App,
     ) -> Task<Result<()>> {
-        if agent_settings::AgentSettings::get_global(cx).always_allow_tool_actions {
+        let settings = agent_settings::AgentSettings::get_global(cx);
+        // Only rules that deny or ask decide on their own. An allow rule such as `*.json` must
+        // not skip the confirmations below, which protect the settings files holding the rules.
+        if matches!(
+            settings
+                .tool_permissions
+                .decide_for_paths(Self::name(), &[input.path.as_str()]),
+            Some(ToolPermissionAction::Deny | ToolPermissionAction::Ask)
+        ) && let Some(authorize) = event_stream.authorize_paths(
+            Self::name(),
+            &[input.path.as_str()],
+            &input.display_description,
+            cx,
+        ) {
+            return authorize;
+        }
+        if settings.always_allow_tool_actions {
             return Task::ready(Ok(()));
         }
 
@@ -1238,6 +1255,96 @@ mod tests {
         assert!(stream_rx.try_next().is_err());
     }
 
+    #[gpui::test]
+    async fn test_allow_rule_does_not_skip_local_settings_confirmation(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = project::FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({})).await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        let context_server_registry =
+            cx.new(|cx| ContextServerRegistry::new(project.read(cx).context_server_store(), cx));
+        let language_registry = project.read_with(cx, |project, _cx| project.languages().clone());
+        let model = Arc::new(FakeLanguageModel::default());
+        let thread = cx.new(|cx| {
+            Thread::new(
+                project.clone(),
+                cx.new(|_cx| ProjectContext::default()),
+                context_server_registry,
+                Templates::new(),
+                Some(model.clone()),
+                cx,
+            )
+        });
+        let tool = Arc::new(EditFileTool::new(
+            project.clone(),
+            thread.downgrade(),
+            language_registry,
+            Templates::new(),
+        ));
+        cx.update(|cx| {
+            let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+            let mut rules = collections::IndexMap::default();
+            rules.insert(
+                "edit_file".into(),
+                vec![settings::ToolPermissionRuleContent {
+                    pattern: "*.json".into(),
+                    action: ToolPermissionAction::Allow,
+                }],
+            );
+            settings.tool_permissions = agent_settings::ToolPermissions::new(rules);
+            agent_settings::AgentSettings::override_global(settings, cx);
+        });
+
+        let (stream_tx, mut stream_rx) = ToolCallEventStream::test();
+        let _auth = cx.update(|cx| {
+            tool.authorize(
+                &EditFileToolInput {
+                    display_description: "edit settings".into(),
+                    path: "root/.zed/settings.json".into(),
+                    mode: EditFileMode::Edit,
+                },
+                &stream_tx,
+                cx,
+            )
+        });
+        let event = stream_rx.expect_authorization().await;
+        assert_eq!(
+            event.tool_call.fields.title,
+            Some("edit settings (local settings)".into())
+        );
+
+        let (stream_tx, mut stream_rx) = ToolCallEventStream::test();
+        let _auth = cx.update(|cx| {
+            tool.authorize(
+                &EditFileToolInput {
+                    display_description: "edit outside".into(),
+                    path: "/etc/config.json".into(),
+                    mode: EditFileMode::Edit,
+                },
+                &stream_tx,
+                cx,
+            )
+        });
+        let event = stream_rx.expect_authorization().await;
+        assert_eq!(event.tool_call.fields.title, Some("edit outside".into()));
+
+        let (stream_tx, mut stream_rx) = ToolCallEventStream::test();
+        cx.update(|cx| {
+            tool.authorize(
+                &EditFileToolInput {
+                    display_description: "edit data".into(),
+                    path: "root/data.json".into(),
+                    mode: EditFileMode::Edit,
+                },
+                &stream_tx,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+        assert!(stream_rx.try_next().is_err());
+    }
+
     #[gpui::test]
     async fn test_authorize_global_config(cx: &mut TestAppContext) {
         init_test(cx);
@@ -0,0 +1,130 @@
+use crate::tools::code_navigation::{describe_location, resolve_symbol_position};
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Finds all references to a symbol across the project, using the language server for the file.
+///
+/// Prefer this tool to a regex search when you need every usage of a specific function, type or variable, such as before changing its signature.
+/// Results are paginated with 50 references per page. Use the optional 'offset' parameter to request subsequent pages.
+///
+/// <example>
+/// To find the usages of the `Config` struct defined on line 10 of `src/config.rs`:
+/// {
+///     "path": "project/src/config.rs",
+///     "line": 10,
+///     "symbol": "Config"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindReferencesToolInput {
+    /// The path of a file where the symbol is defined or used.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number where the symbol appears.
+    pub line: u32,
+    /// The symbol as it is written on that line.
+    pub symbol: String,
+    /// Optional starting position for paginated results (0-based).
+    /// When not provided, starts from the beginning.
+    #[serde(default)]
+    pub offset: u32,
+}
+
+const RESULTS_PER_PAGE: usize = 50;
+
+pub struct FindReferencesTool {
+    project: Entity<Project>,
+}
+
+impl FindReferencesTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for FindReferencesTool {
+    type Input = FindReferencesToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "find_references"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Find references to {}", MarkdownInlineCode(&input.symbol)).into(),
+            Err(_) => "Find references".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let symbol =
+            resolve_symbol_position(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let symbol = symbol.await?;
+            let references = project
+                .update(cx, |project, cx| {
+                    project.references(&symbol.buffer, symbol.position, cx)
+                })?
+                .await?
+                .unwrap_or_default();
+
+            if references.is_empty() {
+                return Ok(format!(
+                    "No references to `{}` were found. The language server may still be starting, or there may be none for this file.",
+                    input.symbol
+                ));
+            }
+
+            let total = references.len();
+            let offset = input.offset as usize;
+            let mut output = String::new();
+            for reference in references.iter().skip(offset).take(RESULTS_PER_PAGE) {
+                writeln!(
+                    output,
+                    "{}",
+                    describe_location(&reference.buffer, &reference.range, cx)?
+                )?;
+            }
+
+            if output.is_empty() {
+                return Ok(format!(
+                    "No references found at offset {offset}; there are {total} in total."
+                ));
+            }
+            if offset + RESULTS_PER_PAGE < total {
+                writeln!(
+                    output,
+                    "\nShowing references {}-{} of {total}. Use offset: {} to see the next page.",
+                    offset + 1,
+                    offset + RESULTS_PER_PAGE,
+                    offset + RESULTS_PER_PAGE,
+                )?;
+            }
+            Ok(output)
+        })
+    }
+}
@@ -0,0 +1,120 @@
+use crate::tools::code_navigation::{describe_location, resolve_symbol_position};
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Finds where a symbol is defined, using the language server for the file.
+///
+/// This is more precise than searching for the symbol's name, because it resolves imports, aliases and methods of the same name on different types.
+///
+/// <example>
+/// To find the definition of `render` called on line 42 of `src/app.rs`:
+/// {
+///     "path": "project/src/app.rs",
+///     "line": 42,
+///     "symbol": "render"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GoToDefinitionToolInput {
+    /// The path of the file where the symbol is used.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number where the symbol appears.
+    pub line: u32,
+    /// The symbol as it is written on that line.
+    pub symbol: String,
+    /// Whether to find the definition of the symbol's type rather than of the symbol itself.
+    #[serde(default)]
+    pub type_definition: bool,
+}
+
+pub struct GoToDefinitionTool {
+    project: Entity<Project>,
+}
+
+impl GoToDefinitionTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for GoToDefinitionTool {
+    type Input = GoToDefinitionToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "go_to_definition"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) if input.type_definition => format!(
+                "Go to type definition of {}",
+                MarkdownInlineCode(&input.symbol)
+            )
+            .into(),
+            Ok(input) => {
+                format!("Go to definition of {}", MarkdownInlineCode(&input.symbol)).into()
+            }
+            Err(_) => "Go to definition".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let symbol =
+            resolve_symbol_position(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let symbol = symbol.await?;
+            let definitions = project
+                .update(cx, |project, cx| {
+                    if input.type_definition {
+                        project.type_definitions(&symbol.buffer, symbol.position, cx)
+                    } else {
+                        project.definitions(&symbol.buffer, symbol.position, cx)
+                    }
+                })?
+                .await?
+                .unwrap_or_default();
+
+            if definitions.is_empty() {
+                return Ok(format!(
+                    "No definition was found for `{}`. The language server may still be starting, or there may be none for this file.",
+                    input.symbol
+                ));
+            }
+
+            let mut output = String::new();
+            for definition in definitions {
+                writeln!(
+                    output,
+                    "{}",
+                    describe_location(&definition.target.buffer, &definition.target.range, cx)?
+                )?;
+            }
+            Ok(output)
+        })
+    }
+}
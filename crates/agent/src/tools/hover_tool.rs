@@ -0,0 +1,113 @@
+use crate::tools::code_navigation::resolve_symbol_position;
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use gpui::{App, Entity, SharedString, Task};
+use project::{HoverBlockKind, Project};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Shows the type and documentation of a symbol, as the language server would when hovering over it in the editor.
+///
+/// Use this tool to learn the inferred type of a variable or the signature of a function without reading its definition.
+///
+/// <example>
+/// To get the type of the `settings` variable on line 27 of `src/main.rs`:
+/// {
+///     "path": "project/src/main.rs",
+///     "line": 27,
+///     "symbol": "settings"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct HoverToolInput {
+    /// The path of the file where the symbol appears.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number where the symbol appears.
+    pub line: u32,
+    /// The symbol as it is written on that line.
+    pub symbol: String,
+}
+
+pub struct HoverTool {
+    project: Entity<Project>,
+}
+
+impl HoverTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for HoverTool {
+    type Input = HoverToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "hover"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Read
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Get type info for {}", MarkdownInlineCode(&input.symbol)).into(),
+            Err(_) => "Get type info".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let symbol =
+            resolve_symbol_position(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let symbol = symbol.await?;
+            let hovers = project
+                .update(cx, |project, cx| {
+                    project.hover(&symbol.buffer, symbol.position, cx)
+                })?
+                .await
+                .unwrap_or_default();
+
+            let mut output = String::new();
+            for block in hovers.iter().flat_map(|hover| &hover.contents) {
+                if block.text.trim().is_empty() {
+                    continue;
+                }
+                match &block.kind {
+                    HoverBlockKind::Code { language } => {
+                        writeln!(output, "```{language}\n{}\n```\n", block.text.trim_end())?
+                    }
+                    HoverBlockKind::PlainText | HoverBlockKind::Markdown => {
+                        writeln!(output, "{}\n", block.text.trim_end())?
+                    }
+                }
+            }
+
+            if output.is_empty() {
+                Ok(format!(
+                    "No information is available for `{}`. The language server may still be starting, or there may be none for this file.",
+                    input.symbol
+                ))
+            } else {
+                Ok(output)
+            }
+        })
+    }
+}
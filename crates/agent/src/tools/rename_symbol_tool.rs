@@ -0,0 +1,530 @@
+use crate::tools::code_navigation::resolve_symbol_position;
+use crate::{AgentTool, ToolCallEventStream};
+use action_log::ActionLog;
+use agent_client_protocol as acp;
+use anyhow::{Result, anyhow};
+use gpui::{App, Entity, SharedString, Task};
+use language::Buffer;
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, fmt::Write, sync::Arc};
+use util::{ResultExt as _, markdown::MarkdownInlineCode};
+
+/// Renames a symbol and updates all its references across the project, using the language server for the file.
+///
+/// Prefer this tool to editing files when renaming a function, type, field or variable, because the language server knows which occurrences refer to the same symbol. The changes are made in the editor and can be reviewed like any other edit. Renames that would also create, move or delete files, such as renaming a module, are refused.
+///
+/// <example>
+/// To rename the `load` function defined on line 15 of `src/config.rs` to `load_from_disk`:
+/// {
+///     "path": "project/src/config.rs",
+///     "line": 15,
+///     "symbol": "load",
+///     "new_name": "load_from_disk"
+/// }
+/// </example>
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RenameSymbolToolInput {
+    /// The path of a file where the symbol is defined or used.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number where the symbol appears.
+    pub line: u32,
+    /// The symbol as it is written on that line.
+    pub symbol: String,
+    /// The new name for the symbol.
+    pub new_name: String,
+}
+
+pub struct RenameSymbolTool {
+    project: Entity<Project>,
+    action_log: Entity<ActionLog>,
+}
+
+impl RenameSymbolTool {
+    pub fn new(project: Entity<Project>, action_log: Entity<ActionLog>) -> Self {
+        Self {
+            project,
+            action_log,
+        }
+    }
+}
+
+impl AgentTool for RenameSymbolTool {
+    type Input = RenameSymbolToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "rename_symbol"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Edit
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!(
+                "Rename {} to {}",
+                MarkdownInlineCode(&input.symbol),
+                MarkdownInlineCode(&input.new_name)
+            )
+            .into(),
+            Err(_) => "Rename symbol".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let title = self.initial_title(Ok(input.clone()), cx);
+        let symbol =
+            resolve_symbol_position(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        let action_log = self.action_log.clone();
+        cx.spawn(async move |cx| {
+            let symbol = symbol.await?;
+
+            // Authorize the rename before making it, for every file that refers to the symbol.
+            let references = project
+                .update(cx, |project, cx| {
+                    project.references(&symbol.buffer, symbol.position, cx)
+                })?
+                .await
+                .log_err()
+                .flatten()
+                .unwrap_or_default();
+            let authorized_paths = cx.update(|cx| {
+                references
+                    .iter()
+                    .map(|location| &location.buffer)
+                    .chain([&symbol.buffer])
+                    .map(|buffer| buffer_path(buffer, cx))
+                    .collect::<BTreeSet<_>>()
+            })?;
+            let authorize = cx.update(|cx| {
+                let paths = authorized_paths.iter().map(String::as_str).collect::<Vec<_>>();
+                event_stream
+                    .authorize_paths(Self::name(), &paths, title.clone(), cx)
+                    .unwrap_or_else(|| event_stream.authorize(title.clone(), cx))
+            })?;
+            authorize.await?;
+
+            let transaction = project
+                .update(cx, |project, cx| {
+                    project.perform_rename_without_resource_operations(
+                        symbol.buffer.clone(),
+                        symbol.position,
+                        input.new_name.clone(),
+                        cx,
+                    )
+                })?
+                .await
+                .map_err(|error| {
+                    anyhow!(
+                        "The language server's rename of `{}` wasn't applied: {error:#}. Edit the files instead.",
+                        input.symbol
+                    )
+                })?;
+            if transaction.0.is_empty() {
+                return Err(anyhow!(
+                    "The language server didn't rename `{}`. It may still be starting, or there may be none for this file.",
+                    input.symbol
+                ));
+            }
+
+            // The rename was applied as if the user had made it. Undo it and make the same edits
+            // again on the agent's behalf, so that the action log shows them for review.
+            let mut renamed_buffers = cx.update(|cx| {
+                transaction
+                    .0
+                    .into_iter()
+                    .map(|(buffer, transaction)| {
+                        let path = buffer_path(&buffer, cx);
+                        let (edits, undone) = buffer.update(cx, |buffer, cx| {
+                            let edits = buffer
+                                .edits_since::<usize>(&transaction.start)
+                                .map(|edit| (edit.old, buffer.text_for_range(edit.new).collect()))
+                                .collect::<Vec<(_, String)>>();
+                            let undone = buffer.undo_transaction(transaction.id, cx);
+                            (edits, undone)
+                        });
+                        (path, buffer, edits, undone)
+                    })
+                    .collect::<Vec<_>>()
+            })?;
+            renamed_buffers.sort_by(|a, b| a.0.cmp(&b.0));
+
+            // The language server may edit files that it didn't report as references. Those edits
+            // have been undone, and are only made again if the rules allow them.
+            let unexpected_paths = renamed_buffers
+                .iter()
+                .map(|(path, ..)| path.as_str())
+                .filter(|path| !authorized_paths.contains(*path))
+                .collect::<Vec<_>>();
+            if !unexpected_paths.is_empty() {
+                let authorize = cx.update(|cx| {
+                    event_stream.authorize_paths(Self::name(), &unexpected_paths, title, cx)
+                })?;
+                if let Some(authorize) = authorize {
+                    authorize.await?;
+                }
+            }
+
+            cx.update(|cx| {
+                let mut output = format!(
+                    "Renamed `{}` to `{}` in {} file(s):\n",
+                    input.symbol,
+                    input.new_name,
+                    renamed_buffers.len()
+                );
+                for (path, buffer, edits, undone) in renamed_buffers {
+                    writeln!(output, "- {path}: {} edit(s)", edits.len())?;
+                    action_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx));
+                    if undone {
+                        buffer.update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+                    }
+                    action_log.update(cx, |log, cx| log.buffer_edited(buffer, cx));
+                }
+                anyhow::Ok(output)
+            })?
+        })
+    }
+}
+
+fn buffer_path(buffer: &Entity<Buffer>, cx: &App) -> String {
+    buffer
+        .read(cx)
+        .file()
+        .map(|file| file.full_path(cx).display().to_string())
+        .unwrap_or_else(|| "untitled".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_settings::{AgentSettings, ToolPermissionAction, ToolPermissions};
+    use collections::IndexMap;
+    use futures::StreamExt as _;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher};
+    use lsp::FakeLanguageServer;
+    use project::{FakeFs, lsp_store::OpenLspBufferHandle};
+    use serde_json::json;
+    use settings::{Settings as _, SettingsStore, ToolPermissionRuleContent};
+    use util::path;
+
+    #[gpui::test]
+    async fn test_rename_symbol_across_files(cx: &mut TestAppContext) {
+        let (project, tool, _lsp_handle, _) = init_test(cx).await;
+        update_agent_settings(cx, |settings| settings.always_allow_tool_actions = true);
+
+        let (event_stream, mut events) = ToolCallEventStream::test();
+        let output = cx
+            .update(|cx| tool.run(rename_input(), event_stream, cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            output,
+            "Renamed `load` to `load_from_disk` in 2 file(s):\n\
+             - root/src/lib.rs: 1 edit(s)\n\
+             - root/src/main.rs: 1 edit(s)\n"
+        );
+        assert!(events.try_next().is_err());
+        assert_eq!(
+            buffer_text(&project, path!("/root/src/lib.rs"), cx).await,
+            "pub fn load_from_disk() {}\n"
+        );
+        assert_eq!(
+            buffer_text(&project, path!("/root/src/main.rs"), cx).await,
+            "fn main() {\n    lib::load_from_disk();\n}\n"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_rename_symbol_denied_path(cx: &mut TestAppContext) {
+        let (project, tool, _lsp_handle, _) = init_test(cx).await;
+        update_agent_settings(cx, |settings| {
+            settings.always_allow_tool_actions = true;
+            let mut rules = IndexMap::default();
+            rules.insert(
+                "rename_symbol".into(),
+                vec![ToolPermissionRuleContent {
+                    pattern: "main.rs".into(),
+                    action: ToolPermissionAction::Deny,
+                }],
+            );
+            settings.tool_permissions = ToolPermissions::new(rules);
+        });
+
+        // The agent only passed `lib.rs`, but the rename also edits the denied `main.rs`.
+        let (event_stream, _events) = ToolCallEventStream::test();
+        let result = cx
+            .update(|cx| tool.run(rename_input(), event_stream, cx))
+            .await;
+        assert!(result.is_err());
+        assert_eq!(
+            buffer_text(&project, path!("/root/src/lib.rs"), cx).await,
+            "pub fn load() {}\n"
+        );
+        assert_eq!(
+            buffer_text(&project, path!("/root/src/main.rs"), cx).await,
+            "fn main() {\n    lib::load();\n}\n"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_rename_symbol_asks_without_rules(cx: &mut TestAppContext) {
+        let (project, tool, _lsp_handle, _) = init_test(cx).await;
+
+        let (event_stream, mut events) = ToolCallEventStream::test();
+        let task = cx.update(|cx| tool.clone().run(rename_input(), event_stream, cx));
+        let authorization = events.expect_authorization().await;
+        assert_eq!(
+            authorization.tool_call.fields.title,
+            Some("Rename `load` to `load_from_disk`".into())
+        );
+        authorization
+            .response
+            .send(acp::PermissionOptionId::new("deny"))
+            .unwrap();
+        assert!(task.await.is_err());
+        assert_eq!(
+            buffer_text(&project, path!("/root/src/main.rs"), cx).await,
+            "fn main() {\n    lib::load();\n}\n"
+        );
+
+        let (event_stream, mut events) = ToolCallEventStream::test();
+        let task = cx.update(|cx| tool.run(rename_input(), event_stream, cx));
+        events
+            .expect_authorization()
+            .await
+            .response
+            .send(acp::PermissionOptionId::new("allow"))
+            .unwrap();
+        task.await.unwrap();
+        assert_eq!(
+            buffer_text(&project, path!("/root/src/main.rs"), cx).await,
+            "fn main() {\n    lib::load_from_disk();\n}\n"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_rename_symbol_asks_before_renaming(cx: &mut TestAppContext) {
+        let (_project, tool, _lsp_handle, fake_language_server) = init_test(cx).await;
+        let mut renames = fake_language_server
+            .set_request_handler::<lsp::request::Rename, _, _>(|_, _| async move { Ok(None) });
+
+        let (event_stream, mut events) = ToolCallEventStream::test();
+        let task = cx.update(|cx| tool.run(rename_input(), event_stream, cx));
+        let authorization = events.expect_authorization().await;
+        cx.run_until_parked();
+        assert!(
+            renames.try_next().is_err(),
+            "the language server was asked to rename before the rename was authorized"
+        );
+        authorization
+            .response
+            .send(acp::PermissionOptionId::new("allow"))
+            .unwrap();
+        // The handler doesn't rename anything, so the tool reports that nothing happened.
+        assert!(task.await.is_err());
+        assert!(renames.next().await.is_some());
+    }
+
+    #[gpui::test]
+    async fn test_rename_symbol_rejects_resource_operations(cx: &mut TestAppContext) {
+        let (project, tool, _lsp_handle, fake_language_server) = init_test(cx).await;
+        update_agent_settings(cx, |settings| settings.always_allow_tool_actions = true);
+        fake_language_server.set_request_handler::<lsp::request::Rename, _, _>(|_, _| async move {
+            let old_uri = lsp::Uri::from_file_path(path!("/root/src/lib.rs")).unwrap();
+            let new_uri = lsp::Uri::from_file_path(path!("/root/src/loader.rs")).unwrap();
+            Ok(Some(lsp::WorkspaceEdit {
+                document_changes: Some(lsp::DocumentChanges::Operations(vec![
+                    lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                        text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                            uri: old_uri.clone(),
+                            version: None,
+                        },
+                        edits: vec![lsp::Edit::Plain(lsp::TextEdit::new(
+                            lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 11)),
+                            "load_from_disk".into(),
+                        ))],
+                    }),
+                    lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(lsp::RenameFile {
+                        old_uri,
+                        new_uri,
+                        options: None,
+                        annotation_id: None,
+                    })),
+                ])),
+                ..Default::default()
+            }))
+        });
+
+        let (event_stream, _events) = ToolCallEventStream::test();
+        let result = cx
+            .update(|cx| tool.run(rename_input(), event_stream, cx))
+            .await;
+        assert!(result.is_err());
+        assert_eq!(
+            buffer_text(&project, path!("/root/src/lib.rs"), cx).await,
+            "pub fn load() {}\n"
+        );
+        project.read_with(cx, |project, cx| {
+            let path = project
+                .find_project_path(path!("/root/src/loader.rs"), cx)
+                .unwrap();
+            assert!(project.entry_for_path(&path, cx).is_none());
+        });
+    }
+
+    fn rename_input() -> RenameSymbolToolInput {
+        RenameSymbolToolInput {
+            path: "root/src/lib.rs".into(),
+            line: 1,
+            symbol: "load".into(),
+            new_name: "load_from_disk".into(),
+        }
+    }
+
+    async fn init_test(
+        cx: &mut TestAppContext,
+    ) -> (
+        Entity<Project>,
+        Arc<RenameSymbolTool>,
+        OpenLspBufferHandle,
+        FakeLanguageServer,
+    ) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "lib.rs": "pub fn load() {}\n",
+                    "main.rs": "fn main() {\n    lib::load();\n}\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_language_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    rename_provider: Some(lsp::OneOf::Left(true)),
+                    references_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/root/src/lib.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let lsp_handle = project.update(cx, |project, cx| {
+            project.register_buffer_with_language_servers(&buffer, cx)
+        });
+        let fake_language_server = fake_language_servers.next().await.unwrap();
+        fake_language_server.set_request_handler::<lsp::request::References, _, _>(
+            |_, _| async move {
+                let location = |path: &str, line, start, end| {
+                    lsp::Location::new(
+                        lsp::Uri::from_file_path(path).unwrap(),
+                        lsp::Range::new(
+                            lsp::Position::new(line, start),
+                            lsp::Position::new(line, end),
+                        ),
+                    )
+                };
+                Ok(Some(vec![
+                    location(path!("/root/src/lib.rs"), 0, 7, 11),
+                    location(path!("/root/src/main.rs"), 1, 9, 13),
+                ]))
+            },
+        );
+        fake_language_server.set_request_handler::<lsp::request::Rename, _, _>(
+            |params, _| async move {
+                let edit = |path: &str, line, start, end| {
+                    (
+                        lsp::Uri::from_file_path(path).unwrap(),
+                        vec![lsp::TextEdit::new(
+                            lsp::Range::new(
+                                lsp::Position::new(line, start),
+                                lsp::Position::new(line, end),
+                            ),
+                            params.new_name.clone(),
+                        )],
+                    )
+                };
+                Ok(Some(lsp::WorkspaceEdit {
+                    changes: Some(
+                        [
+                            edit(path!("/root/src/lib.rs"), 0, 7, 11),
+                            edit(path!("/root/src/main.rs"), 1, 9, 13),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                    ..Default::default()
+                }))
+            },
+        );
+
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let tool = Arc::new(RenameSymbolTool::new(project.clone(), action_log));
+        (project, tool, lsp_handle, fake_language_server)
+    }
+
+    fn update_agent_settings(cx: &mut TestAppContext, f: impl FnOnce(&mut AgentSettings)) {
+        cx.update(|cx| {
+            let mut settings = AgentSettings::get_global(cx).clone();
+            f(&mut settings);
+            AgentSettings::override_global(settings, cx);
+        });
+    }
+
+    async fn buffer_text(
+        project: &Entity<Project>,
+        abs_path: &str,
+        cx: &mut TestAppContext,
+    ) -> String {
+        let buffer: Entity<Buffer> = project
+            .update(cx, |project, cx| project.open_local_buffer(abs_path, cx))
+            .await
+            .unwrap();
+        buffer.read_with(cx, |buffer, _| buffer.text())
+    }
+}
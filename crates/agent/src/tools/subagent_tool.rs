@@ -0,0 +1,185 @@
+use std::{rc::Rc, sync::Arc};
+
+use acp_thread::UserMessageId;
+use agent_client_protocol as acp;
+use agent_settings::AgentProfileId;
+use anyhow::{Result, anyhow};
+use futures::StreamExt as _;
+use gpui::{App, SharedString, Task, WeakEntity};
+use indoc::formatdoc;
+use language_model::LanguageModelToolResultContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{AgentTool, Thread, ThreadEnvironment, ThreadEvent, ToolCallEventStream};
+
+/// The profile sub-agents use when none is requested.
+const DEFAULT_SUBAGENT_PROFILE: &str = "ask";
+
+/// Delegates a focused task to a sub-agent, which works on it in a separate conversation and replies with a report.
+///
+/// Use this tool for tasks that require reading many files or searching broadly across the project, but whose result can be summarized, such as finding where something is implemented or how a feature works. The intermediate steps won't be added to this conversation, which keeps it small.
+///
+/// The sub-agent can't see this conversation, so the task must include all the context it needs. To work on several independent tasks in parallel, use this tool several times in the same response.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SubagentToolInput {
+    /// A short, user-friendly description of the task, shown in the UI.
+    ///
+    /// <example>Find where settings are migrated</example>
+    pub label: String,
+    /// A complete description of the task, including what the report should contain.
+    pub task: String,
+    /// The profile whose tools the sub-agent can use. Defaults to "ask", which can only read the
+    /// project. The sub-agent never gets a tool that isn't available in this conversation.
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubagentToolOutput {
+    report: String,
+    /// The sub-agent's conversation, in Markdown.
+    transcript: String,
+}
+
+impl From<SubagentToolOutput> for LanguageModelToolResultContent {
+    fn from(output: SubagentToolOutput) -> Self {
+        output.report.into()
+    }
+}
+
+pub struct SubagentTool {
+    thread: WeakEntity<Thread>,
+    environment: Rc<dyn ThreadEnvironment>,
+}
+
+impl SubagentTool {
+    pub fn new(thread: WeakEntity<Thread>, environment: Rc<dyn ThreadEnvironment>) -> Self {
+        Self {
+            thread,
+            environment,
+        }
+    }
+}
+
+impl AgentTool for SubagentTool {
+    type Input = SubagentToolInput;
+    type Output = SubagentToolOutput;
+
+    fn name() -> &'static str {
+        "subagent"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Think
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Sub-agent: {}", input.label).into(),
+            Err(_) => "Sub-agent".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let profile_id = AgentProfileId(
+            input
+                .profile
+                .as_deref()
+                .unwrap_or(DEFAULT_SUBAGENT_PROFILE)
+                .into(),
+        );
+        let subagent = match self.thread.update(cx, |thread, cx| {
+            thread.new_subagent(profile_id, self.environment.clone(), cx)
+        }) {
+            Ok(Ok(subagent)) => subagent,
+            Ok(Err(error)) | Err(error) => return Task::ready(Err(error)),
+        };
+
+        let message = formatdoc! {"
+            You are a sub-agent working on a task for another agent, who will only see your \
+            final message. Don't ask questions, since nobody can answer them. When you are done, \
+            reply with a concise report of your findings, including the relevant paths and \
+            symbols.
+
+            {}
+        ", input.task};
+        let events = subagent.update(cx, |subagent, cx| {
+            subagent.send(UserMessageId::new(), [message], cx)
+        });
+
+        cx.spawn(async move |cx| {
+            let mut events = events?;
+            let update_transcript = |cx: &mut gpui::AsyncApp| -> Result<String> {
+                let transcript = subagent.read_with(cx, |subagent, _| subagent.to_markdown())?;
+                event_stream.update_fields(
+                    acp::ToolCallUpdateFields::new().content(vec![transcript.clone().into()]),
+                );
+                Ok(transcript)
+            };
+
+            while let Some(event) = events.next().await {
+                match event? {
+                    ThreadEvent::ToolCallAuthorization(authorization) => {
+                        let title = authorization
+                            .tool_call
+                            .fields
+                            .title
+                            .unwrap_or_else(|| "Run tool".into());
+                        // The sub-agent's own event stream acts on the chosen option, e.g.
+                        // persisting "Always Allow", so it's passed back as is.
+                        let option_id = event_stream
+                            .authorize_subagent_tool_call(
+                                format!("{}: {title}", input.label),
+                                authorization.options,
+                            )
+                            .await
+                            .unwrap_or_else(|_| acp::PermissionOptionId::new("deny"));
+                        authorization.response.send(option_id).ok();
+                    }
+                    ThreadEvent::ToolCall(_) | ThreadEvent::UserMessage(_) => {
+                        update_transcript(cx)?;
+                    }
+                    ThreadEvent::Stop(acp::StopReason::EndTurn) => break,
+                    ThreadEvent::Stop(reason) => {
+                        update_transcript(cx)?;
+                        return Err(anyhow!("The sub-agent stopped early: {reason:?}"));
+                    }
+                    ThreadEvent::AgentText(_)
+                    | ThreadEvent::AgentThinking(_)
+                    | ThreadEvent::ToolCallUpdate(_)
+                    | ThreadEvent::Retry(_) => {}
+                }
+            }
+
+            let transcript = update_transcript(cx)?;
+            let report = subagent
+                .read_with(cx, |subagent, _| subagent.last_agent_message_text())?
+                .filter(|report| !report.trim().is_empty())
+                .ok_or_else(|| anyhow!("The sub-agent didn't write a report"))?;
+            Ok(SubagentToolOutput { report, transcript })
+        })
+    }
+
+    fn replay(
+        &self,
+        _input: Self::Input,
+        output: Self::Output,
+        event_stream: ToolCallEventStream,
+        _cx: &mut App,
+    ) -> Result<()> {
+        event_stream.update_fields(
+            acp::ToolCallUpdateFields::new().content(vec![output.transcript.into()]),
+        );
+        Ok(())
+    }
+}
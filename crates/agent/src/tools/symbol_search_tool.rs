@@ -0,0 +1,111 @@
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use gpui::{App, Entity, SharedString, Task};
+use project::{Project, lsp_store::SymbolLocation};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Searches the project for symbols, such as functions, types and constants, by name, using the running language servers.
+///
+/// - Prefer this tool to a regex search when you know the name of a definition but not where it is.
+/// - Names are matched fuzzily, so a partial name is enough.
+/// - Language servers only start once a file in their language has been opened, so this may find nothing at the beginning of a conversation. Using another code navigation tool, or reading a file, starts them.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SymbolSearchToolInput {
+    /// The name, or part of the name, of the symbols to find.
+    pub query: String,
+}
+
+const MAX_RESULTS: usize = 50;
+
+pub struct SymbolSearchTool {
+    project: Entity<Project>,
+}
+
+impl SymbolSearchTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for SymbolSearchTool {
+    type Input = SymbolSearchToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "symbol_search"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Search symbols for {}", MarkdownInlineCode(&input.query)).into(),
+            Err(_) => "Search symbols".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let symbols = self
+            .project
+            .update(cx, |project, cx| project.symbols(&input.query, cx));
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let symbols = symbols.await?;
+            if symbols.is_empty() {
+                return Ok(format!("No symbols matching `{}` were found.", input.query));
+            }
+
+            project.read_with(cx, |project, cx| {
+                let mut output = String::new();
+                for symbol in symbols.iter().take(MAX_RESULTS) {
+                    let path = match &symbol.path {
+                        SymbolLocation::InProject(project_path) => {
+                            let Some(worktree) =
+                                project.worktree_for_id(project_path.worktree_id, cx)
+                            else {
+                                continue;
+                            };
+                            worktree
+                                .read(cx)
+                                .full_path(&project_path.path)
+                                .display()
+                                .to_string()
+                        }
+                        SymbolLocation::OutsideProject { abs_path, .. } => {
+                            abs_path.display().to_string()
+                        }
+                    };
+                    writeln!(
+                        output,
+                        "{path}:{}: {}",
+                        symbol.range.start.0.row + 1,
+                        symbol.label.text
+                    )?;
+                }
+                if symbols.len() > MAX_RESULTS {
+                    writeln!(
+                        output,
+                        "\nShowing the first {MAX_RESULTS} of {} symbols. Use a more specific query to narrow the results.",
+                        symbols.len()
+                    )?;
+                }
+                anyhow::Ok(output)
+            })?
+        })
+    }
+}
@@ -103,7 +103,12 @@ impl AgentTool for TerminalTool {
             Err(err) => return Task::ready(Err(err)),
         };
 
-        let authorize = event_stream.authorize(self.initial_title(Ok(input.clone()), cx), cx);
+        let authorize = event_stream.authorize_command(
+            Self::name(),
+            &input.command,
+            self.initial_title(Ok(input.clone()), cx),
+            cx,
+        );
         cx.spawn(async move |cx| {
             authorize.await?;
 
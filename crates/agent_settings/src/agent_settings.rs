@@ -1,4 +1,5 @@
 mod agent_profile;
+mod tool_permissions;
 
 use std::sync::Arc;
 
@@ -15,10 +16,12 @@ use settings::{
 };
 
 pub use crate::agent_profile::*;
+pub use crate::tool_permissions::*;
 
 pub const SUMMARIZE_THREAD_PROMPT: &str = include_str!("prompts/summarize_thread_prompt.txt");
 pub const SUMMARIZE_THREAD_DETAILED_PROMPT: &str =
     include_str!("prompts/summarize_thread_detailed_prompt.txt");
+pub const COMPACT_THREAD_PROMPT: &str = include_str!("prompts/compact_thread_prompt.txt");
 
 #[derive(Clone, Debug, RegisterSetting)]
 pub struct AgentSettings {
@@ -39,6 +42,7 @@ pub struct AgentSettings {
     pub default_view: DefaultAgentView,
     pub profiles: IndexMap<AgentProfileId, AgentProfileSettings>,
     pub always_allow_tool_actions: bool,
+    pub tool_permissions: ToolPermissions,
     pub notify_when_agent_waiting: NotifyWhenAgentWaiting,
     pub play_sound_when_agent_done: bool,
     pub single_file_review: bool,
@@ -49,6 +53,9 @@ pub struct AgentSettings {
     pub expand_terminal_card: bool,
     pub use_modifier_to_send: bool,
     pub message_editor_min_lines: usize,
+    pub auto_compact: bool,
+    pub auto_compact_threshold: f32,
+    pub compaction_preserved_turns: usize,
 }
 
 impl AgentSettings {
@@ -183,6 +190,7 @@ impl Settings for AgentSettings {
                 .map(|(key, val)| (AgentProfileId(key), val.into()))
                 .collect(),
             always_allow_tool_actions: agent.always_allow_tool_actions.unwrap(),
+            tool_permissions: ToolPermissions::new(agent.tool_permissions.unwrap()),
             notify_when_agent_waiting: agent.notify_when_agent_waiting.unwrap(),
             play_sound_when_agent_done: agent.play_sound_when_agent_done.unwrap(),
             single_file_review: agent.single_file_review.unwrap(),
@@ -193,6 +201,9 @@ impl Settings for AgentSettings {
             expand_terminal_card: agent.expand_terminal_card.unwrap(),
             use_modifier_to_send: agent.use_modifier_to_send.unwrap(),
             message_editor_min_lines: agent.message_editor_min_lines.unwrap(),
+            auto_compact: agent.auto_compact.unwrap(),
+            auto_compact_threshold: agent.auto_compact_threshold.unwrap(),
+            compaction_preserved_turns: agent.compaction_preserved_turns.unwrap(),
         }
     }
 }
@@ -0,0 +1,303 @@
+use std::{path::Path, sync::Arc};
+
+use collections::IndexMap;
+use globset::GlobBuilder;
+pub use settings::ToolPermissionAction;
+use settings::ToolPermissionRuleContent;
+
+/// Rules deciding whether a tool call is allowed, needs confirmation, or is denied.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ToolPermissions {
+    rules: IndexMap<Arc<str>, Vec<ToolPermissionRule>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ToolPermissionRule {
+    pub pattern: String,
+    pub action: ToolPermissionAction,
+}
+
+impl From<ToolPermissionRuleContent> for ToolPermissionRule {
+    fn from(content: ToolPermissionRuleContent) -> Self {
+        Self {
+            pattern: content.pattern,
+            action: content.action,
+        }
+    }
+}
+
+impl From<ToolPermissionRule> for ToolPermissionRuleContent {
+    fn from(rule: ToolPermissionRule) -> Self {
+        Self {
+            pattern: rule.pattern,
+            action: rule.action,
+        }
+    }
+}
+
+impl ToolPermissions {
+    pub fn new(rules: IndexMap<Arc<str>, Vec<ToolPermissionRuleContent>>) -> Self {
+        Self {
+            rules: rules
+                .into_iter()
+                .map(|(tool, rules)| (tool, rules.into_iter().map(Into::into).collect()))
+                .collect(),
+        }
+    }
+
+    pub fn rules_for_tool(&self, tool: &str) -> &[ToolPermissionRule] {
+        self.rules.get(tool).map_or(&[], Vec::as_slice)
+    }
+
+    /// Decides what to do with a tool call acting on `paths`, such as the source and destination
+    /// of a move. Returns `None` when no rule matches any of the paths.
+    pub fn decide_for_paths(&self, tool: &str, paths: &[&str]) -> Option<ToolPermissionAction> {
+        let rules = self.rules_for_tool(tool);
+        let mut matched = paths.iter().flat_map(|path| {
+            rules
+                .iter()
+                .filter(move |rule| path_matches(&rule.pattern, path))
+                .map(|rule| rule.action)
+        });
+        strictest(&mut matched)
+    }
+
+    /// Decides what to do with running `command` in a shell. Chained commands, such as
+    /// `cargo build && rm -rf target`, are only allowed if each of them is, and are denied if any
+    /// of them is. Commands that the rules would allow still need confirmation if they contain
+    /// command substitution, redirection or subshells, which patterns can't see into. Returns
+    /// `None` when the command is not decided by the rules.
+    pub fn decide_for_command(&self, tool: &str, command: &str) -> Option<ToolPermissionAction> {
+        let rules = self.rules_for_tool(tool);
+        let action_for = |command: &str| {
+            let mut matched = rules
+                .iter()
+                .filter(|rule| command_matches(&rule.pattern, command))
+                .map(|rule| rule.action);
+            strictest(&mut matched)
+        };
+
+        let actions = split_commands(command)
+            .into_iter()
+            .map(action_for)
+            .collect::<Vec<_>>();
+        if actions.contains(&Some(ToolPermissionAction::Deny)) {
+            Some(ToolPermissionAction::Deny)
+        } else if actions.contains(&Some(ToolPermissionAction::Ask)) {
+            Some(ToolPermissionAction::Ask)
+        } else if !actions.is_empty() && actions.iter().all(Option::is_some) {
+            if has_nested_commands(command) {
+                Some(ToolPermissionAction::Ask)
+            } else {
+                Some(ToolPermissionAction::Allow)
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// A pattern matching exactly `command`, for rules created by always allowing a command.
+pub fn command_pattern(command: &str) -> String {
+    let mut pattern = String::with_capacity(command.len());
+    for c in command.trim().chars() {
+        if matches!(c, '*' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern
+}
+
+fn strictest(
+    actions: &mut dyn Iterator<Item = ToolPermissionAction>,
+) -> Option<ToolPermissionAction> {
+    actions.fold(None, |strictest, action| match (strictest, action) {
+        (Some(ToolPermissionAction::Deny), _) | (_, ToolPermissionAction::Deny) => {
+            Some(ToolPermissionAction::Deny)
+        }
+        (Some(ToolPermissionAction::Ask), _) | (_, ToolPermissionAction::Ask) => {
+            Some(ToolPermissionAction::Ask)
+        }
+        _ => Some(ToolPermissionAction::Allow),
+    })
+}
+
+/// Matches a path glob against the path given to a tool, which starts with the name of a
+/// project's root folder. Globs also match the path within that folder, and globs without a
+/// separator match file names.
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let Ok(glob) = GlobBuilder::new(pattern).literal_separator(true).build() else {
+        log::error!("invalid tool permission glob: {pattern}");
+        return false;
+    };
+    let glob = glob.compile_matcher();
+    let path = Path::new(path);
+    if glob.is_match(path) {
+        return true;
+    }
+    let mut components = path.components();
+    components.next();
+    if glob.is_match(components.as_path()) {
+        return true;
+    }
+    !pattern.contains('/')
+        && path
+            .file_name()
+            .is_some_and(|file_name| glob.is_match(file_name))
+}
+
+/// Matches a command pattern, where `*` matches any text and `\` escapes the next character.
+fn command_matches(pattern: &str, command: &str) -> bool {
+    let command = command.trim().as_bytes();
+    let pattern = pattern.trim().as_bytes();
+
+    // Positions to resume from when the text after the last `*` fails to match.
+    let (mut p, mut c) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while c < command.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p + 1, c));
+                p += 1;
+                continue;
+            }
+            Some(b'\\') if pattern.get(p + 1) == Some(&command[c]) => {
+                p += 2;
+                c += 1;
+                continue;
+            }
+            Some(&byte) if byte != b'\\' && byte == command[c] => {
+                p += 1;
+                c += 1;
+                continue;
+            }
+            _ => {}
+        }
+        let Some((star_p, star_c)) = backtrack else {
+            return false;
+        };
+        p = star_p;
+        c = star_c + 1;
+        backtrack = Some((star_p, c));
+    }
+    pattern[p..].iter().all(|&byte| byte == b'*')
+}
+
+/// Whether a command line does more than run its chained commands, through command substitution
+/// (`$(...)`, backticks), redirection (`<`, `>`) or subshells. Otherwise an allow rule such as
+/// `cargo test*` would match `cargo test $(rm -rf ~)`.
+fn has_nested_commands(command: &str) -> bool {
+    command.contains(['`', '<', '>', '(', ')'])
+}
+
+/// Splits a shell command line on `&&`, `||`, `;`, `|` and newlines. Quoting is not taken into
+/// account, which can only make the pieces more restrictive to match.
+fn split_commands(command: &str) -> Vec<&str> {
+    let mut commands = Vec::new();
+    let mut rest = command;
+    while let Some(index) = rest.find(['&', '|', ';', '\n']) {
+        // A lone `&` backgrounds the command before it, and also separates two commands.
+        let separator = &rest[index..];
+        let separator_len = if separator.starts_with("&&") || separator.starts_with("||") {
+            2
+        } else {
+            1
+        };
+        commands.push(&rest[..index]);
+        rest = &rest[index + separator_len..];
+    }
+    commands.push(rest);
+    commands
+        .into_iter()
+        .map(str::trim)
+        .filter(|command| !command.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn permissions(tool: &str, rules: &[(&str, ToolPermissionAction)]) -> ToolPermissions {
+        let mut map = IndexMap::default();
+        map.insert(
+            tool.into(),
+            rules
+                .iter()
+                .map(|(pattern, action)| ToolPermissionRuleContent {
+                    pattern: pattern.to_string(),
+                    action: *action,
+                })
+                .collect(),
+        );
+        ToolPermissions::new(map)
+    }
+
+    #[test]
+    fn test_command_rules() {
+        use ToolPermissionAction::*;
+        let permissions = permissions(
+            "terminal",
+            &[
+                ("cargo test*", Allow),
+                ("ls", Allow),
+                ("git push*", Ask),
+                ("rm -rf*", Deny),
+            ],
+        );
+        let decide = |command| permissions.decide_for_command("terminal", command);
+
+        assert_eq!(decide("cargo test -p agent"), Some(Allow));
+        assert_eq!(decide("  ls "), Some(Allow));
+        assert_eq!(decide("ls -la"), None);
+        assert_eq!(decide("git push origin main"), Some(Ask));
+        assert_eq!(decide("rm -rf /"), Some(Deny));
+        assert_eq!(decide("cargo test && ls"), Some(Allow));
+        assert_eq!(decide("cargo test && rm -rf target"), Some(Deny));
+        assert_eq!(decide("cargo test; git push"), Some(Ask));
+        assert_eq!(decide("cargo test | head"), None);
+        assert_eq!(decide("cargo test $(rm -rf ~)"), Some(Ask));
+        assert_eq!(decide("cargo test `rm -rf ~`"), Some(Ask));
+        assert_eq!(decide("cargo test > ~/.bashrc"), Some(Ask));
+        assert_eq!(decide("cargo test < /dev/zero"), Some(Ask));
+        assert_eq!(decide("cargo test <(rm -rf ~)"), Some(Ask));
+        assert_eq!(decide("cargo test && (rm -rf ~)"), None);
+        assert_eq!(decide("cargo test; (rm -rf ~)"), None);
+        assert_eq!(decide("cargo test (rm -rf ~)"), Some(Ask));
+        assert_eq!(decide("ls && cargo test $(ls)"), Some(Ask));
+        assert_eq!(
+            permissions.decide_for_command("edit_file", "rm -rf /"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_command_pattern() {
+        let pattern = command_pattern("ls *.rs");
+        assert!(command_matches(&pattern, "ls *.rs"));
+        assert!(!command_matches(&pattern, "ls main.rs"));
+        assert!(command_matches("a*c*e", "abcde"));
+        assert!(!command_matches("a*c*e", "abcdef"));
+    }
+
+    #[test]
+    fn test_path_rules() {
+        use ToolPermissionAction::*;
+        let permissions = permissions(
+            "move_path",
+            &[("*.lock", Deny), ("src/**", Allow), ("docs/*.md", Ask)],
+        );
+        let decide = |paths: &[&str]| permissions.decide_for_paths("move_path", paths);
+
+        assert_eq!(decide(&["project/Cargo.lock"]), Some(Deny));
+        assert_eq!(decide(&["project/src/main.rs"]), Some(Allow));
+        assert_eq!(
+            decide(&["project/src/main.rs", "project/a.lock"]),
+            Some(Deny)
+        );
+        assert_eq!(decide(&["project/docs/index.md"]), Some(Ask));
+        assert_eq!(decide(&["project/docs/nested/index.md"]), None);
+        assert_eq!(decide(&["project/README.md"]), None);
+    }
+}
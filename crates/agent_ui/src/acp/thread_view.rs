@@ -26,9 +26,9 @@ use futures::FutureExt as _;
 use gpui::{
     Action, Animation, AnimationExt, AnyView, App, BorderStyle, ClickEvent, CursorStyle,
     EdgesRefinement, ElementId, Empty, Entity, FocusHandle, Focusable, Hsla, Length, ListOffset,
-    ListState, PlatformDisplay, SharedString, StyleRefinement, Subscription, Task, TextStyle,
-    TextStyleRefinement, UnderlineStyle, WeakEntity, Window, WindowHandle, div, ease_in_out,
-    linear_color_stop, linear_gradient, list, point, pulsating_between,
+    ListState, PlatformDisplay, PromptLevel, SharedString, StyleRefinement, Subscription, Task,
+    TextStyle, TextStyleRefinement, UnderlineStyle, WeakEntity, Window, WindowHandle, div,
+    ease_in_out, linear_color_stop, linear_gradient, list, point, pulsating_between,
 };
 use language::Buffer;
 
@@ -2159,6 +2159,92 @@ impl AcpThreadView {
             .detach_and_log_err(cx);
     }
 
+    /// Continues the conversation from before the given message in a new thread. If a checkpoint
+    /// was taken when the message was sent, the user is asked whether the project's files should
+    /// also be restored to it; otherwise they are left untouched.
+    fn fork_thread(
+        &mut self,
+        message_id: &UserMessageId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(thread) = self.thread().cloned() else {
+            return;
+        };
+        let Some(connection) = self.as_native_connection(cx) else {
+            return;
+        };
+        let Some((message, has_checkpoint)) = thread
+            .read(cx)
+            .entries()
+            .iter()
+            .filter_map(AgentThreadEntry::user_message)
+            .find(|message| message.id.as_ref() == Some(message_id))
+            .map(|message| (message.chunks.clone(), message.checkpoint.is_some()))
+        else {
+            return;
+        };
+
+        let restore_prompt = has_checkpoint.then(|| {
+            window.prompt(
+                PromptLevel::Info,
+                "Restore the project's files to this message's checkpoint?",
+                Some(
+                    "Restoring reverts every change made to the project since this message was sent, including your own.",
+                ),
+                &["Fork Only", "Fork and Restore Files", "Cancel"],
+                cx,
+            )
+        });
+        let session_id = thread.read(cx).session_id().clone();
+        let message_id = message_id.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let restore_checkpoint = match restore_prompt {
+                Some(prompt) => match prompt.await? {
+                    0 => false,
+                    1 => true,
+                    _ => return Ok(()),
+                },
+                None => false,
+            };
+
+            thread.update(cx, |thread, cx| thread.cancel(cx))?.await;
+            let fork = connection
+                .0
+                .update(cx, |agent, cx| {
+                    agent.fork_thread(&session_id, &message_id, cx)
+                })?
+                .await?;
+            if restore_checkpoint {
+                thread
+                    .update(cx, |thread, cx| {
+                        thread.restore_project_to_checkpoint(&message_id, cx)
+                    })?
+                    .await?;
+            }
+            workspace.update_in(cx, |workspace, window, cx| {
+                if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                    panel.update(cx, |panel, cx| {
+                        panel.open_forked_agent_thread(fork, message, window, cx)
+                    });
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn set_draft_message(
+        &mut self,
+        message: Vec<acp::ContentBlock>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.message_editor.update(cx, |editor, cx| {
+            editor.set_message(message, window, cx);
+        });
+    }
+
     fn render_entry(
         &self,
         entry_ix: usize,
@@ -2286,9 +2372,23 @@ impl AcpThreadView {
                                     .bg(cx.theme().colors().editor_background)
                                     .overflow_hidden();
 
-                                if message.id.is_some() {
+                                if let Some(message_id) = message.id.clone() {
                                     this.child(
                                         base_container
+                                            .when(self.as_native_connection(cx).is_some(), |this| {
+                                                this.child(
+                                                    IconButton::new("fork", IconName::GitBranchAlt)
+                                                        .disabled(self.is_loading_contents)
+                                                        .icon_color(Color::Muted)
+                                                        .icon_size(IconSize::XSmall)
+                                                        .tooltip(Tooltip::text(
+                                                            "Continue from before this message in a new thread"
+                                                        ))
+                                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                                            this.fork_thread(&message_id, window, cx);
+                                                        }))
+                                                )
+                                            })
                                             .child(
                                                 IconButton::new("cancel", IconName::Close)
                                                     .disabled(self.is_loading_contents)
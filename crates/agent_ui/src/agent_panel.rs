@@ -1466,6 +1466,34 @@ impl AgentPanel {
         );
     }
 
+    /// Opens a thread created by forking another one, with the message the fork was made at
+    /// ready to be edited and sent again.
+    pub(crate) fn open_forked_agent_thread(
+        &mut self,
+        thread: DbThreadMetadata,
+        message: Vec<agent_client_protocol::ContentBlock>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let server = ExternalAgent::NativeAgent.server(self.fs.clone(), self.history_store.clone());
+        self._external_thread(
+            server,
+            Some(thread),
+            None,
+            self.workspace.clone(),
+            self.project.clone(),
+            self.loading,
+            ExternalAgent::NativeAgent,
+            window,
+            cx,
+        );
+        if let Some(thread_view) = self.active_thread_view() {
+            thread_view.update(cx, |thread_view, cx| {
+                thread_view.set_draft_message(message, window, cx)
+            });
+        }
+    }
+
     fn _external_thread(
         &mut self,
         server: Rc<dyn AgentServer>,
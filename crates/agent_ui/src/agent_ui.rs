@@ -327,6 +327,7 @@ fn update_command_palette_filter(cx: &mut App) {
                 }
                 EditPredictionProvider::Zed
                 | EditPredictionProvider::Codestral
+                | EditPredictionProvider::Local
                 | EditPredictionProvider::Experimental(_) => {
                     filter.show_namespace("edit_prediction");
                     filter.hide_namespace("copilot");
@@ -472,6 +473,7 @@ mod tests {
             default_view: DefaultAgentView::Thread,
             profiles: Default::default(),
             always_allow_tool_actions: false,
+            tool_permissions: Default::default(),
             notify_when_agent_waiting: NotifyWhenAgentWaiting::default(),
             play_sound_when_agent_done: false,
             single_file_review: false,
@@ -482,6 +484,9 @@ mod tests {
             expand_terminal_card: true,
             use_modifier_to_send: true,
             message_editor_min_lines: 1,
+            auto_compact: true,
+            auto_compact_threshold: 0.8,
+            compaction_preserved_turns: 2,
         };
 
         cx.update(|cx| {
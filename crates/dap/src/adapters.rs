@@ -345,6 +345,15 @@ pub async fn download_adapter_from_github(
     Ok(version_path)
 }
 
+/// Returns the core dump opened by a post-mortem debug configuration, if any.
+///
+/// Post-mortem configurations name the crashed executable in `program` and the core dump in
+/// `coreFile`; each adapter translates them into whatever its debugger needs to load the core
+/// without running the program.
+pub fn core_file_from_config(config: &serde_json::Value) -> Option<&str> {
+    config.get("coreFile").and_then(|value| value.as_str())
+}
+
 #[async_trait(?Send)]
 pub trait DebugAdapter: 'static + Send + Sync {
     fn name(&self) -> DebugAdapterName;
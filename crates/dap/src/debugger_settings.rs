@@ -31,6 +31,10 @@ pub struct DebuggerSettings {
     ///
     /// Default: Bottom
     pub dock: settings::DockPosition,
+    /// Directories searched for core dumps when debugging a crashed program.
+    ///
+    /// Default: []
+    pub core_dump_directories: Vec<String>,
 }
 
 impl Settings for DebuggerSettings {
@@ -46,6 +50,7 @@ impl Settings for DebuggerSettings {
             log_dap_communications: content.log_dap_communications.unwrap(),
             format_dap_log_messages: content.format_dap_log_messages.unwrap(),
             dock: content.dock.unwrap(),
+            core_dump_directories: content.core_dump_directories.unwrap(),
         }
     }
 }
@@ -26,10 +26,26 @@ impl CodeLldbDebugAdapter {
         mut configuration: Value,
         label: &str,
     ) -> Result<dap::StartDebuggingRequestArguments> {
+        let core_target = adapters::core_file_from_config(&configuration).map(|core_file| {
+            match configuration.get("program").and_then(Value::as_str) {
+                Some(program) => format!("target create --core \"{core_file}\" \"{program}\""),
+                None => format!("target create --core \"{core_file}\""),
+            }
+        });
+
         let obj = configuration
             .as_object_mut()
             .context("CodeLLDB is not a valid json object")?;
 
+        // Loading a core is a custom launch: the target is created from the core and no
+        // process is started.
+        if let Some(core_target) = core_target {
+            obj.entry("targetCreateCommands")
+                .or_insert_with(|| json!([core_target]));
+            obj.entry("processCreateCommands")
+                .or_insert_with(|| json!([]));
+        }
+
         // CodeLLDB uses `name` for a terminal label.
         obj.entry("name")
             .or_insert(Value::String(String::from(label)));
@@ -274,6 +290,10 @@ impl DebugAdapter for CodeLldbDebugAdapter {
                     "type": ["integer", "string"],
                     "description": "Process id to attach to"
                 },
+                "coreFile": {
+                    "type": "string",
+                    "description": "A core dump of the program to inspect. The program is not run; the core is loaded for post-mortem debugging instead"
+                },
                 "waitFor": {
                     "type": "boolean",
                     "description": "Wait for the process to launch (MacOS only)",
@@ -131,6 +131,10 @@ impl DebugAdapter for GdbDebugAdapter {
                                     "type": "boolean",
                                     "description": "When true, GDB will set a temporary breakpoint at the program's first instruction, like the 'starti' command.",
                                     "default": false
+                                },
+                                "coreFile": {
+                                    "type": "string",
+                                    "description": "A core dump of the program to inspect. The program is not run; GDB opens the core for post-mortem debugging instead."
                                 }
                             },
                             "required": ["program"]
@@ -212,7 +216,7 @@ impl DebugAdapter for GdbDebugAdapter {
         };
 
         // Arguments: use gdb_args from config if present, else user_args, else default
-        let gdb_args = {
+        let mut gdb_args = {
             let args = config
                 .config
                 .get("gdb_args")
@@ -232,6 +236,23 @@ impl DebugAdapter for GdbDebugAdapter {
             configuration
                 .entry("cwd")
                 .or_insert_with(|| delegate.worktree_root_path().to_string_lossy().into());
+
+            // GDB's DAP interface can't open a core file, so the program and the core are
+            // loaded on the command line. Its launch request still runs the program once the
+            // configuration is done, so the native target that would run it is turned off:
+            // running fails, and the core stays open for inspection.
+            if let Some(core_file) = adapters::core_file_from_config(&config.config) {
+                gdb_args.extend(["-ex".into(), "set auto-connect-native-target off".into()]);
+                configuration.remove("stopOnEntry");
+                configuration.remove("stopAtBeginningOfMainSubprogram");
+                if let Some(program) = configuration
+                    .remove("program")
+                    .and_then(|program| program.as_str().map(ToOwned::to_owned))
+                {
+                    gdb_args.push(program);
+                }
+                gdb_args.push(format!("--core={core_file}"));
+            }
         }
 
         let mut base_env = delegate.shell_env().await;
@@ -263,3 +284,57 @@ impl DebugAdapter for GdbDebugAdapter {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dap::adapters::StartDebuggingRequestArgumentsRequest;
+    use gpui::TestAppContext;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_core_file_is_opened_without_running_the_program(cx: &mut TestAppContext) {
+        let config = DebugTaskDefinition {
+            label: "Core dump of app".into(),
+            adapter: GdbDebugAdapter::ADAPTER_NAME.into(),
+            config: json!({
+                "request": "launch",
+                "program": "/bin/app",
+                "coreFile": "/tmp/core.4242",
+                "stopOnEntry": true,
+                "gdb_path": "/usr/bin/gdb",
+            }),
+            tcp_connection: None,
+        };
+
+        let binary = GdbDebugAdapter
+            .get_binary(
+                &test_mocks::MockDelegate::new(),
+                &config,
+                None,
+                None,
+                None,
+                &mut cx.to_async(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            binary.arguments,
+            [
+                "-i=dap",
+                "-ex",
+                "set auto-connect-native-target off",
+                "/bin/app",
+                "--core=/tmp/core.4242",
+            ]
+        );
+        assert!(matches!(
+            binary.request_args.request,
+            StartDebuggingRequestArgumentsRequest::Launch
+        ));
+        let configuration = binary.request_args.configuration.as_object().unwrap();
+        assert!(!configuration.contains_key("program"));
+        assert!(!configuration.contains_key("stopOnEntry"));
+    }
+}
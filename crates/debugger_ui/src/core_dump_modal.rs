@@ -0,0 +1,532 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context as _, Result};
+use dap::{adapters::DebugAdapterName, debugger_settings::DebuggerSettings};
+use futures::StreamExt as _;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    AppContext, DismissEvent, Entity, EventEmitter, Focusable, PathPromptOptions, Render,
+    Subscription, Task, WeakEntity,
+};
+use picker::{Picker, PickerDelegate};
+use project::{Fs, Project, RemoveOptions};
+use serde::Deserialize;
+use serde_json::json;
+use settings::Settings as _;
+use task::{DebugScenario, TaskContext};
+use ui::{Context, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, Workspace, notifications::DetachAndPromptErr as _};
+
+use crate::debugger_panel::DebugPanel;
+
+/// Where a core dump can be read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CoreDumpSource {
+    /// A core kept by systemd-coredump, extracted on demand with `coredumpctl dump`.
+    Coredumpctl { pid: u32 },
+    /// A core file found in one of the `core_dump_directories`.
+    File(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CoreDump {
+    pub(crate) source: CoreDumpSource,
+    /// The executable that crashed, when the source records it.
+    pub(crate) executable: Option<PathBuf>,
+    pub(crate) timestamp: Option<SystemTime>,
+}
+
+impl CoreDump {
+    fn label(&self) -> String {
+        let path = match &self.source {
+            CoreDumpSource::File(path) => self.executable.as_deref().unwrap_or(path),
+            CoreDumpSource::Coredumpctl { .. } => match self.executable.as_deref() {
+                Some(executable) => executable,
+                None => return "unknown executable".to_string(),
+            },
+        };
+        path.file_name()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn detail(&self) -> String {
+        match &self.source {
+            CoreDumpSource::Coredumpctl { pid } => format!("coredumpctl, process {pid}"),
+            CoreDumpSource::File(path) => path.to_string_lossy().into_owned(),
+        }
+    }
+}
+
+pub(crate) struct CoreDumpModalDelegate {
+    selected_index: usize,
+    matches: Vec<StringMatch>,
+    workspace: WeakEntity<Workspace>,
+    pub(crate) core_dumps: Arc<[CoreDump]>,
+}
+
+pub struct CoreDumpModal {
+    _subscription: Subscription,
+    pub(crate) picker: Entity<Picker<CoreDumpModalDelegate>>,
+}
+
+impl CoreDumpModal {
+    pub(crate) fn show(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let weak_workspace = cx.weak_entity();
+        let project = workspace.project().clone();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            Self::new(weak_workspace, project, window, cx)
+        });
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let core_dumps_task = find_core_dumps(&project, cx);
+
+        let picker = cx.new(|cx| {
+            Picker::uniform_list(
+                CoreDumpModalDelegate {
+                    selected_index: 0,
+                    matches: Vec::new(),
+                    workspace,
+                    core_dumps: Arc::new([]),
+                },
+                window,
+                cx,
+            )
+            .modal(true)
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            let core_dumps = core_dumps_task.await;
+            this.update_in(cx, |modal, window, cx| {
+                modal.picker.update(cx, |picker, cx| {
+                    picker.delegate.core_dumps = core_dumps;
+                    picker.refresh(window, cx);
+                });
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+
+        Self {
+            _subscription: cx.subscribe(&picker, |_, _, _, cx| {
+                cx.emit(DismissEvent);
+            }),
+            picker,
+        }
+    }
+}
+
+impl Render for CoreDumpModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl ui::IntoElement {
+        v_flex()
+            .key_context("CoreDumpModal")
+            .track_focus(&self.focus_handle(cx))
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl EventEmitter<DismissEvent> for CoreDumpModal {}
+
+impl Focusable for CoreDumpModal {
+    fn focus_handle(&self, cx: &App) -> gpui::FocusHandle {
+        self.picker.read(cx).focus_handle(cx)
+    }
+}
+
+impl ModalView for CoreDumpModal {}
+
+impl PickerDelegate for CoreDumpModalDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select the core dump you want to debug".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No core dumps found".into())
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let core_dumps = self.core_dumps.clone();
+        cx.spawn(async move |this, cx| {
+            let candidates = core_dumps
+                .iter()
+                .enumerate()
+                .map(|(id, core_dump)| {
+                    StringMatchCandidate::new(
+                        id,
+                        &format!("{} {}", core_dump.label(), core_dump.detail()),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    100,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+
+            this.update(cx, |this, _| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+            })
+            .ok();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(core_dump) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.core_dumps.get(hit.candidate_id))
+            .cloned()
+        else {
+            return;
+        };
+
+        let adapter = core_dump_adapter(secondary);
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let fs = workspace.read_with(cx, |workspace, cx| {
+                workspace.project().read(cx).fs().clone()
+            })?;
+            let (core_file, extracted) = match &core_dump.source {
+                CoreDumpSource::File(path) => (path.clone(), false),
+                CoreDumpSource::Coredumpctl { pid } => {
+                    (extract_coredumpctl_core(*pid).await?, true)
+                }
+            };
+
+            let session = async {
+                let program = match core_dump.executable {
+                    Some(executable) => executable,
+                    None => {
+                        let paths = cx
+                            .update(|_, cx| {
+                                cx.prompt_for_paths(PathPromptOptions {
+                                    files: true,
+                                    directories: false,
+                                    multiple: false,
+                                    prompt: Some("Select Crashed Executable".into()),
+                                })
+                            })?
+                            .await??;
+                        let Some(program) = paths.and_then(|paths| paths.into_iter().next()) else {
+                            return Ok(None);
+                        };
+                        program
+                    }
+                };
+
+                let scenario = core_dump_scenario(adapter, &program, &core_file);
+                anyhow::Ok(workspace.update_in(cx, |workspace, window, cx| {
+                    let panel = workspace.panel::<DebugPanel>(cx)?;
+                    panel.update(cx, |panel, cx| {
+                        panel.start_session(
+                            scenario,
+                            TaskContext::default(),
+                            None,
+                            None,
+                            window,
+                            cx,
+                        )
+                    })
+                })?)
+            }
+            .await;
+
+            // A core extracted from coredumpctl is only kept for as long as its session exists.
+            if extracted {
+                match &session {
+                    Ok(Some(session)) => cx.update(|_, cx| {
+                        cx.observe_release(session, move |_, cx| {
+                            cx.background_spawn(async move {
+                                remove_extracted_core(fs.as_ref(), &core_file).await
+                            })
+                            .detach();
+                        })
+                        .detach();
+                    })?,
+                    _ => remove_extracted_core(fs.as_ref(), &core_file).await,
+                }
+            }
+            if session?.is_none() {
+                return Ok(());
+            }
+
+            this.update(cx, |_, cx| cx.emit(DismissEvent))?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to open core dump", window, cx, |_, _, _| None);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let core_dump = self.core_dumps.get(hit.candidate_id)?;
+        let detail = core_dump.detail();
+
+        Some(
+            ListItem::new(format!("core-dump-entry-{ix}"))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    v_flex()
+                        .items_start()
+                        .child(Label::new(core_dump.label()))
+                        .child(
+                            div()
+                                .id(format!("core-dump-entry-{ix}-detail"))
+                                .tooltip(Tooltip::text(detail.clone()))
+                                .child(
+                                    Label::new(detail)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                ),
+                        ),
+                ),
+        )
+    }
+}
+
+/// The adapter used to open a core dump: GDB on Linux and CodeLLDB elsewhere, swapped when
+/// confirming with the secondary action.
+fn core_dump_adapter(secondary: bool) -> DebugAdapterName {
+    let prefer_gdb = cfg!(target_os = "linux") != secondary;
+    DebugAdapterName(if prefer_gdb { "GDB" } else { "CodeLLDB" }.into())
+}
+
+pub(crate) fn core_dump_scenario(
+    adapter: DebugAdapterName,
+    program: &Path,
+    core_file: &Path,
+) -> DebugScenario {
+    let program_name = program
+        .file_name()
+        .unwrap_or(program.as_os_str())
+        .to_string_lossy();
+    DebugScenario {
+        adapter: adapter.0,
+        label: format!("Core dump of {program_name}").into(),
+        build: None,
+        config: json!({
+            "request": "launch",
+            "program": program,
+            "coreFile": core_file,
+        }),
+        tcp_connection: None,
+    }
+}
+
+fn find_core_dumps(project: &Entity<Project>, cx: &mut App) -> Task<Arc<[CoreDump]>> {
+    // Cores live on the machine that ran the program, which we can only search locally.
+    if !project.read(cx).is_local() {
+        return Task::ready(Arc::new([]));
+    }
+
+    let directories = DebuggerSettings::get_global(cx)
+        .core_dump_directories
+        .iter()
+        .map(|directory| match directory.strip_prefix("~/") {
+            Some(relative) => util::paths::home_dir().join(relative),
+            None => PathBuf::from(directory),
+        })
+        .collect();
+    let from_directories = core_dumps_in_directories(project, directories, cx);
+
+    cx.background_spawn(async move {
+        let mut core_dumps = from_directories.await;
+        if cfg!(target_os = "linux") {
+            core_dumps.extend(coredumpctl_core_dumps().await.log_err().unwrap_or_default());
+        }
+        core_dumps.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        core_dumps.into()
+    })
+}
+
+pub(crate) fn core_dumps_in_directories(
+    project: &Entity<Project>,
+    directories: Vec<PathBuf>,
+    cx: &App,
+) -> Task<Vec<CoreDump>> {
+    let fs = project.read(cx).fs().clone();
+    cx.background_spawn(async move {
+        let mut core_dumps = Vec::new();
+        for directory in directories {
+            let Some(mut entries) = fs.read_dir(&directory).await.log_err() else {
+                continue;
+            };
+            while let Some(path) = entries.next().await {
+                let Some(path) = path.log_err() else {
+                    continue;
+                };
+                if !path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(is_core_file_name)
+                {
+                    continue;
+                }
+                let Ok(Some(metadata)) = fs.metadata(&path).await else {
+                    continue;
+                };
+                if metadata.is_dir {
+                    continue;
+                }
+                core_dumps.push(CoreDump {
+                    source: CoreDumpSource::File(path),
+                    executable: None,
+                    timestamp: Some(metadata.mtime.timestamp_for_user()),
+                });
+            }
+        }
+        core_dumps
+    })
+}
+
+/// Matches the names of the core files written with the kernel's default `core_pattern`: `core`,
+/// or `core.<pid>` with `core_uses_pid`. Other files such as `core.rs` aren't cores.
+fn is_core_file_name(name: &str) -> bool {
+    match name.strip_prefix("core") {
+        Some("") => true,
+        Some(rest) => rest
+            .strip_prefix('.')
+            .is_some_and(|pid| !pid.is_empty() && pid.bytes().all(|byte| byte.is_ascii_digit())),
+        None => false,
+    }
+}
+
+async fn coredumpctl_core_dumps() -> Result<Vec<CoreDump>> {
+    let output = util::command::new_smol_command("coredumpctl")
+        .args(["list", "--json=short", "--no-pager"])
+        .output()
+        .await
+        .context("running coredumpctl")?;
+    // coredumpctl exits with an error when there are no cores to list.
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+    parse_coredumpctl_list(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[derive(Deserialize)]
+struct CoredumpctlEntry {
+    /// Microseconds since the Unix epoch.
+    time: u64,
+    pid: u32,
+    corefile: String,
+    exe: Option<PathBuf>,
+}
+
+pub(crate) fn parse_coredumpctl_list(json: &str) -> Result<Vec<CoreDump>> {
+    let entries: Vec<CoredumpctlEntry> =
+        serde_json::from_str(json).context("parsing coredumpctl output")?;
+    Ok(entries
+        .into_iter()
+        .filter(|entry| entry.corefile == "present")
+        .map(|entry| CoreDump {
+            source: CoreDumpSource::Coredumpctl { pid: entry.pid },
+            executable: entry.exe,
+            timestamp: Some(UNIX_EPOCH + Duration::from_micros(entry.time)),
+        })
+        .collect())
+}
+
+async fn remove_extracted_core(fs: &dyn Fs, core_file: &Path) {
+    fs.remove_file(
+        core_file,
+        RemoveOptions {
+            ignore_if_not_exists: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .log_err();
+}
+
+async fn extract_coredumpctl_core(pid: u32) -> Result<PathBuf> {
+    let core_file = paths::temp_dir().join(format!("core.{pid}"));
+    let output = util::command::new_smol_command("coredumpctl")
+        .arg("dump")
+        .arg("--no-pager")
+        .arg(format!("--output={}", core_file.display()))
+        .arg(pid.to_string())
+        .output()
+        .await
+        .context("running coredumpctl")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "coredumpctl could not extract the core of process {pid}: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(core_file)
+}
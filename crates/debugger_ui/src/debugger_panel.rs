@@ -16,6 +16,7 @@ use dap::{DapRegistry, StartDebuggingRequestArguments};
 use dap::{client::SessionId, debugger_settings::DebuggerSettings};
 use editor::{Editor, MultiBufferOffset, ToPoint};
 use feature_flags::{FeatureFlag, FeatureFlagAppExt as _};
+use futures::channel::oneshot;
 use gpui::{
     Action, App, AsyncWindowContext, ClipboardItem, Context, Corner, DismissEvent, Entity,
     EntityId, EventEmitter, FocusHandle, Focusable, MouseButton, MouseDownEvent, Point,
@@ -30,13 +31,13 @@ use project::{Project, debugger::session::ThreadStatus};
 use rpc::proto::{self};
 use settings::Settings;
 use std::sync::{Arc, LazyLock};
-use task::{DebugScenario, TaskContext};
+use task::{DebugCompound, DebugScenario, TaskContext};
 use tree_sitter::{Query, StreamingIterator as _};
 use ui::{
     ContextMenu, Divider, PopoverMenu, PopoverMenuHandle, SplitButton, Tab, Tooltip, prelude::*,
 };
 use util::rel_path::RelPath;
-use util::{ResultExt, debug_panic, maybe};
+use util::{ResultExt, debug_panic, maybe, post_inc};
 use workspace::SplitDirection;
 use workspace::item::SaveOptions;
 use workspace::{
@@ -53,6 +54,22 @@ impl FeatureFlag for DebuggerHistoryFeatureFlag {
 
 const DEBUG_PANEL_KEY: &str = "DebugPanel";
 
+/// Sessions that were started together from a compound debug configuration.
+pub(crate) struct DebugCompoundSessions {
+    id: usize,
+    pub(crate) label: SharedString,
+    stop_all: bool,
+    sessions: Vec<WeakEntity<Session>>,
+}
+
+impl DebugCompoundSessions {
+    pub(crate) fn contains(&self, session: &Entity<Session>) -> bool {
+        self.sessions
+            .iter()
+            .any(|member| member.entity_id() == session.entity_id())
+    }
+}
+
 pub struct DebugPanel {
     size: Pixels,
     active_session: Option<Entity<DebugSession>>,
@@ -63,6 +80,8 @@ pub struct DebugPanel {
     debug_scenario_scheduled_last: bool,
     pub(crate) sessions_with_children:
         IndexMap<Entity<DebugSession>, Vec<WeakEntity<DebugSession>>>,
+    pub(crate) compounds: Vec<DebugCompoundSessions>,
+    next_compound_id: usize,
     pub(crate) thread_picker_menu_handle: PopoverMenuHandle<ContextMenu>,
     pub(crate) session_picker_menu_handle: PopoverMenuHandle<ContextMenu>,
     fs: Arc<dyn Fs>,
@@ -94,6 +113,8 @@ impl DebugPanel {
             Self {
                 size: px(300.),
                 sessions_with_children: Default::default(),
+                compounds: Vec::new(),
+                next_compound_id: 0,
                 active_session: None,
                 focus_handle,
                 breakpoint_list: BreakpointList::new(
@@ -181,11 +202,9 @@ impl DebugPanel {
         worktree_id: Option<WorktreeId>,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) {
+    ) -> Option<Entity<Session>> {
         let dap_store = self.project.read(cx).dap_store();
-        let Some(adapter) = DapRegistry::global(cx).adapter(&scenario.adapter) else {
-            return;
-        };
+        let adapter = DapRegistry::global(cx).adapter(&scenario.adapter)?;
         let quirks = SessionQuirks {
             compact: adapter.compact_child_session(),
             prefer_thread_name: adapter.prefer_thread_name(),
@@ -212,7 +231,7 @@ impl DebugPanel {
             .or_else(|| self.project.read(cx).visible_worktrees(cx).next())
         else {
             log::debug!("Could not find a worktree to spawn the debug session in");
-            return;
+            return None;
         };
 
         self.debug_scenario_scheduled_last = true;
@@ -301,6 +320,182 @@ impl DebugPanel {
                 debug_panic!("Session state should be in building because we are just starting it");
             }
         });
+
+        Some(session)
+    }
+
+    /// Starts the scenarios of a compound one after another, waiting for each debug adapter to
+    /// come up before starting the next one.
+    pub fn start_compound(
+        &mut self,
+        compound: DebugCompound,
+        task_context: TaskContext,
+        worktree_id: Option<WorktreeId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let scenarios = self
+            .project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .context("No task inventory to resolve the compound with")
+            .and_then(|inventory| {
+                inventory
+                    .read(cx)
+                    .resolve_debug_compound(&compound, worktree_id)
+            });
+        let scenarios = match scenarios {
+            Ok(scenarios) => scenarios,
+            Err(error) => {
+                self.workspace
+                    .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+                    .ok();
+                return;
+            }
+        };
+
+        self.compounds.retain(|group| {
+            group
+                .sessions
+                .iter()
+                .any(|session| session.upgrade().is_some())
+        });
+        let id = post_inc(&mut self.next_compound_id);
+        self.compounds.push(DebugCompoundSessions {
+            id,
+            label: compound.label,
+            stop_all: compound.stop_all,
+            sessions: Vec::new(),
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            for scenario in scenarios {
+                let label = scenario.label.clone();
+                let session = this.update_in(cx, |this, window, cx| {
+                    let session = this.start_session(
+                        scenario,
+                        task_context.clone(),
+                        None,
+                        worktree_id,
+                        window,
+                        cx,
+                    )?;
+                    this.add_to_compound(id, &session, window, cx);
+                    Some(session)
+                })?;
+                let Some(session) = session else {
+                    let error = anyhow::anyhow!(
+                        "Couldn't start {label:?}, so the rest of the compound wasn't started"
+                    );
+                    this.update(cx, |this, cx| {
+                        this.workspace
+                            .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+                    })??;
+                    return Err(error);
+                };
+
+                let (started_tx, started_rx) = oneshot::channel();
+                let mut started_tx = Some(started_tx);
+                let _subscription = cx.update(|_, cx| {
+                    cx.subscribe(&session, move |_, event: &SessionStateEvent, _| {
+                        let started = match event {
+                            SessionStateEvent::Running => true,
+                            SessionStateEvent::Shutdown => false,
+                            _ => return,
+                        };
+                        if let Some(started_tx) = started_tx.take() {
+                            started_tx.send(started).ok();
+                        }
+                    })
+                })?;
+                if !started_rx.await.unwrap_or(false) {
+                    anyhow::bail!("Stopped starting compound after a session failed to start");
+                }
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn add_to_compound(
+        &mut self,
+        id: usize,
+        session: &Entity<Session>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(group) = self.compounds.iter_mut().find(|group| group.id == id) else {
+            return;
+        };
+        group.sessions.push(session.downgrade());
+        cx.subscribe_in(
+            session,
+            window,
+            |this, session, event: &SessionStateEvent, _, cx| {
+                if *event != SessionStateEvent::Shutdown {
+                    return;
+                }
+                let Some(ix) = this
+                    .compounds
+                    .iter()
+                    .position(|group| group.stop_all && group.contains(session))
+                else {
+                    return;
+                };
+                let group = this.compounds.remove(ix);
+                this.shutdown_compound_sessions(&group, cx);
+            },
+        )
+        .detach();
+    }
+
+    pub(crate) fn compound_for_session(
+        &self,
+        session: &Entity<Session>,
+    ) -> Option<&DebugCompoundSessions> {
+        self.compounds.iter().find(|group| group.contains(session))
+    }
+
+    fn shutdown_compound_sessions(&self, group: &DebugCompoundSessions, cx: &mut Context<Self>) {
+        let running_states = self
+            .sessions_with_children
+            .keys()
+            .map(|session| session.read(cx).running_state().clone())
+            .filter(|running_state| group.contains(running_state.read(cx).session()))
+            .collect::<Vec<_>>();
+        for running_state in running_states {
+            running_state.update(cx, |running_state, cx| running_state.shutdown(cx));
+        }
+    }
+
+    /// Stops every session of the compound the given session was started with.
+    pub(crate) fn stop_compound(&mut self, session: &Entity<Session>, cx: &mut Context<Self>) {
+        let Some(ix) = self
+            .compounds
+            .iter()
+            .position(|group| group.contains(session))
+        else {
+            return;
+        };
+        let group = self.compounds.remove(ix);
+        self.shutdown_compound_sessions(&group, cx);
+    }
+
+    /// Restarts every session of the compound the given session was started with.
+    pub(crate) fn restart_compound(&mut self, session: &Entity<Session>, cx: &mut Context<Self>) {
+        let Some(group) = self.compound_for_session(session) else {
+            return;
+        };
+        let sessions = group
+            .sessions
+            .iter()
+            .filter_map(|session| session.upgrade())
+            .collect::<Vec<_>>();
+        for session in sessions {
+            session.update(cx, |session, cx| session.restart(None, cx));
+        }
     }
 
     pub(crate) fn rerun_last_session(
@@ -390,6 +585,17 @@ impl DebugPanel {
         let curr_session_id = curr_session.read(cx).session_id();
         self.sessions_with_children
             .retain(|session, _| session.read(cx).session_id(cx) != curr_session_id);
+        // Leave the compound before shutting down, so that the rest of it keeps running.
+        let compound_id = self
+            .compounds
+            .iter_mut()
+            .find(|group| group.contains(&curr_session))
+            .map(|group| {
+                group
+                    .sessions
+                    .retain(|member| member.entity_id() != curr_session.entity_id());
+                group.id
+            });
         let task = dap_store_handle.update(cx, |dap_store, cx| {
             dap_store.shutdown_session(curr_session_id, cx)
         });
@@ -405,6 +611,11 @@ impl DebugPanel {
                 });
                 (session, task)
             })?;
+            if let Some(compound_id) = compound_id {
+                this.update_in(cx, |this, window, cx| {
+                    this.add_to_compound(compound_id, &session, window, cx)
+                })?;
+            }
             Self::register_session(this.clone(), session.clone(), true, cx).await?;
 
             if let Err(error) = task.await {
@@ -671,6 +882,11 @@ impl DebugPanel {
             )
         };
 
+        let compound_session = active_session
+            .as_ref()
+            .map(|session| session.read(cx).session(cx))
+            .filter(|session| self.compound_for_session(session).is_some());
+
         let thread_status = active_session
             .as_ref()
             .map(|session| session.read(cx).running_state())
@@ -820,17 +1036,29 @@ impl DebugPanel {
                                     .child(
                                         IconButton::new("debug-restart", IconName::RotateCcw)
                                             .icon_size(IconSize::Small)
-                                            .on_click(window.listener_for(
-                                                running_state,
-                                                |this, _, window, cx| {
-                                                    this.rerun_session(window, cx);
-                                                },
-                                            ))
+                                            .map(|button| match compound_session.clone() {
+                                                Some(session) => button.on_click(cx.listener(
+                                                    move |this, _, _window, cx| {
+                                                        this.restart_compound(&session, cx);
+                                                    },
+                                                )),
+                                                None => button.on_click(window.listener_for(
+                                                    running_state,
+                                                    |this, _, window, cx| {
+                                                        this.rerun_session(window, cx);
+                                                    },
+                                                )),
+                                            })
                                             .tooltip({
                                                 let focus_handle = focus_handle.clone();
+                                                let label = if compound_session.is_some() {
+                                                    "Restart Compound"
+                                                } else {
+                                                    "Rerun Session"
+                                                };
                                                 move |_window, cx| {
                                                     Tooltip::for_action_in(
-                                                        "Rerun Session",
+                                                        label,
                                                         &RerunSession,
                                                         &focus_handle,
                                                         cx,
@@ -841,18 +1069,28 @@ impl DebugPanel {
                                     .child(
                                         IconButton::new("debug-stop", IconName::Power)
                                             .icon_size(IconSize::Small)
-                                            .on_click(window.listener_for(
-                                                running_state,
-                                                |this, _, _window, cx| {
-                                                    if this.session().read(cx).is_building() {
-                                                        this.session().update(cx, |session, cx| {
-                                                            session.shutdown(cx).detach()
-                                                        });
-                                                    } else {
-                                                        this.stop_thread(cx);
-                                                    }
-                                                },
-                                            ))
+                                            .map(|button| match compound_session.clone() {
+                                                Some(session) => button.on_click(cx.listener(
+                                                    move |this, _, _window, cx| {
+                                                        this.stop_compound(&session, cx);
+                                                    },
+                                                )),
+                                                None => button.on_click(window.listener_for(
+                                                    running_state,
+                                                    |this, _, _window, cx| {
+                                                        if this.session().read(cx).is_building() {
+                                                            this.session().update(
+                                                                cx,
+                                                                |session, cx| {
+                                                                    session.shutdown(cx).detach()
+                                                                },
+                                                            );
+                                                        } else {
+                                                            this.stop_thread(cx);
+                                                        }
+                                                    },
+                                                )),
+                                            })
                                             .disabled(active_session.as_ref().is_none_or(
                                                 |session| {
                                                     session
@@ -864,7 +1102,9 @@ impl DebugPanel {
                                             ))
                                             .tooltip({
                                                 let focus_handle = focus_handle.clone();
-                                                let label = if capabilities
+                                                let label = if compound_session.is_some() {
+                                                    "Stop Compound"
+                                                } else if capabilities
                                                     .supports_terminate_threads_request
                                                     .unwrap_or_default()
                                                 {
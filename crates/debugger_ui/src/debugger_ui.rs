@@ -1,5 +1,6 @@
 use std::any::TypeId;
 
+use core_dump_modal::CoreDumpModal;
 use debugger_panel::DebugPanel;
 use editor::{Editor, MultiBufferOffsetUtf16};
 use gpui::{Action, App, DispatchPhase, EntityInputHandler, actions};
@@ -18,8 +19,10 @@ use zed_actions::ToggleFocus;
 use zed_actions::debugger::OpenOnboardingModal;
 
 pub mod attach_modal;
+mod core_dump_modal;
 pub mod debugger_panel;
 mod dropdown_menus;
+mod goto_target_picker;
 mod new_process_modal;
 mod onboarding_modal;
 mod persistence;
@@ -34,6 +37,8 @@ actions!(
     [
         /// Starts a new debugging session.
         Start,
+        /// Opens a core dump of a crashed program for post-mortem debugging.
+        DebugCoreDump,
         /// Continues execution until the next breakpoint.
         Continue,
         /// Detaches the debugger from the running process.
@@ -52,6 +57,10 @@ actions!(
         StepOut,
         /// Steps back to the previous statement.
         StepBack,
+        /// Steps into the next machine instruction.
+        StepIntoInstruction,
+        /// Steps over the current machine instruction.
+        StepOverInstruction,
         /// Stops the debugging session.
         Stop,
         /// Toggles whether to ignore all breakpoints.
@@ -124,6 +133,9 @@ pub fn init(cx: &mut App) {
             .register_action(|workspace: &mut Workspace, _: &Start, window, cx| {
                 NewProcessModal::show(workspace, window, NewProcessMode::Debug, None, cx);
             })
+            .register_action(|workspace: &mut Workspace, _: &DebugCoreDump, window, cx| {
+                CoreDumpModal::show(workspace, window, cx);
+            })
             .register_action(|workspace: &mut Workspace, _: &Rerun, window, cx| {
                 let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) else {
                     return;
@@ -165,6 +177,9 @@ pub fn init(cx: &mut App) {
                 let supports_step_back = caps.supports_step_back.unwrap_or_default();
                 let supports_detach = running_state.session().read(cx).is_attached();
                 let status = running_state.thread_status(cx);
+                let compound_session = Some(running_state.session().clone())
+                    .filter(|session| debug_panel.read(cx).compound_for_session(session).is_some());
+                let debug_panel = debug_panel.downgrade();
 
                 let active_item = active_item.downgrade();
                 div.when(status == Some(ThreadStatus::Running), |div| {
@@ -194,6 +209,22 @@ pub fn init(cx: &mut App) {
                             active_item.update(cx, |item, cx| item.step_out(cx)).ok();
                         }
                     })
+                    .on_action({
+                        let active_item = active_item.clone();
+                        move |_: &StepIntoInstruction, _, cx| {
+                            active_item
+                                .update(cx, |item, cx| item.step_in_instruction(cx))
+                                .ok();
+                        }
+                    })
+                    .on_action({
+                        let active_item = active_item.clone();
+                        move |_: &StepOverInstruction, _, cx| {
+                            active_item
+                                .update(cx, |item, cx| item.step_over_instruction(cx))
+                                .ok();
+                        }
+                    })
                     .when(supports_step_back, |div| {
                         let active_item = active_item.clone();
                         div.on_action(move |_: &StepBack, _, cx| {
@@ -258,19 +289,40 @@ pub fn init(cx: &mut App) {
                             .ok();
                     }
                 })
-                .on_action({
-                    let active_item = active_item.clone();
-                    move |_: &RerunSession, window, cx| {
-                        active_item
-                            .update(cx, |item, cx| item.rerun_session(window, cx))
-                            .ok();
-                    }
-                })
-                .on_action({
-                    let active_item = active_item.clone();
-                    move |_: &Stop, _, cx| {
-                        active_item.update(cx, |item, cx| item.stop_thread(cx)).ok();
-                    }
+                .map(|div| match compound_session {
+                    Some(session) => div
+                        .on_action({
+                            let debug_panel = debug_panel.clone();
+                            let session = session.clone();
+                            move |_: &RerunSession, _, cx| {
+                                debug_panel
+                                    .update(cx, |panel, cx| panel.restart_compound(&session, cx))
+                                    .ok();
+                            }
+                        })
+                        .on_action({
+                            let debug_panel = debug_panel.clone();
+                            move |_: &Stop, _, cx| {
+                                debug_panel
+                                    .update(cx, |panel, cx| panel.stop_compound(&session, cx))
+                                    .ok();
+                            }
+                        }),
+                    None => div
+                        .on_action({
+                            let active_item = active_item.clone();
+                            move |_: &RerunSession, window, cx| {
+                                active_item
+                                    .update(cx, |item, cx| item.rerun_session(window, cx))
+                                    .ok();
+                            }
+                        })
+                        .on_action({
+                            let active_item = active_item.clone();
+                            move |_: &Stop, _, cx| {
+                                active_item.update(cx, |item, cx| item.stop_thread(cx)).ok();
+                            }
+                        }),
                 })
                 .on_action({
                     let active_item = active_item.clone();
@@ -384,8 +436,60 @@ pub fn init(cx: &mut App) {
                         },
                     );
 
-                    window.on_action(
-                        TypeId::of::<editor::actions::EvaluateSelectedText>(),
+                    window.on_action_when(
+                        session.any_stopped_thread()
+                            && session
+                                .capabilities()
+                                .supports_goto_targets_request
+                                .unwrap_or_default(),
+                        TypeId::of::<editor::actions::SetNextStatement>(),
+                        {
+                            let editor = editor.clone();
+                            let active_session = active_session.clone();
+                            move |_, phase, window, cx| {
+                                if phase != DispatchPhase::Bubble {
+                                    return;
+                                }
+                                maybe!({
+                                    let (buffer, position, _) = editor
+                                        .update(cx, |editor, cx| {
+                                            let cursor_point: language::Point = editor
+                                                .selections
+                                                .newest(&editor.display_snapshot(cx))
+                                                .head();
+
+                                            editor
+                                                .buffer()
+                                                .read(cx)
+                                                .point_to_buffer_point(cursor_point, cx)
+                                        })
+                                        .ok()??;
+
+                                    let path =
+                                debugger::breakpoint_store::BreakpointStore::abs_path_from_buffer(
+                                    &buffer, cx,
+                                )?;
+
+                                    active_session.update(cx, |session, cx| {
+                                        session.running_state().update(cx, |state, cx| {
+                                            state.set_next_statement(
+                                                path,
+                                                position.row,
+                                                window,
+                                                cx,
+                                            );
+                                        });
+                                    });
+
+                                    Some(())
+                                });
+                            }
+                        },
+                    );
+
+                    window.on_action(TypeId::of::<editor::actions::EvaluateSelectedText>(), {
+                        let editor = editor.clone();
+                        let active_session = active_session.clone();
                         move |_, _, window, cx| {
                             let status = maybe!({
                                 let text = editor
@@ -428,6 +532,50 @@ pub fn init(cx: &mut App) {
                             if status.is_some() {
                                 cx.stop_propagation();
                             }
+                        }
+                    });
+
+                    window.on_action_when(
+                        session.any_stopped_thread(),
+                        TypeId::of::<editor::actions::AddSelectionToWatch>(),
+                        move |_, _, window, cx| {
+                            let status = maybe!({
+                                let text = editor
+                                    .update(cx, |editor, cx| {
+                                        let range = editor
+                                            .selections
+                                            .newest::<MultiBufferOffsetUtf16>(
+                                                &editor.display_snapshot(cx),
+                                            )
+                                            .range();
+                                        editor.text_for_range(
+                                            range.start.0.0..range.end.0.0,
+                                            &mut None,
+                                            window,
+                                            cx,
+                                        )
+                                    })
+                                    .ok()??;
+                                let text = text.trim();
+                                if text.is_empty() {
+                                    return None;
+                                }
+
+                                let running_state = active_session.read(cx).running_state();
+                                let stack_id =
+                                    running_state.read(cx).selected_stack_frame_id(cx)?;
+                                let session = running_state.read(cx).session().clone();
+                                session
+                                    .update(cx, |session, cx| {
+                                        session.add_watcher(text.to_string().into(), stack_id, cx)
+                                    })
+                                    .detach_and_log_err(cx);
+
+                                Some(())
+                            });
+                            if status.is_some() {
+                                cx.stop_propagation();
+                            }
                         },
                     );
                 })
@@ -115,6 +115,34 @@ impl DebugPanel {
             session_entries.push(root_entry);
         }
 
+        // Sessions started from the same compound are listed together, under the compound's label.
+        // Child sessions directly follow their parents, so they inherit the compound of the last root.
+        let mut current_compound = None;
+        let mut session_entries = session_entries
+            .into_iter()
+            .map(|entry| {
+                let root = entry
+                    .ancestors
+                    .first()
+                    .unwrap_or(&entry.leaf)
+                    .read(cx)
+                    .session(cx);
+                if root.read(cx).parent_id(cx).is_none() {
+                    current_compound = self
+                        .compounds
+                        .iter()
+                        .position(|group| group.contains(&root));
+                }
+                (current_compound, entry)
+            })
+            .collect::<Vec<_>>();
+        session_entries.sort_by_key(|(compound, _)| *compound);
+        let compound_labels = self
+            .compounds
+            .iter()
+            .map(|group| group.label.clone())
+            .collect::<Vec<_>>();
+
         let weak = cx.weak_entity();
         let trigger_label = if let Some(active_session) = active_session.clone() {
             active_session.update(cx, |active_session, cx| {
@@ -160,7 +188,14 @@ impl DebugPanel {
             ContextMenu::build(window, cx, move |mut this, _, cx| {
                 let context_menu = cx.weak_entity();
                 let mut session_depths = HashMap::default();
-                for session_entry in session_entries {
+                let mut previous_compound = None;
+                for (compound, session_entry) in session_entries {
+                    if compound != previous_compound {
+                        if let Some(label) = compound.and_then(|ix| compound_labels.get(ix)) {
+                            this = this.header(label.clone());
+                        }
+                        previous_compound = compound;
+                    }
                     let session_id = session_entry.leaf.read(cx).session_id(cx);
                     let parent_depth = session_entry
                         .ancestors
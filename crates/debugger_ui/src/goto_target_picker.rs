@@ -0,0 +1,199 @@
+use std::sync::Arc;
+
+use dap::GotoTarget;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{DismissEvent, Entity, EventEmitter, Focusable, Render, Subscription, Task};
+use picker::{Picker, PickerDelegate};
+use project::debugger::session::{Session, ThreadId};
+use ui::{Context, HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use workspace::ModalView;
+
+/// Lets the user choose where to move the instruction pointer when several goto targets exist
+/// for the line under the cursor.
+pub(crate) struct GotoTargetPicker {
+    _subscription: Subscription,
+    picker: Entity<Picker<GotoTargetPickerDelegate>>,
+}
+
+impl GotoTargetPicker {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        thread_id: ThreadId,
+        targets: Vec<GotoTarget>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let picker = cx.new(|cx| {
+            Picker::uniform_list(
+                GotoTargetPickerDelegate {
+                    session,
+                    thread_id,
+                    targets: targets.into(),
+                    matches: Vec::new(),
+                    selected_index: 0,
+                },
+                window,
+                cx,
+            )
+        });
+        Self {
+            _subscription: cx.subscribe(&picker, |_, _, _, cx| {
+                cx.emit(DismissEvent);
+            }),
+            picker,
+        }
+    }
+}
+
+impl Render for GotoTargetPicker {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("GotoTargetPicker")
+            .track_focus(&self.focus_handle(cx))
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl EventEmitter<DismissEvent> for GotoTargetPicker {}
+
+impl Focusable for GotoTargetPicker {
+    fn focus_handle(&self, cx: &App) -> gpui::FocusHandle {
+        self.picker.read(cx).focus_handle(cx)
+    }
+}
+
+impl ModalView for GotoTargetPicker {}
+
+pub(crate) struct GotoTargetPickerDelegate {
+    session: Entity<Session>,
+    thread_id: ThreadId,
+    targets: Arc<[GotoTarget]>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for GotoTargetPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select where execution should continue".into()
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let candidates = self
+            .targets
+            .iter()
+            .enumerate()
+            .map(|(id, target)| StringMatchCandidate::new(id, &target.label))
+            .collect::<Vec<_>>();
+
+        cx.spawn(async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    100,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+
+            this.update(cx, |this, _| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+            })
+            .ok();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(target) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|string_match| self.targets.get(string_match.candidate_id))
+        {
+            let target_id = target.id;
+            let thread_id = self.thread_id;
+            self.session.update(cx, |session, cx| {
+                session.goto(thread_id, target_id, cx);
+            });
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let string_match = self.matches.get(ix)?;
+        let target = self.targets.get(string_match.candidate_id)?;
+        let location = match target.column {
+            Some(column) => format!("{}:{}", target.line, column),
+            None => target.line.to_string(),
+        };
+
+        Some(
+            ListItem::new(("goto-target", ix))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_2()
+                        .justify_between()
+                        .child(HighlightedLabel::new(
+                            target.label.clone(),
+                            string_match.positions.clone(),
+                        ))
+                        .child(Label::new(location).color(Color::Muted)),
+                ),
+        )
+    }
+}
@@ -21,7 +21,7 @@ use gpui::{
 use itertools::Itertools as _;
 use picker::{Picker, PickerDelegate, highlighted_match_with_paths::HighlightedMatch};
 use project::{DebugScenarioContext, Project, TaskContexts, TaskSourceKind, task_store::TaskStore};
-use task::{DebugScenario, RevealTarget, VariableName, ZedDebugConfig};
+use task::{DebugCompound, DebugScenario, RevealTarget, VariableName, ZedDebugConfig};
 use ui::{
     ContextMenu, DropdownMenu, FluentBuilder, IconWithIndicator, Indicator, KeyBinding, ListItem,
     ListItemSpacing, Switch, SwitchLabelPosition, ToggleButtonGroup, ToggleButtonSimple,
@@ -951,6 +951,7 @@ pub(super) struct DebugDelegate {
         DebugScenario,
         Option<DebugScenarioContext>,
     )>,
+    compounds: Vec<(TaskSourceKind, DebugCompound)>,
     selected_index: usize,
     matches: Vec<StringMatch>,
     prompt: String,
@@ -965,6 +966,7 @@ impl DebugDelegate {
         Self {
             task_store,
             candidates: Vec::default(),
+            compounds: Vec::default(),
             selected_index: 0,
             matches: Vec::new(),
             prompt: String::new(),
@@ -1058,6 +1060,68 @@ impl DebugDelegate {
         (language_name, scenario)
     }
 
+    fn start_compound(
+        &self,
+        compound: DebugCompound,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let (task_context, worktree_id) = self
+            .task_contexts
+            .as_ref()
+            .and_then(|task_contexts| {
+                Some((
+                    task_contexts.active_context().cloned()?,
+                    task_contexts.worktree(),
+                ))
+            })
+            .unwrap_or_default();
+        self.debug_panel
+            .update(cx, |panel, cx| {
+                panel.start_compound(compound, task_context, worktree_id, window, cx);
+            })
+            .ok();
+    }
+
+    fn render_compound_match(
+        &self,
+        ix: usize,
+        hit: &StringMatch,
+        task_kind: &TaskSourceKind,
+        selected: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> ListItem {
+        let highlighted_location = HighlightedMatch {
+            text: hit.string.clone(),
+            highlight_positions: hit.positions.clone(),
+            color: Color::Default,
+        };
+        let subtitle = self.get_task_subtitle(&Some(task_kind.clone()), &None, cx);
+
+        ListItem::new(format!("debug-scenario-selection-{ix}"))
+            .inset(true)
+            .start_slot(
+                Icon::new(IconName::ListTree)
+                    .color(Color::Muted)
+                    .size(IconSize::Small),
+            )
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(selected)
+            .child(
+                v_flex()
+                    .items_start()
+                    .child(highlighted_location.render(window, cx))
+                    .when_some(subtitle, |this, subtitle_text| {
+                        this.child(
+                            Label::new(subtitle_text)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+    }
+
     pub fn tasks_loaded(
         &mut self,
         task_contexts: Arc<TaskContexts>,
@@ -1068,6 +1132,16 @@ impl DebugDelegate {
         cx: &mut Context<Picker<Self>>,
     ) -> Task<()> {
         self.task_contexts = Some(task_contexts.clone());
+        self.compounds = self
+            .task_store
+            .read(cx)
+            .task_inventory()
+            .map(|inventory| {
+                inventory
+                    .read(cx)
+                    .list_debug_compounds(task_contexts.worktree())
+            })
+            .unwrap_or_default();
         let task = self.task_store.update(cx, |task_store, cx| {
             task_store.task_inventory().map(|inventory| {
                 inventory.update(cx, |inventory, cx| {
@@ -1172,14 +1246,20 @@ impl PickerDelegate for DebugDelegate {
         cx: &mut Context<picker::Picker<Self>>,
     ) -> gpui::Task<()> {
         let candidates = self.candidates.clone();
+        let compound_labels = self
+            .compounds
+            .iter()
+            .map(|(_, compound)| compound.label.clone())
+            .collect::<Vec<_>>();
 
         cx.spawn_in(window, async move |picker, cx| {
+            // Compounds are listed after all scenarios, so their candidate ids start past the last scenario.
             let candidates: Vec<_> = candidates
                 .into_iter()
+                .map(|(_, _, candidate, _)| candidate.label)
+                .chain(compound_labels)
                 .enumerate()
-                .map(|(index, (_, _, candidate, _))| {
-                    StringMatchCandidate::new(index, candidate.label.as_ref())
-                })
+                .map(|(index, label)| StringMatchCandidate::new(index, label.as_ref()))
                 .collect();
 
             let matches = fuzzy::match_strings(
@@ -1345,12 +1425,27 @@ impl PickerDelegate for DebugDelegate {
         window: &mut Window,
         cx: &mut Context<picker::Picker<Self>>,
     ) {
-        let debug_scenario = self
+        let Some(candidate_id) = self
             .matches
             .get(self.selected_index())
-            .and_then(|match_candidate| self.candidates.get(match_candidate.candidate_id).cloned());
+            .map(|match_candidate| match_candidate.candidate_id)
+        else {
+            return;
+        };
+
+        if let Some((_, compound)) = candidate_id
+            .checked_sub(self.candidates.len())
+            .and_then(|ix| self.compounds.get(ix))
+        {
+            if !secondary {
+                self.start_compound(compound.clone(), window, cx);
+            }
+            cx.emit(DismissEvent);
+            return;
+        }
 
-        let Some((kind, _, debug_scenario, context)) = debug_scenario else {
+        let Some((kind, _, debug_scenario, context)) = self.candidates.get(candidate_id).cloned()
+        else {
             return;
         };
 
@@ -1474,6 +1569,13 @@ impl PickerDelegate for DebugDelegate {
         cx: &mut Context<picker::Picker<Self>>,
     ) -> Option<Self::ListItem> {
         let hit = &self.matches.get(ix)?;
+        if let Some((task_kind, _)) = hit
+            .candidate_id
+            .checked_sub(self.candidates.len())
+            .and_then(|ix| self.compounds.get(ix))
+        {
+            return Some(self.render_compound_match(ix, hit, task_kind, selected, window, cx));
+        }
         let (task_kind, language_name, _scenario, context) = &self.candidates[hit.candidate_id];
 
         let highlighted_location = HighlightedMatch {
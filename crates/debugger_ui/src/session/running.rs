@@ -1,5 +1,6 @@
 pub(crate) mod breakpoint_list;
 pub(crate) mod console;
+pub(crate) mod disassembly_view;
 pub(crate) mod loaded_source_list;
 pub(crate) mod memory_view;
 pub(crate) mod module_list;
@@ -8,7 +9,7 @@ pub mod variable_list;
 use std::{
     any::Any,
     ops::ControlFlow,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, LazyLock},
     time::Duration,
 };
@@ -16,6 +17,7 @@ use std::{
 use crate::{
     ToggleExpandItem,
     attach_modal::{AttachModal, ModalIntent},
+    goto_target_picker::GotoTargetPicker,
     new_process_modal::resolve_path,
     persistence::{self, DebuggerPaneItem, SerializedLayout},
     session::running::memory_view::MemoryView,
@@ -26,11 +28,12 @@ use breakpoint_list::BreakpointList;
 use collections::{HashMap, IndexMap};
 use console::Console;
 use dap::{
-    Capabilities, DapRegistry, RunInTerminalRequestArguments, Thread,
+    Capabilities, DapRegistry, RunInTerminalRequestArguments, SteppingGranularity, Thread,
     adapters::{DebugAdapterName, DebugTaskDefinition},
     client::SessionId,
     debugger_settings::DebuggerSettings,
 };
+use disassembly_view::DisassemblyView;
 use futures::{SinkExt, channel::mpsc};
 use gpui::{
     Action as _, AnyView, AppContext, Axis, Entity, EntityId, EventEmitter, FocusHandle, Focusable,
@@ -60,7 +63,8 @@ use util::ResultExt;
 use variable_list::VariableList;
 use workspace::{
     ActivePaneDecorator, DraggedTab, Item, ItemHandle, Member, Pane, PaneGroup, SplitDirection,
-    Workspace, item::TabContentParams, move_item, pane::Event,
+    Toast, Workspace, item::TabContentParams, move_item, notifications::NotificationId,
+    pane::Event,
 };
 
 static PROCESS_ID_PLACEHOLDER: LazyLock<String> =
@@ -89,6 +93,7 @@ pub struct RunningState {
     pub(crate) scenario: Option<DebugScenario>,
     pub(crate) scenario_context: Option<DebugScenarioContext>,
     memory_view: Entity<MemoryView>,
+    disassembly_view: Entity<DisassemblyView>,
 }
 
 impl RunningState {
@@ -189,6 +194,26 @@ impl SubView {
         this
     }
 
+    pub(crate) fn disassembly_view(view: Entity<DisassemblyView>, cx: &mut App) -> Entity<Self> {
+        let weak_view = view.downgrade();
+        let this = Self::new(
+            view.focus_handle(cx),
+            view.into(),
+            DebuggerPaneItem::Disassembly,
+            cx,
+        );
+
+        this.update(cx, |this, _| {
+            this.with_actions(Box::new(move |_, cx| {
+                weak_view
+                    .update(cx, |this, _| this.render_control_strip())
+                    .unwrap_or_else(|_| div().into_any_element())
+            }));
+        });
+
+        this
+    }
+
     pub(crate) fn console(console: Entity<Console>, cx: &mut App) -> Entity<Self> {
         let weak_console = console.downgrade();
         let this = Self::new(
@@ -773,6 +798,15 @@ impl RunningState {
 
         let loaded_source_list = cx.new(|cx| LoadedSourceList::new(session.clone(), cx));
 
+        let disassembly_view = cx.new(|cx| {
+            DisassemblyView::new(
+                session.clone(),
+                stack_frame_list.clone(),
+                project.clone(),
+                cx,
+            )
+        });
+
         let console = cx.new(|cx| {
             Console::new(
                 session.clone(),
@@ -842,6 +876,9 @@ impl RunningState {
                         {
                             this.remove_pane_item(DebuggerPaneItem::LoadedSources, window, cx);
                         }
+                        if !capabilities.supports_disassemble_request.unwrap_or(false) {
+                            this.remove_pane_item(DebuggerPaneItem::Disassembly, window, cx);
+                        }
                     }
                     SessionEvent::RunInTerminal { request, sender } => this
                         .handle_run_in_terminal(request, sender.clone(), window, cx)
@@ -880,6 +917,7 @@ impl RunningState {
                 &loaded_source_list,
                 &debug_terminal,
                 &memory_view,
+                &disassembly_view,
                 &mut pane_close_subscriptions,
                 window,
                 cx,
@@ -909,6 +947,7 @@ impl RunningState {
 
         Self {
             memory_view,
+            disassembly_view,
             session,
             workspace,
             focus_handle,
@@ -1355,6 +1394,9 @@ impl RunningState {
                 item_kind,
                 cx,
             )),
+            DebuggerPaneItem::Disassembly => {
+                Box::new(SubView::disassembly_view(self.disassembly_view.clone(), cx))
+            }
         }
     }
 
@@ -1539,6 +1581,11 @@ impl RunningState {
         &self.module_list
     }
 
+    #[cfg(test)]
+    pub(crate) fn disassembly_view(&self) -> &Entity<DisassemblyView> {
+        &self.disassembly_view
+    }
+
     pub(crate) fn activate_item(
         &mut self,
         item: DebuggerPaneItem,
@@ -1657,6 +1704,26 @@ impl RunningState {
         });
     }
 
+    pub(crate) fn step_over_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_over(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
+    pub(crate) fn step_in_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_in(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
     pub(crate) fn step_out(&mut self, cx: &mut Context<Self>) {
         let Some(thread_id) = self.thread_id else {
             return;
@@ -1681,6 +1748,56 @@ impl RunningState {
         });
     }
 
+    /// Moves the instruction pointer of the selected thread to the given row of a source file,
+    /// letting the user pick a target when the adapter reports several of them.
+    pub(crate) fn set_next_statement(
+        &mut self,
+        path: Arc<Path>,
+        row: u32,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        let session = self.session.clone();
+        let targets = session.update(cx, |session, cx| session.goto_targets(path, row, cx));
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let Some(mut targets) = targets.await else {
+                return;
+            };
+            if targets.len() == 1 {
+                let target_id = targets.remove(0).id;
+                session
+                    .update(cx, |session, cx| session.goto(thread_id, target_id, cx))
+                    .ok();
+                return;
+            }
+
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    if targets.is_empty() {
+                        workspace.show_toast(
+                            Toast::new(
+                                NotificationId::unique::<GotoTargetPicker>(),
+                                "Execution cannot be moved to this line",
+                            )
+                            .autohide(),
+                            cx,
+                        );
+                    } else {
+                        workspace.toggle_modal(window, cx, |window, cx| {
+                            GotoTargetPicker::new(session, thread_id, targets, window, cx)
+                        });
+                    }
+                })
+                .ok();
+        })
+        .detach();
+    }
+
     pub fn rerun_session(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if let Some((scenario, context)) = self.scenario.take().zip(self.scenario_context.take())
             && scenario.build.is_some()
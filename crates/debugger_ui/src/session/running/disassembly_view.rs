@@ -0,0 +1,423 @@
+use std::ops::Range;
+
+use collections::{HashMap, HashSet};
+use dap::DisassembledInstruction;
+use gpui::{
+    Action as _, AnyElement, Entity, FocusHandle, Focusable, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, uniform_list,
+};
+use language::{BufferSnapshot, Point};
+use project::{Project, debugger::session::Session};
+use ui::{Tooltip, WithScrollbar, prelude::*};
+use util::ResultExt;
+
+use super::stack_frame_list::{StackFrameList, StackFrameListEvent};
+use crate::{StepIntoInstruction, StepOverInstruction};
+
+/// How many instructions are disassembled before and after the instruction pointer.
+const INSTRUCTIONS_BEFORE: u64 = 50;
+const INSTRUCTIONS_AFTER: u64 = 100;
+
+enum DisassemblyEntry {
+    /// A source line that the instructions following it were compiled from.
+    Source {
+        location: SharedString,
+        text: Option<SharedString>,
+    },
+    Instruction(DisassembledInstruction),
+}
+
+pub(crate) struct DisassemblyView {
+    session: Entity<Session>,
+    stack_frame_list: Entity<StackFrameList>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    entries: Vec<DisassemblyEntry>,
+    instruction_pointer: Option<String>,
+    error: Option<SharedString>,
+    is_stale: bool,
+    _fetch_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DisassemblyView {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        stack_frame_list: Entity<StackFrameList>,
+        project: Entity<Project>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = vec![
+            cx.subscribe(&stack_frame_list, |this, _, event, cx| match event {
+                StackFrameListEvent::SelectedStackFrameChanged(_) => {
+                    this.is_stale = true;
+                    cx.notify();
+                }
+                StackFrameListEvent::BuiltEntries => {}
+            }),
+            // Instruction breakpoints are stored in the session.
+            cx.observe(&session, |_, _, cx| cx.notify()),
+        ];
+
+        Self {
+            session,
+            stack_frame_list,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            entries: Vec::new(),
+            instruction_pointer: None,
+            error: None,
+            is_stale: true,
+            _fetch_task: Task::ready(()),
+            _subscriptions,
+        }
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        self.is_stale = false;
+        let Some(stack_frame) = self.stack_frame_list.read(cx).opened_stack_frame() else {
+            self.entries.clear();
+            self.instruction_pointer = None;
+            self.error = None;
+            return;
+        };
+        let Some(instruction_pointer) = stack_frame.instruction_pointer_reference else {
+            self.entries.clear();
+            self.instruction_pointer = None;
+            self.error = Some("The selected stack frame has no instruction pointer".into());
+            return;
+        };
+
+        let instructions = self.session.update(cx, |session, cx| {
+            session.disassemble(
+                instruction_pointer.clone(),
+                -(INSTRUCTIONS_BEFORE as i64),
+                INSTRUCTIONS_BEFORE + INSTRUCTIONS_AFTER,
+                cx,
+            )
+        });
+        let project = self.project.clone();
+        self._fetch_task = cx.spawn(async move |this, cx| {
+            let Some(instructions) = instructions.await else {
+                this.update(cx, |this, cx| {
+                    this.entries.clear();
+                    this.error = Some("Failed to disassemble the selected stack frame".into());
+                    cx.notify();
+                })
+                .ok();
+                return;
+            };
+
+            let paths = instructions
+                .iter()
+                .filter_map(|instruction| instruction.location.as_ref()?.path.clone())
+                .collect::<HashSet<_>>();
+            let mut sources = HashMap::default();
+            for path in paths {
+                let Some(buffer) = project
+                    .update(cx, |project, cx| project.open_local_buffer(&path, cx))
+                    .log_err()
+                else {
+                    continue;
+                };
+                if let Some(snapshot) = buffer
+                    .await
+                    .log_err()
+                    .and_then(|buffer| buffer.read_with(cx, |buffer, _| buffer.snapshot()).ok())
+                {
+                    sources.insert(path, snapshot);
+                }
+            }
+
+            this.update(cx, |this, cx| {
+                this.entries = interleave_source_lines(instructions, &sources);
+                this.error = None;
+                this.instruction_pointer = Some(instruction_pointer);
+                if let Some(ix) = this.instruction_pointer_ix() {
+                    this.scroll_handle
+                        .scroll_to_item(ix, ScrollStrategy::Center);
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn instruction_pointer_ix(&self) -> Option<usize> {
+        let instruction_pointer = self.instruction_pointer.as_deref()?;
+        self.entries.iter().position(|entry| match entry {
+            DisassemblyEntry::Instruction(instruction) => {
+                is_same_address(&instruction.address, instruction_pointer)
+            }
+            DisassemblyEntry::Source { .. } => false,
+        })
+    }
+
+    #[cfg(test)]
+    pub(crate) fn instructions(&self) -> Vec<(String, bool)> {
+        let instruction_pointer_ix = self.instruction_pointer_ix();
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(ix, entry)| match entry {
+                DisassemblyEntry::Source { location, text } => (
+                    format!("{location}: {}", text.as_deref().unwrap_or_default()),
+                    false,
+                ),
+                DisassemblyEntry::Instruction(instruction) => (
+                    format!("{} {}", instruction.address, instruction.instruction),
+                    Some(ix) == instruction_pointer_ix,
+                ),
+            })
+            .collect()
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        match &self.entries[ix] {
+            DisassemblyEntry::Source { location, text } => h_flex()
+                .id(("disassembly-source", ix))
+                .w_full()
+                .px_1()
+                .gap_2()
+                .text_ui_xs(cx)
+                .text_color(cx.theme().colors().text_muted)
+                .child(
+                    Label::new(location.clone())
+                        .size(LabelSize::XSmall)
+                        .italic(),
+                )
+                .when_some(text.clone(), |this, text| {
+                    this.child(Label::new(text).size(LabelSize::XSmall).buffer_font(cx))
+                })
+                .into_any_element(),
+            DisassemblyEntry::Instruction(instruction) => {
+                let is_instruction_pointer = self
+                    .instruction_pointer
+                    .as_deref()
+                    .is_some_and(|pointer| is_same_address(&instruction.address, pointer));
+                let session = self.session.read(cx);
+                let has_breakpoint = session.instruction_breakpoints().any(|breakpoint| {
+                    is_same_address(&breakpoint.instruction_reference, &instruction.address)
+                });
+                let supports_breakpoints = session
+                    .capabilities()
+                    .supports_instruction_breakpoints
+                    .unwrap_or_default();
+                let address = instruction.address.clone();
+
+                h_flex()
+                    .id(("disassembly-instruction", ix))
+                    .w_full()
+                    .px_1()
+                    .gap_2()
+                    .group("disassembly-instruction")
+                    .when(is_instruction_pointer, |this| {
+                        this.bg(cx.theme().colors().editor_debugger_active_line_background)
+                    })
+                    .hover(|style| style.bg(cx.theme().colors().element_hover))
+                    .child(
+                        div()
+                            .id(("disassembly-breakpoint", ix))
+                            .size_3()
+                            .flex_none()
+                            .when(has_breakpoint, |this| {
+                                this.child(
+                                    Icon::new(IconName::DebugBreakpoint)
+                                        .size(IconSize::XSmall)
+                                        .color(Color::Debugger),
+                                )
+                            })
+                            .when(!has_breakpoint && supports_breakpoints, |this| {
+                                this.child(
+                                    div().visible_on_hover("disassembly-instruction").child(
+                                        Icon::new(IconName::DebugBreakpoint)
+                                            .size(IconSize::XSmall)
+                                            .color(Color::Hint),
+                                    ),
+                                )
+                            })
+                            .when(supports_breakpoints, |this| {
+                                this.cursor_pointer()
+                                    .tooltip(Tooltip::text("Toggle Instruction Breakpoint"))
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.session.update(cx, |session, cx| {
+                                            session
+                                                .toggle_instruction_breakpoint(address.clone(), cx)
+                                        });
+                                    }))
+                            }),
+                    )
+                    .child(
+                        Label::new(instruction.address.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    )
+                    .when_some(instruction.instruction_bytes.clone(), |this, bytes| {
+                        this.child(
+                            div().w_40().flex_none().overflow_hidden().child(
+                                Label::new(bytes)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .buffer_font(cx),
+                            ),
+                        )
+                    })
+                    .child(
+                        Label::new(instruction.instruction.clone())
+                            .size(LabelSize::Small)
+                            .buffer_font(cx),
+                    )
+                    .when_some(instruction.symbol.clone(), |this, symbol| {
+                        this.child(
+                            Label::new(format!("<{symbol}>"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .buffer_font(cx),
+                        )
+                    })
+                    .into_any_element()
+            }
+        }
+    }
+
+    pub(crate) fn render_control_strip(&self) -> AnyElement {
+        h_flex()
+            .gap_0p5()
+            .child(
+                IconButton::new("disassembly-step-over", IconName::DebugStepOver)
+                    .icon_size(IconSize::Small)
+                    .tooltip(move |_window, cx| {
+                        Tooltip::for_action("Step Over Instruction", &StepOverInstruction, cx)
+                    })
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(StepOverInstruction.boxed_clone(), cx)
+                    }),
+            )
+            .child(
+                IconButton::new("disassembly-step-into", IconName::DebugStepInto)
+                    .icon_size(IconSize::Small)
+                    .tooltip(move |_window, cx| {
+                        Tooltip::for_action("Step Into Instruction", &StepIntoInstruction, cx)
+                    })
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(StepIntoInstruction.boxed_clone(), cx)
+                    }),
+            )
+            .into_any_element()
+    }
+}
+
+/// Inserts the source line that each run of instructions was compiled from before that run.
+///
+/// Adapters may omit an instruction's source location when it's the same as the previous one's,
+/// so the last known file is carried over.
+fn interleave_source_lines(
+    instructions: Vec<DisassembledInstruction>,
+    sources: &HashMap<String, BufferSnapshot>,
+) -> Vec<DisassemblyEntry> {
+    let mut entries = Vec::with_capacity(instructions.len());
+    let mut current_source: Option<(String, SharedString)> = None;
+    let mut current_line = None;
+    for instruction in instructions {
+        if let Some(source) = &instruction.location {
+            let name = source
+                .name
+                .clone()
+                .or_else(|| source.path.clone())
+                .unwrap_or_default();
+            if let Some(path) = source.path.clone()
+                && current_source
+                    .as_ref()
+                    .is_none_or(|(current, _)| *current != path)
+            {
+                current_source = Some((path, name.into()));
+                current_line = None;
+            }
+        }
+
+        if let Some((path, name)) = &current_source
+            && let Some(line) = instruction.line
+            && current_line != Some(line)
+        {
+            current_line = Some(line);
+            let text = sources.get(path).and_then(|snapshot| {
+                let row = line.checked_sub(1)? as u32;
+                if row > snapshot.max_point().row {
+                    return None;
+                }
+                let text = snapshot
+                    .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+                    .collect::<String>();
+                Some(SharedString::from(text.trim().to_string()))
+            });
+            entries.push(DisassemblyEntry::Source {
+                location: format!("{name}:{line}").into(),
+                text,
+            });
+        }
+
+        entries.push(DisassemblyEntry::Instruction(instruction));
+    }
+    entries
+}
+
+/// Compares two memory references, which adapters may format differently (e.g. with or without
+/// leading zeros).
+fn is_same_address(left: &str, right: &str) -> bool {
+    fn parse(address: &str) -> Option<u64> {
+        let address = address.trim();
+        match address
+            .strip_prefix("0x")
+            .or_else(|| address.strip_prefix("0X"))
+        {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => address.parse().ok(),
+        }
+    }
+
+    match (parse(left), parse(right)) {
+        (Some(left), Some(right)) => left == right,
+        _ => left == right,
+    }
+}
+
+impl Focusable for DisassemblyView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DisassemblyView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.is_stale {
+            self.refresh(cx);
+        }
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_1()
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    h_flex()
+                        .p_1()
+                        .child(Label::new(error).size(LabelSize::Small).color(Color::Muted)),
+                )
+            })
+            .child(
+                uniform_list(
+                    "disassembly-view",
+                    self.entries.len(),
+                    cx.processor(|this, range: Range<usize>, _window, cx| {
+                        range.map(|ix| this.render_entry(ix, cx)).collect()
+                    }),
+                )
+                .track_scroll(&self.scroll_handle)
+                .size_full(),
+            )
+            .vertical_scrollbar_for(&self.scroll_handle, window, cx)
+    }
+}
@@ -218,6 +218,19 @@ impl StackFrameList {
         self.opened_stack_frame_id
     }
 
+    pub(crate) fn opened_stack_frame(&self) -> Option<dap::StackFrame> {
+        let opened_stack_frame_id = self.opened_stack_frame_id?;
+        self.entries.iter().find_map(|entry| match entry {
+            StackFrameEntry::Normal(frame) | StackFrameEntry::Label(frame) => {
+                (frame.id == opened_stack_frame_id).then(|| frame.clone())
+            }
+            StackFrameEntry::Collapsed(frames) => frames
+                .iter()
+                .find(|frame| frame.id == opened_stack_frame_id)
+                .cloned(),
+        })
+    }
+
     pub(super) fn schedule_refresh(
         &mut self,
         select_first: bool,
@@ -34,8 +34,12 @@ actions!(
         CopyVariableName,
         /// Copies the variable value to the clipboard.
         CopyVariableValue,
+        /// Copies an expression that evaluates to the variable to the clipboard.
+        CopyVariableExpression,
         /// Edits the value of the selected variable.
         EditVariable,
+        /// Edits the expression of the selected watch.
+        EditWatchExpression,
         /// Adds the selected variable to the watch list.
         AddWatch,
         /// Removes the selected variable from the watch list.
@@ -186,6 +190,12 @@ struct VariableColor {
     value: Option<Hsla>,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum EditedField {
+    Value,
+    WatchExpression,
+}
+
 pub struct VariableList {
     entries: Vec<ListEntry>,
     max_width_index: Option<usize>,
@@ -196,7 +206,7 @@ pub struct VariableList {
     selection: Option<EntryPath>,
     open_context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
     focus_handle: FocusHandle,
-    edited_path: Option<(EntryPath, Entity<Editor>)>,
+    edited_path: Option<(EntryPath, EditedField, Entity<Editor>)>,
     disabled: bool,
     memory_view: Entity<MemoryView>,
     weak_running: WeakEntity<RunningState>,
@@ -533,32 +543,95 @@ impl VariableList {
         cx.notify();
     }
 
-    fn confirm(&mut self, _: &menu::Confirm, _window: &mut Window, cx: &mut Context<Self>) {
-        if let Some((var_path, editor)) = self.edited_path.take() {
-            let Some(state) = self.entry_states.get(&var_path) else {
-                return;
-            };
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((var_path, field, editor)) = self.edited_path.take() else {
+            return;
+        };
+        self.focus_handle.focus(window, cx);
 
-            let variables_reference = state.parent_reference;
-            let Some(name) = var_path.leaf_name else {
-                return;
-            };
+        let Some(stack_frame_id) = self.selected_stack_frame_id else {
+            return;
+        };
 
-            let Some(stack_frame_id) = self.selected_stack_frame_id else {
-                return;
-            };
+        let Some(entry) = self.entries.iter().find(|entry| entry.path == var_path) else {
+            return;
+        };
 
-            let value = editor.read(cx).text(cx);
+        let text = editor.read(cx).text(cx);
 
-            self.session.update(cx, |session, cx| {
-                session.set_variable_value(
-                    stack_frame_id,
-                    variables_reference,
-                    name.into(),
-                    value,
-                    cx,
-                )
-            });
+        match (&entry.entry, field) {
+            (DapEntry::Watcher(watcher), EditedField::WatchExpression) => {
+                let expression = text.trim();
+                if expression.is_empty() || expression == watcher.expression.as_ref() {
+                    return;
+                }
+
+                let old_expression = watcher.expression.clone();
+                let add_watcher_task = self.session.update(cx, |session, cx| {
+                    session.add_watcher(expression.to_string().into(), stack_frame_id, cx)
+                });
+
+                cx.spawn(async move |this, cx| {
+                    add_watcher_task.await?;
+
+                    this.update(cx, |this, cx| {
+                        this.session.update(cx, |session, _| {
+                            session.remove_watcher(old_expression);
+                        });
+                        this.build_entries(cx);
+                    })
+                })
+                .detach_and_log_err(cx);
+            }
+            (DapEntry::Watcher(watcher), EditedField::Value) => {
+                let expression = watcher.expression.to_string();
+                self.session.update(cx, |session, cx| {
+                    session.set_expression_value(stack_frame_id, expression, text, cx)
+                });
+            }
+            (DapEntry::Variable(variable), EditedField::Value) => {
+                let Some(state) = self.entry_states.get(&var_path) else {
+                    return;
+                };
+
+                let variables_reference = state.parent_reference;
+                let name = variable.name.clone();
+                let evaluate_name = variable.evaluate_name.clone();
+
+                self.session.update(cx, |session, cx| {
+                    if session
+                        .capabilities()
+                        .supports_set_variable
+                        .unwrap_or_default()
+                    {
+                        session.set_variable_value(
+                            stack_frame_id,
+                            variables_reference,
+                            name,
+                            text,
+                            cx,
+                        )
+                    } else if let Some(evaluate_name) = evaluate_name {
+                        session.set_expression_value(stack_frame_id, evaluate_name, text, cx)
+                    }
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the value of `entry` can be changed in place, either through
+    /// `setVariable` or, when the adapter lacks it, through `setExpression`.
+    fn can_edit_value(&self, entry: &DapEntry, cx: &App) -> bool {
+        let capabilities = self.session.read(cx).capabilities();
+        let supports_set_expression = capabilities.supports_set_expression.unwrap_or_default();
+        match entry {
+            DapEntry::Watcher(_) => supports_set_expression,
+            DapEntry::Variable(variable) => {
+                capabilities.supports_set_variable.unwrap_or_default()
+                    || (supports_set_expression && variable.evaluate_name.is_some())
+            }
+            DapEntry::Scope(_) => false,
         }
     }
 
@@ -656,13 +729,10 @@ impl VariableList {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let (supports_set_variable, supports_data_breakpoints, supports_go_to_memory) =
+        let can_edit_value = self.can_edit_value(&entry.entry, cx);
+        let (supports_data_breakpoints, supports_go_to_memory) =
             self.session.read_with(cx, |session, _| {
                 (
-                    session
-                        .capabilities()
-                        .supports_set_variable
-                        .unwrap_or_default(),
                     session
                         .capabilities()
                         .supports_data_breakpoints
@@ -706,7 +776,8 @@ impl VariableList {
                     menu.when_some(entry.as_variable(), |menu, _| {
                         menu.action("Copy Name", CopyVariableName.boxed_clone())
                             .action("Copy Value", CopyVariableValue.boxed_clone())
-                            .when(supports_set_variable, |menu| {
+                            .action("Copy as Expression", CopyVariableExpression.boxed_clone())
+                            .when(can_edit_value, |menu| {
                                 menu.action("Edit Value", EditVariable.boxed_clone())
                             })
                             .when(supports_go_to_memory, |menu| {
@@ -747,7 +818,8 @@ impl VariableList {
                     .when(entry.as_watcher().is_some(), |menu| {
                         menu.action("Copy Name", CopyVariableName.boxed_clone())
                             .action("Copy Value", CopyVariableValue.boxed_clone())
-                            .when(supports_set_variable, |menu| {
+                            .action("Edit Expression", EditWatchExpression.boxed_clone())
+                            .when(can_edit_value, |menu| {
                                 menu.action("Edit Value", EditVariable.boxed_clone())
                             })
                             .action("Remove Watch", RemoveWatch.boxed_clone())
@@ -895,6 +967,32 @@ impl VariableList {
         cx.write_to_clipboard(ClipboardItem::new_string(variable_value));
     }
 
+    fn copy_variable_expression(
+        &mut self,
+        _: &CopyVariableExpression,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(selection) = self.selection.as_ref() else {
+            return;
+        };
+
+        let Some(entry) = self.entries.iter().find(|entry| &entry.path == selection) else {
+            return;
+        };
+
+        let expression = match &entry.entry {
+            DapEntry::Variable(dap) => dap
+                .evaluate_name
+                .clone()
+                .unwrap_or_else(|| dap.name.clone()),
+            DapEntry::Watcher(watcher) => watcher.expression.to_string(),
+            DapEntry::Scope(_) => return,
+        };
+
+        cx.write_to_clipboard(ClipboardItem::new_string(expression));
+    }
+
     fn edit_variable(&mut self, _: &EditVariable, window: &mut Window, cx: &mut Context<Self>) {
         let Some(selection) = self.selection.as_ref() else {
             return;
@@ -904,6 +1002,10 @@ impl VariableList {
             return;
         };
 
+        if !self.can_edit_value(&entry.entry, cx) {
+            return;
+        }
+
         let variable_value = match &entry.entry {
             DapEntry::Watcher(watcher) => watcher.value.to_string(),
             DapEntry::Variable(variable) => variable.value.clone(),
@@ -911,7 +1013,31 @@ impl VariableList {
         };
 
         let editor = Self::create_variable_editor(&variable_value, window, cx);
-        self.edited_path = Some((entry.path.clone(), editor));
+        self.edited_path = Some((entry.path.clone(), EditedField::Value, editor));
+
+        cx.notify();
+    }
+
+    fn edit_watch_expression(
+        &mut self,
+        _: &EditWatchExpression,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(selection) = self.selection.as_ref() else {
+            return;
+        };
+
+        let Some(entry) = self.entries.iter().find(|entry| &entry.path == selection) else {
+            return;
+        };
+
+        let Some(watcher) = entry.as_watcher() else {
+            return;
+        };
+
+        let editor = Self::create_variable_editor(&watcher.expression, window, cx);
+        self.edited_path = Some((entry.path.clone(), EditedField::WatchExpression, editor));
 
         cx.notify();
     }
@@ -1113,22 +1239,16 @@ impl VariableList {
                 .w_full()
                 .id(entry.item_value_id())
                 .map(|this| {
-                    if let Some((_, editor)) = self
-                        .edited_path
-                        .as_ref()
-                        .filter(|(path, _)| path == &entry.path)
+                    if let Some((_, _, editor)) =
+                        self.edited_path.as_ref().filter(|(path, field, _)| {
+                            path == &entry.path && *field == EditedField::Value
+                        })
                     {
                         this.child(div().size_full().px_2().child(editor.clone()))
                     } else {
                         this.text_color(cx.theme().colors().text_muted)
                             .when(
-                                !self.disabled
-                                    && self
-                                        .session
-                                        .read(cx)
-                                        .capabilities()
-                                        .supports_set_variable
-                                        .unwrap_or_default(),
+                                !self.disabled && self.can_edit_value(&entry.entry, cx),
                                 |this| {
                                     let path = entry.path.clone();
                                     let variable_value = value.clone();
@@ -1142,7 +1262,8 @@ impl VariableList {
                                                 window,
                                                 cx,
                                             );
-                                            this.edited_path = Some((path.clone(), editor));
+                                            this.edited_path =
+                                                Some((path.clone(), EditedField::Value, editor));
 
                                             cx.notify();
                                         },
@@ -1307,15 +1428,13 @@ impl VariableList {
                         .gap_1()
                         .text_ui_sm(cx)
                         .w_full()
-                        .child(
-                            Label::new(&Self::center_truncate_string(
-                                watcher.expression.as_ref(),
-                                watcher_len,
-                            ))
-                            .when_some(variable_color.name, |this, color| {
-                                this.color(Color::from(color))
-                            }),
-                        )
+                        .child(self.render_watch_expression(
+                            entry,
+                            watcher,
+                            &variable_color,
+                            watcher_len,
+                            cx,
+                        ))
                         .child(self.render_variable_value(
                             entry,
                             &variable_color,
@@ -1346,6 +1465,54 @@ impl VariableList {
             .into_any()
     }
 
+    fn render_watch_expression(
+        &self,
+        entry: &ListEntry,
+        watcher: &Watcher,
+        variable_color: &VariableColor,
+        max_chars: usize,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        if let Some((_, _, editor)) = self.edited_path.as_ref().filter(|(path, field, _)| {
+            path == &entry.path && *field == EditedField::WatchExpression
+        }) {
+            return div()
+                .w_full()
+                .px_2()
+                .child(editor.clone())
+                .into_any_element();
+        }
+
+        div()
+            .id(SharedString::from(format!(
+                "watcher-{}-expression",
+                watcher.expression
+            )))
+            .when(!self.disabled, |this| {
+                let path = entry.path.clone();
+                let expression = watcher.expression.clone();
+                this.on_click(cx.listener(move |this, click: &ClickEvent, window, cx| {
+                    if click.click_count() < 2 {
+                        return;
+                    }
+                    let editor = Self::create_variable_editor(&expression, window, cx);
+                    this.edited_path = Some((path.clone(), EditedField::WatchExpression, editor));
+
+                    cx.notify();
+                }))
+            })
+            .child(
+                Label::new(&Self::center_truncate_string(
+                    watcher.expression.as_ref(),
+                    max_chars,
+                ))
+                .when_some(variable_color.name, |this, color| {
+                    this.color(Color::from(color))
+                }),
+            )
+            .into_any_element()
+    }
+
     fn render_scope(
         &self,
         entry: &ListEntry,
@@ -1519,7 +1686,34 @@ impl VariableList {
                             dap.value.clone(),
                             cx,
                         )),
-                ),
+                )
+                .when(!self.disabled, |list_item| {
+                    let path = variable.path.clone();
+                    let weak = cx.weak_entity();
+                    let focus_handle = self.focus_handle.clone();
+                    list_item.end_slot(
+                        div().visible_on_hover("variable_list_entry").child(
+                            IconButton::new(
+                                SharedString::from(format!(
+                                    "variable-{}-{}-add-watch-button",
+                                    dap.name, state.depth
+                                )),
+                                IconName::Eye,
+                            )
+                            .on_click(move |_, window, cx| {
+                                weak.update(cx, |variable_list, cx| {
+                                    variable_list.selection = Some(path.clone());
+                                    variable_list.add_watcher(&AddWatch, window, cx);
+                                })
+                                .ok();
+                            })
+                            .tooltip(move |_window, cx| {
+                                Tooltip::for_action_in("Add Watch", &AddWatch, &focus_handle, cx)
+                            })
+                            .icon_size(ui::IconSize::Indicator),
+                        ),
+                    )
+                }),
             )
             .into_any()
     }
@@ -1549,7 +1743,9 @@ impl Render for VariableList {
             .on_action(cx.listener(Self::collapse_selected_entry))
             .on_action(cx.listener(Self::copy_variable_name))
             .on_action(cx.listener(Self::copy_variable_value))
+            .on_action(cx.listener(Self::copy_variable_expression))
             .on_action(cx.listener(Self::edit_variable))
+            .on_action(cx.listener(Self::edit_watch_expression))
             .on_action(cx.listener(Self::add_watcher))
             .on_action(cx.listener(Self::remove_watcher))
             .on_action(cx.listener(Self::toggle_data_breakpoint))
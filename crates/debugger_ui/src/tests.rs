@@ -17,10 +17,14 @@ mod attach_modal;
 #[cfg(test)]
 mod console;
 #[cfg(test)]
+mod core_dump_modal;
+#[cfg(test)]
 mod dap_logger;
 #[cfg(test)]
 mod debugger_panel;
 #[cfg(test)]
+mod disassembly_view;
+#[cfg(test)]
 mod inline_values;
 #[cfg(test)]
 mod module_list;
@@ -0,0 +1,111 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use dap::adapters::DebugAdapterName;
+use gpui::{BackgroundExecutor, TestAppContext};
+use project::{FakeFs, Project};
+use serde_json::json;
+use util::path;
+
+use crate::core_dump_modal::{
+    CoreDump, CoreDumpSource, core_dump_scenario, core_dumps_in_directories, parse_coredumpctl_list,
+};
+use crate::tests::init_test;
+
+#[gpui::test]
+async fn test_core_dumps_in_directories(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.c": "int main() { return *(int *)0; }",
+        }),
+    )
+    .await;
+    fs.insert_tree(
+        path!("/cores"),
+        json!({
+            "core.1234": "",
+            "core.rs": "",
+            "core.12ab": "",
+            "service.core": "",
+            "notes.txt": "",
+            "core": {
+                "nested": "",
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+
+    let mut core_files = cx
+        .update(|cx| {
+            core_dumps_in_directories(
+                &project,
+                vec![
+                    PathBuf::from(path!("/cores")),
+                    PathBuf::from(path!("/missing")),
+                ],
+                cx,
+            )
+        })
+        .await
+        .into_iter()
+        .map(|core_dump| {
+            assert_eq!(None, core_dump.executable);
+            assert!(core_dump.timestamp.is_some());
+            core_dump.source
+        })
+        .collect::<Vec<_>>();
+    core_files.sort_by_key(|source| format!("{source:?}"));
+
+    assert_eq!(
+        vec![CoreDumpSource::File(PathBuf::from(path!(
+            "/cores/core.1234"
+        ))),],
+        core_files
+    );
+}
+
+#[test]
+fn test_parse_coredumpctl_list() {
+    let core_dumps = parse_coredumpctl_list(
+        r#"[
+            {"time":1700000000000000,"pid":4242,"uid":1000,"gid":1000,"sig":11,"corefile":"present","exe":"/usr/bin/service","size":1024},
+            {"time":1700000001000000,"pid":4343,"uid":1000,"gid":1000,"sig":6,"corefile":"missing","exe":"/usr/bin/other","size":null}
+        ]"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        vec![CoreDump {
+            source: CoreDumpSource::Coredumpctl { pid: 4242 },
+            executable: Some(PathBuf::from("/usr/bin/service")),
+            timestamp: Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        }],
+        core_dumps
+    );
+}
+
+#[test]
+fn test_core_dump_scenario() {
+    let scenario = core_dump_scenario(
+        DebugAdapterName("GDB".into()),
+        Path::new("/usr/bin/service"),
+        Path::new("/tmp/core.4242"),
+    );
+
+    assert_eq!("GDB", scenario.adapter.as_ref());
+    assert_eq!("Core dump of service", scenario.label.as_ref());
+    assert_eq!(
+        json!({
+            "request": "launch",
+            "program": "/usr/bin/service",
+            "coreFile": "/tmp/core.4242",
+        }),
+        scenario.config
+    );
+}
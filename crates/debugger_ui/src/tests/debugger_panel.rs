@@ -9,8 +9,8 @@ use dap::{
     adapters::DebugTaskDefinition,
     client::SessionId,
     requests::{
-        Continue, Disconnect, Launch, Next, RunInTerminal, SetBreakpoints, StackTrace,
-        StartDebugging, StepBack, StepIn, StepOut, Threads,
+        Continue, Disconnect, Goto, GotoTargets, Launch, Next, RunInTerminal, SetBreakpoints,
+        StackTrace, StartDebugging, StepBack, StepIn, StepOut, Threads,
     },
 };
 use editor::{
@@ -1944,3 +1944,319 @@ async fn test_adapter_shutdown_with_child_sessions_on_app_quit(
         "Child session should have received disconnect request"
     );
 }
+
+#[gpui::test]
+async fn test_set_next_statement(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<dap::requests::Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_goto_targets_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: Vec::default(),
+            total_frames: None,
+        })
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    let goto_target = |id: u64, label: &str| dap::GotoTarget {
+        id,
+        label: label.into(),
+        line: 2,
+        column: None,
+        end_line: None,
+        end_column: None,
+        instruction_pointer_reference: None,
+    };
+
+    client.on_request::<GotoTargets, _>(move |_, args| {
+        assert_eq!(path!("/project/main.rs"), args.source.path.unwrap());
+        assert_eq!(2, args.line);
+
+        Ok(dap::GotoTargetsResponse {
+            targets: vec![goto_target(7, "Second line")],
+        })
+    });
+
+    let called_goto = Arc::new(AtomicBool::new(false));
+    client.on_request::<Goto, _>({
+        let called_goto = called_goto.clone();
+        move |_, args| {
+            assert_eq!(1, args.thread_id);
+            assert_eq!(7, args.target_id);
+            called_goto.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    });
+
+    let running_state = active_debug_session_panel(workspace, cx)
+        .read_with(cx, |item, _| item.running_state().clone());
+
+    running_state.update_in(cx, |running_state, window, cx| {
+        running_state.set_next_statement(
+            Path::new(path!("/project/main.rs")).into(),
+            1,
+            window,
+            cx,
+        );
+    });
+
+    cx.run_until_parked();
+
+    assert!(
+        called_goto.load(Ordering::SeqCst),
+        "Goto should be requested directly when there is a single target"
+    );
+
+    client.on_request::<GotoTargets, _>(move |_, _| {
+        Ok(dap::GotoTargetsResponse {
+            targets: vec![goto_target(7, "let a"), goto_target(8, "let b")],
+        })
+    });
+
+    running_state.update_in(cx, |running_state, window, cx| {
+        running_state.set_next_statement(
+            Path::new(path!("/project/main.rs")).into(),
+            1,
+            window,
+            cx,
+        );
+    });
+
+    cx.run_until_parked();
+
+    workspace
+        .update(cx, |workspace, _, cx| {
+            assert!(
+                workspace
+                    .active_modal::<crate::goto_target_picker::GotoTargetPicker>(cx)
+                    .is_some(),
+                "A picker should be shown when there are several targets"
+            );
+        })
+        .unwrap();
+}
+
+#[gpui::test]
+async fn test_compound_sessions_start_in_order_and_stop_together(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".zed": {
+                "debug.json": r#"[
+                    {
+                        "adapter": "fake-adapter",
+                        "label": "Backend",
+                        "request": "launch"
+                    },
+                    {
+                        "adapter": "fake-adapter",
+                        "label": "Frontend",
+                        "request": "launch"
+                    }
+                ]"#
+            },
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let dap_store = project.update(cx, |project, _| project.dap_store());
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    cx.run_until_parked();
+
+    let _subscription = project::debugger::test::intercept_debug_sessions(cx, |client| {
+        client.on_request::<Disconnect, _>(move |_, _| Ok(()));
+    });
+    let debug_panel = workspace
+        .update(cx, |workspace, _, cx| workspace.panel::<DebugPanel>(cx))
+        .unwrap()
+        .unwrap();
+
+    let start_compound = |stop_all: bool, cx: &mut VisualTestContext| {
+        debug_panel.update_in(cx, |panel, window, cx| {
+            panel.start_compound(
+                task::DebugCompound {
+                    label: "Full stack".into(),
+                    configurations: vec!["Backend".into(), "Frontend".into()],
+                    stop_all,
+                },
+                task::TaskContext::default(),
+                Some(worktree_id),
+                window,
+                cx,
+            );
+        });
+        cx.run_until_parked();
+
+        let sessions = dap_store.read_with(cx, |dap_store, _| {
+            dap_store.sessions().cloned().collect::<Vec<_>>()
+        });
+        assert_eq!(
+            sessions
+                .iter()
+                .map(|session| session.read_with(cx, |session, _| session.label()))
+                .collect::<Vec<_>>(),
+            vec![Some("Backend".into()), Some("Frontend".into())],
+            "Compound scenarios should be started in the order they are listed"
+        );
+        debug_panel.read_with(cx, |panel, _| {
+            for session in &sessions {
+                assert!(panel.compound_for_session(session).is_some());
+            }
+        });
+        sessions
+    };
+
+    // Stopping the compound from the panel stops every session in it.
+    let sessions = start_compound(false, cx);
+    debug_panel.update(cx, |panel, cx| panel.stop_compound(&sessions[0], cx));
+    cx.run_until_parked();
+    dap_store.read_with(cx, |dap_store, _| {
+        assert_eq!(dap_store.sessions().count(), 0);
+    });
+
+    // With `stop_all`, a session ending on its own takes the rest of the compound down with it.
+    let sessions = start_compound(true, cx);
+    dap_store
+        .update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(sessions[0].read(cx).session_id(), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    dap_store.read_with(cx, |dap_store, _| {
+        assert_eq!(dap_store.sessions().count(), 0);
+    });
+}
+
+#[gpui::test]
+async fn test_compound_stops_when_a_session_cannot_be_started(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".zed": {
+                "debug.json": r#"[
+                    {
+                        "adapter": "missing-adapter",
+                        "label": "Backend",
+                        "request": "launch"
+                    },
+                    {
+                        "adapter": "fake-adapter",
+                        "label": "Frontend",
+                        "request": "launch"
+                    }
+                ]"#
+            },
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let dap_store = project.update(cx, |project, _| project.dap_store());
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    cx.run_until_parked();
+
+    let debug_panel = workspace
+        .update(cx, |workspace, _, cx| workspace.panel::<DebugPanel>(cx))
+        .unwrap()
+        .unwrap();
+    debug_panel.update_in(cx, |panel, window, cx| {
+        panel.start_compound(
+            task::DebugCompound {
+                label: "Full stack".into(),
+                configurations: vec!["Backend".into(), "Frontend".into()],
+                stop_all: false,
+            },
+            task::TaskContext::default(),
+            Some(worktree_id),
+            window,
+            cx,
+        );
+    });
+    cx.run_until_parked();
+
+    dap_store.read_with(cx, |dap_store, _| {
+        assert_eq!(
+            dap_store.sessions().count(),
+            0,
+            "The rest of the compound shouldn't start after a scenario fails to"
+        );
+    });
+    workspace
+        .update(cx, |workspace, _, _| {
+            assert_eq!(workspace.notification_ids().len(), 1);
+        })
+        .unwrap();
+}
@@ -0,0 +1,171 @@
+use crate::{
+    debugger_panel::DebugPanel,
+    persistence::DebuggerPaneItem,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    StackFrame, StoppedEvent,
+    requests::{Disassemble, Initialize, Scopes, StackTrace, Threads},
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{FakeFs, Project};
+use serde_json::json;
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+use util::path;
+
+fn instruction(address: &str, instruction: &str, line: u64) -> dap::DisassembledInstruction {
+    dap::DisassembledInstruction {
+        address: address.into(),
+        instruction_bytes: None,
+        instruction: instruction.into(),
+        symbol: None,
+        location: None,
+        line: Some(line),
+        column: None,
+        end_line: None,
+        end_column: None,
+        presentation_hint: None,
+    }
+}
+
+#[gpui::test]
+async fn test_disassembly_view(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.c": "int main() {\n    return 0;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_disassemble_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 1,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: Some("0x1004".into()),
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    let called_disassemble = Arc::new(AtomicBool::new(false));
+    client.on_request::<Disassemble, _>({
+        let called_disassemble = called_disassemble.clone();
+        move |_, args| {
+            called_disassemble.store(true, Ordering::SeqCst);
+            assert_eq!(args.memory_reference, "0x1004");
+            assert_eq!(args.instruction_offset, Some(-50));
+            assert_eq!(args.instruction_count, 150);
+
+            Ok(dap::DisassembleResponse {
+                instructions: vec![
+                    dap::DisassembledInstruction {
+                        location: Some(dap::Source {
+                            name: Some("main.c".into()),
+                            path: Some(path!("/project/main.c").into()),
+                            source_reference: None,
+                            presentation_hint: None,
+                            origin: None,
+                            sources: None,
+                            adapter_data: None,
+                            checksums: None,
+                        }),
+                        ..instruction("0x1000", "push rbp", 1)
+                    },
+                    instruction("0x1004", "mov rbp, rsp", 1),
+                    instruction("0x1008", "ret", 2),
+                ],
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            item.running_state().clone()
+        });
+
+    running_state.update_in(cx, |this, window, cx| {
+        this.activate_item(DebuggerPaneItem::Disassembly, window, cx);
+        cx.refresh_windows();
+    });
+
+    cx.run_until_parked();
+
+    assert!(
+        called_disassemble.load(Ordering::SeqCst),
+        "Disassemble should be requested once the disassembly view is shown"
+    );
+
+    let instructions = running_state.update(cx, |state, cx| {
+        state.disassembly_view().read(cx).instructions()
+    });
+    assert_eq!(
+        instructions,
+        vec![
+            ("main.c:1: int main() {".to_string(), false),
+            ("0x1000 push rbp".to_string(), false),
+            ("0x1004 mov rbp, rsp".to_string(), true),
+            ("main.c:2: return 0;".to_string(), false),
+            ("0x1008 ret".to_string(), false),
+        ]
+    );
+}
@@ -7,17 +7,18 @@ use crate::{
     DebugPanel,
     persistence::DebuggerPaneItem,
     session::running::variable_list::{
-        AddWatch, CollapseSelectedEntry, ExpandSelectedEntry, RemoveWatch,
+        AddWatch, CollapseSelectedEntry, EditVariable, EditWatchExpression, ExpandSelectedEntry,
+        RemoveWatch,
     },
     tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
 };
 use collections::HashMap;
 use dap::{
     Scope, StackFrame, Variable,
-    requests::{Evaluate, Initialize, Launch, Scopes, StackTrace, Variables},
+    requests::{Evaluate, Initialize, Launch, Scopes, SetExpression, StackTrace, Variables},
 };
 use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
-use menu::{SelectFirst, SelectNext, SelectPrevious};
+use menu::{Confirm, SelectFirst, SelectNext, SelectPrevious};
 use project::{FakeFs, Project};
 use serde_json::json;
 use ui::SharedString;
@@ -2340,3 +2341,223 @@ async fn test_refresh_watchers(executor: BackgroundExecutor, cx: &mut TestAppCon
         assert_eq!(3, watcher.variables_reference);
     });
 }
+
+#[gpui::test]
+async fn test_edit_watcher_value_and_expression(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    let test_file_content = r#"
+        const variable1 = "Value 1";
+        const variable2 = "Value 2";
+    "#
+    .unindent();
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+           "src": {
+               "test.js": test_file_content,
+           }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_set_expression: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<dap::requests::Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "Stack Frame 1".into(),
+                source: Some(dap::Source {
+                    name: Some("test.js".into()),
+                    path: Some(path!("/project/src/test.js").into()),
+                    source_reference: None,
+                    presentation_hint: None,
+                    origin: None,
+                    sources: None,
+                    adapter_data: None,
+                    checksums: None,
+                }),
+                line: 1,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: None,
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+
+    let value_was_set = Arc::new(AtomicBool::new(false));
+
+    client.on_request::<Evaluate, _>({
+        let value_was_set = value_was_set.clone();
+        move |_, args| {
+            let result = match args.expression.as_str() {
+                "variable1" if value_was_set.load(Ordering::SeqCst) => "42",
+                "variable1" => "value 1",
+                "variable2" => "value 2",
+                expression => panic!("Unexpected watch expression: {expression}"),
+            };
+
+            Ok(dap::EvaluateResponse {
+                result: result.to_owned(),
+                type_: None,
+                presentation_hint: None,
+                variables_reference: 0,
+                named_variables: None,
+                indexed_variables: None,
+                memory_reference: None,
+                value_location_reference: None,
+            })
+        }
+    });
+
+    client.on_request::<SetExpression, _>({
+        let value_was_set = value_was_set.clone();
+        move |_, args| {
+            assert_eq!("variable1", args.expression);
+            assert_eq!("42", args.value);
+            assert_eq!(Some(1), args.frame_id);
+
+            value_was_set.store(true, Ordering::SeqCst);
+
+            Ok(dap::SetExpressionResponse {
+                value: "42".to_owned(),
+                type_: None,
+                presentation_hint: None,
+                variables_reference: None,
+                named_variables: None,
+                indexed_variables: None,
+                memory_reference: None,
+                value_location_reference: None,
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            let running = item.running_state().clone();
+
+            let variable_list = running.update(cx, |state, cx| {
+                // have to do this because the variable list pane should be shown/active
+                // for testing the variable list
+                state.activate_item(DebuggerPaneItem::Variables, window, cx);
+
+                state.variable_list().clone()
+            });
+            variable_list.update(cx, |_, cx| cx.focus_self(window));
+            running
+        });
+    cx.run_until_parked();
+
+    session
+        .update(cx, |session, cx| {
+            session.add_watcher("variable1".into(), 1, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.variable_list().update(cx, |_, cx| {
+            cx.dispatch_action(&SelectFirst);
+            cx.dispatch_action(&EditVariable);
+        });
+    });
+    cx.run_until_parked();
+
+    cx.simulate_input("42");
+    cx.dispatch_action(Confirm);
+    cx.run_until_parked();
+
+    assert!(value_was_set.load(Ordering::SeqCst));
+    session.update(cx, |session, _| {
+        let watcher = session
+            .watchers()
+            .get(&SharedString::from("variable1"))
+            .unwrap();
+
+        assert_eq!("42", watcher.value.to_string());
+    });
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.variable_list().update(cx, |_, cx| {
+            cx.dispatch_action(&SelectFirst);
+            cx.dispatch_action(&EditWatchExpression);
+        });
+    });
+    cx.run_until_parked();
+
+    cx.simulate_input("variable2");
+    cx.dispatch_action(Confirm);
+    cx.run_until_parked();
+
+    session.update(cx, |session, _| {
+        assert!(
+            !session
+                .watchers()
+                .contains_key(&SharedString::from("variable1"))
+        );
+
+        let watcher = session
+            .watchers()
+            .get(&SharedString::from("variable2"))
+            .unwrap();
+
+        assert_eq!("value 2", watcher.value.to_string());
+        assert_eq!("variable2", watcher.expression.to_string());
+    });
+}
@@ -52,6 +52,7 @@ use workspace::notifications::{ErrorMessagePrompt, NotificationId, show_app_noti
 pub mod cursor_excerpt;
 pub mod example_spec;
 mod license_detection;
+pub mod local_fim;
 pub mod mercury;
 mod onboarding_modal;
 pub mod open_ai_response;
@@ -185,6 +186,7 @@ pub enum EditPredictionModel {
     Zeta2,
     Sweep,
     Mercury,
+    Local,
 }
 
 pub struct EditPredictionModelInput {
@@ -1105,7 +1107,9 @@ impl EditPredictionStore {
                     return;
                 }
             }
-            EditPredictionModel::Sweep | EditPredictionModel::Mercury => return,
+            EditPredictionModel::Sweep
+            | EditPredictionModel::Mercury
+            | EditPredictionModel::Local => return,
         }
 
         let Some(project_state) = self.projects.get_mut(&project.entity_id()) else {
@@ -1259,7 +1263,9 @@ impl EditPredictionStore {
                     return;
                 }
             }
-            EditPredictionModel::Sweep | EditPredictionModel::Mercury => return,
+            EditPredictionModel::Sweep
+            | EditPredictionModel::Mercury
+            | EditPredictionModel::Local => return,
         }
 
         self.reject_predictions_tx
@@ -1664,6 +1670,7 @@ impl EditPredictionStore {
             EditPredictionModel::Zeta2 => zeta2::request_prediction_with_zeta2(self, inputs, cx),
             EditPredictionModel::Sweep => self.sweep_ai.request_prediction_with_sweep(inputs, cx),
             EditPredictionModel::Mercury => self.mercury.request_prediction(inputs, cx),
+            EditPredictionModel::Local => local_fim::request_prediction_with_local_fim(inputs, cx),
         };
 
         cx.spawn(async move |this, cx| {
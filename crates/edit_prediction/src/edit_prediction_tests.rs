@@ -2150,6 +2150,80 @@ fn test_compute_diff_between_snapshots(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_local_fim_prediction(cx: &mut TestAppContext) {
+    let (ep_store, _requests) = init_test_with_fake_client(cx);
+    let captured_request: Arc<Mutex<Option<(String, serde_json::Value)>>> = Default::default();
+    let local_http_client = FakeHttpClient::create({
+        let captured_request = captured_request.clone();
+        move |req| {
+            let captured_request = captured_request.clone();
+            async move {
+                let uri = req.uri().to_string();
+                let mut body = String::new();
+                req.into_body().read_to_string(&mut body).await?;
+                *captured_request.lock() = Some((uri, serde_json::from_str(&body).unwrap()));
+                Ok(Response::builder()
+                    .status(200)
+                    .body(json!({ "response": "x + y\n" }).to_string().into())
+                    .unwrap())
+            }
+        }
+    });
+    cx.update(|cx| cx.set_http_client(local_http_client));
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/project",
+        json!({
+            "math.rs": "fn add(x: i32, y: i32) -> i32 {\n    \n}\n"
+        }),
+    )
+    .await;
+    let project = Project::test(fs, vec![path!("/project").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            let path = project
+                .find_project_path(path!("/project/math.rs"), cx)
+                .unwrap();
+            project.open_buffer(path, cx)
+        })
+        .await
+        .unwrap();
+    let cursor = buffer.read_with(cx, |buffer, _| buffer.anchor_before(Point::new(1, 4)));
+
+    ep_store.update(cx, |ep_store, cx| {
+        ep_store.set_edit_prediction_model(EditPredictionModel::Local);
+        ep_store.register_buffer(&buffer, &project, cx);
+    });
+    let prediction = ep_store
+        .update(cx, |ep_store, cx| {
+            ep_store.request_prediction(&project, &buffer, cursor, Default::default(), cx)
+        })
+        .await
+        .unwrap()
+        .unwrap()
+        .prediction
+        .unwrap();
+
+    let (uri, body) = captured_request.lock().clone().unwrap();
+    assert_eq!(uri, "http://localhost:11434/api/generate");
+    assert_eq!(body["model"], "qwen2.5-coder:1.5b");
+    assert_eq!(body["raw"], true);
+    assert_eq!(
+        body["prompt"],
+        "<|file_sep|>project/math.rs\n<|fim_prefix|>fn add(x: i32, y: i32) -> i32 {\n    <|fim_suffix|>\n}\n<|fim_middle|>"
+    );
+
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit(prediction.edits.iter().cloned(), None, cx)
+    });
+    assert_eq!(
+        buffer.read_with(cx, |buffer, _| buffer.text()),
+        "fn add(x: i32, y: i32) -> i32 {\n    x + y\n}\n"
+    );
+}
+
 #[ctor::ctor]
 fn init_logger() {
     zlog::init_test();
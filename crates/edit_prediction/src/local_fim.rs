@@ -0,0 +1,401 @@
+use crate::{
+    DebugEvent, EditPredictionFinishedDebugEvent, EditPredictionId, EditPredictionModelInput,
+    EditPredictionStartedDebugEvent, prediction::EditPredictionResult,
+};
+use anyhow::{Context as _, Result};
+use futures::AsyncReadExt as _;
+use gpui::{
+    App, AppContext as _, Task,
+    http_client::{self, AsyncBody, Method},
+};
+use language::{
+    OffsetRangeExt as _, ToOffset, ToPoint as _,
+    language_settings::{
+        FimPromptFormat, LocalEditPredictionApi, LocalEditPredictionSettings, all_language_settings,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write as _, path::Path, sync::Arc, time::Instant};
+use zeta_prompt::ZetaPromptInput;
+
+const MAX_EDITABLE_TOKENS: usize = 512;
+const MAX_CONTEXT_TOKENS: usize = 1024;
+
+pub(crate) fn request_prediction_with_local_fim(
+    EditPredictionModelInput {
+        buffer,
+        snapshot,
+        position,
+        events,
+        related_files,
+        debug_tx,
+        ..
+    }: EditPredictionModelInput,
+    cx: &mut App,
+) -> Task<Result<Option<EditPredictionResult>>> {
+    let settings = all_language_settings(None, cx)
+        .edit_predictions
+        .local
+        .clone();
+    let full_path: Arc<Path> = snapshot
+        .file()
+        .map(|file| file.full_path(cx))
+        .unwrap_or_else(|| "untitled".into())
+        .into();
+
+    let http_client = cx.http_client();
+    let cursor_point = position.to_point(&snapshot);
+    let buffer_snapshotted_at = Instant::now();
+    let active_buffer = buffer.clone();
+
+    let result = cx.background_spawn(async move {
+        let (editable_range, context_range) =
+            crate::cursor_excerpt::editable_and_context_ranges_for_cursor_position(
+                cursor_point,
+                &snapshot,
+                MAX_EDITABLE_TOKENS,
+                MAX_CONTEXT_TOKENS,
+            );
+        let context_offset_range = context_range.to_offset(&snapshot);
+        let editable_offset_range = editable_range.to_offset(&snapshot);
+        let cursor_offset = cursor_point.to_offset(&snapshot);
+
+        let inputs = ZetaPromptInput {
+            events,
+            related_files,
+            cursor_offset_in_excerpt: cursor_offset - context_offset_range.start,
+            cursor_path: full_path,
+            cursor_excerpt: snapshot
+                .text_for_range(context_range)
+                .collect::<String>()
+                .into(),
+            editable_range_in_excerpt: (editable_offset_range.start - context_offset_range.start)
+                ..(editable_offset_range.end - context_offset_range.start),
+        };
+
+        let prompt = build_prompt(settings.prompt_format, &inputs);
+
+        if let Some(debug_tx) = &debug_tx {
+            debug_tx
+                .unbounded_send(DebugEvent::EditPredictionStarted(
+                    EditPredictionStartedDebugEvent {
+                        buffer: active_buffer.downgrade(),
+                        prompt: Some(prompt.clone()),
+                        position,
+                    },
+                ))
+                .ok();
+        }
+
+        let completion = request_completion(&settings, prompt, http_client.as_ref()).await?;
+        let response_received_at = Instant::now();
+
+        if let Some(debug_tx) = &debug_tx {
+            debug_tx
+                .unbounded_send(DebugEvent::EditPredictionFinished(
+                    EditPredictionFinishedDebugEvent {
+                        buffer: active_buffer.downgrade(),
+                        model_output: Some(completion.clone()),
+                        position,
+                    },
+                ))
+                .ok();
+        }
+
+        let completion = completion.trim_end();
+        let mut edits = Vec::new();
+        if !completion.is_empty() {
+            let anchor = snapshot.anchor_after(cursor_offset);
+            edits.push((anchor..anchor, Arc::from(completion)));
+        }
+
+        anyhow::Ok((edits, snapshot, response_received_at, inputs))
+    });
+
+    cx.spawn(async move |cx| {
+        let (edits, old_snapshot, response_received_at, inputs) =
+            result.await.context("Local edit prediction failed")?;
+        anyhow::Ok(Some(
+            EditPredictionResult::new(
+                EditPredictionId(uuid::Uuid::new_v4().to_string().into()),
+                &buffer,
+                &old_snapshot,
+                edits.into(),
+                buffer_snapshotted_at,
+                response_received_at,
+                inputs,
+                cx,
+            )
+            .await,
+        ))
+    })
+}
+
+async fn request_completion(
+    settings: &LocalEditPredictionSettings,
+    prompt: String,
+    http_client: &dyn http_client::HttpClient,
+) -> Result<String> {
+    let api_url = settings.api_url.trim_end_matches('/');
+    let stop = stop_tokens(settings.prompt_format);
+    let (uri, body) = match settings.api {
+        LocalEditPredictionApi::Ollama => (
+            format!("{api_url}/api/generate"),
+            serde_json::to_vec(&OllamaGenerateRequest {
+                model: &settings.model,
+                prompt,
+                raw: true,
+                stream: false,
+                options: OllamaGenerateOptions {
+                    num_predict: settings.max_tokens,
+                    temperature: 0.0,
+                    stop,
+                },
+            })?,
+        ),
+        LocalEditPredictionApi::OpenAiCompatible => (
+            format!("{api_url}/completions"),
+            serde_json::to_vec(&CompletionRequest {
+                model: &settings.model,
+                prompt,
+                max_tokens: settings.max_tokens,
+                temperature: 0.0,
+                stop,
+            })?,
+        ),
+    };
+
+    let request = http_client::Request::builder()
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .method(Method::POST)
+        .body(AsyncBody::from(body))
+        .context("Failed to create request")?;
+
+    let mut response = http_client
+        .send(request)
+        .await
+        .context("Failed to send request")?;
+
+    let mut body: Vec<u8> = Vec::new();
+    response
+        .body_mut()
+        .read_to_end(&mut body)
+        .await
+        .context("Failed to read response body")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Request failed with status: {:?}\nBody: {}",
+            response.status(),
+            String::from_utf8_lossy(&body),
+        );
+    }
+
+    match settings.api {
+        LocalEditPredictionApi::Ollama => {
+            let response: OllamaGenerateResponse =
+                serde_json::from_slice(&body).context("Failed to parse response")?;
+            Ok(response.response)
+        }
+        LocalEditPredictionApi::OpenAiCompatible => {
+            let response: CompletionResponse =
+                serde_json::from_slice(&body).context("Failed to parse response")?;
+            Ok(response
+                .choices
+                .into_iter()
+                .next()
+                .map(|choice| choice.text)
+                .unwrap_or_default())
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OllamaGenerateRequest<'a> {
+    model: &'a str,
+    prompt: String,
+    raw: bool,
+    stream: bool,
+    options: OllamaGenerateOptions,
+}
+
+#[derive(Serialize)]
+struct OllamaGenerateOptions {
+    num_predict: u32,
+    temperature: f32,
+    stop: &'static [&'static str],
+}
+
+#[derive(Deserialize)]
+struct OllamaGenerateResponse {
+    response: String,
+}
+
+#[derive(Serialize)]
+struct CompletionRequest<'a> {
+    model: &'a str,
+    prompt: String,
+    max_tokens: u32,
+    temperature: f32,
+    stop: &'static [&'static str],
+}
+
+#[derive(Deserialize)]
+struct CompletionResponse {
+    choices: Vec<CompletionChoice>,
+}
+
+#[derive(Deserialize)]
+struct CompletionChoice {
+    text: String,
+}
+
+fn stop_tokens(format: FimPromptFormat) -> &'static [&'static str] {
+    match format {
+        FimPromptFormat::QwenCoder => &[
+            "<|endoftext|>",
+            "<|file_sep|>",
+            "<|fim_prefix|>",
+            "<|fim_suffix|>",
+            "<|fim_middle|>",
+            "<|repo_name|>",
+        ],
+        FimPromptFormat::StarCoder => &[
+            "<|endoftext|>",
+            "<file_sep>",
+            "<fim_prefix>",
+            "<fim_suffix>",
+            "<fim_middle>",
+        ],
+        FimPromptFormat::DeepseekCoder => &[
+            "<｜end▁of▁sentence｜>",
+            "<｜fim▁begin｜>",
+            "<｜fim▁hole｜>",
+            "<｜fim▁end｜>",
+        ],
+    }
+}
+
+/// Builds a fill-in-the-middle prompt in which the related files retrieved for the cursor come
+/// before the excerpt around the cursor, so that the model can use them as context.
+fn build_prompt(format: FimPromptFormat, inputs: &ZetaPromptInput) -> String {
+    let excerpt = &inputs.cursor_excerpt;
+    let (prefix, suffix) = excerpt.split_at(inputs.cursor_offset_in_excerpt);
+    let cursor_path = inputs.cursor_path.to_string_lossy();
+    let related_files = inputs.related_files.iter().map(|related_file| {
+        let text = related_file
+            .excerpts
+            .iter()
+            .map(|excerpt| excerpt.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n...\n");
+        (related_file.path.to_string_lossy(), text)
+    });
+
+    let mut prompt = String::new();
+    match format {
+        FimPromptFormat::QwenCoder => {
+            for (path, text) in related_files {
+                write!(prompt, "<|file_sep|>{path}\n{text}\n").ok();
+            }
+            write!(
+                prompt,
+                "<|file_sep|>{cursor_path}\n<|fim_prefix|>{prefix}<|fim_suffix|>{suffix}<|fim_middle|>"
+            )
+            .ok();
+        }
+        FimPromptFormat::StarCoder => {
+            for (path, text) in related_files {
+                write!(prompt, "<file_sep>{path}\n{text}\n").ok();
+            }
+            write!(
+                prompt,
+                "<file_sep>{cursor_path}\n<fim_prefix>{prefix}<fim_suffix>{suffix}<fim_middle>"
+            )
+            .ok();
+        }
+        FimPromptFormat::DeepseekCoder => {
+            prompt.push_str("<｜fim▁begin｜>");
+            for (path, text) in related_files {
+                write!(prompt, "# {path}\n{text}\n").ok();
+            }
+            write!(
+                prompt,
+                "# {cursor_path}\n{prefix}<｜fim▁hole｜>{suffix}<｜fim▁end｜>"
+            )
+            .ok();
+        }
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use zeta_prompt::{RelatedExcerpt, RelatedFile};
+
+    #[test]
+    fn test_build_prompt() {
+        let inputs = ZetaPromptInput {
+            events: Vec::new(),
+            related_files: vec![RelatedFile {
+                path: Path::new("project/src/point.rs").into(),
+                max_row: 3,
+                excerpts: vec![RelatedExcerpt {
+                    row_range: 0..3,
+                    text: "struct Point {\n    x: i32,\n    y: i32,\n}".into(),
+                }],
+            }]
+            .into(),
+            cursor_offset_in_excerpt: 24,
+            cursor_path: Path::new("project/src/main.rs").into(),
+            cursor_excerpt: "fn main() {\n    let p = \n}\n".into(),
+            editable_range_in_excerpt: 0..27,
+        };
+
+        assert_eq!(
+            build_prompt(FimPromptFormat::QwenCoder, &inputs),
+            indoc! {"
+                <|file_sep|>project/src/point.rs
+                struct Point {
+                    x: i32,
+                    y: i32,
+                }
+                <|file_sep|>project/src/main.rs
+                <|fim_prefix|>fn main() {
+                    let p = <|fim_suffix|>
+                }
+                <|fim_middle|>"}
+        );
+        assert_eq!(
+            build_prompt(FimPromptFormat::StarCoder, &inputs),
+            indoc! {"
+                <file_sep>project/src/point.rs
+                struct Point {
+                    x: i32,
+                    y: i32,
+                }
+                <file_sep>project/src/main.rs
+                <fim_prefix>fn main() {
+                    let p = <fim_suffix>
+                }
+                <fim_middle>"}
+        );
+        assert_eq!(
+            build_prompt(FimPromptFormat::DeepseekCoder, &inputs),
+            indoc! {"
+                <｜fim▁begin｜># project/src/point.rs
+                struct Point {
+                    x: i32,
+                    y: i32,
+                }
+                # project/src/main.rs
+                fn main() {
+                    let p = <｜fim▁hole｜>
+                }
+                <｜fim▁end｜>"}
+        );
+    }
+}
@@ -349,8 +349,12 @@ actions!(
     [
         /// Runs program execution to the current cursor position.
         RunToCursor,
+        /// Moves program execution to the current cursor position without running the code in between.
+        SetNextStatement,
         /// Evaluates the selected text in the debugger context.
-        EvaluateSelectedText
+        EvaluateSelectedText,
+        /// Adds the selected text to the debugger's watch list.
+        AddSelectionToWatch
     ]
 );
 
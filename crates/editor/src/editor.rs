@@ -1181,6 +1181,8 @@ pub struct Editor {
     tasks_update_task: Option<Task<()>>,
     breakpoint_store: Option<Entity<BreakpointStore>>,
     gutter_breakpoint_indicator: (Option<PhantomBreakpointIndicator>, Option<Task<()>>),
+    /// The row the current execution position marker is being dragged to, if any.
+    execution_position_drag: Option<DisplayRow>,
     hovered_diff_hunk_row: Option<DisplayRow>,
     pull_diagnostics_task: Task<()>,
     pull_diagnostics_background_task: Task<()>,
@@ -2344,6 +2346,7 @@ impl Editor {
 
             breakpoint_store,
             gutter_breakpoint_indicator: (None, None),
+            execution_position_drag: None,
             hovered_diff_hunk_row: None,
             _subscriptions: (!is_minimap)
                 .then(|| {
@@ -8459,6 +8462,7 @@ impl Editor {
         };
 
         let run_to_cursor = window.is_action_available(&RunToCursor, cx);
+        let set_next_statement = window.is_action_available(&SetNextStatement, cx);
 
         let toggle_state_msg = breakpoint.as_ref().map_or(None, |bp| match bp.1.state {
             BreakpointState::Enabled => Some("Disable"),
@@ -8487,6 +8491,24 @@ impl Editor {
 
                         window.dispatch_action(Box::new(RunToCursor), cx);
                     })
+                    .when(!set_next_statement, |this| this.separator())
+                })
+                .when(set_next_statement, |this| {
+                    let weak_editor = weak_editor.clone();
+                    this.entry("Set next statement", None, move |window, cx| {
+                        weak_editor
+                            .update(cx, |editor, cx| {
+                                editor.change_selections(
+                                    SelectionEffects::no_scroll(),
+                                    window,
+                                    cx,
+                                    |s| s.select_ranges([Point::new(row, 0)..Point::new(row, 0)]),
+                                );
+                            })
+                            .ok();
+
+                        window.dispatch_action(Box::new(SetNextStatement), cx);
+                    })
                     .separator()
                 })
                 .when_some(toggle_state_msg, |this, msg| {
@@ -8573,6 +8595,82 @@ impl Editor {
         })
     }
 
+    /// Whether the active debug session's adapter can move execution to another line.
+    fn supports_set_next_statement(&self, cx: &App) -> bool {
+        self.project
+            .as_ref()
+            .and_then(|project| project.read(cx).active_debug_session(cx))
+            .is_some_and(|(session, _)| {
+                session
+                    .read(cx)
+                    .capabilities()
+                    .supports_goto_targets_request
+                    .unwrap_or_default()
+            })
+    }
+
+    fn render_execution_position_marker(
+        &self,
+        row: DisplayRow,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let is_dragging = self.execution_position_drag.is_some();
+        let marker = IconButton::new("execution_position_marker", IconName::ArrowRight)
+            .icon_size(IconSize::XSmall)
+            .size(ui::ButtonSize::None)
+            .icon_color(if is_dragging {
+                Color::Hint
+            } else {
+                Color::Debugger
+            })
+            .style(ButtonStyle::Transparent)
+            .on_right_click(cx.listener(move |editor, event: &ClickEvent, window, cx| {
+                editor.set_breakpoint_context_menu(row, None, event.position(), window, cx);
+            }))
+            .when(!is_dragging, |this| {
+                this.tooltip(Tooltip::text("Drag to set the next statement"))
+            });
+
+        div()
+            .cursor_grab()
+            .child(marker)
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |editor, _, window, cx| {
+                    editor.execution_position_drag = Some(row);
+                    window.prevent_default();
+                    cx.stop_propagation();
+                    cx.notify();
+                }),
+            )
+            .into_any_element()
+    }
+
+    fn active_debug_display_row(&self, snapshot: &DisplaySnapshot) -> Option<DisplayRow> {
+        let highlight = self
+            .highlighted_rows
+            .get(&TypeId::of::<ActiveDebugLine>())?
+            .last()?;
+        Some(highlight.range.start.to_display_point(snapshot).row())
+    }
+
+    /// Moves the cursor to `row` and asks the debugger to continue execution from there,
+    /// without running the code in between.
+    pub(crate) fn set_next_statement_at_row(
+        &mut self,
+        row: DisplayRow,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.snapshot(window, cx);
+        let point = DisplayPoint::new(row, 0).to_point(&snapshot.display_snapshot);
+        self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(point.row, 0)..Point::new(point.row, 0)])
+        });
+        self.focus_handle
+            .dispatch_action(&SetNextStatement, window, cx);
+    }
+
     fn render_breakpoint(
         &self,
         position: Anchor,
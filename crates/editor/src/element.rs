@@ -907,6 +907,15 @@ impl EditorElement {
         window: &mut Window,
         cx: &mut Context<Editor>,
     ) {
+        if let Some(row) = editor.execution_position_drag.take() {
+            if editor.active_debug_display_row(&position_map.snapshot) != Some(row) {
+                editor.set_next_statement_at_row(row, window, cx);
+            }
+            cx.stop_propagation();
+            cx.notify();
+            return;
+        }
+
         let text_hitbox = &position_map.text_hitbox;
         let end_selection = editor.has_pending_selection();
         let pending_nonempty_selections = editor.has_pending_nonempty_selection();
@@ -1059,6 +1068,19 @@ impl EditorElement {
         window: &mut Window,
         cx: &mut Context<Editor>,
     ) {
+        if editor.execution_position_drag.is_some() {
+            let row = position_map
+                .point_for_position(event.position)
+                .previous_valid
+                .row();
+            if editor.execution_position_drag != Some(row) {
+                editor.execution_position_drag = Some(row);
+                cx.notify();
+            }
+            cx.stop_propagation();
+            return;
+        }
+
         if !editor.has_pending_selection()
             && matches!(editor.selection_drag_state, SelectionDragState::None)
         {
@@ -3024,6 +3046,49 @@ impl EditorElement {
         })
     }
 
+    fn layout_execution_position_marker(
+        &self,
+        line_height: Pixels,
+        range: Range<DisplayRow>,
+        scroll_position: gpui::Point<ScrollOffset>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        display_hunks: &[(DisplayDiffHunk, Option<Hitbox>)],
+        snapshot: &EditorSnapshot,
+        breakpoints: &HashMap<DisplayRow, (Anchor, Breakpoint, Option<BreakpointSessionState>)>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            if !editor.supports_set_next_statement(cx) {
+                return None;
+            }
+            let active_row = editor.active_debug_display_row(snapshot)?;
+            let display_row = editor.execution_position_drag.unwrap_or(active_row);
+            if !range.contains(&display_row) {
+                return None;
+            }
+            // A breakpoint on the current line stays in place so it can still be toggled; the
+            // marker only covers it while it's being dragged over it.
+            if editor.execution_position_drag.is_none() && breakpoints.contains_key(&display_row) {
+                return None;
+            }
+
+            let marker = editor.render_execution_position_marker(display_row, cx);
+            Some(prepaint_gutter_button(
+                marker,
+                display_row,
+                line_height,
+                gutter_dimensions,
+                scroll_position,
+                gutter_hitbox,
+                display_hunks,
+                window,
+                cx,
+            ))
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_run_indicators(
         &self,
@@ -7976,7 +8041,7 @@ impl AcceptEditPredictionBinding {
 }
 
 fn prepaint_gutter_button(
-    button: IconButton,
+    button: impl IntoElement,
     row: DisplayRow,
     line_height: Pixels,
     gutter_dimensions: &GutterDimensions,
@@ -10051,7 +10116,23 @@ impl Element for EditorElement {
                     let show_breakpoints = snapshot
                         .show_breakpoints
                         .unwrap_or(gutter_settings.breakpoints);
-                    let breakpoints = if show_breakpoints {
+                    let execution_position_marker = if show_breakpoints {
+                        self.layout_execution_position_marker(
+                            line_height,
+                            start_row..end_row,
+                            scroll_position,
+                            &gutter_dimensions,
+                            &gutter_hitbox,
+                            &display_hunks,
+                            &snapshot,
+                            &breakpoint_rows,
+                            window,
+                            cx,
+                        )
+                    } else {
+                        None
+                    };
+                    let mut breakpoints = if show_breakpoints {
                         self.layout_breakpoints(
                             line_height,
                             start_row..end_row,
@@ -10068,6 +10149,7 @@ impl Element for EditorElement {
                     } else {
                         Vec::new()
                     };
+                    breakpoints.extend(execution_position_marker);
 
                     self.layout_signature_help(
                         &hitbox,
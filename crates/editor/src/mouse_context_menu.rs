@@ -1,8 +1,9 @@
 use crate::{
-    Copy, CopyAndTrim, CopyPermalinkToLine, Cut, DisplayPoint, DisplaySnapshot, Editor,
-    EvaluateSelectedText, FindAllReferences, GoToDeclaration, GoToDefinition, GoToImplementation,
-    GoToTypeDefinition, Paste, Rename, RevealInFileManager, RunToCursor, SelectMode,
-    SelectionEffects, SelectionExt, ToDisplayPoint, ToggleCodeActions,
+    AddSelectionToWatch, Copy, CopyAndTrim, CopyPermalinkToLine, Cut, DisplayPoint,
+    DisplaySnapshot, Editor, EvaluateSelectedText, FindAllReferences, GoToDeclaration,
+    GoToDefinition, GoToImplementation, GoToTypeDefinition, Paste, Rename, RevealInFileManager,
+    RunToCursor, SelectMode, SelectionEffects, SelectionExt, SetNextStatement, ToDisplayPoint,
+    ToggleCodeActions,
     actions::{Format, FormatSelections},
     selections_collection::SelectionsCollection,
 };
@@ -215,7 +216,9 @@ pub fn deploy_context_menu(
             });
 
         let evaluate_selection = window.is_action_available(&EvaluateSelectedText, cx);
+        let add_selection_to_watch = window.is_action_available(&AddSelectionToWatch, cx);
         let run_to_cursor = window.is_action_available(&RunToCursor, cx);
+        let set_next_statement = window.is_action_available(&SetNextStatement, cx);
         let disable_ai = DisableAiSettings::get_global(cx).disable_ai;
 
         ui::ContextMenu::build(window, cx, |menu, _window, _cx| {
@@ -224,11 +227,19 @@ pub fn deploy_context_menu(
                 .when(run_to_cursor, |builder| {
                     builder.action("Run to Cursor", Box::new(RunToCursor))
                 })
+                .when(set_next_statement, |builder| {
+                    builder.action("Set Next Statement", Box::new(SetNextStatement))
+                })
                 .when(evaluate_selection && has_selections, |builder| {
                     builder.action("Evaluate Selection", Box::new(EvaluateSelectedText))
                 })
+                .when(add_selection_to_watch && has_selections, |builder| {
+                    builder.action("Add Selection to Watch", Box::new(AddSelectionToWatch))
+                })
                 .when(
-                    run_to_cursor || (evaluate_selection && has_selections),
+                    run_to_cursor
+                        || set_next_statement
+                        || ((evaluate_selection || add_selection_to_watch) && has_selections),
                     |builder| builder.separator(),
                 )
                 .action("Go to Definition", Box::new(GoToDefinition))
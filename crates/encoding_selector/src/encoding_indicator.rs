@@ -0,0 +1,99 @@
+use editor::Editor;
+use gpui::{Action as _, Corner, Entity, Subscription, WeakEntity};
+use ui::{ContextMenu, PopoverMenu, Tooltip, prelude::*};
+use workspace::{StatusBarSettings, StatusItemView, item::ItemHandle, item::Settings};
+
+use crate::{
+    EncodingAction, EncodingSelector, ReopenWithEncoding, SaveWithEncoding, encoding_label,
+};
+
+#[derive(Default)]
+pub struct EncodingIndicator {
+    encoding: Option<SharedString>,
+    active_editor: Option<WeakEntity<Editor>>,
+    _observe_active_editor: Option<Subscription>,
+}
+
+impl EncodingIndicator {
+    fn update(&mut self, editor: Entity<Editor>, _: &mut Window, cx: &mut Context<Self>) {
+        self.encoding = None;
+        self.active_editor = None;
+
+        if let Some((_, buffer, _)) = editor.read(cx).active_excerpt(cx) {
+            let buffer = buffer.read(cx);
+            if buffer.file().and_then(|file| file.as_local()).is_some() {
+                self.encoding = Some(encoding_label(buffer.encoding(), buffer.has_bom()));
+                self.active_editor = Some(editor.downgrade());
+            }
+        }
+
+        cx.notify();
+    }
+}
+
+impl Render for EncodingIndicator {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if !StatusBarSettings::get_global(cx).encoding_button {
+            return div();
+        }
+        let (Some(encoding), Some(editor)) = (self.encoding.clone(), self.active_editor.clone())
+        else {
+            return div();
+        };
+
+        div().child(
+            PopoverMenu::new("encoding-menu")
+                .menu(move |window, cx| {
+                    let editor = editor.clone();
+                    Some(ContextMenu::build(window, cx, move |menu, _, _| {
+                        menu.entry(
+                            "Reopen with Encoding",
+                            Some(ReopenWithEncoding.boxed_clone()),
+                            {
+                                let editor = editor.clone();
+                                move |window, cx| {
+                                    EncodingSelector::toggle(
+                                        &editor,
+                                        EncodingAction::Reopen,
+                                        window,
+                                        cx,
+                                    )
+                                }
+                            },
+                        )
+                        .entry(
+                            "Save with Encoding",
+                            Some(SaveWithEncoding.boxed_clone()),
+                            move |window, cx| {
+                                EncodingSelector::toggle(&editor, EncodingAction::Save, window, cx)
+                            },
+                        )
+                    }))
+                })
+                .anchor(Corner::BottomRight)
+                .trigger_with_tooltip(
+                    Button::new("change-encoding", encoding).label_size(LabelSize::Small),
+                    Tooltip::text("Select Encoding"),
+                ),
+        )
+    }
+}
+
+impl StatusItemView for EncodingIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.downcast::<Editor>()) {
+            self._observe_active_editor = Some(cx.observe_in(&editor, window, Self::update));
+            self.update(editor, window, cx);
+        } else {
+            self.encoding = None;
+            self.active_editor = None;
+            self._observe_active_editor = None;
+        }
+        cx.notify();
+    }
+}
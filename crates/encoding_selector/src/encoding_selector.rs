@@ -0,0 +1,525 @@
+mod encoding_indicator;
+
+use editor::Editor;
+use encoding_rs::Encoding;
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, PromptLevel, Task, WeakEntity,
+    actions,
+};
+use language::{Buffer, Rope};
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::ModalView;
+
+pub use encoding_indicator::EncodingIndicator;
+
+actions!(
+    encoding_selector,
+    [
+        /// Reloads the active file from disk, decoding it with a chosen encoding.
+        ReopenWithEncoding,
+        /// Saves the active file, encoding it with a chosen encoding.
+        SaveWithEncoding
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(EncodingSelector::register).detach();
+}
+
+/// The encodings offered by the selector, in the order they are listed.
+const ENCODINGS: &[(&Encoding, &str)] = &[
+    (encoding_rs::UTF_8, "Unicode (UTF-8)"),
+    (encoding_rs::UTF_16LE, "Unicode (UTF-16 LE)"),
+    (encoding_rs::UTF_16BE, "Unicode (UTF-16 BE)"),
+    (encoding_rs::WINDOWS_1252, "Western (Windows 1252)"),
+    (encoding_rs::ISO_8859_15, "Western (ISO 8859-15)"),
+    (encoding_rs::MACINTOSH, "Western (Mac Roman)"),
+    (encoding_rs::WINDOWS_1250, "Central European (Windows 1250)"),
+    (encoding_rs::ISO_8859_2, "Central European (ISO 8859-2)"),
+    (encoding_rs::WINDOWS_1251, "Cyrillic (Windows 1251)"),
+    (encoding_rs::ISO_8859_5, "Cyrillic (ISO 8859-5)"),
+    (encoding_rs::KOI8_R, "Cyrillic (KOI8-R)"),
+    (encoding_rs::KOI8_U, "Cyrillic (KOI8-U)"),
+    (encoding_rs::IBM866, "Cyrillic (CP 866)"),
+    (encoding_rs::WINDOWS_1253, "Greek (Windows 1253)"),
+    (encoding_rs::ISO_8859_7, "Greek (ISO 8859-7)"),
+    (encoding_rs::WINDOWS_1254, "Turkish (Windows 1254)"),
+    (encoding_rs::WINDOWS_1255, "Hebrew (Windows 1255)"),
+    (encoding_rs::ISO_8859_8, "Hebrew (ISO 8859-8)"),
+    (encoding_rs::WINDOWS_1256, "Arabic (Windows 1256)"),
+    (encoding_rs::ISO_8859_6, "Arabic (ISO 8859-6)"),
+    (encoding_rs::WINDOWS_1257, "Baltic (Windows 1257)"),
+    (encoding_rs::ISO_8859_4, "Baltic (ISO 8859-4)"),
+    (encoding_rs::ISO_8859_13, "Baltic (ISO 8859-13)"),
+    (encoding_rs::WINDOWS_1258, "Vietnamese (Windows 1258)"),
+    (encoding_rs::WINDOWS_874, "Thai (Windows 874)"),
+    (encoding_rs::SHIFT_JIS, "Japanese (Shift JIS)"),
+    (encoding_rs::EUC_JP, "Japanese (EUC-JP)"),
+    (encoding_rs::ISO_2022_JP, "Japanese (ISO-2022-JP)"),
+    (encoding_rs::GBK, "Simplified Chinese (GBK)"),
+    (encoding_rs::GB18030, "Simplified Chinese (GB 18030)"),
+    (encoding_rs::BIG5, "Traditional Chinese (Big5)"),
+    (encoding_rs::EUC_KR, "Korean (EUC-KR)"),
+];
+
+/// Returns a short label for an encoding, as shown in the status bar.
+pub fn encoding_label(encoding: &'static Encoding, has_bom: bool) -> SharedString {
+    let name = if encoding == encoding_rs::UTF_16LE {
+        "UTF-16 LE"
+    } else if encoding == encoding_rs::UTF_16BE {
+        "UTF-16 BE"
+    } else {
+        encoding.name()
+    };
+    if has_bom {
+        format!("{name} with BOM").into()
+    } else {
+        name.into()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EncodingAction {
+    Reopen,
+    Save,
+}
+
+#[derive(Clone, Copy)]
+struct EncodingOption {
+    encoding: &'static Encoding,
+    has_bom: bool,
+    label: &'static str,
+}
+
+impl EncodingOption {
+    fn all(action: EncodingAction) -> Vec<Self> {
+        let mut options = Vec::with_capacity(ENCODINGS.len() + 3);
+        for &(encoding, label) in ENCODINGS {
+            options.push(Self {
+                encoding,
+                has_bom: false,
+                label,
+            });
+            // Whether a file has a BOM is detected when it is read, so it's only a choice when saving.
+            if action == EncodingAction::Save && is_unicode(encoding) {
+                options.push(Self {
+                    encoding,
+                    has_bom: true,
+                    label,
+                });
+            }
+        }
+        options
+    }
+
+    fn display_name(&self) -> String {
+        let label = if self.has_bom {
+            format!("{} with BOM", self.label)
+        } else {
+            self.label.to_string()
+        };
+        format!("{label} — {}", self.encoding.name())
+    }
+}
+
+pub struct EncodingSelector {
+    picker: Entity<Picker<EncodingSelectorDelegate>>,
+}
+
+impl EncodingSelector {
+    fn register(editor: &mut Editor, _window: Option<&mut Window>, cx: &mut Context<Editor>) {
+        let editor_handle = cx.weak_entity();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |_: &ReopenWithEncoding, window, cx| {
+                    Self::toggle(&editor_handle, EncodingAction::Reopen, window, cx);
+                }
+            })
+            .detach();
+        editor
+            .register_action(move |_: &SaveWithEncoding, window, cx| {
+                Self::toggle(&editor_handle, EncodingAction::Save, window, cx);
+            })
+            .detach();
+    }
+
+    fn toggle(
+        editor: &WeakEntity<Editor>,
+        action: EncodingAction,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some((workspace, buffer)) = editor
+            .update(cx, |editor, cx| {
+                Some((editor.workspace()?, editor.active_excerpt(cx)?.1))
+            })
+            .ok()
+            .flatten()
+        else {
+            return;
+        };
+        // Encodings are only tracked for files on this machine's disk.
+        if buffer
+            .read(cx)
+            .file()
+            .and_then(|file| file.as_local())
+            .is_none()
+        {
+            return;
+        }
+
+        workspace.update(cx, |workspace, cx| {
+            let project = workspace.project().clone();
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                EncodingSelector::new(action, buffer, project, window, cx)
+            });
+        })
+    }
+
+    fn new(
+        action: EncodingAction,
+        buffer: Entity<Buffer>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate =
+            EncodingSelectorDelegate::new(cx.entity().downgrade(), action, buffer, project, cx);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+impl Render for EncodingSelector {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl Focusable for EncodingSelector {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for EncodingSelector {}
+impl ModalView for EncodingSelector {}
+
+struct EncodingSelectorDelegate {
+    encoding_selector: WeakEntity<EncodingSelector>,
+    action: EncodingAction,
+    buffer: Entity<Buffer>,
+    project: Entity<Project>,
+    current: (&'static Encoding, bool),
+    options: Vec<EncodingOption>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl EncodingSelectorDelegate {
+    fn new(
+        encoding_selector: WeakEntity<EncodingSelector>,
+        action: EncodingAction,
+        buffer: Entity<Buffer>,
+        project: Entity<Project>,
+        cx: &App,
+    ) -> Self {
+        let current = {
+            let buffer = buffer.read(cx);
+            (buffer.encoding(), buffer.has_bom())
+        };
+        let options = EncodingOption::all(action);
+        let candidates = options
+            .iter()
+            .enumerate()
+            .map(|(id, option)| StringMatchCandidate::new(id, &option.display_name()))
+            .collect::<Vec<_>>();
+        let mut this = Self {
+            encoding_selector,
+            action,
+            buffer,
+            project,
+            current,
+            options,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        this.selected_index = this
+            .options
+            .iter()
+            .position(|option| this.is_current(option))
+            .unwrap_or(0);
+        this
+    }
+
+    fn is_current(&self, option: &EncodingOption) -> bool {
+        option.encoding == self.current.0
+            && (self.action == EncodingAction::Reopen || option.has_bom == self.current.1)
+    }
+}
+
+impl PickerDelegate for EncodingSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        match self.action {
+            EncodingAction::Reopen => "Reopen with encoding…".into(),
+            EncodingAction::Save => "Save with encoding…".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(option) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|mat| self.options.get(mat.candidate_id))
+            .copied()
+        {
+            match self.action {
+                EncodingAction::Reopen => {
+                    reopen_with_encoding(self.buffer.clone(), option.encoding, window, cx)
+                }
+                EncodingAction::Save => save_with_encoding(
+                    self.buffer.clone(),
+                    self.project.clone(),
+                    option,
+                    window,
+                    cx,
+                ),
+            }
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.encoding_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let option = self.options.get(mat.candidate_id)?;
+
+        let mut list_item = ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(selected)
+            .child(HighlightedLabel::new(
+                mat.string.clone(),
+                mat.positions.clone(),
+            ));
+
+        if self.is_current(option) {
+            list_item = list_item.end_slot(Icon::new(IconName::Check).color(Color::Muted));
+        }
+
+        Some(list_item)
+    }
+}
+
+fn reopen_with_encoding(
+    buffer: Entity<Buffer>,
+    encoding: &'static Encoding,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let answer = buffer.read(cx).is_dirty().then(|| {
+        window.prompt(
+            PromptLevel::Warning,
+            "Discard unsaved changes?",
+            Some("Reopening the file with another encoding discards your unsaved changes."),
+            &["Reopen", "Cancel"],
+            cx,
+        )
+    });
+    window
+        .spawn(cx, async move |cx| {
+            if let Some(answer) = answer
+                && answer.await != Ok(0)
+            {
+                return;
+            }
+            buffer
+                .update(cx, |buffer, cx| {
+                    buffer.set_encoding(encoding);
+                    buffer.reload(cx)
+                })
+                .log_err();
+        })
+        .detach();
+}
+
+fn save_with_encoding(
+    buffer: Entity<Buffer>,
+    project: Entity<Project>,
+    option: EncodingOption,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let encoding = option.encoding;
+    let text = buffer.read(cx).as_rope().clone();
+    let is_lossless = cx
+        .background_executor()
+        .spawn(async move { can_encode(encoding, &text) });
+    window
+        .spawn(cx, async move |cx| {
+            if !is_lossless.await {
+                let answer = cx.update(|window, cx| {
+                    window.prompt(
+                        PromptLevel::Warning,
+                        &format!("Some characters can't be encoded as {}", encoding.name()),
+                        Some("They will be replaced by numeric character references when saving."),
+                        &["Save Anyway", "Cancel"],
+                        cx,
+                    )
+                })?;
+                if answer.await != Ok(0) {
+                    return Ok(());
+                }
+            }
+            buffer.update(cx, |buffer, cx| {
+                buffer.set_encoding(encoding);
+                buffer.set_has_bom(option.has_bom);
+                cx.notify();
+            })?;
+            project
+                .update(cx, |project, cx| project.save_buffer(buffer, cx))?
+                .await
+        })
+        .detach_and_log_err(cx);
+}
+
+fn is_unicode(encoding: &'static Encoding) -> bool {
+    encoding == encoding_rs::UTF_8
+        || encoding == encoding_rs::UTF_16LE
+        || encoding == encoding_rs::UTF_16BE
+}
+
+/// Returns whether every character of the text can be represented in the encoding.
+fn can_encode(encoding: &'static Encoding, text: &Rope) -> bool {
+    if is_unicode(encoding) {
+        return true;
+    }
+    let mut encoder = encoding.new_encoder();
+    let mut output = vec![0; 4096];
+    for chunk in text.chunks() {
+        let mut input = chunk;
+        loop {
+            let (result, read, _) =
+                encoder.encode_from_utf8_without_replacement(input, &mut output, false);
+            match result {
+                encoding_rs::EncoderResult::InputEmpty => break,
+                encoding_rs::EncoderResult::OutputFull => input = &input[read..],
+                encoding_rs::EncoderResult::Unmappable(_) => return false,
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can_encode() {
+        let ascii = Rope::from("fn main() {}\n");
+        let japanese = Rope::from("こんにちは\n");
+        let accented = Rope::from("Café\n");
+
+        assert!(can_encode(encoding_rs::WINDOWS_1252, &ascii));
+        assert!(can_encode(encoding_rs::WINDOWS_1252, &accented));
+        assert!(!can_encode(encoding_rs::WINDOWS_1252, &japanese));
+        assert!(can_encode(encoding_rs::SHIFT_JIS, &japanese));
+        assert!(can_encode(encoding_rs::UTF_16LE, &japanese));
+        assert!(can_encode(encoding_rs::UTF_8, &japanese));
+    }
+
+    #[test]
+    fn test_encoding_label() {
+        assert_eq!(encoding_label(encoding_rs::UTF_8, false), "UTF-8");
+        assert_eq!(encoding_label(encoding_rs::UTF_8, true), "UTF-8 with BOM");
+        assert_eq!(
+            encoding_label(encoding_rs::UTF_16LE, true),
+            "UTF-16 LE with BOM"
+        );
+        assert_eq!(encoding_label(encoding_rs::SHIFT_JIS, false), "Shift_JIS");
+    }
+}
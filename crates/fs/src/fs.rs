@@ -37,7 +37,8 @@ use rope::Rope;
 use serde::{Deserialize, Serialize};
 use smol::io::AsyncWriteExt;
 use std::{
-    io::{self, Write},
+    io::{self, Read as _, Seek as _, Write},
+    ops::Range,
     path::{Component, Path, PathBuf},
     pin::Pin,
     sync::Arc,
@@ -121,6 +122,8 @@ pub trait Fs: Send + Sync {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Loads the bytes of the file within `range`, fewer if the file ends before it.
+    async fn load_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
     async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
@@ -764,6 +767,22 @@ impl Fs for RealFs {
         Ok(bytes)
     }
 
+    async fn load_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>> {
+        let path = path.to_path_buf();
+        let bytes = self
+            .executor
+            .spawn(async move {
+                let mut file = std::fs::File::open(path)?;
+                file.seek(io::SeekFrom::Start(range.start))?;
+                let mut bytes = Vec::new();
+                file.take(range.end.saturating_sub(range.start))
+                    .read_to_end(&mut bytes)?;
+                io::Result::Ok(bytes)
+            })
+            .await?;
+        Ok(bytes)
+    }
+
     #[cfg(not(target_os = "windows"))]
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
@@ -2568,6 +2587,13 @@ impl Fs for FakeFs {
         self.load_internal(path).await
     }
 
+    async fn load_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>> {
+        let content = self.load_internal(path).await?;
+        let start = (range.start as usize).min(content.len());
+        let end = (range.end as usize).clamp(start, content.len());
+        Ok(content[start..end].to_vec())
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path.as_path());
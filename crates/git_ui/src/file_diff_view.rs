@@ -8,7 +8,7 @@ use gpui::{
     AnyElement, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, FocusHandle,
     Focusable, IntoElement, Render, Task, Window,
 };
-use language::{Buffer, LanguageRegistry};
+use language::{Buffer, Capability, LanguageRegistry};
 use project::Project;
 use std::{
     any::{Any, TypeId},
@@ -28,6 +28,7 @@ use workspace::{
 pub struct FileDiffView {
     editor: Entity<Editor>,
     old_buffer: Entity<Buffer>,
+    old_title: Option<SharedString>,
     new_buffer: Entity<Buffer>,
     buffer_changes_tx: watch::Sender<()>,
     _recalculate_diff_task: Task<Result<()>>,
@@ -35,6 +36,32 @@ pub struct FileDiffView {
 
 const RECALCULATE_DIFF_DEBOUNCE: Duration = Duration::from_millis(250);
 
+/// Opens a diff for each buffer of the workspace's project whose offline edits conflicted with
+/// changes made on the remote while the project was disconnected.
+pub fn register(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    cx.subscribe_in(
+        workspace.project(),
+        window,
+        |workspace, _, event, window, cx| {
+            if let project::Event::OfflineEditConflict {
+                buffer,
+                remote_text,
+            } = event
+            {
+                FileDiffView::open_offline_conflict(
+                    buffer.clone(),
+                    remote_text.clone(),
+                    workspace,
+                    window,
+                    cx,
+                )
+                .detach_and_log_err(cx);
+            }
+        },
+    )
+    .detach();
+}
+
 impl FileDiffView {
     pub fn open(
         old_path: PathBuf,
@@ -78,6 +105,52 @@ impl FileDiffView {
         })
     }
 
+    /// Opens a diff between a file's content on the remote and the buffer holding the version
+    /// that was edited while disconnected, so the two can be reconciled before saving.
+    pub fn open_offline_conflict(
+        buffer: Entity<Buffer>,
+        remote_text: Arc<str>,
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let workspace = workspace.weak_handle();
+        window.spawn(cx, async move |cx| {
+            let project = workspace.update(cx, |workspace, _| workspace.project().clone())?;
+            let languages = project.update(cx, |project, _| project.languages().clone())?;
+            let remote_buffer = cx.new(|cx| {
+                let mut remote_buffer = Buffer::local(remote_text, cx);
+                remote_buffer.set_language(buffer.read(cx).language().cloned(), cx);
+                remote_buffer.set_capability(Capability::ReadOnly, cx);
+                remote_buffer
+            })?;
+
+            let buffer_diff = build_buffer_diff(&remote_buffer, &buffer, languages, cx).await?;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let diff_view = cx.new(|cx| {
+                    let mut diff_view = FileDiffView::new(
+                        remote_buffer,
+                        buffer,
+                        buffer_diff,
+                        project.clone(),
+                        window,
+                        cx,
+                    );
+                    diff_view.old_title = Some("Remote".into());
+                    diff_view
+                });
+
+                let pane = workspace.active_pane();
+                pane.update(cx, |pane, cx| {
+                    pane.add_item(Box::new(diff_view.clone()), true, true, None, window, cx);
+                });
+
+                diff_view
+            })
+        })
+    }
+
     pub fn new(
         old_buffer: Entity<Buffer>,
         new_buffer: Entity<Buffer>,
@@ -122,6 +195,7 @@ impl FileDiffView {
             editor,
             buffer_changes_tx,
             old_buffer,
+            old_title: None,
             new_buffer,
             _recalculate_diff_task: cx.spawn(async move |this, cx| {
                 while buffer_changes_rx.recv().await.is_ok() {
@@ -238,7 +312,10 @@ impl Item for FileDiffView {
                 })
                 .unwrap_or_else(|| "untitled".into())
         };
-        let old_filename = title_text(&self.old_buffer);
+        let old_filename = self
+            .old_title
+            .clone()
+            .unwrap_or_else(|| title_text(&self.old_buffer).into());
         let new_filename = title_text(&self.new_buffer);
 
         format!("{old_filename} ↔ {new_filename}").into()
@@ -252,7 +329,10 @@ impl Item for FileDiffView {
                 .map(|file| file.full_path(cx).compact().to_string_lossy().into_owned())
                 .unwrap_or_else(|| "untitled".into())
         };
-        let old_path = path(&self.old_buffer);
+        let old_path = self
+            .old_title
+            .clone()
+            .unwrap_or_else(|| path(&self.old_buffer).into());
         let new_path = path(&self.new_buffer);
 
         Some(format!("{old_path} ↔ {new_path}").into())
@@ -67,8 +67,11 @@ pub fn init(cx: &mut App) {
     })
     .detach();
 
-    cx.observe_new(|workspace: &mut Workspace, _, cx| {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
         ProjectDiff::register(workspace, cx);
+        if let Some(window) = window {
+            file_diff_view::register(workspace, window, cx);
+        }
         CommitModal::register(workspace);
         git_panel::register(workspace);
         repository_selector::register(workspace);
@@ -0,0 +1,925 @@
+mod hex_file;
+mod hex_prompt;
+
+use std::{cell::Cell, ops::Range, rc::Rc};
+
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, ClickEvent, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    KeyDownEvent, MouseButton, MouseDownEvent, ScrollWheelEvent, Subscription, Task, Window,
+    actions, canvas,
+};
+use project::Project;
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::prelude::*;
+use util::{ResultExt as _, paths::PathExt as _, size::format_file_size};
+use workspace::{
+    ItemSettings, Pane, Workspace,
+    invalid_item_view::InvalidItemView,
+    item::{Item, ItemBufferKind, ItemEvent, ProjectItem, SaveOptions},
+    notifications::DetachAndPromptErr as _,
+};
+use zed_actions::hex_editor::OpenInHexEditor;
+
+pub use crate::hex_file::*;
+use crate::hex_prompt::{HexPrompt, HexPromptKind};
+
+actions!(
+    hex_editor,
+    [
+        /// Moves the cursor to the previous byte.
+        MoveLeft,
+        /// Moves the cursor to the next byte.
+        MoveRight,
+        /// Moves the cursor to the previous row.
+        MoveUp,
+        /// Moves the cursor to the next row.
+        MoveDown,
+        /// Moves the cursor up by a page.
+        PageUp,
+        /// Moves the cursor down by a page.
+        PageDown,
+        /// Moves the cursor to the start of the row.
+        MoveToRowStart,
+        /// Moves the cursor to the end of the row.
+        MoveToRowEnd,
+        /// Moves the cursor to the first byte of the file.
+        MoveToBeginning,
+        /// Moves the cursor to the last byte of the file.
+        MoveToEnd,
+        /// Switches typing between the hex and the ASCII column.
+        ToggleColumn,
+        /// Prompts for an offset to move the cursor to.
+        GoToOffset,
+        /// Prompts for a byte sequence or quoted text to search for.
+        Find,
+        /// Moves the cursor to the next match of the last search.
+        FindNext,
+    ]
+);
+
+const BYTES_PER_ROW: u64 = 16;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(open_in_hex_editor);
+    })
+    .detach();
+}
+
+/// Opens the file of the active item in the hex editor, including files that failed to open as text.
+fn open_in_hex_editor(
+    workspace: &mut Workspace,
+    _: &OpenInHexEditor,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(active_item) = workspace.active_item(cx) else {
+        return;
+    };
+    let project = workspace.project().clone();
+    let project_path = active_item.project_path(cx).or_else(|| {
+        let invalid_item = active_item.downcast::<InvalidItemView>()?;
+        let abs_path = invalid_item.read(cx).abs_path.clone();
+        project.read(cx).find_project_path(&abs_path, cx)
+    });
+    let Some(project_path) = project_path else {
+        return;
+    };
+
+    let open_hex_file = HexFile::open(&project, project_path, cx);
+    let pane = workspace.active_pane().clone();
+    cx.spawn_in(window, async move |workspace, cx| {
+        let hex_file = open_hex_file.await?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.open_project_item::<HexEditor>(
+                pane, hex_file, true, true, false, false, window, cx,
+            );
+        })
+    })
+    .detach_and_prompt_err("Failed to open the hex editor", window, cx, |_, _, _| None);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Hex,
+    Ascii,
+}
+
+pub struct HexEditor {
+    hex_file: Entity<HexFile>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    cursor: u64,
+    /// High nibble typed into the hex column, waiting for the low one.
+    pending_nibble: Option<u8>,
+    column: Column,
+    scroll_row: u64,
+    scroll_remainder: Pixels,
+    viewport_height: Rc<Cell<Pixels>>,
+    last_search: Option<Vec<u8>>,
+    search_task: Option<Task<()>>,
+    status: Option<SharedString>,
+    _subscription: Subscription,
+}
+
+impl HexEditor {
+    pub fn new(
+        hex_file: Entity<HexFile>,
+        project: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&hex_file, |_, _, event, cx| match event {
+            HexFileEvent::Edited | HexFileEvent::Saved | HexFileEvent::Reloaded => {
+                cx.emit(HexEditorEvent::DirtyChanged);
+                cx.notify();
+            }
+            HexFileEvent::PagesLoaded => cx.notify(),
+        });
+        Self {
+            hex_file,
+            project,
+            focus_handle: cx.focus_handle(),
+            cursor: 0,
+            pending_nibble: None,
+            column: Column::Hex,
+            scroll_row: 0,
+            scroll_remainder: Pixels::ZERO,
+            viewport_height: Rc::default(),
+            last_search: None,
+            search_task: None,
+            status: None,
+            _subscription: subscription,
+        }
+    }
+
+    pub fn cursor(&self) -> u64 {
+        self.cursor
+    }
+
+    fn row_height(&self, cx: &App) -> Pixels {
+        let settings = ThemeSettings::get_global(cx);
+        (settings.buffer_font_size(cx) * settings.line_height()).round()
+    }
+
+    fn row_count(&self, cx: &App) -> u64 {
+        self.hex_file.read(cx).len().div_ceil(BYTES_PER_ROW)
+    }
+
+    fn visible_rows(&self, window: &Window, cx: &App) -> u64 {
+        let mut height = self.viewport_height.get();
+        if height <= Pixels::ZERO {
+            height = window.viewport_size().height;
+        }
+        ((height / self.row_height(cx)).floor() as u64).max(1)
+    }
+
+    pub fn move_cursor_to(&mut self, offset: u64, window: &Window, cx: &mut Context<Self>) {
+        let len = self.hex_file.read(cx).len();
+        self.cursor = offset.min(len.saturating_sub(1));
+        self.pending_nibble = None;
+        self.status = None;
+
+        let row = self.cursor / BYTES_PER_ROW;
+        let visible_rows = self.visible_rows(window, cx);
+        if row < self.scroll_row {
+            self.scroll_row = row;
+        } else if row >= self.scroll_row + visible_rows {
+            self.scroll_row = row + 1 - visible_rows;
+        }
+        cx.notify();
+    }
+
+    fn move_by(&mut self, delta: i64, window: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.cursor.saturating_add_signed(delta);
+        if delta < 0 && self.cursor < delta.unsigned_abs() {
+            self.move_cursor_to(self.cursor % BYTES_PER_ROW, window, cx);
+        } else if offset < self.hex_file.read(cx).len() {
+            self.move_cursor_to(offset, window, cx);
+        }
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-1, window, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(1, window, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-(BYTES_PER_ROW as i64), window, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(BYTES_PER_ROW as i64, window, cx);
+    }
+
+    fn page_up(&mut self, _: &PageUp, window: &mut Window, cx: &mut Context<Self>) {
+        let rows = self.visible_rows(window, cx);
+        self.scroll_row = self.scroll_row.saturating_sub(rows);
+        let offset = self.cursor.saturating_sub(rows * BYTES_PER_ROW);
+        self.move_cursor_to(offset, window, cx);
+    }
+
+    fn page_down(&mut self, _: &PageDown, window: &mut Window, cx: &mut Context<Self>) {
+        let rows = self.visible_rows(window, cx);
+        self.scroll_row = (self.scroll_row + rows).min(self.row_count(cx).saturating_sub(rows));
+        let offset = self.cursor.saturating_add(rows * BYTES_PER_ROW);
+        self.move_cursor_to(offset, window, cx);
+    }
+
+    fn move_to_row_start(
+        &mut self,
+        _: &MoveToRowStart,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_cursor_to(self.cursor - self.cursor % BYTES_PER_ROW, window, cx);
+    }
+
+    fn move_to_row_end(&mut self, _: &MoveToRowEnd, window: &mut Window, cx: &mut Context<Self>) {
+        let row_start = self.cursor - self.cursor % BYTES_PER_ROW;
+        self.move_cursor_to(row_start + BYTES_PER_ROW - 1, window, cx);
+    }
+
+    fn move_to_beginning(
+        &mut self,
+        _: &MoveToBeginning,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_cursor_to(0, window, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_to(u64::MAX, window, cx);
+    }
+
+    fn toggle_column(&mut self, _: &ToggleColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Ascii,
+            Column::Ascii => Column::Hex,
+        };
+        self.pending_nibble = None;
+        cx.notify();
+    }
+
+    fn go_to_offset(&mut self, _: &GoToOffset, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_prompt(HexPromptKind::GoToOffset, window, cx);
+    }
+
+    fn find(&mut self, _: &Find, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_prompt(HexPromptKind::Find, window, cx);
+    }
+
+    fn find_next(&mut self, _: &FindNext, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(pattern) = self.last_search.clone() {
+            self.search(pattern, self.cursor + 1, window, cx);
+        } else {
+            self.show_prompt(HexPromptKind::Find, window, cx);
+        }
+    }
+
+    fn show_prompt(&mut self, kind: HexPromptKind, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = window.root::<Workspace>().flatten() else {
+            return;
+        };
+        let hex_editor = cx.entity().downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                HexPrompt::new(kind, hex_editor, window, cx)
+            });
+        });
+    }
+
+    /// Moves the cursor to the next occurrence of `pattern`, starting at `start`.
+    pub fn search(
+        &mut self,
+        pattern: Vec<u8>,
+        start: u64,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.last_search = Some(pattern.clone());
+        self.status = Some("Searching…".into());
+        let search = self.hex_file.read(cx).find(pattern, start, cx);
+        self.search_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = search.await;
+            this.update_in(cx, |this, window, cx| {
+                match result {
+                    Ok(Some(offset)) => this.move_cursor_to(offset, window, cx),
+                    Ok(None) => this.status = Some("No matches".into()),
+                    Err(error) => this.status = Some(format!("Search failed: {error}").into()),
+                }
+                cx.notify();
+            })
+            .log_err();
+        }));
+        cx.notify();
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.platform || modifiers.function {
+            return;
+        }
+        let Some(key_char) = event.keystroke.key_char.as_deref() else {
+            return;
+        };
+        let mut chars = key_char.chars();
+        let (Some(ch), None) = (chars.next(), chars.next()) else {
+            return;
+        };
+        let Some(current) = self.hex_file.read(cx).byte(self.cursor) else {
+            return;
+        };
+
+        let (value, advance) = match self.column {
+            Column::Hex => {
+                let Some(digit) = ch.to_digit(16).map(|digit| digit as u8) else {
+                    return;
+                };
+                match self.pending_nibble.take() {
+                    Some(high) => ((high << 4) | digit, true),
+                    None => {
+                        self.pending_nibble = Some(digit);
+                        ((digit << 4) | (current & 0x0f), false)
+                    }
+                }
+            }
+            Column::Ascii => {
+                if !ch.is_ascii() || ch.is_ascii_control() {
+                    return;
+                }
+                (ch as u8, true)
+            }
+        };
+        cx.stop_propagation();
+
+        let cursor = self.cursor;
+        self.hex_file
+            .update(cx, |hex_file, cx| hex_file.set_byte(cursor, value, cx));
+        if advance {
+            self.move_cursor_to(self.cursor + 1, window, cx);
+        }
+        cx.notify();
+    }
+
+    fn scroll_wheel(
+        &mut self,
+        event: &ScrollWheelEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let row_height = self.row_height(cx);
+        self.scroll_remainder += event.delta.pixel_delta(row_height).y;
+        let rows = (self.scroll_remainder / row_height).trunc();
+        if rows == 0. {
+            return;
+        }
+        self.scroll_remainder -= row_height * rows;
+        let max_scroll_row = self
+            .row_count(cx)
+            .saturating_sub(self.visible_rows(window, cx));
+        self.scroll_row = if rows > 0. {
+            self.scroll_row.saturating_sub(rows as u64)
+        } else {
+            (self.scroll_row + (-rows) as u64).min(max_scroll_row)
+        };
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn click_byte(
+        &mut self,
+        offset: u64,
+        column: Column,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.column = column;
+        self.move_cursor_to(offset, window, cx);
+        window.focus(&self.focus_handle, cx);
+    }
+
+    fn render_row(&self, row: u64, offset_digits: usize, cx: &Context<Self>) -> AnyElement {
+        let hex_file = self.hex_file.read(cx);
+        let colors = cx.theme().colors();
+        let modified = cx.theme().status().modified;
+        let row_start = row * BYTES_PER_ROW;
+        let row_bytes = row_start..(row_start + BYTES_PER_ROW).min(hex_file.len());
+
+        let cell_background = |offset: u64, column: Column| {
+            if offset != self.cursor {
+                None
+            } else if column == self.column {
+                Some(colors.element_selected)
+            } else {
+                Some(colors.element_hover)
+            }
+        };
+
+        let mut hex = h_flex().gap_1p5();
+        let mut ascii = h_flex();
+        for offset in row_bytes.clone() {
+            let byte = hex_file.byte(offset);
+            let text_color = if hex_file.is_edited(offset) {
+                modified
+            } else if byte.is_none() {
+                colors.text_disabled
+            } else {
+                colors.text
+            };
+
+            let hex_text = match byte {
+                Some(byte) if offset == self.cursor && self.pending_nibble.is_some() => {
+                    format!("{:X}_", byte >> 4)
+                }
+                Some(byte) => format!("{byte:02X}"),
+                None => "··".to_string(),
+            };
+            hex = hex.child(
+                div()
+                    .id(("hex-byte", offset as usize))
+                    .when(offset % BYTES_PER_ROW == BYTES_PER_ROW / 2, |this| {
+                        this.ml_2()
+                    })
+                    .text_color(text_color)
+                    .when_some(cell_background(offset, Column::Hex), |this, background| {
+                        this.bg(background)
+                    })
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _: &MouseDownEvent, window, cx| {
+                            this.click_byte(offset, Column::Hex, window, cx)
+                        }),
+                    )
+                    .child(hex_text),
+            );
+
+            let ascii_text = match byte {
+                Some(byte) if byte.is_ascii_graphic() || byte == b' ' => (byte as char).to_string(),
+                Some(_) => ".".to_string(),
+                None => "·".to_string(),
+            };
+            ascii = ascii.child(
+                div()
+                    .id(("ascii-byte", offset as usize))
+                    .text_color(text_color)
+                    .when_some(
+                        cell_background(offset, Column::Ascii),
+                        |this, background| this.bg(background),
+                    )
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _: &MouseDownEvent, window, cx| {
+                            this.click_byte(offset, Column::Ascii, window, cx)
+                        }),
+                    )
+                    .child(ascii_text),
+            );
+        }
+        // Keep the ASCII column aligned on the last, partial row.
+        for missing in row_bytes.end..row_start + BYTES_PER_ROW {
+            hex = hex.child(
+                div()
+                    .when(missing % BYTES_PER_ROW == BYTES_PER_ROW / 2, |this| {
+                        this.ml_2()
+                    })
+                    .child("  "),
+            );
+        }
+
+        h_flex()
+            .h(self.row_height(cx))
+            .gap_4()
+            .child(
+                div()
+                    .text_color(colors.text_muted)
+                    .child(format!("{row_start:0offset_digits$X}")),
+            )
+            .child(hex)
+            .child(ascii)
+            .into_any_element()
+    }
+
+    fn render_header(&self, offset_digits: usize, cx: &App) -> impl IntoElement {
+        h_flex()
+            .h(self.row_height(cx))
+            .gap_4()
+            .text_color(cx.theme().colors().text_muted)
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(format!("{:offset_digits$}", "Offset"))
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .children((0..BYTES_PER_ROW).map(|column| {
+                        div()
+                            .when(column == BYTES_PER_ROW / 2, |this| this.ml_2())
+                            .child(format!("{column:02X}"))
+                    })),
+            )
+            .child("ASCII")
+    }
+
+    fn render_status(&self, cx: &App) -> impl IntoElement {
+        let hex_file = self.hex_file.read(cx);
+        let position = format!(
+            "Offset 0x{:X} ({}) of {}",
+            self.cursor,
+            self.cursor,
+            format_file_size(hex_file.len(), false)
+        );
+        let column = match self.column {
+            Column::Hex => "Hex",
+            Column::Ascii => "ASCII",
+        };
+        let edits = match hex_file.edit_count() {
+            0 => None,
+            1 => Some("1 byte modified".to_string()),
+            count => Some(format!("{count} bytes modified")),
+        };
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_4()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(position).size(LabelSize::Small))
+            .child(
+                Label::new(format!("Overwrite · {column}"))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .children(edits.map(|edits| {
+                Label::new(edits)
+                    .size(LabelSize::Small)
+                    .color(Color::Modified)
+            }))
+            .children(self.status.clone().map(|status| {
+                Label::new(status)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+            }))
+    }
+}
+
+pub enum HexEditorEvent {
+    DirtyChanged,
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let buffer_font = settings.buffer_font.clone();
+        let font_size = settings.buffer_font_size(cx);
+
+        let len = self.hex_file.read(cx).len();
+        let offset_digits = if len > u32::MAX as u64 { 16 } else { 8 };
+        let visible_rows = self.visible_rows(window, cx);
+        let rows = self.scroll_row..(self.scroll_row + visible_rows).min(self.row_count(cx));
+        let visible_bytes: Range<u64> = rows.start * BYTES_PER_ROW..rows.end * BYTES_PER_ROW;
+        self.hex_file
+            .update(cx, |hex_file, cx| hex_file.load_range(visible_bytes, cx));
+
+        let viewport_height = self.viewport_height.clone();
+        v_flex()
+            .key_context("HexEditor")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::page_up))
+            .on_action(cx.listener(Self::page_down))
+            .on_action(cx.listener(Self::move_to_row_start))
+            .on_action(cx.listener(Self::move_to_row_end))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::toggle_column))
+            .on_action(cx.listener(Self::go_to_offset))
+            .on_action(cx.listener(Self::find))
+            .on_action(cx.listener(Self::find_next))
+            .on_key_down(cx.listener(Self::key_down))
+            .on_scroll_wheel(cx.listener(Self::scroll_wheel))
+            .font(buffer_font)
+            .text_size(font_size)
+            .child(div().px_2().child(self.render_header(offset_digits, cx)))
+            .child(
+                div()
+                    .id("hex-rows")
+                    .relative()
+                    .flex_1()
+                    .px_2()
+                    .overflow_hidden()
+                    .child(
+                        canvas(
+                            move |bounds, window, _| {
+                                if viewport_height.replace(bounds.size.height) != bounds.size.height
+                                {
+                                    window.refresh();
+                                }
+                            },
+                            |_, _, _, _| {},
+                        )
+                        .absolute()
+                        .size_full(),
+                    )
+                    .children(rows.map(|row| self.render_row(row, offset_digits, cx)))
+                    .on_click(cx.listener(|this, _: &ClickEvent, window, cx| {
+                        window.focus(&this.focus_handle, cx);
+                    })),
+            )
+            .child(self.render_status(cx))
+    }
+}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::DirtyChanged => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.hex_file.entity_id(), self.hex_file.read(cx))
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.hex_file
+            .read(cx)
+            .abs_path()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_default()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.hex_file.read(cx).abs_path().compact();
+        Some(abs_path.to_string_lossy().into_owned().into())
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.hex_file.read(cx).abs_path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        project::ProjectItem::is_dirty(self.hex_file.read(cx))
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _: SaveOptions,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.hex_file.update(cx, |hex_file, cx| hex_file.save(cx))
+    }
+
+    fn reload(
+        &mut self,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.hex_file.update(cx, |hex_file, cx| hex_file.reload(cx))
+    }
+
+    fn can_split(&self) -> bool {
+        true
+    }
+
+    fn clone_on_split(
+        &self,
+        _: Option<workspace::WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Entity<Self>>> {
+        let hex_file = self.hex_file.clone();
+        let project = self.project.clone();
+        Task::ready(Some(cx.new(|cx| Self::new(hex_file, project, window, cx))))
+    }
+
+    fn buffer_kind(&self, _: &App) -> ItemBufferKind {
+        ItemBufferKind::Singleton
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = HexFile;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self::new(item, project, window, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex_file::tests::MemoryFile;
+    use gpui::{TestAppContext, VisualTestContext};
+    use parking_lot::Mutex;
+    use project::{FakeFs, ProjectPath, RealFs, WorktreeId};
+    use settings::SettingsStore;
+    use std::{path::PathBuf, sync::Arc};
+    use util::{rel_path::rel_path, test::TempTree};
+    use workspace::AppState;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+    }
+
+    async fn open_hex_editor(
+        bytes: Vec<u8>,
+        cx: &mut TestAppContext,
+    ) -> (
+        Entity<HexEditor>,
+        Entity<HexFile>,
+        Arc<MemoryFile>,
+        &mut VisualTestContext,
+    ) {
+        init_test(cx);
+        let project = Project::test(FakeFs::new(cx.executor()), [], cx).await;
+        let len = bytes.len() as u64;
+        let source = Arc::new(MemoryFile(Mutex::new(bytes)));
+        let hex_file = cx.new(|_| {
+            HexFile::new(
+                PathBuf::from("/dir/data.bin"),
+                ProjectPath {
+                    worktree_id: WorktreeId::from_usize(0),
+                    path: rel_path("data.bin").into(),
+                },
+                None,
+                source.clone(),
+                len,
+            )
+        });
+        let (hex_editor, cx) = cx.add_window_view({
+            let hex_file = hex_file.clone();
+            |window, cx| HexEditor::new(hex_file, project, window, cx)
+        });
+        hex_editor.update_in(cx, |hex_editor, window, cx| {
+            window.focus(&hex_editor.focus_handle, cx);
+        });
+        cx.run_until_parked();
+        (hex_editor, hex_file, source, cx)
+    }
+
+    #[gpui::test]
+    async fn test_overwrite_bytes(cx: &mut TestAppContext) {
+        let (hex_editor, hex_file, source, cx) =
+            open_hex_editor(b"Hello, hex editor!".to_vec(), cx).await;
+        assert_eq!(
+            hex_file.read_with(cx, |hex_file, _| hex_file.byte(0)),
+            Some(b'H')
+        );
+
+        // The first nibble replaces the high half of the byte, the second completes it.
+        cx.simulate_input("6");
+        hex_file.read_with(cx, |hex_file, _| assert_eq!(hex_file.byte(0), Some(0x68)));
+        hex_editor.read_with(cx, |hex_editor, _| assert_eq!(hex_editor.cursor(), 0));
+        cx.simulate_input("A");
+        hex_file.read_with(cx, |hex_file, _| assert_eq!(hex_file.byte(0), Some(0x6A)));
+        hex_editor.read_with(cx, |hex_editor, cx| {
+            assert_eq!(hex_editor.cursor(), 1);
+            assert!(hex_editor.is_dirty(cx));
+        });
+
+        // Characters that aren't hex digits are ignored in the hex column.
+        cx.simulate_input("z");
+        hex_file.read_with(cx, |hex_file, _| assert_eq!(hex_file.byte(1), Some(b'e')));
+
+        cx.dispatch_action(ToggleColumn);
+        cx.simulate_input("ip");
+        hex_file.read_with(cx, |hex_file, _| {
+            assert_eq!(hex_file.byte(1), Some(b'i'));
+            assert_eq!(hex_file.byte(2), Some(b'p'));
+            assert_eq!(hex_file.edit_count(), 3);
+        });
+
+        // Writing back the original value drops the edit.
+        cx.dispatch_action(MoveToBeginning);
+        cx.simulate_input("H");
+        hex_file.read_with(cx, |hex_file, _| assert_eq!(hex_file.edit_count(), 2));
+
+        hex_file
+            .update(cx, |hex_file, cx| hex_file.save(cx))
+            .await
+            .unwrap();
+        assert_eq!(*source.0.lock(), b"Hiplo, hex editor!".to_vec());
+        hex_editor.read_with(cx, |hex_editor, cx| assert!(!hex_editor.is_dirty(cx)));
+    }
+
+    #[gpui::test]
+    async fn test_move_cursor(cx: &mut TestAppContext) {
+        let (hex_editor, _, _, cx) = open_hex_editor(vec![0; 40], cx).await;
+        let cursor = |cx: &mut VisualTestContext| hex_editor.read_with(cx, |e, _| e.cursor());
+
+        cx.dispatch_action(MoveLeft);
+        assert_eq!(cursor(cx), 0);
+        cx.dispatch_action(MoveDown);
+        assert_eq!(cursor(cx), 16);
+        cx.dispatch_action(MoveRight);
+        assert_eq!(cursor(cx), 17);
+        cx.dispatch_action(MoveToRowEnd);
+        assert_eq!(cursor(cx), 31);
+        cx.dispatch_action(MoveToRowStart);
+        assert_eq!(cursor(cx), 16);
+
+        // The last row is partial, so moving down from the end of this one stays in place.
+        cx.dispatch_action(MoveToRowEnd);
+        cx.dispatch_action(MoveDown);
+        assert_eq!(cursor(cx), 31);
+        cx.dispatch_action(MoveToEnd);
+        assert_eq!(cursor(cx), 39);
+        cx.dispatch_action(MoveToRowEnd);
+        assert_eq!(cursor(cx), 39);
+        cx.dispatch_action(MoveUp);
+        assert_eq!(cursor(cx), 23);
+        cx.dispatch_action(MoveToBeginning);
+        assert_eq!(cursor(cx), 0);
+    }
+
+    #[gpui::test]
+    async fn test_open_binary_file_in_hex_editor(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+        let dir = TempTree::new(serde_json::json!({}));
+        let bytes = [0x7f, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0, 0, 0];
+        std::fs::write(dir.path().join("app.bin"), bytes).unwrap();
+        let project =
+            Project::test(Arc::new(RealFs::new(None, cx.executor())), [dir.path()], cx).await;
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let item = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("app.bin")), None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        assert!(
+            item.downcast::<InvalidItemView>().is_some(),
+            "binary files can't be opened as text"
+        );
+
+        cx.dispatch_action(OpenInHexEditor);
+        cx.run_until_parked();
+        let hex_editor = workspace
+            .read_with(cx, |workspace, cx| {
+                workspace.active_item_as::<HexEditor>(cx)
+            })
+            .expect("the file should be open in the hex editor");
+        hex_editor.read_with(cx, |hex_editor, cx| {
+            assert_eq!(hex_editor.hex_file.read(cx).len(), bytes.len() as u64);
+        });
+    }
+}
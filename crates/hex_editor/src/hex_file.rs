@@ -0,0 +1,577 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fs::{File, OpenOptions},
+    io,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result, anyhow};
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Task};
+use project::{Project, ProjectEntryId, ProjectPath};
+use util::ResultExt as _;
+
+/// Number of bytes read from disk at once.
+pub const PAGE_SIZE: u64 = 64 * 1024;
+/// Upper bound on the number of pages kept in memory, so that scrolling through
+/// a large file doesn't end up loading all of it.
+const MAX_CACHED_PAGES: usize = 256;
+const SEARCH_CHUNK_SIZE: u64 = 1024 * 1024;
+
+/// Random access to the bytes of a file.
+pub trait ByteSource: Send + Sync {
+    fn len(&self) -> io::Result<u64>;
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize>;
+    /// Writes each run of bytes at its offset, leaving the rest of the file untouched.
+    fn write_runs(&self, runs: &[(u64, Vec<u8>)]) -> io::Result<()>;
+
+    fn read_exact_at(&self, mut offset: u64, mut buf: &mut [u8]) -> io::Result<()> {
+        while !buf.is_empty() {
+            match self.read_at(offset, buf)? {
+                0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                n => {
+                    offset += n as u64;
+                    buf = &mut buf[n..];
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+struct LocalFile {
+    path: PathBuf,
+    file: File,
+}
+
+impl LocalFile {
+    fn open(path: PathBuf) -> io::Result<Self> {
+        let file = File::open(&path)?;
+        Ok(Self { path, file })
+    }
+}
+
+impl ByteSource for LocalFile {
+    fn len(&self) -> io::Result<u64> {
+        Ok(self.file.metadata()?.len())
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::FileExt::read_at(&self.file, buf, offset)
+        }
+        #[cfg(windows)]
+        {
+            std::os::windows::fs::FileExt::seek_read(&self.file, buf, offset)
+        }
+    }
+
+    fn write_runs(&self, runs: &[(u64, Vec<u8>)]) -> io::Result<()> {
+        let file = OpenOptions::new().write(true).open(&self.path)?;
+        for (offset, bytes) in runs {
+            #[cfg(unix)]
+            std::os::unix::fs::FileExt::write_all_at(&file, bytes, *offset)?;
+            #[cfg(windows)]
+            {
+                let mut written = 0;
+                while written < bytes.len() {
+                    written += std::os::windows::fs::FileExt::seek_write(
+                        &file,
+                        &bytes[written..],
+                        offset + written as u64,
+                    )?;
+                }
+            }
+        }
+        file.sync_all()
+    }
+}
+
+pub enum HexFileEvent {
+    PagesLoaded,
+    Edited,
+    Saved,
+    Reloaded,
+}
+
+/// A file opened for byte-level viewing and overwrite-mode editing.
+///
+/// The file is never read as a whole: pages are loaded as they are displayed,
+/// and edits are kept aside until they are written back in place.
+pub struct HexFile {
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+    source: Arc<dyn ByteSource>,
+    len: u64,
+    pages: HashMap<u64, Arc<[u8]>>,
+    page_order: VecDeque<u64>,
+    loading_pages: HashSet<u64>,
+    edits: BTreeMap<u64, u8>,
+}
+
+impl EventEmitter<HexFileEvent> for HexFile {}
+
+impl HexFile {
+    pub fn open(
+        project: &Entity<Project>,
+        project_path: ProjectPath,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let project = project.read(cx);
+        if !project.is_local() {
+            return Task::ready(Err(anyhow!("The hex editor only supports local files")));
+        }
+        let Some(abs_path) = project.absolute_path(&project_path, cx) else {
+            return Task::ready(Err(anyhow!(
+                "Couldn't find the absolute path of {project_path:?}"
+            )));
+        };
+        let entry_id = project
+            .entry_for_path(&project_path, cx)
+            .map(|entry| entry.id);
+
+        cx.spawn(async move |cx| {
+            let (source, len) = cx
+                .background_spawn({
+                    let abs_path = abs_path.clone();
+                    async move {
+                        let source = LocalFile::open(abs_path)?;
+                        let len = source.len()?;
+                        anyhow::Ok((source, len))
+                    }
+                })
+                .await
+                .with_context(|| format!("opening {abs_path:?}"))?;
+
+            cx.new(|_| Self::new(abs_path, project_path, entry_id, Arc::new(source), len))
+        })
+    }
+
+    pub(crate) fn new(
+        abs_path: PathBuf,
+        project_path: ProjectPath,
+        entry_id: Option<ProjectEntryId>,
+        source: Arc<dyn ByteSource>,
+        len: u64,
+    ) -> Self {
+        Self {
+            abs_path,
+            project_path,
+            entry_id,
+            source,
+            len,
+            pages: HashMap::default(),
+            page_order: VecDeque::new(),
+            loading_pages: HashSet::default(),
+            edits: BTreeMap::new(),
+        }
+    }
+
+    pub fn abs_path(&self) -> &Path {
+        &self.abs_path
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the byte at the given offset, or `None` if it hasn't been loaded yet.
+    pub fn byte(&self, offset: u64) -> Option<u8> {
+        if offset >= self.len {
+            return None;
+        }
+        if let Some(byte) = self.edits.get(&offset) {
+            return Some(*byte);
+        }
+        let page = self.pages.get(&(offset / PAGE_SIZE))?;
+        page.get((offset % PAGE_SIZE) as usize).copied()
+    }
+
+    pub fn is_edited(&self, offset: u64) -> bool {
+        self.edits.contains_key(&offset)
+    }
+
+    pub fn edit_count(&self) -> usize {
+        self.edits.len()
+    }
+
+    /// Loads the pages covering the given range that are not in memory yet.
+    pub fn load_range(&mut self, range: Range<u64>, cx: &mut Context<Self>) {
+        let end = range.end.min(self.len);
+        if range.start >= end {
+            return;
+        }
+        let pages = (range.start / PAGE_SIZE..=(end - 1) / PAGE_SIZE)
+            .filter(|page| !self.pages.contains_key(page) && !self.loading_pages.contains(page))
+            .collect::<Vec<_>>();
+        if pages.is_empty() {
+            return;
+        }
+        self.loading_pages.extend(pages.iter().copied());
+
+        let source = self.source.clone();
+        let len = self.len;
+        cx.spawn(async move |this, cx| {
+            let loaded = cx
+                .background_spawn({
+                    let pages = pages.clone();
+                    async move {
+                        pages
+                            .into_iter()
+                            .map(|page| {
+                                let start = page * PAGE_SIZE;
+                                let mut bytes = vec![0; (len - start).min(PAGE_SIZE) as usize];
+                                source.read_exact_at(start, &mut bytes)?;
+                                Ok((page, Arc::<[u8]>::from(bytes)))
+                            })
+                            .collect::<io::Result<Vec<_>>>()
+                    }
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                for page in &pages {
+                    this.loading_pages.remove(page);
+                }
+                if let Some(loaded) = loaded.log_err() {
+                    for (page, bytes) in loaded {
+                        this.insert_page(page, bytes);
+                    }
+                    cx.emit(HexFileEvent::PagesLoaded);
+                    cx.notify();
+                }
+            })
+        })
+        .detach();
+    }
+
+    fn insert_page(&mut self, page: u64, bytes: Arc<[u8]>) {
+        if self.pages.insert(page, bytes).is_none() {
+            self.page_order.push_back(page);
+        }
+        while self.pages.len() > MAX_CACHED_PAGES {
+            let Some(oldest) = self.page_order.pop_front() else {
+                break;
+            };
+            self.pages.remove(&oldest);
+        }
+    }
+
+    /// Overwrites a single byte. Writing back the original value drops the edit.
+    pub fn set_byte(&mut self, offset: u64, value: u8, cx: &mut Context<Self>) {
+        if offset >= self.len {
+            return;
+        }
+        let original = self
+            .pages
+            .get(&(offset / PAGE_SIZE))
+            .and_then(|page| page.get((offset % PAGE_SIZE) as usize).copied());
+        if original == Some(value) {
+            self.edits.remove(&offset);
+        } else {
+            self.edits.insert(offset, value);
+        }
+        cx.emit(HexFileEvent::Edited);
+        cx.notify();
+    }
+
+    pub fn save(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let edits = self.edits.clone();
+        let runs = edit_runs(&edits);
+        let source = self.source.clone();
+        cx.spawn(async move |this, cx| {
+            cx.background_spawn(async move { source.write_runs(&runs) })
+                .await
+                .context("writing edits")?;
+            this.update(cx, |this, cx| {
+                for offset in edits.keys() {
+                    let page = offset / PAGE_SIZE;
+                    if this.pages.remove(&page).is_some() {
+                        this.page_order.retain(|cached| *cached != page);
+                    }
+                }
+                // Edits made while saving are kept for the next save.
+                this.edits
+                    .retain(|offset, value| edits.get(offset) != Some(value));
+                cx.emit(HexFileEvent::Saved);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Discards unsaved edits and re-reads the file from disk.
+    pub fn reload(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let source = self.source.clone();
+        cx.spawn(async move |this, cx| {
+            let len = cx.background_spawn(async move { source.len() }).await?;
+            this.update(cx, |this, cx| {
+                this.len = len;
+                this.edits.clear();
+                this.pages.clear();
+                this.page_order.clear();
+                cx.emit(HexFileEvent::Reloaded);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Finds the next occurrence of `pattern` starting at or after `start`,
+    /// wrapping around to the beginning of the file.
+    pub fn find(&self, pattern: Vec<u8>, start: u64, cx: &App) -> Task<Result<Option<u64>>> {
+        let source = self.source.clone();
+        let edits = self.edits.clone();
+        let len = self.len;
+        cx.background_spawn(async move {
+            Ok(find_in_source(
+                source.as_ref(),
+                len,
+                &edits,
+                &pattern,
+                start.min(len),
+            )?)
+        })
+    }
+}
+
+impl project::ProjectItem for HexFile {
+    /// Files are only opened in the hex editor on request, see [`HexFile::open`].
+    fn try_open(
+        _: &Entity<Project>,
+        _: &ProjectPath,
+        _: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        None
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        !self.edits.is_empty()
+    }
+}
+
+/// Groups edits at consecutive offsets, so that they can be written with one call each.
+fn edit_runs(edits: &BTreeMap<u64, u8>) -> Vec<(u64, Vec<u8>)> {
+    let mut runs: Vec<(u64, Vec<u8>)> = Vec::new();
+    for (&offset, &value) in edits {
+        match runs.last_mut() {
+            Some((start, bytes)) if *start + bytes.len() as u64 == offset => bytes.push(value),
+            _ => runs.push((offset, vec![value])),
+        }
+    }
+    runs
+}
+
+fn find_in_source(
+    source: &dyn ByteSource,
+    len: u64,
+    edits: &BTreeMap<u64, u8>,
+    pattern: &[u8],
+    start: u64,
+) -> io::Result<Option<u64>> {
+    if pattern.is_empty() {
+        return Ok(None);
+    }
+    if let Some(found) = find_in_range(source, len, edits, pattern, start..len)? {
+        return Ok(Some(found));
+    }
+    find_in_range(source, len, edits, pattern, 0..start)
+}
+
+/// Finds the first occurrence of `pattern` that starts within `range`, reading
+/// the file in chunks that overlap by the pattern's length.
+fn find_in_range(
+    source: &dyn ByteSource,
+    len: u64,
+    edits: &BTreeMap<u64, u8>,
+    pattern: &[u8],
+    range: Range<u64>,
+) -> io::Result<Option<u64>> {
+    let overlap = pattern.len() as u64 - 1;
+    let mut buffer = Vec::new();
+    let mut chunk_start = range.start;
+    while chunk_start < range.end {
+        let chunk_end = (chunk_start + SEARCH_CHUNK_SIZE).min(range.end);
+        let read_end = (chunk_end + overlap).min(len);
+        buffer.resize((read_end - chunk_start) as usize, 0);
+        source.read_exact_at(chunk_start, &mut buffer)?;
+        for (offset, value) in edits.range(chunk_start..read_end) {
+            buffer[(offset - chunk_start) as usize] = *value;
+        }
+        if let Some(ix) = buffer
+            .windows(pattern.len())
+            .position(|window| window == pattern)
+        {
+            return Ok(Some(chunk_start + ix as u64));
+        }
+        chunk_start = chunk_end;
+    }
+    Ok(None)
+}
+
+/// Parses a search query: either quoted text, or hex bytes with optional whitespace.
+pub fn parse_byte_pattern(query: &str) -> Result<Vec<u8>> {
+    let query = query.trim();
+    if let Some(text) = query
+        .strip_prefix('"')
+        .and_then(|query| query.strip_suffix('"'))
+    {
+        anyhow::ensure!(!text.is_empty(), "Search text is empty");
+        return Ok(text.as_bytes().to_vec());
+    }
+
+    let digits = query
+        .strip_prefix("0x")
+        .unwrap_or(query)
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    anyhow::ensure!(!digits.is_empty(), "Search pattern is empty");
+    anyhow::ensure!(
+        digits.len() % 2 == 0,
+        "Hex pattern must have an even number of digits"
+    );
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let high = pair[0].to_digit(16);
+            let low = pair[1].to_digit(16);
+            match (high, low) {
+                (Some(high), Some(low)) => Ok((high * 16 + low) as u8),
+                _ => Err(anyhow!("Invalid hex byte {}{}", pair[0], pair[1])),
+            }
+        })
+        .collect()
+}
+
+/// Parses an offset given in decimal or `0x`-prefixed hex. A leading `+` or `-`
+/// makes it relative to `current`.
+pub fn parse_offset(query: &str, current: u64) -> Option<u64> {
+    let query = query.trim();
+    let (sign, number) = match query.as_bytes().first()? {
+        b'+' => (Some(true), &query[1..]),
+        b'-' => (Some(false), &query[1..]),
+        _ => (None, query),
+    };
+    let number = number.trim();
+    let value = match number
+        .strip_prefix("0x")
+        .or_else(|| number.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+        None => number.parse::<u64>().ok()?,
+    };
+    match sign {
+        Some(true) => current.checked_add(value),
+        Some(false) => current.checked_sub(value),
+        None => Some(value),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    pub(crate) struct MemoryFile(pub(crate) Mutex<Vec<u8>>);
+
+    impl ByteSource for MemoryFile {
+        fn len(&self) -> io::Result<u64> {
+            Ok(self.0.lock().len() as u64)
+        }
+
+        fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+            let data = self.0.lock();
+            let start = (offset as usize).min(data.len());
+            let n = buf.len().min(data.len() - start);
+            buf[..n].copy_from_slice(&data[start..start + n]);
+            Ok(n)
+        }
+
+        fn write_runs(&self, runs: &[(u64, Vec<u8>)]) -> io::Result<()> {
+            let mut data = self.0.lock();
+            for (offset, bytes) in runs {
+                let start = *offset as usize;
+                data[start..start + bytes.len()].copy_from_slice(bytes);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_find_across_chunks_and_edits() {
+        let len = SEARCH_CHUNK_SIZE as usize * 2;
+        let mut data = vec![0u8; len];
+        let boundary = SEARCH_CHUNK_SIZE as usize;
+        data[boundary - 2..boundary + 2].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+        let source = MemoryFile(Mutex::new(data));
+        let pattern = [0xDE, 0xAD, 0xBE, 0xEF];
+
+        let found = find_in_source(&source, len as u64, &BTreeMap::new(), &pattern, 0).unwrap();
+        assert_eq!(found, Some(boundary as u64 - 2));
+
+        // Searching past the only match wraps around to it.
+        let found = find_in_source(
+            &source,
+            len as u64,
+            &BTreeMap::new(),
+            &pattern,
+            boundary as u64,
+        )
+        .unwrap();
+        assert_eq!(found, Some(boundary as u64 - 2));
+
+        // Unsaved edits are searched instead of the bytes on disk.
+        let edits = BTreeMap::from_iter([(10, 0xDE), (11, 0xAD), (12, 0xBE), (13, 0xEF)]);
+        let found = find_in_source(&source, len as u64, &edits, &pattern, 0).unwrap();
+        assert_eq!(found, Some(10));
+        let edits = BTreeMap::from_iter([(boundary as u64, 0)]);
+        let found = find_in_source(&source, len as u64, &edits, &pattern, 0).unwrap();
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn test_edit_runs() {
+        let edits = BTreeMap::from_iter([(1, 1), (2, 2), (3, 3), (7, 7), (9, 9), (10, 10)]);
+        assert_eq!(
+            edit_runs(&edits),
+            vec![(1, vec![1, 2, 3]), (7, vec![7]), (9, vec![9, 10])]
+        );
+
+        let source = MemoryFile(Mutex::new(vec![0; 12]));
+        source.write_runs(&edit_runs(&edits)).unwrap();
+        assert_eq!(*source.0.lock(), vec![0, 1, 2, 3, 0, 0, 0, 7, 0, 9, 10, 0]);
+    }
+
+    #[test]
+    fn test_parse_queries() {
+        assert_eq!(
+            parse_byte_pattern("7f 45 4C46").unwrap(),
+            vec![0x7f, 0x45, 0x4c, 0x46]
+        );
+        assert_eq!(parse_byte_pattern("0xCAFE").unwrap(), vec![0xca, 0xfe]);
+        assert_eq!(parse_byte_pattern("\"ELF\"").unwrap(), b"ELF".to_vec());
+        assert!(parse_byte_pattern("abc").is_err());
+        assert!(parse_byte_pattern("zz").is_err());
+        assert!(parse_byte_pattern("").is_err());
+
+        assert_eq!(parse_offset("1024", 0), Some(1024));
+        assert_eq!(parse_offset("0x400", 0), Some(1024));
+        assert_eq!(parse_offset("+0x10", 32), Some(48));
+        assert_eq!(parse_offset("-16", 32), Some(16));
+        assert_eq!(parse_offset("-64", 32), None);
+        assert_eq!(parse_offset("oops", 0), None);
+    }
+}
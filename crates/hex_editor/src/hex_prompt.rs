@@ -0,0 +1,141 @@
+use editor::Editor;
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, WeakEntity,
+};
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::{HexEditor, parse_byte_pattern, parse_offset};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HexPromptKind {
+    GoToOffset,
+    Find,
+}
+
+/// A single-line prompt for the offset to jump to, or the byte pattern to search for.
+pub(crate) struct HexPrompt {
+    kind: HexPromptKind,
+    query_editor: Entity<Editor>,
+    hex_editor: WeakEntity<HexEditor>,
+    error: Option<SharedString>,
+    _subscription: Subscription,
+}
+
+impl ModalView for HexPrompt {}
+
+impl EventEmitter<DismissEvent> for HexPrompt {}
+
+impl Focusable for HexPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.query_editor.focus_handle(cx)
+    }
+}
+
+impl HexPrompt {
+    pub(crate) fn new(
+        kind: HexPromptKind,
+        hex_editor: WeakEntity<HexEditor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let query_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            let placeholder = match kind {
+                HexPromptKind::GoToOffset => "0x1F00, 7936, or +0x10",
+                HexPromptKind::Find => "7F 45 4C 46, or \"text\"",
+            };
+            editor.set_placeholder_text(placeholder, window, cx);
+            editor
+        });
+        let subscription = cx.subscribe(&query_editor, |this, _, event, cx| match event {
+            editor::EditorEvent::Blurred => cx.emit(DismissEvent),
+            editor::EditorEvent::BufferEdited => {
+                this.error = None;
+                cx.notify();
+            }
+            _ => {}
+        });
+
+        Self {
+            kind,
+            query_editor,
+            hex_editor,
+            error: None,
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(hex_editor) = self.hex_editor.upgrade() else {
+            cx.emit(DismissEvent);
+            return;
+        };
+        let query = self.query_editor.read(cx).text(cx);
+        match self.kind {
+            HexPromptKind::GoToOffset => {
+                let cursor = hex_editor.read(cx).cursor();
+                let Some(offset) = parse_offset(&query, cursor) else {
+                    self.error = Some("Not a valid offset".into());
+                    cx.notify();
+                    return;
+                };
+                hex_editor.update(cx, |hex_editor, cx| {
+                    hex_editor.move_cursor_to(offset, window, cx)
+                });
+            }
+            HexPromptKind::Find => {
+                let pattern = match parse_byte_pattern(&query) {
+                    Ok(pattern) => pattern,
+                    Err(error) => {
+                        self.error = Some(error.to_string().into());
+                        cx.notify();
+                        return;
+                    }
+                };
+                hex_editor.update(cx, |hex_editor, cx| {
+                    let start = hex_editor.cursor();
+                    hex_editor.search(pattern, start, window, cx)
+                });
+            }
+        }
+        window.focus(&hex_editor.focus_handle(cx), cx);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for HexPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let help_text = match (&self.error, self.kind) {
+            (Some(error), _) => error.clone(),
+            (None, HexPromptKind::GoToOffset) => "Go to offset".into(),
+            (None, HexPromptKind::Find) => "Find bytes".into(),
+        };
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("HexPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.query_editor.clone()),
+            )
+            .child(h_flex().px_2().py_1().child(Label::new(help_text).color(
+                if self.error.is_some() {
+                    Color::Error
+                } else {
+                    Color::Muted
+                },
+            )))
+    }
+}
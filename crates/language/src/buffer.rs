@@ -1544,7 +1544,10 @@ impl Buffer {
             };
 
             let bytes = load_bytes_task.await?;
-            let (cow, _encoding_used, _has_errors) = encoding.decode(&bytes);
+            // A byte order mark overrides the buffer's encoding, as it does when the file is first loaded.
+            let (cow, encoding_used, _has_errors) = encoding.decode(&bytes);
+            let has_bom = Encoding::for_bom(&bytes)
+                .is_some_and(|(bom_encoding, _)| bom_encoding == encoding_used);
             let new_text = cow.into_owned();
 
             let diff = this.update(cx, |this, cx| this.diff(new_text, cx))?.await;
@@ -1554,6 +1557,8 @@ impl Buffer {
                     this.apply_diff(diff, cx);
                     tx.send(this.finalize_last_transaction().cloned()).ok();
                     this.has_conflict = false;
+                    this.encoding = encoding_used;
+                    this.has_bom = has_bom;
                     this.did_reload(this.version(), this.line_ending(), new_mtime, cx);
                 } else {
                     if !diff.edits.is_empty()
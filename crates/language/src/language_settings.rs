@@ -11,9 +11,10 @@ use gpui::{App, Modifiers, SharedString};
 use itertools::{Either, Itertools};
 
 pub use settings::{
-    CompletionSettingsContent, EditPredictionProvider, EditPredictionsMode, FormatOnSave,
-    Formatter, FormatterList, InlayHintKind, LanguageSettingsContent, LspInsertMode,
-    RewrapBehavior, ShowWhitespaceSetting, SoftWrap, WordsCompletionMode,
+    CompletionSettingsContent, EditPredictionProvider, EditPredictionsMode, FimPromptFormat,
+    FormatOnSave, Formatter, FormatterList, InlayHintKind, LanguageSettingsContent,
+    LocalEditPredictionApi, LspInsertMode, RewrapBehavior, ShowWhitespaceSetting, SoftWrap,
+    WordsCompletionMode,
 };
 use settings::{RegisterSetting, Settings, SettingsLocation, SettingsStore};
 use shellexpand;
@@ -389,6 +390,8 @@ pub struct EditPredictionSettings {
     pub copilot: CopilotSettings,
     /// Settings specific to Codestral.
     pub codestral: CodestralSettings,
+    /// Settings specific to locally hosted fill-in-the-middle models.
+    pub local: LocalEditPredictionSettings,
     /// Whether edit predictions are enabled in the assistant panel.
     /// This setting has no effect if globally disabled.
     pub enabled_in_text_threads: bool,
@@ -436,6 +439,20 @@ pub struct CodestralSettings {
     pub api_url: Option<String>,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct LocalEditPredictionSettings {
+    /// The kind of API served at `api_url`.
+    pub api: LocalEditPredictionApi,
+    /// Api URL of the local model server.
+    pub api_url: String,
+    /// Model to use for completions.
+    pub model: String,
+    /// The fill-in-the-middle prompt template the model was trained with.
+    pub prompt_format: FimPromptFormat,
+    /// Maximum tokens to generate.
+    pub max_tokens: u32,
+}
+
 impl AllLanguageSettings {
     /// Returns the [`LanguageSettings`] for the language with the specified name.
     pub fn language<'a>(
@@ -662,6 +679,15 @@ impl settings::Settings for AllLanguageSettings {
             api_url: codestral.api_url,
         };
 
+        let local = edit_predictions.local.unwrap();
+        let local_settings = LocalEditPredictionSettings {
+            api: local.api.unwrap(),
+            api_url: local.api_url.unwrap(),
+            model: local.model.unwrap(),
+            prompt_format: local.prompt_format.unwrap(),
+            max_tokens: local.max_tokens.unwrap(),
+        };
+
         let enabled_in_text_threads = edit_predictions.enabled_in_text_threads.unwrap();
 
         let mut file_types: FxHashMap<Arc<str>, (GlobSet, Vec<String>)> = FxHashMap::default();
@@ -700,6 +726,7 @@ impl settings::Settings for AllLanguageSettings {
                 mode: edit_predictions_mode,
                 copilot: copilot_settings,
                 codestral: codestral_settings,
+                local: local_settings,
                 enabled_in_text_threads,
                 examples_dir: edit_predictions.examples_dir,
                 example_capture_rate: edit_predictions.example_capture_rate,
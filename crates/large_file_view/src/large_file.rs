@@ -0,0 +1,508 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context as _, Result, anyhow};
+use gpui::{App, AppContext as _, AsyncApp, Entity, EventEmitter, Task, WeakEntity};
+use project::{Project, ProjectEntryId, ProjectPath};
+use settings::Settings as _;
+use util::ResultExt as _;
+use worktree::{FileRangeReader, MAX_FILE_RANGE_LEN, WorktreeSettings};
+
+/// Every this many lines, the offset of the line start is recorded. Finding any other
+/// line means scanning forward from the closest recorded one, which keeps the index
+/// small even for files with billions of lines.
+const LINE_INDEX_STRIDE: u64 = 1024;
+const INDEX_CHUNK_SIZE: u64 = MAX_FILE_RANGE_LEN;
+const SEARCH_CHUNK_SIZE: usize = 1024 * 1024;
+const READ_CHUNK_SIZE: u64 = 64 * 1024;
+/// Lines longer than this are cut off when displayed.
+pub const MAX_DISPLAYED_LINE_LEN: usize = 4096;
+
+pub enum LargeFileEvent {
+    IndexUpdated,
+}
+
+/// A file too large to be loaded into a buffer, read on demand through its worktree.
+///
+/// Line starts are indexed in the background as the file is opened; lines become
+/// reachable as soon as the indexing has passed them.
+pub struct LargeFile {
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+    reader: FileRangeReader,
+    len: u64,
+    line_index: Vec<u64>,
+    line_count: u64,
+    indexed_bytes: u64,
+    _index_task: Task<()>,
+}
+
+impl EventEmitter<LargeFileEvent> for LargeFile {}
+
+impl LargeFile {
+    pub fn open(
+        project: &Entity<Project>,
+        project_path: ProjectPath,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let project = project.read(cx);
+        let Some(abs_path) = project.absolute_path(&project_path, cx) else {
+            return Task::ready(Err(anyhow!(
+                "Couldn't find the absolute path of {project_path:?}"
+            )));
+        };
+        let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("Couldn't find the worktree of {abs_path:?}")));
+        };
+        let Some(entry) = project.entry_for_path(&project_path, cx) else {
+            return Task::ready(Err(anyhow!("Couldn't find {abs_path:?}")));
+        };
+        let reader = worktree.read(cx).file_range_reader(&project_path.path);
+        let entry_id = Some(entry.id);
+        let len = entry.size;
+
+        Task::ready(Ok(cx.new(|cx| {
+            let index_task = cx.spawn({
+                let reader = reader.clone();
+                async move |this, cx| {
+                    Self::index_lines(reader, len, this, cx)
+                        .await
+                        .with_context(|| format!("indexing the lines of {abs_path:?}"))
+                        .log_err();
+                }
+            });
+            Self {
+                abs_path,
+                project_path,
+                entry_id,
+                reader,
+                len,
+                line_index: vec![0],
+                line_count: 1,
+                indexed_bytes: 0,
+                _index_task: index_task,
+            }
+        })))
+    }
+
+    async fn index_lines(
+        reader: FileRangeReader,
+        len: u64,
+        this: WeakEntity<Self>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let mut offset = 0;
+        let mut line_count = 1;
+        while offset < len {
+            let (new_starts, new_line_count, read) = cx
+                .background_spawn({
+                    let reader = reader.clone();
+                    async move {
+                        let chunk = reader.read(offset..offset + INDEX_CHUNK_SIZE).await?;
+                        let mut new_starts = Vec::new();
+                        let mut line_count = line_count;
+                        index_chunk(&chunk, offset, &mut line_count, &mut new_starts);
+                        anyhow::Ok((new_starts, line_count, chunk.len()))
+                    }
+                })
+                .await?;
+            if read == 0 {
+                break;
+            }
+            offset += read as u64;
+            line_count = new_line_count;
+            this.update(cx, |this, cx| {
+                this.line_index.extend(new_starts);
+                this.line_count = line_count;
+                this.indexed_bytes = offset;
+                cx.emit(LargeFileEvent::IndexUpdated);
+                cx.notify();
+            })?;
+        }
+        this.update(cx, |this, cx| {
+            this.indexed_bytes = this.len;
+            cx.emit(LargeFileEvent::IndexUpdated);
+            cx.notify();
+        })
+    }
+
+    pub fn abs_path(&self) -> &Path {
+        &self.abs_path
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of lines found so far. Only final once [`Self::is_indexed`] returns true.
+    pub fn line_count(&self) -> u64 {
+        self.line_count
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.indexed_bytes >= self.len
+    }
+
+    /// Fraction of the file scanned for line starts.
+    pub fn index_progress(&self) -> f32 {
+        if self.len == 0 {
+            1.
+        } else {
+            self.indexed_bytes as f32 / self.len as f32
+        }
+    }
+
+    /// Offset of the closest indexed line start at or before `line`, and the number of lines to skip from it.
+    fn index_position(&self, line: u64) -> (u64, u64) {
+        let slot = ((line / LINE_INDEX_STRIDE) as usize).min(self.line_index.len() - 1);
+        let base_line = slot as u64 * LINE_INDEX_STRIDE;
+        (self.line_index[slot], line - base_line)
+    }
+
+    /// Reads the given lines, cutting off long ones at [`MAX_DISPLAYED_LINE_LEN`] bytes.
+    pub fn read_lines(&self, lines: Range<u64>, cx: &App) -> Task<Result<Vec<String>>> {
+        let lines = lines.start..lines.end.min(self.line_count);
+        if lines.is_empty() {
+            return Task::ready(Ok(Vec::new()));
+        }
+        let (base_offset, skip) = self.index_position(lines.start);
+        let reader = self.reader.clone();
+        cx.background_spawn(async move {
+            read_lines(&reader, base_offset, skip, lines.end - lines.start).await
+        })
+    }
+
+    /// Finds the first line at or after `start_line` containing `query`, wrapping around
+    /// to the start of the file. The search is case-insensitive unless the query contains
+    /// uppercase characters.
+    pub fn find(&self, query: String, start_line: u64, cx: &App) -> Task<Result<Option<u64>>> {
+        let start_line = start_line.min(self.line_count - 1);
+        let (base_offset, skip) = self.index_position(start_line);
+        let reader = self.reader.clone();
+        let len = self.len;
+        cx.background_spawn(async move {
+            let case_sensitive = query.chars().any(char::is_uppercase);
+            let mut pattern = query.into_bytes();
+            if !case_sensitive {
+                pattern.make_ascii_lowercase();
+            }
+            let start_offset = skip_lines(&reader, base_offset, skip).await?;
+            if let Some(line) = find_line(
+                &reader,
+                &pattern,
+                case_sensitive,
+                start_offset..len,
+                start_line,
+            )
+            .await?
+            {
+                return Ok(Some(line));
+            }
+            find_line(&reader, &pattern, case_sensitive, 0..start_offset, 0).await
+        })
+    }
+}
+
+impl project::ProjectItem for LargeFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        let entry = project.read(cx).entry_for_path(path, cx)?;
+        let settings = WorktreeSettings::get(Some(path.into()), cx);
+        if !entry.is_file() || !settings.is_large_file(entry.size) {
+            return None;
+        }
+        Some(Self::open(project, path.clone(), cx))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        false
+    }
+}
+
+/// Records the starts of the lines in `chunk`, which begins at `chunk_offset`, in `line_index`.
+fn index_chunk(chunk: &[u8], chunk_offset: u64, line_count: &mut u64, line_index: &mut Vec<u64>) {
+    for (ix, byte) in chunk.iter().enumerate() {
+        if *byte == b'\n' {
+            if *line_count % LINE_INDEX_STRIDE == 0 {
+                line_index.push(chunk_offset + ix as u64 + 1);
+            }
+            *line_count += 1;
+        }
+    }
+}
+
+/// Returns the offset of the start of the line `skip` lines after `offset`.
+async fn skip_lines(reader: &FileRangeReader, mut offset: u64, mut skip: u64) -> Result<u64> {
+    while skip > 0 {
+        let chunk = reader.read(offset..offset + READ_CHUNK_SIZE).await?;
+        if chunk.is_empty() {
+            break;
+        }
+        match skip_newlines(&chunk, &mut skip) {
+            Some(ix) => return Ok(offset + ix as u64),
+            None => offset += chunk.len() as u64,
+        }
+    }
+    Ok(offset)
+}
+
+/// Returns the position just past the `skip`th newline in `chunk`, or subtracts the
+/// newlines in `chunk` from `skip` if there are fewer.
+fn skip_newlines(chunk: &[u8], skip: &mut u64) -> Option<usize> {
+    for (ix, byte) in chunk.iter().enumerate() {
+        if *byte == b'\n' {
+            *skip -= 1;
+            if *skip == 0 {
+                return Some(ix + 1);
+            }
+        }
+    }
+    None
+}
+
+async fn read_lines(
+    reader: &FileRangeReader,
+    offset: u64,
+    skip: u64,
+    count: u64,
+) -> Result<Vec<String>> {
+    let mut offset = skip_lines(reader, offset, skip).await?;
+    let mut lines = LineCollector::new(count);
+    while !lines.is_full() {
+        let chunk = reader.read(offset..offset + READ_CHUNK_SIZE).await?;
+        if chunk.is_empty() {
+            break;
+        }
+        offset += chunk.len() as u64;
+        lines.push(&chunk);
+    }
+    Ok(lines.finish())
+}
+
+/// Splits consecutive chunks of a file into lines without their newlines, keeping at
+/// most [`MAX_DISPLAYED_LINE_LEN`] bytes of each.
+struct LineCollector {
+    count: u64,
+    lines: Vec<String>,
+    line: Vec<u8>,
+    in_line: bool,
+}
+
+impl LineCollector {
+    fn new(count: u64) -> Self {
+        Self {
+            count,
+            lines: Vec::new(),
+            line: Vec::new(),
+            in_line: false,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.lines.len() as u64 >= self.count
+    }
+
+    fn push(&mut self, mut chunk: &[u8]) {
+        while !chunk.is_empty() && !self.is_full() {
+            let newline = chunk.iter().position(|byte| *byte == b'\n');
+            let content_len = newline.unwrap_or(chunk.len());
+            let room = MAX_DISPLAYED_LINE_LEN.saturating_sub(self.line.len());
+            self.line.extend_from_slice(&chunk[..content_len.min(room)]);
+            self.in_line = true;
+            let Some(newline) = newline else {
+                break;
+            };
+            self.end_line();
+            chunk = &chunk[newline + 1..];
+        }
+    }
+
+    fn end_line(&mut self) {
+        if self.line.last() == Some(&b'\r') {
+            self.line.pop();
+        }
+        self.lines
+            .push(String::from_utf8_lossy(&self.line).replace('\t', "    "));
+        self.line.clear();
+        self.in_line = false;
+    }
+
+    /// Returns the lines, including a last one that isn't followed by a newline.
+    fn finish(mut self) -> Vec<String> {
+        if self.in_line && !self.is_full() {
+            self.end_line();
+        }
+        self.lines
+    }
+}
+
+/// Finds the line of the first match of `pattern` starting within `range`, where `first_line`
+/// is the line number at `range.start`. A lowercase pattern is expected for case-insensitive searches.
+async fn find_line(
+    reader: &FileRangeReader,
+    pattern: &[u8],
+    case_sensitive: bool,
+    range: Range<u64>,
+    first_line: u64,
+) -> Result<Option<u64>> {
+    if pattern.is_empty() {
+        return Ok(None);
+    }
+    let overlap = pattern.len() - 1;
+    let mut line = first_line;
+    let mut chunk_start = range.start;
+    while chunk_start < range.end {
+        let chunk_len = SEARCH_CHUNK_SIZE.min((range.end - chunk_start) as usize);
+        let mut buffer = reader
+            .read(chunk_start..chunk_start + (chunk_len + overlap) as u64)
+            .await?;
+        if !case_sensitive {
+            buffer.make_ascii_lowercase();
+        }
+        if let Some(ix) = buffer
+            .windows(pattern.len())
+            .take(chunk_len)
+            .position(|window| window == pattern)
+        {
+            return Ok(Some(line + count_newlines(&buffer[..ix])));
+        }
+        line += count_newlines(&buffer[..chunk_len.min(buffer.len())]);
+        chunk_start += chunk_len as u64;
+    }
+    Ok(None)
+}
+
+fn count_newlines(bytes: &[u8]) -> u64 {
+    bytes.iter().filter(|byte| **byte == b'\n').count() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use util::path;
+
+    fn numbered_lines(count: u64) -> Vec<u8> {
+        (0..count)
+            .map(|line| format!("line {line}\n"))
+            .collect::<String>()
+            .into_bytes()
+    }
+
+    async fn fake_file(content: Vec<u8>, cx: &TestAppContext) -> FileRangeReader {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(path!("/large.log"), content).await;
+        FileRangeReader::Local {
+            fs,
+            abs_path: PathBuf::from(path!("/large.log")),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_index_and_read_lines(cx: &mut TestAppContext) {
+        let text = numbered_lines(3000);
+        let mut line_index = vec![0];
+        let mut line_count = 1;
+        // Index in uneven chunks, as line starts can fall on chunk boundaries.
+        for (ix, chunk) in text.chunks(777).enumerate() {
+            index_chunk(chunk, ix as u64 * 777, &mut line_count, &mut line_index);
+        }
+        assert_eq!(line_count, 3001);
+        assert_eq!(line_index.len(), 3);
+
+        let reader = fake_file(text, cx).await;
+        for start in [0, 1023, 1024, 2047, 2999] {
+            let slot = (start / LINE_INDEX_STRIDE) as usize;
+            let lines = read_lines(
+                &reader,
+                line_index[slot],
+                start - slot as u64 * LINE_INDEX_STRIDE,
+                2,
+            )
+            .await
+            .unwrap();
+            let expected = (start..(start + 2).min(3000))
+                .map(|line| format!("line {line}"))
+                .collect::<Vec<_>>();
+            assert_eq!(lines, expected);
+        }
+    }
+
+    #[test]
+    fn test_collect_long_lines() {
+        let mut text = vec![b'a'; MAX_DISPLAYED_LINE_LEN * 3];
+        text.extend_from_slice(b"\r\nshort\tline");
+        let mut lines = LineCollector::new(10);
+        // Lines and their `\r\n` endings can be split across chunks.
+        for chunk in text.chunks(1000) {
+            lines.push(chunk);
+        }
+        let lines = lines.finish();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), MAX_DISPLAYED_LINE_LEN);
+        assert_eq!(lines[1], "short    line");
+
+        let mut lines = LineCollector::new(10);
+        lines.push(b"one\n\ntwo\n");
+        assert_eq!(lines.finish(), ["one", "", "two"]);
+    }
+
+    #[gpui::test]
+    async fn test_find_line(cx: &mut TestAppContext) {
+        let text = numbered_lines(200_000);
+        let len = text.len() as u64;
+        let reader = fake_file(text, cx).await;
+        assert_eq!(
+            find_line(&reader, b"line 150000", true, 0..len, 0)
+                .await
+                .unwrap(),
+            Some(150_000)
+        );
+        assert_eq!(
+            find_line(&reader, b"line 7\n", false, 0..len, 0)
+                .await
+                .unwrap(),
+            Some(7)
+        );
+        assert_eq!(
+            find_line(&reader, b"missing", true, 0..len, 0)
+                .await
+                .unwrap(),
+            None
+        );
+
+        // Matches must start within the range, but may extend past its end.
+        let offset = skip_lines(&reader, 0, 10).await.unwrap();
+        assert_eq!(
+            find_line(&reader, b"line 9\nline 10", true, 0..offset, 0)
+                .await
+                .unwrap(),
+            Some(9)
+        );
+        assert_eq!(
+            find_line(&reader, b"line 1", true, offset..len, 10)
+                .await
+                .unwrap(),
+            Some(10)
+        );
+    }
+}
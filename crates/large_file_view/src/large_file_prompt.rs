@@ -0,0 +1,135 @@
+use editor::Editor;
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, WeakEntity,
+};
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::LargeFileView;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LargeFilePromptKind {
+    GoToLine,
+    Find,
+}
+
+/// A single-line prompt for the line to go to, or the text to search for.
+pub(crate) struct LargeFilePrompt {
+    kind: LargeFilePromptKind,
+    query_editor: Entity<Editor>,
+    view: WeakEntity<LargeFileView>,
+    error: Option<SharedString>,
+    _subscription: Subscription,
+}
+
+impl ModalView for LargeFilePrompt {}
+
+impl EventEmitter<DismissEvent> for LargeFilePrompt {}
+
+impl Focusable for LargeFilePrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.query_editor.focus_handle(cx)
+    }
+}
+
+impl LargeFilePrompt {
+    pub(crate) fn new(
+        kind: LargeFilePromptKind,
+        view: WeakEntity<LargeFileView>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let query_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            let placeholder = match kind {
+                LargeFilePromptKind::GoToLine => "Line number",
+                LargeFilePromptKind::Find => "Search text",
+            };
+            editor.set_placeholder_text(placeholder, window, cx);
+            editor
+        });
+        let subscription = cx.subscribe(&query_editor, |this, _, event, cx| match event {
+            editor::EditorEvent::Blurred => cx.emit(DismissEvent),
+            editor::EditorEvent::BufferEdited => {
+                this.error = None;
+                cx.notify();
+            }
+            _ => {}
+        });
+
+        Self {
+            kind,
+            query_editor,
+            view,
+            error: None,
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(view) = self.view.upgrade() else {
+            cx.emit(DismissEvent);
+            return;
+        };
+        let query = self.query_editor.read(cx).text(cx);
+        match self.kind {
+            LargeFilePromptKind::GoToLine => {
+                let Some(line) = query.trim().parse::<u64>().ok().filter(|line| *line > 0) else {
+                    self.error = Some("Not a valid line number".into());
+                    cx.notify();
+                    return;
+                };
+                view.update(cx, |view, cx| view.reveal_line(line - 1, window, cx));
+            }
+            LargeFilePromptKind::Find => {
+                if query.is_empty() {
+                    self.error = Some("Search text is empty".into());
+                    cx.notify();
+                    return;
+                }
+                view.update(cx, |view, cx| {
+                    let start = view.highlighted_line().unwrap_or(0);
+                    view.search(query, start, window, cx)
+                });
+            }
+        }
+        window.focus(&view.focus_handle(cx), cx);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for LargeFilePrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let help_text = match (&self.error, self.kind) {
+            (Some(error), _) => error.clone(),
+            (None, LargeFilePromptKind::GoToLine) => "Go to line".into(),
+            (None, LargeFilePromptKind::Find) => "Find the next line containing the text".into(),
+        };
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("LargeFilePrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.query_editor.clone()),
+            )
+            .child(h_flex().px_2().py_1().child(Label::new(help_text).color(
+                if self.error.is_some() {
+                    Color::Error
+                } else {
+                    Color::Muted
+                },
+            )))
+    }
+}
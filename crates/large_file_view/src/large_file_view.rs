@@ -0,0 +1,532 @@
+mod large_file;
+mod large_file_prompt;
+
+use std::{cell::Cell, ops::Range, path::Path, rc::Rc};
+
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, ClickEvent, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    ScrollWheelEvent, Subscription, Task, Window, actions, canvas,
+};
+use project::Project;
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::prelude::*;
+use util::{ResultExt as _, paths::PathExt as _, size::format_file_size};
+use workspace::{
+    ItemSettings, Pane, Workspace,
+    invalid_item_view::InvalidItemView,
+    item::{Item, ItemBufferKind, ItemEvent, ProjectItem},
+};
+
+pub use crate::large_file::*;
+use crate::large_file_prompt::{LargeFilePrompt, LargeFilePromptKind};
+
+actions!(
+    large_file_view,
+    [
+        /// Scrolls up by one line.
+        ScrollUp,
+        /// Scrolls down by one line.
+        ScrollDown,
+        /// Scrolls up by a page.
+        PageUp,
+        /// Scrolls down by a page.
+        PageDown,
+        /// Scrolls to the first line of the file.
+        ScrollToBeginning,
+        /// Scrolls to the last indexed line of the file.
+        ScrollToEnd,
+        /// Prompts for a line number to scroll to.
+        GoToLine,
+        /// Prompts for text to search for.
+        Find,
+        /// Moves to the next line matching the last search.
+        FindNext,
+    ]
+);
+
+/// Lines read around the visible ones, so that scrolling doesn't immediately hit the disk.
+const LINE_MARGIN: u64 = 100;
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<LargeFileView>(cx);
+}
+
+/// A read-only view of a file too large to be opened in an editor.
+///
+/// No buffer is created for the file, so it isn't parsed, sent to language servers
+/// or diffed against git.
+pub struct LargeFileView {
+    large_file: Entity<LargeFile>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_line: u64,
+    scroll_remainder: Pixels,
+    viewport_height: Rc<Cell<Pixels>>,
+    loaded_lines: Range<u64>,
+    lines: Vec<SharedString>,
+    load_task: Option<(Range<u64>, Task<()>)>,
+    highlighted_line: Option<u64>,
+    last_search: Option<String>,
+    search_task: Option<Task<()>>,
+    status: Option<SharedString>,
+    _subscription: Subscription,
+}
+
+impl LargeFileView {
+    pub fn new(
+        large_file: Entity<LargeFile>,
+        project: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&large_file, |_, _, event, cx| match event {
+            LargeFileEvent::IndexUpdated => cx.notify(),
+        });
+        Self {
+            large_file,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_line: 0,
+            scroll_remainder: Pixels::ZERO,
+            viewport_height: Rc::default(),
+            loaded_lines: 0..0,
+            lines: Vec::new(),
+            load_task: None,
+            highlighted_line: None,
+            last_search: None,
+            search_task: None,
+            status: None,
+            _subscription: subscription,
+        }
+    }
+
+    fn line_height(&self, cx: &App) -> Pixels {
+        let settings = ThemeSettings::get_global(cx);
+        (settings.buffer_font_size(cx) * settings.line_height()).round()
+    }
+
+    fn visible_lines(&self, window: &Window, cx: &App) -> u64 {
+        let mut height = self.viewport_height.get();
+        if height <= Pixels::ZERO {
+            height = window.viewport_size().height;
+        }
+        ((height / self.line_height(cx)).floor() as u64).max(1)
+    }
+
+    fn max_scroll_line(&self, window: &Window, cx: &App) -> u64 {
+        self.large_file
+            .read(cx)
+            .line_count()
+            .saturating_sub(self.visible_lines(window, cx))
+    }
+
+    fn scroll_to(&mut self, line: u64, window: &Window, cx: &mut Context<Self>) {
+        self.scroll_line = line.min(self.max_scroll_line(window, cx));
+        cx.notify();
+    }
+
+    /// Scrolls the given line into the middle of the view and highlights it.
+    pub fn reveal_line(&mut self, line: u64, window: &Window, cx: &mut Context<Self>) {
+        let line = line.min(self.large_file.read(cx).line_count().saturating_sub(1));
+        self.highlighted_line = Some(line);
+        self.status = None;
+        let visible_lines = self.visible_lines(window, cx);
+        self.scroll_to(line.saturating_sub(visible_lines / 2), window, cx);
+    }
+
+    pub fn highlighted_line(&self) -> Option<u64> {
+        self.highlighted_line
+    }
+
+    fn scroll_up(&mut self, _: &ScrollUp, window: &mut Window, cx: &mut Context<Self>) {
+        self.scroll_to(self.scroll_line.saturating_sub(1), window, cx);
+    }
+
+    fn scroll_down(&mut self, _: &ScrollDown, window: &mut Window, cx: &mut Context<Self>) {
+        self.scroll_to(self.scroll_line + 1, window, cx);
+    }
+
+    fn page_up(&mut self, _: &PageUp, window: &mut Window, cx: &mut Context<Self>) {
+        let page = self.visible_lines(window, cx);
+        self.scroll_to(self.scroll_line.saturating_sub(page), window, cx);
+    }
+
+    fn page_down(&mut self, _: &PageDown, window: &mut Window, cx: &mut Context<Self>) {
+        let page = self.visible_lines(window, cx);
+        self.scroll_to(self.scroll_line + page, window, cx);
+    }
+
+    fn scroll_to_beginning(
+        &mut self,
+        _: &ScrollToBeginning,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.scroll_to(0, window, cx);
+    }
+
+    fn scroll_to_end(&mut self, _: &ScrollToEnd, window: &mut Window, cx: &mut Context<Self>) {
+        self.scroll_to(u64::MAX, window, cx);
+    }
+
+    fn go_to_line(&mut self, _: &GoToLine, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_prompt(LargeFilePromptKind::GoToLine, window, cx);
+    }
+
+    fn find(&mut self, _: &Find, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_prompt(LargeFilePromptKind::Find, window, cx);
+    }
+
+    fn find_next(&mut self, _: &FindNext, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(query) = self.last_search.clone() {
+            let start = self
+                .highlighted_line
+                .map_or(self.scroll_line, |line| line + 1);
+            self.search(query, start, window, cx);
+        } else {
+            self.show_prompt(LargeFilePromptKind::Find, window, cx);
+        }
+    }
+
+    fn show_prompt(
+        &mut self,
+        kind: LargeFilePromptKind,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = window.root::<Workspace>().flatten() else {
+            return;
+        };
+        let view = cx.entity().downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                LargeFilePrompt::new(kind, view, window, cx)
+            });
+        });
+    }
+
+    /// Reveals the next line containing `query`, starting at `start_line`.
+    pub fn search(
+        &mut self,
+        query: String,
+        start_line: u64,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.last_search = Some(query.clone());
+        self.status = Some("Searching…".into());
+        let search = self.large_file.read(cx).find(query, start_line, cx);
+        self.search_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = search.await;
+            this.update_in(cx, |this, window, cx| {
+                match result {
+                    Ok(Some(line)) => this.reveal_line(line, window, cx),
+                    Ok(None) => this.status = Some("No matches".into()),
+                    Err(error) => this.status = Some(format!("Search failed: {error}").into()),
+                }
+                cx.notify();
+            })
+            .log_err();
+        }));
+        cx.notify();
+    }
+
+    fn scroll_wheel(
+        &mut self,
+        event: &ScrollWheelEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let line_height = self.line_height(cx);
+        self.scroll_remainder += event.delta.pixel_delta(line_height).y;
+        let lines = (self.scroll_remainder / line_height).trunc();
+        if lines == 0. {
+            return;
+        }
+        self.scroll_remainder -= line_height * lines;
+        let scroll_line = if lines > 0. {
+            self.scroll_line.saturating_sub(lines as u64)
+        } else {
+            self.scroll_line + (-lines) as u64
+        };
+        self.scroll_to(scroll_line, window, cx);
+        cx.stop_propagation();
+    }
+
+    /// Loads the lines around `visible`, unless they are already loaded or being loaded.
+    fn load_lines(&mut self, visible: Range<u64>, cx: &mut Context<Self>) {
+        let large_file = self.large_file.read(cx);
+        let line_count = large_file.line_count();
+        let visible = visible.start..visible.end.min(line_count);
+        let is_loaded = |loaded: &Range<u64>| {
+            loaded.start <= visible.start && (visible.end <= loaded.end || loaded.end == line_count)
+        };
+        if is_loaded(&self.loaded_lines)
+            || self
+                .load_task
+                .as_ref()
+                .is_some_and(|(loading, _)| is_loaded(loading))
+        {
+            return;
+        }
+
+        let range = visible.start.saturating_sub(LINE_MARGIN)..visible.end + LINE_MARGIN;
+        let range = range.start..range.end.min(line_count);
+        let read = large_file.read_lines(range.clone(), cx);
+        let task = cx.spawn({
+            let range = range.clone();
+            async move |this, cx| {
+                let lines = read.await;
+                this.update(cx, |this, cx| {
+                    this.load_task = None;
+                    if let Some(lines) = lines.log_err() {
+                        this.loaded_lines = range.start..range.start + lines.len() as u64;
+                        this.lines = lines.into_iter().map(SharedString::from).collect();
+                        cx.notify();
+                    }
+                })
+                .ok();
+            }
+        });
+        self.load_task = Some((range, task));
+    }
+
+    fn render_line(
+        &self,
+        line: u64,
+        number_width: usize,
+        line_height: Pixels,
+        cx: &App,
+    ) -> AnyElement {
+        let colors = cx.theme().colors();
+        let text = line
+            .checked_sub(self.loaded_lines.start)
+            .filter(|_| line < self.loaded_lines.end)
+            .and_then(|ix| self.lines.get(ix as usize).cloned())
+            .unwrap_or_default();
+        let is_highlighted = self.highlighted_line == Some(line);
+
+        h_flex()
+            .h(line_height)
+            .gap_4()
+            .when(is_highlighted, |this| {
+                this.bg(colors.editor_highlighted_line_background)
+            })
+            .child(
+                div()
+                    .flex_none()
+                    .text_color(if is_highlighted {
+                        colors.editor_active_line_number
+                    } else {
+                        colors.editor_line_number
+                    })
+                    .child(format!("{:>number_width$}", line + 1)),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .text_color(colors.editor_foreground)
+                    .child(text),
+            )
+            .into_any_element()
+    }
+
+    fn render_status(&self, cx: &App) -> impl IntoElement {
+        let large_file = self.large_file.read(cx);
+        let line_count = large_file.line_count();
+        let position = format!(
+            "Line {} of {}{}",
+            (self.scroll_line + 1).min(line_count),
+            line_count,
+            if large_file.is_indexed() { "" } else { "+" }
+        );
+        let indexing = (!large_file.is_indexed())
+            .then(|| format!("Indexing lines… {:.0}%", large_file.index_progress() * 100.));
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_4()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(position).size(LabelSize::Small))
+            .child(
+                Label::new(format!(
+                    "Read-only large file ({}) · no syntax highlighting or language features",
+                    format_file_size(large_file.len(), false)
+                ))
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .children(indexing.map(|indexing| {
+                Label::new(indexing)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+            }))
+            .children(self.status.clone().map(|status| {
+                Label::new(status)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+            }))
+    }
+}
+
+impl EventEmitter<()> for LargeFileView {}
+
+impl Focusable for LargeFileView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for LargeFileView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let buffer_font = settings.buffer_font.clone();
+        let font_size = settings.buffer_font_size(cx);
+        let line_height = self.line_height(cx);
+
+        let line_count = self.large_file.read(cx).line_count();
+        let number_width = line_count.to_string().len();
+        let visible =
+            self.scroll_line..(self.scroll_line + self.visible_lines(window, cx)).min(line_count);
+        self.load_lines(visible.clone(), cx);
+
+        let viewport_height = self.viewport_height.clone();
+        v_flex()
+            .key_context("LargeFileView")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::scroll_up))
+            .on_action(cx.listener(Self::scroll_down))
+            .on_action(cx.listener(Self::page_up))
+            .on_action(cx.listener(Self::page_down))
+            .on_action(cx.listener(Self::scroll_to_beginning))
+            .on_action(cx.listener(Self::scroll_to_end))
+            .on_action(cx.listener(Self::go_to_line))
+            .on_action(cx.listener(Self::find))
+            .on_action(cx.listener(Self::find_next))
+            .on_scroll_wheel(cx.listener(Self::scroll_wheel))
+            .font(buffer_font)
+            .text_size(font_size)
+            .child(
+                div()
+                    .id("large-file-lines")
+                    .relative()
+                    .flex_1()
+                    .px_2()
+                    .overflow_hidden()
+                    .child(
+                        canvas(
+                            move |bounds, window, _| {
+                                if viewport_height.replace(bounds.size.height) != bounds.size.height
+                                {
+                                    window.refresh();
+                                }
+                            },
+                            |_, _, _, _| {},
+                        )
+                        .absolute()
+                        .size_full(),
+                    )
+                    .children(
+                        visible.map(|line| self.render_line(line, number_width, line_height, cx)),
+                    )
+                    .on_click(cx.listener(|this, _: &ClickEvent, window, cx| {
+                        window.focus(&this.focus_handle, cx);
+                    })),
+            )
+            .child(self.render_status(cx))
+    }
+}
+
+impl Item for LargeFileView {
+    type Event = ();
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(ItemEvent)) {}
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.large_file.entity_id(), self.large_file.read(cx))
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.large_file
+            .read(cx)
+            .abs_path()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_default()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.large_file.read(cx).abs_path().compact();
+        Some(abs_path.to_string_lossy().into_owned().into())
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.large_file.read(cx).abs_path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn is_read_only(&self, _: &App) -> bool {
+        true
+    }
+
+    fn can_split(&self) -> bool {
+        true
+    }
+
+    fn clone_on_split(
+        &self,
+        _: Option<workspace::WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Entity<Self>>> {
+        let large_file = self.large_file.clone();
+        let project = self.project.clone();
+        Task::ready(Some(
+            cx.new(|cx| Self::new(large_file, project, window, cx)),
+        ))
+    }
+
+    fn buffer_kind(&self, _: &App) -> ItemBufferKind {
+        ItemBufferKind::Singleton
+    }
+}
+
+impl ProjectItem for LargeFileView {
+    type Item = LargeFile;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self::new(item, project, window, cx)
+    }
+
+    fn for_broken_project_item(
+        abs_path: &Path,
+        is_local: bool,
+        e: &anyhow::Error,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<InvalidItemView> {
+        Some(InvalidItemView::new(abs_path, is_local, e, window, cx))
+    }
+}
@@ -1060,17 +1060,23 @@ impl BufferStore {
     }
 
     pub fn disconnected_from_host(&mut self, cx: &mut App) {
-        for open_buffer in self.opened_buffers.values_mut() {
-            if let Some(buffer) = open_buffer.upgrade() {
-                buffer.update(cx, |buffer, _| buffer.give_up_waiting());
-            }
-        }
+        self.disconnected_keeping_buffers_editable(cx);
 
         for buffer in self.buffers() {
             buffer.update(cx, |buffer, cx| {
                 buffer.set_capability(Capability::ReadOnly, cx)
             });
         }
+    }
+
+    /// Like [`Self::disconnected_from_host`], but leaves the open buffers editable, so that edits
+    /// made while offline can be replayed once the project is reopened.
+    pub fn disconnected_keeping_buffers_editable(&mut self, cx: &mut App) {
+        for open_buffer in self.opened_buffers.values_mut() {
+            if let Some(buffer) = open_buffer.upgrade() {
+                buffer.update(cx, |buffer, _| buffer.give_up_waiting());
+            }
+        }
 
         if let Some(remote) = self.as_remote_mut() {
             // Wake up all futures currently waiting on a buffer to get opened,
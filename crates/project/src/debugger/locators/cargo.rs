@@ -1,5 +1,6 @@
 use anyhow::{Context as _, Result};
 use async_trait::async_trait;
+use collections::HashMap;
 use dap::{DapLocator, DebugRequest, adapters::DebugAdapterName};
 use gpui::SharedString;
 use serde_json::{Value, json};
@@ -80,6 +81,13 @@ impl DapLocator for CargoLocator {
                     .iter()
                     .position(|arg| arg == "--")
                     .unwrap_or(task_template.args.len());
+                // Doc tests are compiled and run by rustdoc, so there's no test binary to debug.
+                if task_template.args[..delimiter]
+                    .iter()
+                    .any(|arg| arg == "--doc")
+                {
+                    return None;
+                }
                 if !task_template.args[..delimiter]
                     .iter()
                     .any(|arg| arg == "--no-run")
@@ -144,8 +152,6 @@ impl DapLocator for CargoLocator {
             .first()
             .is_some_and(|arg| arg == "test" || arg == "t");
 
-        let is_ignored = build_config.args.contains(&"--include-ignored".to_owned());
-
         let executables = output
             .lines()
             .filter(|line| !line.trim().is_empty())
@@ -174,41 +180,20 @@ impl DapLocator for CargoLocator {
             "Couldn't get executable in cargo locator"
         );
 
-        let mut test_name = None;
-        if is_test {
-            test_name = build_config
-                .args
-                .iter()
-                .rev()
-                .take_while(|name| "--" != name.as_str())
-                .find(|name| !name.starts_with("-"))
-                .cloned();
-        }
-        let executable = {
-            if let Some(name) = test_name.as_ref().and_then(|name| {
-                name.strip_prefix('$')
-                    .map(|name| build_config.env.get(name))
-                    .unwrap_or(Some(name))
-            }) {
-                find_best_executable(&executables, name).await
-            } else {
-                None
-            }
+        let args = binary_args(&build_config.args, &build_config.env);
+        let test_name = is_test
+            .then(|| args.iter().rev().find(|arg| !arg.starts_with('-')))
+            .flatten();
+        let executable = if let Some(name) = test_name {
+            find_best_executable(&executables, name).await
+        } else {
+            None
         };
 
         let Some(executable) = executable.or_else(|| executables.first().cloned()) else {
             anyhow::bail!("Couldn't get executable in cargo locator");
         };
 
-        let mut args: Vec<_> = test_name.into_iter().collect();
-        if is_test {
-            args.push("--nocapture".to_owned());
-            if is_ignored {
-                args.push("--include-ignored".to_owned());
-                args.push("--exact".to_owned());
-            }
-        }
-
         Ok(DebugRequest::Launch(task::LaunchRequest {
             program: executable,
             cwd: build_config.cwd,
@@ -217,3 +202,158 @@ impl DapLocator for CargoLocator {
         }))
     }
 }
+
+/// The arguments to launch the binary built by a cargo command with.
+fn binary_args(cargo_args: &[String], env: &HashMap<String, String>) -> Vec<String> {
+    let is_test = cargo_args
+        .first()
+        .is_some_and(|arg| arg == "test" || arg == "t");
+    let is_ignored = cargo_args.iter().any(|arg| arg == "--include-ignored");
+
+    // Everything after `--` is passed to the binary: libtest's filter and flags for tests,
+    // the program's own arguments otherwise. Without a shell to expand them, variables
+    // referring to the task's environment are resolved here.
+    let mut args = cargo_args
+        .iter()
+        .skip_while(|arg| arg.as_str() != "--")
+        .skip(1)
+        .map(|arg| {
+            arg.strip_prefix('$')
+                .and_then(|name| env.get(name))
+                .unwrap_or(arg)
+                .clone()
+        })
+        .collect::<Vec<_>>();
+
+    if is_test {
+        if !args.iter().any(|arg| arg == "--nocapture") {
+            args.push("--nocapture".to_owned());
+        }
+        // Gutter runnables include ignored tests and name a single test, which
+        // shouldn't also run every test whose name it is a prefix of.
+        if is_ignored && !args.iter().any(|arg| arg == "--exact") {
+            args.push("--exact".to_owned());
+        }
+    }
+    args
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_cargo_locator_test_task() {
+        let adapter = DebugAdapterName("CodeLLDB".into());
+        let test_task = TaskTemplate {
+            label: "cargo test -p $RUST_PACKAGE $RUST_TEST_NAME".into(),
+            command: "cargo".into(),
+            args: vec![
+                "test".into(),
+                "-p".into(),
+                "$RUST_PACKAGE".into(),
+                "--".into(),
+                "--nocapture".into(),
+                "--include-ignored".into(),
+                "$RUST_TEST_NAME".into(),
+            ],
+            ..Default::default()
+        };
+
+        let scenario = CargoLocator
+            .create_scenario(&test_task, "cargo test -p app tests::it_works", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        let Some(BuildTaskDefinition::Template { task_template, .. }) = scenario.build else {
+            panic!("Expected a build task");
+        };
+        assert_eq!(
+            task_template.args,
+            [
+                "test",
+                "-p",
+                "$RUST_PACKAGE",
+                "--no-run",
+                "--",
+                "--nocapture",
+                "--include-ignored",
+                "$RUST_TEST_NAME"
+            ]
+        );
+
+        let doc_test_task = TaskTemplate {
+            args: vec![
+                "test".into(),
+                "--doc".into(),
+                "--".into(),
+                "$RUST_DOC_TEST_NAME".into(),
+            ],
+            ..test_task
+        };
+        assert!(
+            CargoLocator
+                .create_scenario(&doc_test_task, "doc test", &adapter)
+                .await
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_cargo_locator_binary_args() {
+        let env = HashMap::from_iter([
+            ("RUST_PACKAGE".to_owned(), "app".to_owned()),
+            ("RUST_TEST_NAME".to_owned(), "tests::it_works".to_owned()),
+        ]);
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        // Test runnables name a single test, which must not match other tests it's a prefix of.
+        assert_eq!(
+            binary_args(
+                &args(&[
+                    "test",
+                    "-p",
+                    "$RUST_PACKAGE",
+                    "--no-run",
+                    "--",
+                    "--nocapture",
+                    "--include-ignored",
+                    "$RUST_TEST_NAME",
+                ]),
+                &env,
+            ),
+            [
+                "--nocapture",
+                "--include-ignored",
+                "tests::it_works",
+                "--exact"
+            ]
+        );
+
+        // Without `--include-ignored` the filter is kept as a prefix, as it is for `cargo test`.
+        assert_eq!(
+            binary_args(&args(&["t", "--no-run", "--", "$RUST_TEST_NAME"]), &env),
+            ["tests::it_works", "--nocapture"]
+        );
+
+        // Program arguments are forwarded as they are, resolving only variables the task defines.
+        assert_eq!(
+            binary_args(
+                &args(&[
+                    "run",
+                    "-p",
+                    "$RUST_PACKAGE",
+                    "--",
+                    "--port",
+                    "$PORT",
+                    "$RUST_PACKAGE"
+                ]),
+                &env,
+            ),
+            ["--port", "$PORT", "app"]
+        );
+        assert_eq!(
+            binary_args(&args(&["run", "-p", "$RUST_PACKAGE"]), &env),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -12,6 +12,8 @@ pub(crate) struct NodeLocator;
 const TYPESCRIPT_RUNNER_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("TYPESCRIPT_RUNNER"));
 
+const TEST_RUNNERS: [&str; 3] = ["jest", "vitest", "mocha"];
+
 #[async_trait]
 impl DapLocator for NodeLocator {
     fn name(&self) -> SharedString {
@@ -28,24 +30,40 @@ impl DapLocator for NodeLocator {
         if adapter.0.as_ref() != "JavaScript" {
             return None;
         }
-        if build_config.command != TYPESCRIPT_RUNNER_VARIABLE.template_value()
-            && build_config.command != "npm"
-            && build_config.command != "pnpm"
-            && build_config.command != "yarn"
-        {
+        let is_package_runner = build_config.command == TYPESCRIPT_RUNNER_VARIABLE.template_value()
+            || ["npm", "npx", "pnpm", "yarn"].contains(&build_config.command.as_str());
+        let is_test_runner = TEST_RUNNERS.contains(&build_config.command.as_str());
+        if !is_package_runner && !is_test_runner {
             return None;
         }
 
-        let config = serde_json::json!({
+        let mut args = build_config.args.clone();
+        let test_runner = if is_test_runner {
+            Some(build_config.command.as_str())
+        } else {
+            args.iter()
+                .map(String::as_str)
+                .find(|arg| TEST_RUNNERS.contains(arg))
+        };
+        if let Some(test_runner) = test_runner {
+            configure_test_runner(test_runner, &mut args);
+        }
+
+        let mut config = serde_json::json!({
             "request": "launch",
             "type": "pwa-node",
-            "args": build_config.args.clone(),
+            "args": args,
             "cwd": build_config.cwd.clone(),
             "runtimeExecutable": build_config.command.clone(),
             "env": build_config.env.clone(),
             "runtimeArgs": ["--inspect-brk"],
             "console": "integratedTerminal",
         });
+        if test_runner.is_some() {
+            // Test runners execute tests in worker processes, which need to be attached to
+            // for breakpoints to be hit.
+            config["autoAttachChildProcesses"] = true.into();
+        }
 
         Some(DebugScenario {
             adapter: adapter.0.clone(),
@@ -60,3 +78,148 @@ impl DapLocator for NodeLocator {
         bail!("JavaScript locator should not require DapLocator::run to be ran");
     }
 }
+
+/// Adjusts test runner arguments for running under the debugger: a single process,
+/// no watch mode, and test name filters without the quoting meant for a shell.
+fn configure_test_runner(test_runner: &str, args: &mut Vec<String>) {
+    let mut filter_value = false;
+    for arg in args.iter_mut() {
+        if filter_value {
+            if let Some(unquoted) = arg.strip_prefix('"').and_then(|arg| arg.strip_suffix('"')) {
+                *arg = unquoted.to_owned();
+            }
+        }
+        filter_value = matches!(arg.as_str(), "-t" | "--testNamePattern" | "-g" | "--grep");
+    }
+
+    let mut options_position = args
+        .iter()
+        .position(|arg| arg == test_runner)
+        .map_or(0, |position| position + 1);
+    if test_runner == "vitest" {
+        // Options go after the subcommand, which vitest only recognizes as the first argument.
+        if !args
+            .get(options_position)
+            .is_some_and(|arg| arg == "run" || arg == "watch")
+        {
+            args.insert(options_position, "run".to_owned());
+        }
+        options_position += 1;
+    }
+    let mut ensure_arg = |arg: &str| {
+        if !args.iter().any(|existing| existing == arg) {
+            args.insert(options_position, arg.to_owned());
+        }
+    };
+    match test_runner {
+        "jest" => ensure_arg("--runInBand"),
+        "vitest" => ensure_arg("--no-file-parallelism"),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_node_locator_jest_test() {
+        let adapter = DebugAdapterName("JavaScript".into());
+        let build_task = TaskTemplate {
+            label: "jest $ZED_SYMBOL".into(),
+            command: TYPESCRIPT_RUNNER_VARIABLE.template_value(),
+            args: vec![
+                "exec".into(),
+                "--".into(),
+                "jest".into(),
+                "--testNamePattern".into(),
+                "\"adds numbers\"".into(),
+                "$ZED_FILE".into(),
+            ],
+            cwd: Some("$ZED_WORKTREE_ROOT".into()),
+            ..Default::default()
+        };
+
+        let scenario = NodeLocator
+            .create_scenario(&build_task, "jest adds numbers", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config["args"],
+            json!([
+                "exec",
+                "--",
+                "jest",
+                "--runInBand",
+                "--testNamePattern",
+                "adds numbers",
+                "$ZED_FILE"
+            ])
+        );
+        assert_eq!(scenario.config["autoAttachChildProcesses"], json!(true));
+    }
+
+    #[gpui::test]
+    async fn test_node_locator_vitest_command() {
+        let adapter = DebugAdapterName("JavaScript".into());
+        let build_task = TaskTemplate {
+            label: "vitest".into(),
+            command: "vitest".into(),
+            args: vec![
+                "-t".into(),
+                "\"parses input\"".into(),
+                "src/parse.test.ts".into(),
+            ],
+            ..Default::default()
+        };
+
+        let scenario = NodeLocator
+            .create_scenario(&build_task, "vitest", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config["args"],
+            json!([
+                "run",
+                "--no-file-parallelism",
+                "-t",
+                "parses input",
+                "src/parse.test.ts"
+            ])
+        );
+        assert_eq!(scenario.config["runtimeExecutable"], json!("vitest"));
+    }
+
+    #[gpui::test]
+    async fn test_node_locator_vitest_run_command() {
+        let adapter = DebugAdapterName("JavaScript".into());
+        let build_task = TaskTemplate {
+            label: "vitest run".into(),
+            command: "npx".into(),
+            args: vec![
+                "vitest".into(),
+                "run".into(),
+                "-t".into(),
+                "\"parses input\"".into(),
+            ],
+            ..Default::default()
+        };
+
+        let scenario = NodeLocator
+            .create_scenario(&build_task, "vitest run", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config["args"],
+            json!([
+                "vitest",
+                "run",
+                "--no-file-parallelism",
+                "-t",
+                "parses input"
+            ])
+        );
+    }
+}
@@ -25,6 +25,27 @@ impl DapLocator for PythonLocator {
         if adapter.0.as_ref() != "Debugpy" {
             return None;
         }
+        let is_pytest = Path::new(&build_config.command)
+            .file_name()
+            .is_some_and(|name| name == "pytest" || name == "py.test");
+        if is_pytest {
+            // Run the test runner as a module so that debugpy launches it with the
+            // selected interpreter instead of the script's shebang.
+            let config = serde_json::json!({
+                "request": "launch",
+                "module": "pytest",
+                "args": build_config.args.clone(),
+                "cwd": build_config.cwd.clone(),
+                "env": build_config.env.clone(),
+            });
+            return Some(DebugScenario {
+                adapter: adapter.0.clone(),
+                label: resolved_label.to_string().into(),
+                build: None,
+                config,
+                tcp_connection: None,
+            });
+        }
         let valid_program = build_config.command.starts_with("$ZED_")
             || Path::new(&build_config.command)
                 .file_name()
@@ -143,4 +164,35 @@ mod test {
             expected_scenario
         );
     }
+
+    #[gpui::test]
+    async fn test_python_locator_pytest_command() {
+        let adapter = DebugAdapterName("Debugpy".into());
+        let build_task = TaskTemplate {
+            label: "pytest $ZED_CUSTOM_PYTHON_TEST_TARGET".into(),
+            command: "pytest".into(),
+            args: vec!["tests/test_app.py::test_login".into()],
+            cwd: Some("$ZED_WORKTREE_ROOT".into()),
+            ..Default::default()
+        };
+
+        let scenario = PythonLocator
+            .create_scenario(
+                &build_task,
+                "pytest tests/test_app.py::test_login",
+                &adapter,
+            )
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config,
+            json!({
+                "request": "launch",
+                "module": "pytest",
+                "args": ["tests/test_app.py::test_login"],
+                "cwd": "$ZED_WORKTREE_ROOT",
+                "env": {},
+            })
+        );
+    }
 }
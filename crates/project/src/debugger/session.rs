@@ -2,12 +2,14 @@ use super::breakpoint_store::{
     BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason, SourceBreakpoint,
 };
 use super::dap_command::{
-    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand, DisconnectCommand,
-    EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
-    ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetDataBreakpointsCommand, SetExceptionBreakpoints, SetVariableValueCommand,
-    StackTraceCommand, StepBackCommand, StepCommand, StepInCommand, StepOutCommand,
-    TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand,
+    DisassembleCommand, DisconnectCommand, EvaluateCommand, GotoCommand, GotoTargetsCommand,
+    Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand, ModulesCommand,
+    NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand, ScopesCommand,
+    SetDataBreakpointsCommand, SetExceptionBreakpoints, SetExpressionCommand,
+    SetInstructionBreakpointsCommand, SetVariableValueCommand, StackTraceCommand, StepBackCommand,
+    StepCommand, StepInCommand, StepOutCommand, TerminateCommand, TerminateThreadsCommand,
+    ThreadsCommand, VariablesCommand,
 };
 use super::dap_store::DapStore;
 use crate::debugger::breakpoint_store::BreakpointSessionState;
@@ -16,7 +18,7 @@ use crate::debugger::memory::{self, Memory, MemoryIterator, MemoryPageBuilder, P
 use anyhow::{Context as _, Result, anyhow, bail};
 use base64::Engine;
 use collections::{HashMap, HashSet, IndexMap};
-use dap::adapters::{DebugAdapterBinary, DebugAdapterName};
+use dap::adapters::{self, DebugAdapterBinary, DebugAdapterName};
 use dap::messages::Response;
 use dap::requests::{Request, RunInTerminal, StartDebugging};
 use dap::transport::TcpTransport;
@@ -710,6 +712,7 @@ pub struct Session {
     ignore_breakpoints: bool,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
     data_breakpoints: BTreeMap<String, DataBreakpointState>,
+    instruction_breakpoints: BTreeMap<String, dap::InstructionBreakpoint>,
     background_tasks: Vec<Task<()>>,
     restart_task: Option<Task<()>>,
     task_context: TaskContext,
@@ -884,6 +887,7 @@ impl Session {
                 ignore_breakpoints: false,
                 breakpoint_store,
                 data_breakpoints: Default::default(),
+                instruction_breakpoints: Default::default(),
                 exception_breakpoints: Default::default(),
                 label,
                 adapter,
@@ -986,6 +990,29 @@ impl Session {
                 })?
                 .await;
 
+            if result.is_ok()
+                && adapters::core_file_from_config(&binary.request_args.configuration).is_some()
+            {
+                // A post-mortem session never runs, but not every adapter reports a stop
+                // after opening the core, so stop all threads to show the crashed one.
+                this.update(cx, |session, cx| {
+                    if !session.any_stopped_thread() {
+                        session.handle_stopped_event(
+                            StoppedEvent {
+                                reason: dap::StoppedEventReason::Exception,
+                                description: Some("Core dump".into()),
+                                thread_id: None,
+                                preserve_focus_hint: None,
+                                text: None,
+                                all_threads_stopped: Some(true),
+                                hit_breakpoint_ids: None,
+                            },
+                            cx,
+                        );
+                    }
+                })?;
+            }
+
             if result.is_err() {
                 let mut console = this.update(cx, |session, cx| session.console_output(cx))?;
 
@@ -2087,6 +2114,103 @@ impl Session {
         self.send_data_breakpoints(cx);
     }
 
+    pub fn instruction_breakpoints(&self) -> impl Iterator<Item = &dap::InstructionBreakpoint> {
+        self.instruction_breakpoints.values()
+    }
+
+    /// Sets a breakpoint on the instruction at `instruction_reference`, or removes the one that's
+    /// already there.
+    pub fn toggle_instruction_breakpoint(
+        &mut self,
+        instruction_reference: String,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .instruction_breakpoints
+            .remove(&instruction_reference)
+            .is_none()
+        {
+            self.instruction_breakpoints.insert(
+                instruction_reference.clone(),
+                dap::InstructionBreakpoint {
+                    instruction_reference,
+                    offset: None,
+                    condition: None,
+                    hit_condition: None,
+                    mode: None,
+                },
+            );
+        }
+        self.send_instruction_breakpoints(cx);
+        cx.notify();
+    }
+
+    fn send_instruction_breakpoints(&mut self, cx: &mut Context<Self>) {
+        if let Some(mode) = self.as_running() {
+            let breakpoints = self.instruction_breakpoints.values().cloned().collect();
+            let command = SetInstructionBreakpointsCommand { breakpoints };
+            mode.request(command).detach_and_log_err(cx);
+        }
+    }
+
+    /// Disassembles `instruction_count` instructions, starting `instruction_offset` instructions
+    /// away from the one at `memory_reference`.
+    pub fn disassemble(
+        &mut self,
+        memory_reference: String,
+        instruction_offset: i64,
+        instruction_count: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Vec<dap::DisassembledInstruction>>> {
+        self.request(
+            DisassembleCommand {
+                memory_reference,
+                instruction_offset,
+                instruction_count,
+            },
+            |_, response, _| response.log_err(),
+            cx,
+        )
+    }
+
+    /// Returns the locations on the given row (zero-based) of a source file that the instruction
+    /// pointer can be moved to without executing the code in between.
+    pub fn goto_targets(
+        &mut self,
+        path: Arc<Path>,
+        row: u32,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Vec<dap::GotoTarget>>> {
+        self.request(
+            GotoTargetsCommand {
+                path,
+                line: row as u64 + 1,
+            },
+            |_, response, _| response.log_err(),
+            cx,
+        )
+    }
+
+    /// Moves the instruction pointer of a stopped thread to one of the targets returned by
+    /// [`Self::goto_targets`]; the adapter reports the new location with a stopped event.
+    pub fn goto(&mut self, thread_id: ThreadId, target_id: u64, cx: &mut Context<Self>) {
+        if self.thread_status(thread_id) != ThreadStatus::Stopped {
+            return;
+        }
+        self.select_historic_snapshot(None, cx);
+
+        self.active_snapshot.thread_states.process_step(thread_id);
+        self.request(
+            GotoCommand {
+                thread_id: thread_id.0,
+                target_id,
+            },
+            Self::on_step_response::<GotoCommand>(thread_id),
+            cx,
+        )
+        .detach();
+    }
+
     pub fn breakpoints_enabled(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -2708,6 +2832,39 @@ impl Session {
         }
     }
 
+    pub fn set_expression_value(
+        &mut self,
+        stack_frame_id: u64,
+        expression: String,
+        value: String,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .capabilities
+            .supports_set_expression
+            .unwrap_or_default()
+        {
+            self.request(
+                SetExpressionCommand {
+                    expression,
+                    value,
+                    frame_id: Some(stack_frame_id),
+                },
+                move |this, response, cx| {
+                    let response = response.log_err()?;
+                    this.invalidate_command_type::<VariablesCommand>();
+                    this.invalidate_command_type::<ReadMemory>();
+                    this.memory.clear(cx.background_executor());
+                    this.refresh_watchers(stack_frame_id, cx);
+                    cx.emit(SessionEvent::Variables);
+                    Some(response)
+                },
+                cx,
+            )
+            .detach();
+        }
+    }
+
     pub fn evaluate(
         &mut self,
         expression: String,
@@ -169,6 +169,8 @@ pub(crate) struct PerformRename {
     pub position: PointUtf16,
     pub new_name: String,
     pub push_to_history: bool,
+    /// Fail without applying anything if the rename creates, renames or deletes files.
+    pub reject_resource_operations: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -505,6 +507,17 @@ impl LspCommand for PerformRename {
         mut cx: AsyncApp,
     ) -> Result<ProjectTransaction> {
         if let Some(edit) = message {
+            if self.reject_resource_operations
+                && let Some(lsp::DocumentChanges::Operations(operations)) = &edit.document_changes
+            {
+                anyhow::ensure!(
+                    operations.iter().all(|operation| matches!(
+                        operation,
+                        lsp::DocumentChangeOperation::Edit(_)
+                    )),
+                    "the rename would create, rename or delete files"
+                );
+            }
             let (_, lsp_server) =
                 language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
             LocalLspStore::deserialize_workspace_edit(
@@ -529,6 +542,7 @@ impl LspCommand for PerformRename {
             )),
             new_name: self.new_name.clone(),
             version: serialize_version(&buffer.version()),
+            reject_resource_operations: self.reject_resource_operations,
         }
     }
 
@@ -551,6 +565,7 @@ impl LspCommand for PerformRename {
             position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer))?,
             new_name: message.new_name,
             push_to_history: false,
+            reject_resource_operations: message.reject_resource_operations,
         })
     }
 
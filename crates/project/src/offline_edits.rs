@@ -0,0 +1,181 @@
+//! Keeps the buffers of a remote project editable after its connection is lost, and replays the
+//! edits made in the meantime once the project has been reopened.
+//!
+//! When the connection drops, each open buffer's version is recorded as its base. The text
+//! operations made after that point are captured along with the history they apply to, and are
+//! replayed onto a replica of the buffer. The result is only applied to the reopened buffer if the
+//! remote file still matches the base. Otherwise the offline version is restored into the buffer
+//! without saving it, and the conflict is reported so it can be reviewed.
+
+use std::{path::PathBuf, sync::Arc};
+
+use clock::ReplicaId;
+use gpui::{App, Context, Entity, WeakEntity};
+use language::{Buffer, LocalFile as _};
+use text::{Operation, Rope};
+use worktree::File;
+
+/// Open buffers of a disconnected project, along with their versions when the connection was lost.
+pub(crate) struct OfflineEditQueue {
+    buffers: Vec<QueuedBuffer>,
+}
+
+struct QueuedBuffer {
+    buffer: WeakEntity<Buffer>,
+    abs_path: PathBuf,
+    base_version: clock::Global,
+    save_requested: bool,
+}
+
+impl OfflineEditQueue {
+    pub(crate) fn new(buffers: impl IntoIterator<Item = Entity<Buffer>>, cx: &App) -> Self {
+        let buffers = buffers
+            .into_iter()
+            .filter_map(|buffer| {
+                let abs_path = File::from_dyn(buffer.read(cx).file())?.abs_path(cx);
+                Some(QueuedBuffer {
+                    base_version: buffer.read(cx).version(),
+                    buffer: buffer.downgrade(),
+                    abs_path,
+                    save_requested: false,
+                })
+            })
+            .collect();
+        Self { buffers }
+    }
+
+    /// Whether the buffer is kept editable until the project is reopened. Its operations must not
+    /// be sent over the lost connection.
+    pub(crate) fn contains(&self, buffer: &Entity<Buffer>) -> bool {
+        self.buffers
+            .iter()
+            .any(|queued| queued.buffer == buffer.downgrade())
+    }
+
+    /// Records that the buffer should be saved once it has been replayed. Returns `false` if the
+    /// buffer was not open when the connection was lost.
+    pub(crate) fn queue_save(&mut self, buffer: &Entity<Buffer>) -> bool {
+        let Some(queued) = self
+            .buffers
+            .iter_mut()
+            .find(|queued| queued.buffer == buffer.downgrade())
+        else {
+            return false;
+        };
+        queued.save_requested = true;
+        true
+    }
+
+    pub(crate) fn has_queued_saves(&self) -> bool {
+        self.buffers.iter().any(|queued| queued.save_requested)
+    }
+
+    /// Captures the text operations made to each buffer since the connection was lost.
+    pub(crate) fn edits(&self, cx: &App) -> OfflineEdits {
+        let buffers = self
+            .buffers
+            .iter()
+            .filter_map(|queued| {
+                let buffer = queued.buffer.upgrade()?.read(cx);
+                let (history, operations) = buffer
+                    .operations()
+                    .iter()
+                    .map(|(_, operation)| operation.clone())
+                    .partition::<Vec<_>, _>(|operation| {
+                        queued.base_version.observed(operation.timestamp())
+                    });
+                if operations.is_empty() && !queued.save_requested {
+                    return None;
+                }
+                Some(OfflineBufferEdits {
+                    abs_path: queued.abs_path.clone(),
+                    history_base_text: buffer.base_text().clone(),
+                    history,
+                    operations,
+                    save: queued.save_requested,
+                })
+            })
+            .collect();
+        OfflineEdits { buffers }
+    }
+}
+
+/// Edits made to the buffers of a remote project while it was disconnected.
+#[derive(Clone, Debug, Default)]
+pub struct OfflineEdits {
+    pub(crate) buffers: Vec<OfflineBufferEdits>,
+}
+
+impl OfflineEdits {
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+
+    /// The remote paths of the edited buffers.
+    pub fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.buffers.iter().map(|buffer| &buffer.abs_path)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct OfflineBufferEdits {
+    pub(crate) abs_path: PathBuf,
+    /// The text the buffer's history starts from.
+    history_base_text: Rope,
+    /// The operations that produced the buffer's state when the connection was lost.
+    history: Vec<Operation>,
+    /// The operations made while disconnected.
+    operations: Vec<Operation>,
+    pub(crate) save: bool,
+}
+
+pub(crate) enum ReplayOutcome {
+    /// The buffer now holds the offline version, and can be saved if a save was queued.
+    Applied,
+    /// The remote file changed while disconnected. The buffer holds the offline version, unsaved,
+    /// and `remote_text` is the file's content on the remote.
+    Conflict { remote_text: Arc<str> },
+}
+
+impl OfflineBufferEdits {
+    pub(crate) fn replay(&self, buffer: &mut Buffer, cx: &mut Context<Buffer>) -> ReplayOutcome {
+        let mut replica = text::Buffer::new_normalized(
+            ReplicaId::LOCAL_BRANCH,
+            buffer.remote_id(),
+            buffer.line_ending(),
+            self.history_base_text.clone(),
+        );
+        replica.apply_ops(self.history.iter().cloned());
+        let base = replica.snapshot();
+        replica.apply_ops(self.operations.iter().cloned());
+
+        let remote_text = buffer.as_rope().clone();
+        if rope_eq(&remote_text, replica.as_rope()) {
+            // Unsaved contents can also be restored by the workspace when it is reopened.
+            return ReplayOutcome::Applied;
+        }
+
+        if rope_eq(&remote_text, base.as_rope()) {
+            let edits = replica
+                .edits_since::<usize>(base.version())
+                .map(|edit| {
+                    let new_text = replica.text_for_range(edit.new).collect::<String>();
+                    (edit.old, new_text)
+                })
+                .collect::<Vec<_>>();
+            buffer.edit(edits, None, cx);
+            return ReplayOutcome::Applied;
+        }
+
+        let remote_text: Arc<str> = remote_text.to_string().into();
+        buffer.set_text(replica.text(), cx);
+        ReplayOutcome::Conflict { remote_text }
+    }
+}
+
+fn rope_eq(a: &Rope, b: &Rope) -> bool {
+    a.len() == b.len()
+        && a.chunks()
+            .flat_map(str::bytes)
+            .eq(b.chunks().flat_map(str::bytes))
+}
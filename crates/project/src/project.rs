@@ -10,6 +10,7 @@ pub mod image_store;
 pub mod lsp_command;
 pub mod lsp_store;
 mod manifest_tree;
+mod offline_edits;
 pub mod prettier_store;
 mod project_search;
 pub mod project_settings;
@@ -39,6 +40,7 @@ use dap::inline_value::{InlineValueLocation, VariableLookupKind, VariableScope};
 use crate::{
     git_store::GitStore,
     lsp_store::{SymbolLocation, log_store::LogKind},
+    offline_edits::{OfflineEditQueue, ReplayOutcome},
     project_search::SearchResultsHandle,
     trusted_worktrees::{PathTrust, RemoteHostLocation, TrustedWorktrees},
 };
@@ -48,6 +50,7 @@ pub use git_store::{
     git_traversal::{ChildEntriesGitIter, GitEntry, GitEntryRef, GitTraversal},
 };
 pub use manifest_tree::ManifestTree;
+pub use offline_edits::OfflineEdits;
 pub use project_search::{Search, SearchResults};
 
 use anyhow::{Context as _, Result, anyhow};
@@ -221,6 +224,7 @@ pub struct Project {
     settings_observer: Entity<SettingsObserver>,
     toolchain_store: Option<Entity<ToolchainStore>>,
     agent_location: Option<AgentLocation>,
+    offline_edits: Option<OfflineEditQueue>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -354,6 +358,12 @@ pub enum Event {
     EntryRenamed(ProjectTransaction, ProjectPath, PathBuf),
     WorkspaceEditApplied(ProjectTransaction),
     AgentLocationChanged,
+    /// A buffer edited while the project was disconnected has also changed on the remote. The
+    /// buffer holds the offline version, unsaved.
+    OfflineEditConflict {
+        buffer: Entity<Buffer>,
+        remote_text: Arc<str>,
+    },
 }
 
 pub struct AgentLocationChanged;
@@ -1255,6 +1265,7 @@ impl Project {
                 toolchain_store: Some(toolchain_store),
 
                 agent_location: None,
+                offline_edits: None,
             }
         })
     }
@@ -1474,6 +1485,7 @@ impl Project {
 
                 toolchain_store: Some(toolchain_store),
                 agent_location: None,
+                offline_edits: None,
             };
 
             // remote server -> local machine handlers
@@ -1742,6 +1754,7 @@ impl Project {
                 remotely_created_models: Arc::new(Mutex::new(RemotelyCreatedModels::default())),
                 toolchain_store: None,
                 agent_location: None,
+                offline_edits: None,
             };
             project.set_role(role, cx);
             for worktree in worktrees {
@@ -2896,11 +2909,103 @@ impl Project {
         })
     }
 
-    pub fn save_buffer(&self, buffer: Entity<Buffer>, cx: &mut Context<Self>) -> Task<Result<()>> {
+    pub fn save_buffer(
+        &mut self,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if let Some(offline_edits) = self.offline_edits.as_mut()
+            && offline_edits.queue_save(&buffer)
+        {
+            cx.emit(Event::Toast {
+                notification_id: "offline-edits-save-queued".into(),
+                message: "Disconnected from the remote project. The file will be saved when you reconnect."
+                    .to_string(),
+            });
+            return Task::ready(Ok(()));
+        }
         self.buffer_store
             .update(cx, |buffer_store, cx| buffer_store.save_buffer(buffer, cx))
     }
 
+    /// Whether saves were requested while the project was disconnected, that will only happen once
+    /// its edits are replayed with [`Self::replay_offline_edits`].
+    pub fn has_queued_offline_saves(&self) -> bool {
+        self.offline_edits
+            .as_ref()
+            .is_some_and(|offline_edits| offline_edits.has_queued_saves())
+    }
+
+    /// The edits made to this project's buffers since its remote connection was lost.
+    pub fn offline_edits(&self, cx: &App) -> OfflineEdits {
+        self.offline_edits
+            .as_ref()
+            .map(|offline_edits| offline_edits.edits(cx))
+            .unwrap_or_default()
+    }
+
+    /// Applies the edits made while a previous instance of this project was disconnected, and
+    /// performs the saves that were queued. Buffers whose files have changed on the remote in the
+    /// meantime are not saved, and are reported with [`Event::OfflineEditConflict`] instead.
+    pub fn replay_offline_edits(
+        &mut self,
+        edits: OfflineEdits,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let tasks = edits
+            .buffers
+            .into_iter()
+            .map(|edits| {
+                let open_buffer =
+                    self.find_worktree(&edits.abs_path, cx)
+                        .map(|(worktree, path)| {
+                            let worktree_id = worktree.read(cx).id();
+                            self.open_buffer((worktree_id, path), cx)
+                        });
+                cx.spawn(async move |this, cx| {
+                    let result = async {
+                        let buffer = open_buffer
+                            .context("file is no longer in the project")?
+                            .await?;
+                        let outcome = buffer.update(cx, |buffer, cx| edits.replay(buffer, cx))?;
+                        match outcome {
+                            ReplayOutcome::Applied if edits.save => {
+                                this.update(cx, |this, cx| this.save_buffer(buffer, cx))?
+                                    .await
+                            }
+                            ReplayOutcome::Applied => Ok(()),
+                            ReplayOutcome::Conflict { remote_text } => this.update(cx, |_, cx| {
+                                cx.emit(Event::OfflineEditConflict {
+                                    buffer,
+                                    remote_text,
+                                })
+                            }),
+                        }
+                    }
+                    .await;
+                    result.with_context(|| format!("restoring edits to {:?}", edits.abs_path))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        cx.background_spawn(async move {
+            let errors = join_all(tasks)
+                .await
+                .into_iter()
+                .filter_map(Result::err)
+                .collect::<Vec<_>>();
+            for error in &errors {
+                log::error!("{error:?}");
+            }
+            anyhow::ensure!(
+                errors.is_empty(),
+                "Failed to restore offline edits to {} file(s)",
+                errors.len()
+            );
+            Ok(())
+        })
+    }
+
     pub fn save_buffer_as(
         &mut self,
         buffer: Entity<Buffer>,
@@ -3290,9 +3395,22 @@ impl Project {
                 self.worktree_store.update(cx, |store, cx| {
                     store.disconnected_from_host(cx);
                 });
-                self.buffer_store.update(cx, |buffer_store, cx| {
-                    buffer_store.disconnected_from_host(cx)
-                });
+                if ProjectSettings::get_global(cx)
+                    .session
+                    .edit_while_disconnected
+                {
+                    self.offline_edits = Some(OfflineEditQueue::new(
+                        self.buffer_store.read(cx).buffers(),
+                        cx,
+                    ));
+                    self.buffer_store.update(cx, |buffer_store, cx| {
+                        buffer_store.disconnected_keeping_buffers_editable(cx)
+                    });
+                } else {
+                    self.buffer_store.update(cx, |buffer_store, cx| {
+                        buffer_store.disconnected_from_host(cx)
+                    });
+                }
                 self.lsp_store.update(cx, |lsp_store, _cx| {
                     lsp_store.disconnected_from_ssh_remote()
                 });
@@ -3427,7 +3545,12 @@ impl Project {
             } => {
                 let operation = language::proto::serialize_operation(operation);
 
-                if let Some(remote) = &self.remote_client {
+                if let Some(remote) = &self.remote_client
+                    && !self
+                        .offline_edits
+                        .as_ref()
+                        .is_some_and(|offline_edits| offline_edits.contains(&buffer))
+                {
                     remote
                         .read(cx)
                         .proto_client()
@@ -4063,6 +4186,29 @@ impl Project {
         position: T,
         new_name: String,
         cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        self.request_rename(buffer, position, new_name, false, cx)
+    }
+
+    /// Like [`Self::perform_rename`], but fails without changing anything when the language
+    /// server would also create, rename or delete files, which can't be undone.
+    pub fn perform_rename_without_resource_operations<T: ToPointUtf16>(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: T,
+        new_name: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        self.request_rename(buffer, position, new_name, true, cx)
+    }
+
+    fn request_rename<T: ToPointUtf16>(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: T,
+        new_name: String,
+        reject_resource_operations: bool,
+        cx: &mut Context<Self>,
     ) -> Task<Result<ProjectTransaction>> {
         let push_to_history = true;
         let position = position.to_point_utf16(buffer.read(cx));
@@ -4073,6 +4219,7 @@ impl Project {
                 position,
                 new_name,
                 push_to_history,
+                reject_resource_operations,
             },
             cx,
         )
@@ -299,6 +299,7 @@ impl Search {
                 };
 
                 let should_find_all_matches = !tx.is_closed();
+                let wait_for_parsing = query.is_structural() || query.scope().is_some();
 
                 let _executor = executor.clone();
                 let worker_pool = executor.spawn(async move {
@@ -334,6 +335,7 @@ impl Search {
                     Some(
                         Self::grab_buffer_snapshots(
                             grab_buffer_snapshot_rx,
+                            wait_for_parsing,
                             find_all_matches_tx,
                             sorted_matches_tx,
                             cx.clone(),
@@ -498,6 +500,7 @@ impl Search {
 
     async fn grab_buffer_snapshots(
         rx: Receiver<Entity<Buffer>>,
+        wait_for_parsing: bool,
         find_all_matches_tx: Sender<(
             Entity<Buffer>,
             BufferSnapshot,
@@ -508,6 +511,11 @@ impl Search {
     ) {
         _ = maybe!(async move {
             while let Ok(buffer) = rx.recv().await {
+                // Structural queries match against the syntax tree, so it must be up to date.
+                if wait_for_parsing {
+                    let parsing_idle = buffer.read_with(&mut cx, |this, _| this.parsing_idle())?;
+                    parsing_idle.await;
+                }
                 let snapshot = buffer.read_with(&mut cx, |this, _| this.snapshot())?;
                 let (tx, rx) = oneshot::channel();
                 find_all_matches_tx.send((buffer, snapshot, tx)).await?;
@@ -531,6 +539,11 @@ impl Search {
                 let Some((buffer, ranges)) = next_buffer_matches.recv().await else {
                     continue;
                 };
+                // Structural and scoped queries can't be fully confirmed before parsing, so some
+                // candidates end up without any matches.
+                if ranges.is_empty() {
+                    continue;
+                }
 
                 if matched_buffers > Search::MAX_SEARCH_RESULT_FILES
                     || matches > Search::MAX_SEARCH_RESULT_RANGES
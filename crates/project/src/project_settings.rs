@@ -90,6 +90,11 @@ pub struct SessionSettings {
     ///
     /// Default: false
     pub trust_all_worktrees: bool,
+    /// Whether to keep the buffers of a remote project editable after its connection is lost.
+    /// Saves are queued, and the edits are applied when the project is reconnected.
+    ///
+    /// Default: true
+    pub edit_while_disconnected: bool,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -601,6 +606,7 @@ impl Settings for ProjectSettings {
             session: SessionSettings {
                 restore_unsaved_buffers: content.session.unwrap().restore_unsaved_buffers.unwrap(),
                 trust_all_worktrees: content.session.unwrap().trust_all_worktrees.unwrap(),
+                edit_while_disconnected: content.session.unwrap().edit_while_disconnected.unwrap(),
             },
         }
     }
@@ -3,6 +3,7 @@
 use crate::{
     Event,
     git_store::{GitStoreEvent, RepositoryEvent, StatusEntry, pending_op},
+    search::SyntaxScope,
     task_inventory::TaskContexts,
     task_store::TaskSettingsLocation,
     *,
@@ -6464,6 +6465,115 @@ async fn test_search_with_unicode(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "fn one() -> Option<u32> { let a = foo().unwrap(); Some(a.unwrap()) }",
+            "two.rs": "fn two() { unwrap(x); }",
+            "three.txt": "x.unwrap()",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+
+    let query = SearchQuery::structural(
+        "$X.unwrap()",
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap()
+    .with_replacement("$X?".to_string());
+    assert_eq!(
+        search(&project, query.clone(), cx).await.unwrap(),
+        HashMap::from_iter([(path!("dir/one.rs").to_string(), vec![34..48, 55..65])])
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/one.rs"), cx)
+        })
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        let snapshot = buffer.snapshot();
+        assert_eq!(
+            query.structural_replacement_for(&snapshot, 34..48),
+            Some("foo()?".to_string())
+        );
+        assert_eq!(
+            query.structural_replacement_for(&snapshot, 55..65),
+            Some("a?".to_string())
+        );
+        assert_eq!(query.structural_replacement_for(&snapshot, 26..49), None);
+    });
+}
+
+#[gpui::test]
+async fn test_scoped_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "// TODO: fix\nfn todo() { log(\"TODO later\"); }",
+            "notes.txt": "TODO",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+
+    let query = |scope| {
+        SearchQuery::text(
+            "todo",
+            false,
+            false,
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+        )
+        .unwrap()
+        .with_scope(scope)
+    };
+    assert_eq!(
+        search(&project, query(None), cx).await.unwrap(),
+        HashMap::from_iter([
+            (path!("dir/one.rs").to_string(), vec![3..7, 16..20, 30..34]),
+            (path!("dir/notes.txt").to_string(), vec![0..4]),
+        ])
+    );
+    assert_eq!(
+        search(&project, query(Some(SyntaxScope::Code)), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([(path!("dir/one.rs").to_string(), vec![16..20])])
+    );
+    assert_eq!(
+        search(&project, query(Some(SyntaxScope::Comments)), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([(path!("dir/one.rs").to_string(), vec![3..7])])
+    );
+    assert_eq!(
+        search(&project, query(Some(SyntaxScope::Strings)), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([(path!("dir/one.rs").to_string(), vec![30..34])])
+    );
+}
+
 #[gpui::test]
 async fn test_create_entry(cx: &mut gpui::TestAppContext) {
     init_test(cx);
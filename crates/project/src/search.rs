@@ -8,6 +8,7 @@ use language::{Buffer, BufferSnapshot, CharKind};
 use smol::future::yield_now;
 use std::{
     borrow::Cow,
+    cmp::Reverse,
     io::{BufRead, BufReader, Read},
     ops::Range,
     sync::{Arc, LazyLock},
@@ -18,6 +19,10 @@ use util::{
     rel_path::RelPath,
 };
 
+pub mod structural;
+
+use structural::StructuralPattern;
+
 #[derive(Debug)]
 pub enum SearchResult {
     Buffer {
@@ -41,6 +46,7 @@ pub struct SearchInputs {
     files_to_exclude: PathMatcher,
     match_full_paths: bool,
     buffers: Option<Vec<Entity<Buffer>>>,
+    scope: Option<SyntaxScope>,
 }
 
 impl SearchInputs {
@@ -57,6 +63,48 @@ impl SearchInputs {
         &self.buffers
     }
 }
+
+/// A syntactic scope that search matches can be restricted to, determined from the captures of
+/// each language's highlights query.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyntaxScope {
+    /// Anything outside of comments and string literals.
+    Code,
+    Comments,
+    Strings,
+}
+
+impl SyntaxScope {
+    /// Whether a match at `range` belongs to this scope, given the sorted, disjoint ranges of the
+    /// comments and string literals around it.
+    fn contains(
+        self,
+        range: &Range<usize>,
+        comments: &[Range<usize>],
+        strings: &[Range<usize>],
+    ) -> bool {
+        fn is_within(ranges: &[Range<usize>], range: &Range<usize>) -> bool {
+            let ix = ranges.partition_point(|candidate| candidate.end < range.end);
+            ranges
+                .get(ix)
+                .is_some_and(|candidate| candidate.start <= range.start)
+        }
+
+        fn intersects(ranges: &[Range<usize>], range: &Range<usize>) -> bool {
+            let ix = ranges.partition_point(|candidate| candidate.end <= range.start);
+            ranges
+                .get(ix)
+                .is_some_and(|candidate| candidate.start < range.end)
+        }
+
+        match self {
+            SyntaxScope::Code => !intersects(comments, range) && !intersects(strings, range),
+            SyntaxScope::Comments => is_within(comments, range),
+            SyntaxScope::Strings => is_within(strings, range),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum SearchQuery {
     Text {
@@ -77,6 +125,12 @@ pub enum SearchQuery {
         one_match_per_line: bool,
         inner: SearchInputs,
     },
+    Structural {
+        pattern: StructuralPattern,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 static WORD_MATCH_TEST: LazyLock<Regex> = LazyLock::new(|| {
@@ -126,6 +180,7 @@ impl SearchQuery {
             files_to_include,
             match_full_paths,
             buffers,
+            scope: None,
         };
         Ok(Self::Text {
             search,
@@ -189,6 +244,7 @@ impl SearchQuery {
             files_to_include,
             match_full_paths,
             buffers,
+            scope: None,
         };
         Ok(Self::Regex {
             regex,
@@ -202,6 +258,34 @@ impl SearchQuery {
         })
     }
 
+    /// Create a structural query, which matches a code pattern against the syntax tree of each
+    /// buffer. See [`structural`] for the pattern syntax.
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+        match_full_paths: bool,
+        buffers: Option<Vec<Entity<Buffer>>>,
+    ) -> Result<Self> {
+        let query = query.to_string();
+        let pattern = StructuralPattern::new(&query)?;
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+            match_full_paths,
+            buffers,
+            scope: None,
+        };
+        Ok(Self::Structural {
+            pattern,
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
     /// Extracts case sensitivity settings from pattern items in the provided
     /// query and returns the same query, with the pattern items removed.
     ///
@@ -266,7 +350,22 @@ impl SearchQuery {
             message.files_to_exclude
         };
 
-        if message.regex {
+        let scope = match proto::search_query::SyntaxScope::from_i32(message.scope) {
+            Some(proto::search_query::SyntaxScope::Code) => Some(SyntaxScope::Code),
+            Some(proto::search_query::SyntaxScope::Comments) => Some(SyntaxScope::Comments),
+            Some(proto::search_query::SyntaxScope::Strings) => Some(SyntaxScope::Strings),
+            Some(proto::search_query::SyntaxScope::Any) | None => None,
+        };
+        let query = if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                PathMatcher::new(files_to_include, path_style)?,
+                PathMatcher::new(files_to_exclude, path_style)?,
+                message.match_full_paths,
+                None, // search opened only don't need search remote
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -289,7 +388,19 @@ impl SearchQuery {
                 false,
                 None, // search opened only don't need search remote
             )
+        }?;
+        Ok(query.with_scope(scope))
+    }
+
+    /// Restricts the matches of this query to the given syntactic scope. Buffers without syntax
+    /// highlighting have no scopes, so they never match a scoped query.
+    pub fn with_scope(mut self, scope: Option<SyntaxScope>) -> Self {
+        match &mut self {
+            Self::Text { inner, .. }
+            | Self::Regex { inner, .. }
+            | Self::Structural { inner, .. } => inner.scope = scope,
         }
+        self
     }
 
     pub fn with_replacement(mut self, new_replacement: String) -> Self {
@@ -301,6 +412,10 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
@@ -314,6 +429,13 @@ impl SearchQuery {
         proto::SearchQuery {
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
+            scope: match self.scope() {
+                None => proto::search_query::SyntaxScope::Any,
+                Some(SyntaxScope::Code) => proto::search_query::SyntaxScope::Code,
+                Some(SyntaxScope::Comments) => proto::search_query::SyntaxScope::Comments,
+                Some(SyntaxScope::Strings) => proto::search_query::SyntaxScope::Strings,
+            } as i32,
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
@@ -389,19 +511,27 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            Self::Structural { pattern, .. } => {
+                reader.read_to_string(&mut text)?;
+                Ok(pattern.literals().all(|literal| text.contains(literal)))
+            }
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
+    ///
+    /// Structural queries need the syntax tree around a match to compute its replacement, so they
+    /// return `None` here; use [`Self::structural_replacement_for`] instead.
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
         match self {
+            SearchQuery::Structural { .. } => None,
             SearchQuery::Text { replacement, .. } => replacement.clone().map(Cow::from),
             SearchQuery::Regex {
                 regex, replacement, ..
@@ -426,6 +556,35 @@ impl SearchQuery {
         }
     }
 
+    /// Returns the replacement for the structural match at `range` in `buffer`, with the
+    /// pattern's metavariables expanded to the text they matched.
+    pub fn structural_replacement_for(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        let SearchQuery::Structural {
+            pattern,
+            replacement,
+            ..
+        } = self
+        else {
+            return None;
+        };
+        let replacement = replacement.as_ref()?;
+        let text = buffer.text_for_range(range.clone()).collect::<String>();
+        buffer.syntax_layers().find_map(|layer| {
+            let captures =
+                pattern.captures_for_range(layer.node(), &text, range.start, range.clone())?;
+            Some(StructuralPattern::render(
+                replacement,
+                &captures,
+                &text,
+                range.start,
+            ))
+        })
+    }
+
     pub async fn search(
         &self,
         buffer: &BufferSnapshot,
@@ -521,6 +680,42 @@ impl SearchQuery {
                     }
                 }
             }
+
+            Self::Structural { pattern, .. } => {
+                let text = rope.to_string();
+                let range = range_offset..range_offset + text.len();
+                for layer in buffer.syntax_layers() {
+                    matches.extend(
+                        pattern
+                            .find_matches(layer.node(), &text, range_offset, range.clone())
+                            .into_iter()
+                            .map(|range| range.start - range_offset..range.end - range_offset),
+                    );
+                    yield_now().await;
+                }
+                // Matches in injected languages may overlap those in the language that contains them.
+                matches.sort_by_key(|range| (range.start, Reverse(range.end)));
+                let mut last_end = 0;
+                matches.retain(|range| {
+                    let is_disjoint = range.start >= last_end;
+                    if is_disjoint {
+                        last_end = range.end;
+                    }
+                    is_disjoint
+                });
+            }
+        }
+
+        if let Some(scope) = self.scope() {
+            let searched_range = range_offset..range_offset + rope.len();
+            if let Some((comments, strings)) = comment_and_string_ranges(buffer, searched_range) {
+                matches.retain(|range| {
+                    let range = range.start + range_offset..range.end + range_offset;
+                    scope.contains(&range, &comments, &strings)
+                });
+            } else {
+                matches.clear();
+            }
         }
 
         matches
@@ -538,6 +733,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -545,6 +741,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => true,
         }
     }
 
@@ -555,6 +752,9 @@ impl SearchQuery {
             } => *include_ignored,
             Self::Regex {
                 include_ignored, ..
+            }
+            | Self::Structural {
+                include_ignored, ..
             } => *include_ignored,
         }
     }
@@ -563,6 +763,14 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
+    pub fn scope(&self) -> Option<SyntaxScope> {
+        self.as_inner().scope
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -606,22 +814,80 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 
     /// Whether this search should replace only one match per line, instead of
     /// all matches.
-    /// Returns `None` for text searches, as only regex searches support this
-    /// option.
+    /// Returns `None` for text and structural searches, as only regex searches
+    /// support this option.
     pub fn one_match_per_line(&self) -> Option<bool> {
         match self {
             Self::Regex {
                 one_match_per_line, ..
             } => Some(*one_match_per_line),
-            Self::Text { .. } => None,
+            Self::Text { .. } | Self::Structural { .. } => None,
+        }
+    }
+}
+
+/// Returns the sorted, disjoint ranges of the comments and the string literals that intersect
+/// `range`, according to the highlights queries of the buffer's languages. Returns `None` if the
+/// buffer isn't highlighted at all.
+fn comment_and_string_ranges(
+    buffer: &BufferSnapshot,
+    range: Range<usize>,
+) -> Option<(Vec<Range<usize>>, Vec<Range<usize>>)> {
+    fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+        // Captures are reported in order of their start, so a capture either extends the last
+        // range (e.g. a `@string.escape` within a `@string`) or comes after it.
+        if let Some(last) = ranges.last_mut()
+            && range.start <= last.end
+        {
+            last.end = last.end.max(range.end);
+        } else {
+            ranges.push(range);
+        }
+    }
+
+    let is_highlighted = buffer
+        .language()
+        .and_then(|language| language.grammar())
+        .is_some_and(|grammar| grammar.highlights_config.is_some());
+    if !is_highlighted {
+        return None;
+    }
+
+    let mut comments = Vec::new();
+    let mut strings = Vec::new();
+    let mut captures = buffer.syntax.captures(range, &buffer.text, |grammar| {
+        grammar
+            .highlights_config
+            .as_ref()
+            .map(|config| &config.query)
+    });
+    while let Some(capture) = captures.peek() {
+        if let Some(config) = captures.grammars()[capture.grammar_index]
+            .highlights_config
+            .as_ref()
+        {
+            let name = config.query.capture_names()[capture.index as usize];
+            let is_scope = |scope: &str| {
+                name.strip_prefix(scope)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+            };
+            if is_scope("comment") {
+                push_range(&mut comments, capture.node.byte_range());
+            } else if is_scope("string") {
+                push_range(&mut strings, capture.node.byte_range());
+            }
         }
+        captures.advance();
     }
+    Some((comments, strings))
 }
 
 #[cfg(test)]
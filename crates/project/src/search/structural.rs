@@ -0,0 +1,485 @@
+//! Structural search, which matches code patterns against the syntax tree of a buffer.
+//!
+//! A pattern is written as code in the language being searched, with metavariables standing in
+//! for parts of it. `$NAME` matches a single syntax node, and `$$$NAME` matches a run of
+//! sibling nodes, possibly empty. A metavariable that appears more than once must match the same
+//! text each time, except for `$_` and `$$$`, which match anything. Whitespace in the pattern is
+//! insignificant, so `foo($A,$B)` also matches `foo(a, b)`.
+//!
+//! Replacements refer to the text matched by a metavariable with the same syntax, so searching
+//! for `$X.unwrap()` and replacing it with `$X?` rewrites `foo().unwrap()` as `foo()?`.
+
+use anyhow::{Result, bail};
+use language::Node;
+use std::ops::Range;
+
+const ANONYMOUS_METAVARIABLE: &str = "_";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PatternToken {
+    Literal(String),
+    Metavariable(String),
+    Ellipsis(String),
+}
+
+/// The text matched by each named metavariable in a pattern.
+#[derive(Clone, Debug, Default)]
+pub struct Captures(Vec<(String, Range<usize>)>);
+
+#[derive(Clone, Debug)]
+pub struct StructuralPattern {
+    tokens: Vec<PatternToken>,
+}
+
+impl StructuralPattern {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let tokens = tokenize(pattern);
+        if tokens.is_empty() {
+            bail!("structural search pattern is empty");
+        }
+        Ok(Self { tokens })
+    }
+
+    /// The literal text in the pattern, all of which appears in any text the pattern matches.
+    pub(crate) fn literals(&self) -> impl Iterator<Item = &str> {
+        self.tokens.iter().filter_map(|token| match token {
+            PatternToken::Literal(literal) => Some(literal.as_str()),
+            PatternToken::Metavariable(_) | PatternToken::Ellipsis(_) => None,
+        })
+    }
+
+    /// Finds the outermost nodes under `root` within `range` that match the pattern.
+    ///
+    /// `text` is the source text starting at `text_offset`, and must span `range`.
+    pub(crate) fn find_matches(
+        &self,
+        root: Node,
+        text: &str,
+        text_offset: usize,
+        range: Range<usize>,
+    ) -> Vec<Range<usize>> {
+        let matcher = Matcher {
+            tokens: &self.tokens,
+            text,
+            text_offset,
+        };
+        let mut matches = Vec::new();
+        let mut cursor = root.walk();
+        'nodes: loop {
+            let node = cursor.node();
+            let mut descend = node.end_byte() > range.start && node.start_byte() < range.end;
+            if descend
+                && node.start_byte() >= range.start
+                && node.end_byte() <= range.end
+                && matcher.match_node(node).is_some()
+            {
+                matches.push(node.byte_range());
+                descend = false;
+            }
+
+            if descend && cursor.goto_first_child() {
+                continue;
+            }
+            loop {
+                if cursor.goto_next_sibling() {
+                    continue 'nodes;
+                }
+                if !cursor.goto_parent() {
+                    break 'nodes;
+                }
+            }
+        }
+        matches
+    }
+
+    /// Matches the pattern against the node under `root` that spans exactly `range`.
+    pub(crate) fn captures_for_range(
+        &self,
+        root: Node,
+        text: &str,
+        text_offset: usize,
+        range: Range<usize>,
+    ) -> Option<Captures> {
+        let matcher = Matcher {
+            tokens: &self.tokens,
+            text,
+            text_offset,
+        };
+        let mut node = root;
+        loop {
+            if node.byte_range() == range
+                && let Some(captures) = matcher.match_node(node)
+            {
+                return Some(captures);
+            }
+            node = node.first_child_for_byte(range.start).filter(|child| {
+                child.start_byte() <= range.start && child.end_byte() >= range.end
+            })?;
+        }
+    }
+
+    /// Expands the metavariables in `template` with the text they matched.
+    ///
+    /// `text` is the source text starting at `text_offset`, and must span every capture.
+    pub(crate) fn render(
+        template: &str,
+        captures: &Captures,
+        text: &str,
+        text_offset: usize,
+    ) -> String {
+        let mut output = String::new();
+        let mut ix = 0;
+        while let Some(dollar_ix) = template[ix..].find('$').map(|dollar_ix| ix + dollar_ix) {
+            output.push_str(&template[ix..dollar_ix]);
+            let (name_start, name) = metavariable_at(template, dollar_ix);
+            let captured = captures
+                .0
+                .iter()
+                .find(|(captured_name, _)| !name.is_empty() && *captured_name == name);
+            if let Some((_, range)) = captured {
+                output.push_str(&text[range.start - text_offset..range.end - text_offset]);
+            } else {
+                output.push_str(&template[dollar_ix..name_start + name.len()]);
+            }
+            ix = name_start + name.len();
+        }
+        output.push_str(&template[ix..]);
+        output
+    }
+}
+
+struct Matcher<'a> {
+    tokens: &'a [PatternToken],
+    text: &'a str,
+    text_offset: usize,
+}
+
+impl Matcher<'_> {
+    fn match_node(&self, node: Node) -> Option<Captures> {
+        if !node.is_named() || node.byte_range().is_empty() {
+            return None;
+        }
+        let mut captures = Captures::default();
+        self.match_tokens(
+            self.tokens,
+            node,
+            node.start_byte(),
+            node.end_byte(),
+            &mut captures,
+        )
+        .then_some(captures)
+    }
+
+    fn match_tokens(
+        &self,
+        tokens: &[PatternToken],
+        scope: Node,
+        position: usize,
+        end: usize,
+        captures: &mut Captures,
+    ) -> bool {
+        let position = self.skip_whitespace(position, end);
+        let Some((token, rest)) = tokens.split_first() else {
+            return position == end;
+        };
+
+        match token {
+            PatternToken::Literal(literal) => {
+                let literal_end = position + literal.len();
+                literal_end <= end
+                    && self.text_from(position).starts_with(literal.as_str())
+                    && !self.splits_word(position, literal_end)
+                    && self.match_tokens(rest, scope, literal_end, end, captures)
+            }
+            PatternToken::Metavariable(name) => {
+                let (_, nodes) = nodes_starting_at(scope, position, end);
+                nodes
+                    .into_iter()
+                    .filter(|node| node.is_named() || node.child_count() == 0)
+                    .any(|node| {
+                        self.bind_and_match(
+                            name,
+                            position..node.end_byte(),
+                            rest,
+                            scope,
+                            end,
+                            captures,
+                        )
+                    })
+            }
+            PatternToken::Ellipsis(name) => {
+                let (parent, nodes) = nodes_starting_at(scope, position, end);
+                let mut run_ends = vec![position];
+                if let Some(first) = nodes.first() {
+                    match parent {
+                        Some(parent) => {
+                            let mut cursor = parent.walk();
+                            run_ends.extend(
+                                parent
+                                    .children(&mut cursor)
+                                    .skip_while(|child| child.start_byte() < first.start_byte())
+                                    .map(|child| child.end_byte())
+                                    .take_while(|child_end| *child_end <= end),
+                            );
+                        }
+                        None => run_ends.push(first.end_byte()),
+                    }
+                }
+                run_ends.into_iter().any(|run_end| {
+                    self.bind_and_match(name, position..run_end, rest, scope, end, captures)
+                })
+            }
+        }
+    }
+
+    fn bind_and_match(
+        &self,
+        name: &str,
+        range: Range<usize>,
+        rest: &[PatternToken],
+        scope: Node,
+        end: usize,
+        captures: &mut Captures,
+    ) -> bool {
+        let capture_count = captures.0.len();
+        if name != ANONYMOUS_METAVARIABLE {
+            let previous = captures
+                .0
+                .iter()
+                .find(|(captured_name, _)| captured_name == name);
+            match previous {
+                Some((_, previous)) => {
+                    if self.text_for(previous.clone()) != self.text_for(range.clone()) {
+                        return false;
+                    }
+                }
+                None => captures.0.push((name.to_string(), range.clone())),
+            }
+        }
+        if self.match_tokens(rest, scope, range.end, end, captures) {
+            true
+        } else {
+            captures.0.truncate(capture_count);
+            false
+        }
+    }
+
+    fn text_from(&self, position: usize) -> &str {
+        &self.text[position - self.text_offset..]
+    }
+
+    fn text_for(&self, range: Range<usize>) -> &str {
+        &self.text[range.start - self.text_offset..range.end - self.text_offset]
+    }
+
+    fn skip_whitespace(&self, position: usize, end: usize) -> usize {
+        let whitespace_len =
+            self.text_for(position..end).len() - self.text_for(position..end).trim_start().len();
+        position + whitespace_len
+    }
+
+    /// Whether the text at `start..end` begins or ends in the middle of a word.
+    fn splits_word(&self, start: usize, end: usize) -> bool {
+        let literal = self.text_for(start..end);
+        let before = self.text[..start - self.text_offset].chars().next_back();
+        let after = self.text_from(end).chars().next();
+        (literal.starts_with(is_word_char) && before.is_some_and(is_word_char))
+            || (literal.ends_with(is_word_char) && after.is_some_and(is_word_char))
+    }
+}
+
+/// Returns the nodes within `scope` that start at `position` and end no later than `end`,
+/// outermost first, along with the parent of the outermost one if it is inside `scope`.
+fn nodes_starting_at<'a>(
+    scope: Node<'a>,
+    position: usize,
+    end: usize,
+) -> (Option<Node<'a>>, Vec<Node<'a>>) {
+    let mut parent = None;
+    let mut nodes = Vec::new();
+    let mut node = scope;
+    loop {
+        if node.start_byte() == position && node.end_byte() > position && node.end_byte() <= end {
+            nodes.push(node);
+        } else if nodes.is_empty() {
+            parent = Some(node);
+        }
+        match node.first_child_for_byte(position) {
+            Some(child) if child.start_byte() <= position => node = child,
+            _ => break,
+        }
+    }
+    (parent, nodes)
+}
+
+fn tokenize(pattern: &str) -> Vec<PatternToken> {
+    let mut tokens = Vec::new();
+    let mut ix = 0;
+    while let Some(c) = pattern[ix..].chars().next() {
+        if c.is_whitespace() {
+            ix += c.len_utf8();
+            continue;
+        }
+
+        if c == '$' {
+            let (name_start, name) = metavariable_at(pattern, ix);
+            let is_ellipsis = name_start - ix == 3;
+            if is_ellipsis || !name.is_empty() {
+                tokens.push(if is_ellipsis {
+                    PatternToken::Ellipsis(if name.is_empty() {
+                        ANONYMOUS_METAVARIABLE.to_string()
+                    } else {
+                        name.to_string()
+                    })
+                } else {
+                    PatternToken::Metavariable(name.to_string())
+                });
+                ix = name_start + name.len();
+                continue;
+            }
+        }
+
+        let len = if is_word_char(c) {
+            word_len(&pattern[ix..])
+        } else if c == '"' {
+            string_literal_len(&pattern[ix..])
+        } else {
+            c.len_utf8()
+        };
+        tokens.push(PatternToken::Literal(pattern[ix..ix + len].to_string()));
+        ix += len;
+    }
+    tokens
+}
+
+/// Parses the metavariable whose `$` is at `ix`, returning the offset of its name and the name.
+fn metavariable_at(text: &str, ix: usize) -> (usize, &str) {
+    let name_start = if text[ix..].starts_with("$$$") {
+        ix + 3
+    } else {
+        ix + 1
+    };
+    let name = &text[name_start..name_start + word_len(&text[name_start..])];
+    (name_start, name)
+}
+
+fn word_len(text: &str) -> usize {
+    text.find(|c| !is_word_char(c)).unwrap_or(text.len())
+}
+
+fn string_literal_len(text: &str) -> usize {
+    let mut escaped = false;
+    for (ix, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return ix + 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use language::{Parser, rust_lang};
+
+    fn find(pattern: &str, text: &str) -> Vec<&'static str> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&rust_lang().grammar().unwrap().ts_language)
+            .unwrap();
+        let tree = parser.parse(text, None).unwrap();
+        let text: &'static str = text.to_string().leak();
+        StructuralPattern::new(pattern)
+            .unwrap()
+            .find_matches(tree.root_node(), text, 0, 0..text.len())
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    fn replace(pattern: &str, template: &str, text: &str) -> String {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&rust_lang().grammar().unwrap().ts_language)
+            .unwrap();
+        let tree = parser.parse(text, None).unwrap();
+        let pattern = StructuralPattern::new(pattern).unwrap();
+        let mut output = text.to_string();
+        let matches = pattern.find_matches(tree.root_node(), text, 0, 0..text.len());
+        for range in matches.into_iter().rev() {
+            let captures = pattern
+                .captures_for_range(tree.root_node(), text, 0, range.clone())
+                .unwrap();
+            output.replace_range(
+                range,
+                &StructuralPattern::render(template, &captures, text, 0),
+            );
+        }
+        output
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("$X.unwrap_or($$$ARGS, \"a b\") $$$"),
+            vec![
+                PatternToken::Metavariable("X".into()),
+                PatternToken::Literal(".".into()),
+                PatternToken::Literal("unwrap_or".into()),
+                PatternToken::Literal("(".into()),
+                PatternToken::Ellipsis("ARGS".into()),
+                PatternToken::Literal(",".into()),
+                PatternToken::Literal("\"a b\"".into()),
+                PatternToken::Literal(")".into()),
+                PatternToken::Ellipsis("_".into()),
+            ]
+        );
+        assert!(StructuralPattern::new("  ").is_err());
+    }
+
+    #[test]
+    fn test_find_matches() {
+        let text = "fn f() { let a = foo(1).unwrap(); let b = bar.baz().unwrap(); unwrap(c); }";
+        assert_eq!(
+            find("$X.unwrap()", text),
+            vec!["foo(1).unwrap()", "bar.baz().unwrap()"]
+        );
+        assert_eq!(find("unwrap($$$)", text), vec!["unwrap(c)"]);
+        assert_eq!(find("let $A = $B;", text).len(), 2);
+
+        // Repeated metavariables must match the same text.
+        let text = "fn f() { a + a; a + b; }";
+        assert_eq!(find("$X + $X", text), vec!["a + a"]);
+        assert_eq!(find("$X + $Y", text), vec!["a + a", "a + b"]);
+
+        // Literals don't match part of a word.
+        assert_eq!(find("foo($$$)", "fn f() { foobar(); }"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_replace() {
+        assert_eq!(
+            replace("$X.unwrap()", "$X?", "fn f() { let a = foo(1).unwrap(); }"),
+            "fn f() { let a = foo(1)?; }"
+        );
+        assert_eq!(
+            replace("swap($A, $B)", "swap($B, $A)", "fn f() { swap(x + 1, y); }"),
+            "fn f() { swap(y, x + 1); }"
+        );
+        assert_eq!(
+            replace(
+                "call($$$ARGS)",
+                "call(cx, $$$ARGS)",
+                "fn f() { call(a, b.c()); }"
+            ),
+            "fn f() { call(cx, a, b.c()); }"
+        );
+    }
+}
@@ -8,11 +8,11 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
 use gpui::{App, AppContext as _, Context, Entity, SharedString, Task, WeakEntity};
-use itertools::Itertools;
+use itertools::{Either, Itertools};
 use language::{
     Buffer, ContextLocation, ContextProvider, File, Language, LanguageToolchainStore, Location,
     language_settings::language_settings,
@@ -21,8 +21,8 @@ use lsp::{LanguageServerId, LanguageServerName};
 use paths::{debug_task_file_name, task_file_name};
 use settings::{InvalidSettingsError, parse_json_with_comments};
 use task::{
-    DebugScenario, ResolvedTask, TaskContext, TaskId, TaskTemplate, TaskTemplates, TaskVariables,
-    VariableName,
+    DebugCompound, DebugScenario, DebugTaskEntry, ResolvedTask, TaskContext, TaskId, TaskTemplate,
+    TaskTemplates, TaskVariables, VariableName,
 };
 use text::{BufferId, Point, ToPoint};
 use util::{NumericPrefixWithSuffix, ResultExt as _, post_inc, rel_path::RelPath};
@@ -43,6 +43,7 @@ pub struct Inventory {
     last_scheduled_scenarios: VecDeque<(DebugScenario, DebugScenarioContext)>,
     templates_from_settings: InventoryFor<TaskTemplate>,
     scenarios_from_settings: InventoryFor<DebugScenario>,
+    compounds_from_settings: InventoryFor<DebugCompound>,
 }
 
 impl std::fmt::Debug for Inventory {
@@ -52,6 +53,7 @@ impl std::fmt::Debug for Inventory {
             .field("last_scheduled_scenarios", &self.last_scheduled_scenarios)
             .field("templates_from_settings", &self.templates_from_settings)
             .field("scenarios_from_settings", &self.scenarios_from_settings)
+            .field("compounds_from_settings", &self.compounds_from_settings)
             .finish()
     }
 }
@@ -73,6 +75,12 @@ impl InventoryContents for DebugScenario {
     const LABEL: &'static str = "debug scenarios";
 }
 
+impl InventoryContents for DebugCompound {
+    const GLOBAL_SOURCE_FILE: &'static str = "debug.json";
+
+    const LABEL: &'static str = "debug compounds";
+}
+
 #[derive(Debug)]
 struct InventoryFor<T> {
     global: HashMap<PathBuf, Vec<T>>,
@@ -246,6 +254,7 @@ impl Inventory {
             last_scheduled_scenarios: VecDeque::default(),
             templates_from_settings: InventoryFor::default(),
             scenarios_from_settings: InventoryFor::default(),
+            compounds_from_settings: InventoryFor::default(),
         })
     }
 
@@ -275,6 +284,50 @@ impl Inventory {
         self.last_scheduled_scenarios.back()
     }
 
+    /// Lists the compound debug configurations defined in the settings of the given worktree and in the global debug.json.
+    pub fn list_debug_compounds(
+        &self,
+        worktree_id: Option<WorktreeId>,
+    ) -> Vec<(TaskSourceKind, DebugCompound)> {
+        worktree_id
+            .into_iter()
+            .flat_map(|worktree_id| self.compounds_from_settings.worktree_scenarios(worktree_id))
+            .chain(self.compounds_from_settings.global_scenarios())
+            .collect()
+    }
+
+    /// Looks up the scenarios a compound refers to, in the order they should be started.
+    /// Scenarios of the given worktree take precedence over the global ones with the same label.
+    pub fn resolve_debug_compound(
+        &self,
+        compound: &DebugCompound,
+        worktree_id: Option<WorktreeId>,
+    ) -> Result<Vec<DebugScenario>> {
+        let scenarios = worktree_id
+            .into_iter()
+            .flat_map(|worktree_id| self.worktree_scenarios_from_settings(worktree_id))
+            .chain(self.global_debug_scenarios_from_settings())
+            .map(|(_, scenario)| scenario)
+            .collect::<Vec<_>>();
+
+        compound
+            .configurations
+            .iter()
+            .map(|label| {
+                scenarios
+                    .iter()
+                    .find(|scenario| &scenario.label == label)
+                    .cloned()
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Compound \"{}\" refers to unknown debug configuration \"{label}\"",
+                            compound.label
+                        )
+                    })
+            })
+            .collect()
+    }
+
     pub fn list_debug_scenarios(
         &self,
         task_contexts: &TaskContexts,
@@ -740,12 +793,15 @@ impl Inventory {
             }
         };
 
-        let new_templates = raw_tasks
+        let (new_templates, new_compounds): (Vec<_>, Vec<_>) = raw_tasks
             .into_iter()
             .filter_map(|raw_template| {
-                serde_json::from_value::<DebugScenario>(raw_template).log_err()
+                serde_json::from_value::<DebugTaskEntry>(raw_template).log_err()
             })
-            .collect::<Vec<_>>();
+            .partition_map(|entry| match entry {
+                DebugTaskEntry::Scenario(scenario) => Either::Left(scenario),
+                DebugTaskEntry::Compound(compound) => Either::Right(compound),
+            });
 
         let parsed_scenarios = &mut self.scenarios_from_settings;
         let mut new_definitions: HashMap<_, _> = new_templates
@@ -764,6 +820,9 @@ impl Inventory {
                     .global
                     .entry(path.to_owned())
                     .insert_entry(new_templates);
+                self.compounds_from_settings
+                    .global
+                    .insert(path.to_owned(), new_compounds);
             }
             TaskSettingsLocation::Worktree(location) => {
                 previously_existing_scenarios = parsed_scenarios
@@ -784,6 +843,22 @@ impl Inventory {
                         .or_default()
                         .insert(Arc::from(location.path), new_templates);
                 }
+
+                if new_compounds.is_empty() {
+                    if let Some(worktree_compounds) = self
+                        .compounds_from_settings
+                        .worktree
+                        .get_mut(&location.worktree_id)
+                    {
+                        worktree_compounds.remove(location.path);
+                    }
+                } else {
+                    self.compounds_from_settings
+                        .worktree
+                        .entry(location.worktree_id)
+                        .or_default()
+                        .insert(Arc::from(location.path), new_compounds);
+                }
             }
         }
         self.last_scheduled_scenarios.retain_mut(|(scenario, _)| {
@@ -1392,6 +1467,86 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_debug_compounds(cx: &mut TestAppContext) {
+        init_test(cx);
+        let inventory = cx.update(|cx| Inventory::new(cx));
+        let worktree_id = WorktreeId::from_usize(0);
+        inventory.update(cx, |inventory, _| {
+            inventory
+                .update_file_based_scenarios(
+                    TaskSettingsLocation::Global(Path::new("")),
+                    Some(
+                        r#"
+                        [{
+                            "label": "Frontend",
+                            "adapter": "JavaScript",
+                            "request": "launch",
+                        }]
+                        "#,
+                    ),
+                )
+                .unwrap();
+            inventory
+                .update_file_based_scenarios(
+                    TaskSettingsLocation::Worktree(SettingsLocation {
+                        worktree_id,
+                        path: rel_path(".zed"),
+                    }),
+                    Some(
+                        r#"
+                        [
+                            {
+                                "label": "Backend",
+                                "adapter": "CodeLLDB",
+                                "request": "launch",
+                                "program": "server",
+                            },
+                            {
+                                "label": "Full stack",
+                                "configurations": ["Backend", "Frontend"],
+                                "stop_all": true,
+                            },
+                            {
+                                "label": "Broken",
+                                "configurations": ["Backend", "Missing"],
+                            },
+                        ]
+                        "#,
+                    ),
+                )
+                .unwrap();
+        });
+
+        inventory.update(cx, |inventory, _| {
+            let compounds = inventory.list_debug_compounds(Some(worktree_id));
+            assert_eq!(
+                compounds
+                    .iter()
+                    .map(|(_, compound)| compound.label.to_string())
+                    .collect::<Vec<_>>(),
+                vec!["Full stack".to_string(), "Broken".to_string()]
+            );
+            assert!(inventory.list_debug_compounds(None).is_empty());
+
+            let scenarios = inventory
+                .resolve_debug_compound(&compounds[0].1, Some(worktree_id))
+                .unwrap();
+            assert_eq!(
+                scenarios
+                    .iter()
+                    .map(|scenario| scenario.label.to_string())
+                    .collect::<Vec<_>>(),
+                vec!["Backend".to_string(), "Frontend".to_string()]
+            );
+            assert!(
+                inventory
+                    .resolve_debug_compound(&compounds[1].1, Some(worktree_id))
+                    .is_err()
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_inventory_static_task_filters(cx: &mut TestAppContext) {
         init_test(cx);
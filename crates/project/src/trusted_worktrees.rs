@@ -162,6 +162,9 @@ impl From<RemoteConnectionOptions> for RemoteHostLocation {
                 Some(SharedString::new(docker_connection_options.name)),
                 SharedString::new(docker_connection_options.container_id),
             ),
+            RemoteConnectionOptions::Command(command_connection_options) => {
+                (None, SharedString::new(command_connection_options.name))
+            }
         };
         Self {
             user_name,
@@ -72,6 +72,7 @@ impl WorktreeStore {
         client.add_entity_request_handler(Self::handle_delete_project_entry);
         client.add_entity_request_handler(Self::handle_expand_project_entry);
         client.add_entity_request_handler(Self::handle_expand_all_for_project_entry);
+        client.add_entity_request_handler(Self::handle_load_file_range);
     }
 
     pub fn local(retain_worktrees: bool, fs: Arc<dyn Fs>) -> Self {
@@ -1055,6 +1056,18 @@ impl WorktreeStore {
         Worktree::handle_expand_all_for_entry(worktree, envelope.payload, cx).await
     }
 
+    pub async fn handle_load_file_range(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::LoadFileRange>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadFileRangeResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let worktree = this
+            .update(&mut cx, |this, cx| this.worktree_for_id(worktree_id, cx))?
+            .context("invalid request")?;
+        Worktree::handle_load_file_range(worktree, envelope.payload, cx).await
+    }
+
     pub fn fs(&self) -> Option<Arc<dyn Fs>> {
         match &self.state {
             WorktreeStoreState::Local { fs } => Some(fs.clone()),
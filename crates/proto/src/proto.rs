@@ -108,6 +108,10 @@ messages!(
     (GetPermalinkToLine, Foreground),
     (GetProcesses, Background),
     (GetProcessesResponse, Background),
+    (GetListeningPorts, Background),
+    (GetListeningPortsResponse, Background),
+    (LoadFileRange, Background),
+    (LoadFileRangeResponse, Background),
     (GetPermalinkToLineResponse, Foreground),
     (GetProjectSymbols, Background),
     (GetProjectSymbolsResponse, Background),
@@ -524,6 +528,8 @@ request_messages!(
     (ToggleLspLogs, Ack),
     (GetDirectoryEnvironment, DirectoryEnvironment),
     (GetProcesses, GetProcessesResponse),
+    (GetListeningPorts, GetListeningPortsResponse),
+    (LoadFileRange, LoadFileRangeResponse),
     (GetAgentServerCommand, AgentServerCommand),
     (RemoteStarted, Ack),
     (GitGetWorktrees, GitWorktreesResponse),
@@ -661,6 +667,8 @@ entity_messages!(
     ResolveToolchain,
     GetPathMetadata,
     GetProcesses,
+    GetListeningPorts,
+    LoadFileRange,
     CancelLanguageServerWork,
     RegisterBufferWithLanguageServers,
     GitShow,
@@ -1,295 +1,1138 @@
+//! Creates and starts the dev containers described by `devcontainer.json` files, by driving the
+//! docker CLI directly.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use gpui::AsyncWindowContext;
-use node_runtime::NodeRuntime;
+use anyhow::{Context as _, Result};
+use futures::AsyncReadExt as _;
+use gpui::{AsyncApp, AsyncWindowContext, WindowHandle};
+use http_client::{AsyncBody, HttpClient, HttpRequestExt as _, RedirectPolicy, Request, Response};
+use itertools::Itertools as _;
+use remote::RemoteConnectionOptions;
 use serde::Deserialize;
+use serde_json::{Value, json};
 use settings::DevContainerConnection;
-use smol::fs;
+use sha2::{Digest as _, Sha256};
+use task::{HideStrategy, RevealStrategy, SpawnInTerminal, TaskId};
+use util::shell::ShellKind;
 use workspace::Workspace;
 
+use crate::dev_container_config::{
+    ContainerSource, DevContainerConfig, ForwardPort, LifecycleCommand, Substitutions, find_config,
+};
 use crate::remote_connections::Connection;
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct DevContainerUp {
-    _outcome: String,
-    container_id: String,
-    _remote_user: String,
-    remote_workspace_folder: String,
-}
+/// Label recording the local folder a container was created for. Shared with the reference
+/// devcontainer CLI, so that containers created by either are reused by the other.
+const LOCAL_FOLDER_LABEL: &str = "devcontainer.local_folder";
+/// Label recording the `devcontainer.json` a container was created from.
+const CONFIG_FILE_LABEL: &str = "devcontainer.config_file";
+/// Label recording the `forwardPorts` of `devcontainer.json`, which the port forwarding panel
+/// forwards once connected to the container.
+const FORWARD_PORTS_LABEL: &str = "dev.zed.forward_ports";
+
+/// Keeps the container running when `overrideCommand` replaces the image's command.
+const KEEP_ALIVE: &str =
+    "echo Container started; trap \"exit 0\" 15; while sleep 1 & wait $!; do :; done";
+
+/// Where features are copied to inside the image while they're being installed.
+const FEATURES_INSTALL_DIR: &str = "/tmp/dev-container-features";
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct DevContainerConfiguration {
-    name: Option<String>,
+const OCI_MANIFEST_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+
+pub(crate) struct DockerCli {
+    program: String,
+    /// When set, long running commands such as image builds stream their output into a terminal
+    /// of this workspace instead of running in the background.
+    terminal: Option<(WindowHandle<Workspace>, AsyncApp)>,
 }
 
-#[derive(Debug, Deserialize)]
-struct DevContainerConfigurationOutput {
-    configuration: DevContainerConfiguration,
+impl DockerCli {
+    async fn output(&self, args: &[String]) -> Result<String> {
+        remote::docker_command_output(&self.program, args).await
+    }
+
+    async fn run(&self, label: &str, args: Vec<String>) -> Result<()> {
+        if let Some((workspace, cx)) = &self.terminal {
+            let shell_kind = ShellKind::system();
+            let command = std::iter::once(&self.program)
+                .chain(&args)
+                .map(|arg| {
+                    shell_kind
+                        .try_quote(arg)
+                        .map(Cow::into_owned)
+                        .unwrap_or_else(|| arg.clone())
+                })
+                .join(" ");
+            let spawn_in_terminal = SpawnInTerminal {
+                id: TaskId("dev-container".to_string()),
+                full_label: label.to_string(),
+                label: label.to_string(),
+                command: Some(command.clone()),
+                command_label: command,
+                allow_concurrent_runs: true,
+                reveal: RevealStrategy::NoFocus,
+                hide: HideStrategy::OnSuccess,
+                show_summary: true,
+                show_command: true,
+                ..SpawnInTerminal::default()
+            };
+            let status = workspace
+                .update(&mut cx.clone(), |workspace, window, cx| {
+                    workspace.spawn_in_terminal(spawn_in_terminal, window, cx)
+                })?
+                .await;
+            match status {
+                Some(Ok(status)) => {
+                    anyhow::ensure!(status.success(), "{label} failed with {status}");
+                    return Ok(());
+                }
+                Some(Err(error)) => return Err(error.context(format!("{label} failed"))),
+                // The workspace has no terminal to run the command in.
+                None => {}
+            }
+        }
+        self.output(&args)
+            .await
+            .with_context(|| format!("{label} failed"))?;
+        Ok(())
+    }
 }
 
-#[cfg(not(target_os = "windows"))]
-fn dev_container_cli() -> String {
-    "devcontainer".to_string()
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DevContainerUp {
+    pub container_id: String,
+    pub remote_user: Option<String>,
+    pub remote_workspace_folder: String,
 }
 
-#[cfg(target_os = "windows")]
-fn dev_container_cli() -> String {
-    "devcontainer.cmd".to_string()
+/// Brings up the dev container for a local folder, reusing a previously created container when
+/// possible.
+pub(crate) struct DevContainerBuilder<'a> {
+    pub docker: &'a DockerCli,
+    /// Used to download features published to OCI registries.
+    pub http_client: Option<Arc<dyn HttpClient>>,
+    /// Where generated Dockerfiles, compose overrides and downloaded features are written.
+    pub scratch_dir: PathBuf,
+    pub local_folder: &'a Path,
+    pub config_path: &'a Path,
+    pub config: &'a DevContainerConfig,
 }
 
-async fn check_for_docker() -> Result<(), DevContainerError> {
-    let mut command = util::command::new_smol_command("docker");
-    command.arg("--version");
+impl DevContainerBuilder<'_> {
+    pub async fn up(&self, rebuild: bool) -> Result<DevContainerUp> {
+        let remote_workspace_folder = self.remote_workspace_folder();
+        let substitutions = Substitutions {
+            local_workspace_folder: self.local_folder,
+            container_workspace_folder: &remote_workspace_folder,
+        };
+        let config_dir = self
+            .config_path
+            .parent()
+            .context("devcontainer.json has no parent directory")?;
+        let source = self.config.source(config_dir)?;
 
-    match command.output().await {
-        Ok(_) => Ok(()),
-        Err(e) => {
-            log::error!("Unable to find docker in $PATH: {:?}", e);
-            Err(DevContainerError::DockerNotAvailable)
+        let existing_container = self.find_container().await?;
+        let (container_id, created) = match existing_container {
+            Some(container_id) if !rebuild => {
+                let running = self
+                    .docker
+                    .output(&args([
+                        "inspect",
+                        "-f",
+                        "{{.State.Running}}",
+                        &container_id,
+                    ]))
+                    .await?;
+                if running.trim() != "true" {
+                    self.docker
+                        .output(&args(["start", &container_id]))
+                        .await
+                        .context("starting dev container")?;
+                }
+                (container_id, false)
+            }
+            existing_container => {
+                if let Some(container_id) = existing_container {
+                    self.docker
+                        .output(&args(["rm", "-f", &container_id]))
+                        .await
+                        .context("removing dev container")?;
+                }
+                let container_id = match source {
+                    ContainerSource::Compose {
+                        files,
+                        service,
+                        run_services,
+                    } => {
+                        self.compose_up(&files, &service, &run_services, rebuild, &substitutions)
+                            .await?
+                    }
+                    source => {
+                        let image = self.build_image(source).await?;
+                        let image = self.install_features(image, config_dir).await?;
+                        let workspace_mount = match &self.config.workspace_mount {
+                            Some(mount) => substitutions.apply(mount),
+                            None => format!(
+                                "type=bind,source={},target={remote_workspace_folder}",
+                                self.local_folder.display()
+                            ),
+                        };
+                        let run_args = run_container_args(
+                            self.config,
+                            &image,
+                            &self.creation_labels(),
+                            &workspace_mount,
+                            &substitutions,
+                        );
+                        self.docker
+                            .output(&run_args)
+                            .await
+                            .context("creating dev container")?
+                            .trim()
+                            .to_string()
+                    }
+                };
+                (container_id, true)
+            }
+        };
+
+        let remote_user = self.config.remote_user().map(|user| user.to_string());
+        let lifecycle_commands = [
+            (
+                "postCreateCommand",
+                self.config.post_create_command.as_ref().filter(|_| created),
+            ),
+            ("postStartCommand", self.config.post_start_command.as_ref()),
+        ];
+        for (name, command) in lifecycle_commands {
+            if let Some(command) = command {
+                self.run_lifecycle_command(
+                    name,
+                    command,
+                    &container_id,
+                    remote_user.as_deref(),
+                    &remote_workspace_folder,
+                    &substitutions,
+                )
+                .await?;
+            }
         }
+
+        Ok(DevContainerUp {
+            container_id,
+            remote_user,
+            remote_workspace_folder,
+        })
     }
-}
 
-async fn ensure_devcontainer_cli(node_runtime: NodeRuntime) -> Result<PathBuf, DevContainerError> {
-    let mut command = util::command::new_smol_command(&dev_container_cli());
-    command.arg("--version");
+    fn remote_workspace_folder(&self) -> String {
+        if let Some(folder) = &self.config.workspace_folder {
+            return Substitutions {
+                local_workspace_folder: self.local_folder,
+                container_workspace_folder: "",
+            }
+            .apply(folder);
+        }
+        if self.config.docker_compose_file.is_some() {
+            return "/".to_string();
+        }
+        let basename = self
+            .local_folder
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        format!("/workspaces/{basename}")
+    }
 
-    if let Err(e) = command.output().await {
-        log::error!(
-            "Unable to find devcontainer CLI in $PATH. Checking for a zed installed version. Error: {:?}",
-            e
-        );
+    fn labels(&self) -> Vec<String> {
+        vec![
+            format!("{LOCAL_FOLDER_LABEL}={}", self.local_folder.display()),
+            format!("{CONFIG_FILE_LABEL}={}", self.config_path.display()),
+        ]
+    }
 
-        let datadir_cli_path = paths::devcontainer_dir()
-            .join("node_modules")
-            .join(".bin")
-            .join(&dev_container_cli());
+    /// The labels of a newly created container: the ones identifying it, plus the ones that
+    /// describe it.
+    fn creation_labels(&self) -> Vec<String> {
+        let mut labels = self.labels();
+        if !self.config.forward_ports.is_empty() {
+            labels.push(format!(
+                "{FORWARD_PORTS_LABEL}={}",
+                self.config
+                    .forward_ports
+                    .iter()
+                    .map(|port| match port {
+                        ForwardPort::Port(port) => port.to_string(),
+                        ForwardPort::HostPort(host_port) => host_port.clone(),
+                    })
+                    .join(",")
+            ));
+        }
+        labels
+    }
 
-        let mut command =
-            util::command::new_smol_command(&datadir_cli_path.as_os_str().display().to_string());
-        command.arg("--version");
+    async fn find_container(&self) -> Result<Option<String>> {
+        let mut ps_args = args(["ps", "-a", "-q"]);
+        for label in self.labels() {
+            ps_args.push("--filter".to_string());
+            ps_args.push(format!("label={label}"));
+        }
+        let output = self.docker.output(&ps_args).await?;
+        Ok(output
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(|line| line.to_string()))
+    }
 
-        if let Err(e) = command.output().await {
-            log::error!(
-                "Unable to find devcontainer CLI in Data dir. Will try to install. Error: {:?}",
-                e
-            );
-        } else {
-            log::info!("Found devcontainer CLI in Data dir");
-            return Ok(datadir_cli_path.clone());
+    async fn build_image(&self, source: ContainerSource) -> Result<String> {
+        match source {
+            ContainerSource::Image(image) => {
+                let present = self
+                    .docker
+                    .output(&args(["image", "inspect", &image]))
+                    .await
+                    .is_ok();
+                if !present {
+                    self.docker
+                        .run("Pull dev container image", args(["pull", &image]))
+                        .await?;
+                }
+                Ok(image)
+            }
+            ContainerSource::Dockerfile {
+                dockerfile,
+                context,
+                args: build_args,
+                target,
+            } => {
+                let tag = container_slug(self.local_folder);
+                let mut build_command = args(["build", "-t", &tag, "-f"]);
+                build_command.push(dockerfile.to_string_lossy().into_owned());
+                for (name, value) in build_args {
+                    build_command.push("--build-arg".to_string());
+                    build_command.push(format!("{name}={value}"));
+                }
+                if let Some(target) = target {
+                    build_command.push("--target".to_string());
+                    build_command.push(target);
+                }
+                build_command.push(context.to_string_lossy().into_owned());
+                self.docker
+                    .run("Build dev container image", build_command)
+                    .await?;
+                Ok(tag)
+            }
+            ContainerSource::Compose { .. } => {
+                anyhow::bail!("compose services are built by docker compose")
+            }
         }
+    }
 
-        if let Err(e) = fs::create_dir_all(paths::devcontainer_dir()).await {
-            log::error!("Unable to create devcontainer directory. Error: {:?}", e);
-            return Err(DevContainerError::DevContainerCliNotAvailable);
+    /// Builds an image on top of `base_image` with the configured features installed.
+    async fn install_features(&self, base_image: String, config_dir: &Path) -> Result<String> {
+        if self.config.features.is_empty() {
+            return Ok(base_image);
         }
 
-        if let Err(e) = node_runtime
-            .npm_install_packages(
-                &paths::devcontainer_dir(),
-                &[("@devcontainers/cli", "latest")],
-            )
+        let context = self.scratch_dir.join("features");
+        smol::fs::remove_dir_all(&context).await.ok();
+        smol::fs::create_dir_all(&context)
             .await
-        {
-            log::error!(
-                "Unable to install devcontainer CLI to data directory. Error: {:?}",
-                e
-            );
-            return Err(DevContainerError::DevContainerCliNotAvailable);
-        };
+            .with_context(|| format!("creating {context:?}"))?;
 
-        let mut command = util::command::new_smol_command(&datadir_cli_path.display().to_string());
-        command.arg("--version");
-        if let Err(e) = command.output().await {
-            log::error!(
-                "Unable to find devcontainer cli after NPM install. Error: {:?}",
-                e
-            );
-            Err(DevContainerError::DevContainerCliNotAvailable)
-        } else {
-            Ok(datadir_cli_path)
+        let base_user = self
+            .docker
+            .output(&args([
+                "image",
+                "inspect",
+                "-f",
+                "{{.Config.User}}",
+                &base_image,
+            ]))
+            .await?
+            .trim()
+            .to_string();
+        let remote_user = self.config.remote_user().unwrap_or(&base_user).to_string();
+        let container_user = self
+            .config
+            .container_user
+            .clone()
+            .unwrap_or_else(|| base_user.clone());
+
+        let mut dockerfile = format!("FROM {base_image}\nUSER root\n");
+        for (index, (id, options)) in self.config.features.iter().enumerate() {
+            let destination = context.join(index.to_string());
+            if id.starts_with("./") || id.starts_with("../") {
+                let source = config_dir.join(id);
+                let destination = destination.clone();
+                smol::unblock(move || copy_dir(&source, &destination))
+                    .await
+                    .with_context(|| format!("copying feature {id}"))?;
+            } else if let Some(http_client) = &self.http_client
+                && let Some(reference) = OciReference::parse(id)
+            {
+                fetch_oci_feature(http_client.as_ref(), &reference, &destination)
+                    .await
+                    .with_context(|| format!("downloading feature {id}"))?;
+            } else {
+                log::warn!("skipping unsupported dev container feature {id}");
+                continue;
+            }
+
+            let metadata = smol::fs::read_to_string(destination.join("devcontainer-feature.json"))
+                .await
+                .ok()
+                .and_then(|contents| serde_json_lenient::from_str::<Value>(&contents).ok())
+                .unwrap_or_default();
+            let mut env = feature_env(options, &metadata);
+            env.insert("_REMOTE_USER".to_string(), remote_user.clone());
+            env.insert("_CONTAINER_USER".to_string(), container_user.clone());
+            let env = env
+                .iter()
+                .map(|(name, value)| {
+                    let value = ShellKind::Posix
+                        .try_quote(value)
+                        .map(Cow::into_owned)
+                        .unwrap_or_else(|| value.clone());
+                    format!("{name}={value}")
+                })
+                .join(" ");
+            dockerfile.push_str(&format!(
+                "COPY {index} {FEATURES_INSTALL_DIR}/{index}\n\
+                 RUN cd {FEATURES_INSTALL_DIR}/{index} && chmod +x install.sh && {env} ./install.sh\n"
+            ));
         }
-    } else {
-        log::info!("Found devcontainer cli on $PATH, using it");
-        Ok(PathBuf::from(&dev_container_cli()))
+        dockerfile.push_str(&format!("RUN rm -rf {FEATURES_INSTALL_DIR}\n"));
+        if !base_user.is_empty() {
+            dockerfile.push_str(&format!("USER {base_user}\n"));
+        }
+        smol::fs::write(context.join("Dockerfile"), dockerfile).await?;
+
+        let tag = format!("{}-features", container_slug(self.local_folder));
+        let mut build_command = args(["build", "-t", &tag]);
+        build_command.push(context.to_string_lossy().into_owned());
+        self.docker
+            .run("Install dev container features", build_command)
+            .await?;
+        Ok(tag)
     }
-}
 
-async fn devcontainer_up(
-    path_to_cli: &PathBuf,
-    path: Arc<Path>,
-) -> Result<DevContainerUp, DevContainerError> {
-    let mut command = util::command::new_smol_command(path_to_cli.display().to_string());
-    command.arg("up");
-    command.arg("--workspace-folder");
-    command.arg(path.display().to_string());
+    async fn compose_up(
+        &self,
+        files: &[PathBuf],
+        service: &str,
+        run_services: &[String],
+        rebuild: bool,
+        substitutions: &Substitutions<'_>,
+    ) -> Result<String> {
+        anyhow::ensure!(
+            self.config.features.is_empty(),
+            "dev container features can't be installed in Docker Compose services yet; \
+             install them in the service's Dockerfile instead"
+        );
 
-    match command.output().await {
-        Ok(output) => {
-            if output.status.success() {
-                let raw = String::from_utf8_lossy(&output.stdout);
-                serde_json::from_str::<DevContainerUp>(&raw).map_err(|e| {
-                    log::error!(
-                        "Unable to parse response from 'devcontainer up' command, error: {:?}",
-                        e
-                    );
-                    DevContainerError::DevContainerParseFailed
-                })
-            } else {
-                log::error!(
-                    "Non-success status running devcontainer up for workspace: out: {:?}, err: {:?}",
-                    String::from_utf8_lossy(&output.stdout),
-                    String::from_utf8_lossy(&output.stderr)
-                );
-                Err(DevContainerError::DevContainerUpFailed)
+        // Rather than editing the user's compose files, an override file labels the service's
+        // container and applies the rest of `devcontainer.json`.
+        let mut service_override = json!({ "labels": self.creation_labels() });
+        if !self.config.container_env.is_empty() {
+            service_override["environment"] = self
+                .config
+                .container_env
+                .iter()
+                .map(|(name, value)| (name.clone(), Value::from(substitutions.apply(value))))
+                .collect::<serde_json::Map<_, _>>()
+                .into();
+        }
+        if let Some(user) = &self.config.container_user {
+            service_override["user"] = json!(user);
+        }
+        if !self.config.mounts.is_empty() {
+            service_override["volumes"] = self
+                .config
+                .mounts
+                .iter()
+                .map(|mount| compose_volume(&substitutions.apply(&mount.to_mount_arg())))
+                .collect();
+        }
+        if self.config.override_command == Some(true) {
+            service_override["entrypoint"] = json!(["/bin/sh", "-c", KEEP_ALIVE]);
+        }
+
+        smol::fs::create_dir_all(&self.scratch_dir).await?;
+        let override_path = self.scratch_dir.join("docker-compose.devcontainer.json");
+        let contents = serde_json::to_string_pretty(&json!({
+            "services": { service: service_override }
+        }))?;
+        smol::fs::write(&override_path, contents).await?;
+
+        let project_name = format!("{}_devcontainer", container_slug(self.local_folder));
+        let mut compose_command = args(["compose", "--project-name", &project_name]);
+        for file in files.iter().chain([&override_path]) {
+            compose_command.push("-f".to_string());
+            compose_command.push(file.to_string_lossy().into_owned());
+        }
+        compose_command.extend(args(["up", "-d", "--build"]));
+        if rebuild {
+            compose_command.push("--force-recreate".to_string());
+        }
+        if !run_services.is_empty() {
+            compose_command.extend(run_services.iter().cloned());
+            if !run_services.iter().any(|name| name == service) {
+                compose_command.push(service.to_string());
             }
         }
-        Err(e) => {
-            log::error!("Error running devcontainer up: {:?}", e);
-            Err(DevContainerError::DevContainerUpFailed)
+        self.docker
+            .run("Start dev container services", compose_command)
+            .await?;
+
+        self.find_container()
+            .await?
+            .with_context(|| format!("service {service} did not start"))
+    }
+
+    async fn run_lifecycle_command(
+        &self,
+        name: &str,
+        command: &LifecycleCommand,
+        container_id: &str,
+        user: Option<&str>,
+        working_directory: &str,
+        substitutions: &Substitutions<'_>,
+    ) -> Result<()> {
+        for argv in command.to_argvs() {
+            let mut exec_command = vec!["exec".to_string()];
+            if let Some(user) = user {
+                exec_command.push("-u".to_string());
+                exec_command.push(user.to_string());
+            }
+            exec_command.extend(args(["-w", working_directory, container_id]));
+            exec_command.extend(argv.iter().map(|arg| substitutions.apply(arg)));
+            self.docker
+                .run(&format!("Run {name}"), exec_command)
+                .await?;
         }
+        Ok(())
     }
 }
 
-async fn devcontainer_read_configuration(
-    path_to_cli: &PathBuf,
-    path: Arc<Path>,
-) -> Result<DevContainerConfigurationOutput, DevContainerError> {
-    let mut command = util::command::new_smol_command(path_to_cli.display().to_string());
-    command.arg("read-configuration");
-    command.arg("--workspace-folder");
-    command.arg(path.display().to_string());
-    match command.output().await {
-        Ok(output) => {
-            if output.status.success() {
-                let raw = String::from_utf8_lossy(&output.stdout);
-                serde_json::from_str::<DevContainerConfigurationOutput>(&raw).map_err(|e| {
-                    log::error!(
-                        "Unable to parse response from 'devcontainer read-configuration' command, error: {:?}",
-                        e
-                    );
-                    DevContainerError::DevContainerParseFailed
-                })
-            } else {
-                log::error!(
-                    "Non-success status running devcontainer read-configuration for workspace: out: {:?}, err: {:?}",
-                    String::from_utf8_lossy(&output.stdout),
-                    String::from_utf8_lossy(&output.stderr)
-                );
-                Err(DevContainerError::DevContainerUpFailed)
+fn args<const N: usize>(args: [&str; N]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+/// A name for the images and compose project of a local folder that is stable across runs.
+fn container_slug(local_folder: &Path) -> String {
+    let basename = local_folder
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>();
+    let digest = Sha256::digest(local_folder.to_string_lossy().as_bytes());
+    format!(
+        "zed-dev-container-{basename}-{}",
+        &format!("{digest:x}")[..8]
+    )
+}
+
+/// The arguments to `docker run` that create a dev container from `image`.
+fn run_container_args(
+    config: &DevContainerConfig,
+    image: &str,
+    labels: &[String],
+    workspace_mount: &str,
+    substitutions: &Substitutions<'_>,
+) -> Vec<String> {
+    let mut run_args = args(["run", "-d"]);
+    for label in labels {
+        run_args.push("--label".to_string());
+        run_args.push(label.clone());
+    }
+    run_args.push("--mount".to_string());
+    run_args.push(workspace_mount.to_string());
+    for mount in &config.mounts {
+        run_args.push("--mount".to_string());
+        run_args.push(substitutions.apply(&mount.to_mount_arg()));
+    }
+    for (name, value) in &config.container_env {
+        run_args.push("-e".to_string());
+        run_args.push(format!("{name}={}", substitutions.apply(value)));
+    }
+    if let Some(user) = &config.container_user {
+        run_args.push("-u".to_string());
+        run_args.push(user.clone());
+    }
+    run_args.extend(config.run_args.iter().map(|arg| substitutions.apply(arg)));
+    if config.override_command.unwrap_or(true) {
+        run_args.extend(args(["--entrypoint", "/bin/sh", image, "-c", KEEP_ALIVE]));
+    } else {
+        run_args.push(image.to_string());
+    }
+    run_args
+}
+
+/// Converts a `--mount` argument into the long form of a compose volume.
+fn compose_volume(mount: &str) -> Value {
+    mount
+        .split(',')
+        .filter_map(|option| {
+            let (key, value) = option.split_once('=')?;
+            let key = match key {
+                "src" => "source",
+                "dst" | "destination" => "target",
+                key => key,
+            };
+            Some((key.to_string(), Value::from(value)))
+        })
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/// The environment variables passed to a feature's `install.sh`: each option, defaulted from the
+/// feature's `devcontainer-feature.json`, uppercased.
+fn feature_env(options: &Value, metadata: &Value) -> BTreeMap<String, String> {
+    let mut values = BTreeMap::new();
+    if let Some(declared) = metadata.get("options").and_then(Value::as_object) {
+        for (name, option) in declared {
+            if let Some(default) = option.get("default") {
+                values.insert(name.clone(), default.clone());
             }
         }
-        Err(e) => {
-            log::error!("Error running devcontainer read-configuration: {:?}", e);
-            Err(DevContainerError::DevContainerUpFailed)
+    }
+    match options {
+        // `"ghcr.io/devcontainers/features/node:1": "18"` is shorthand for the version option.
+        Value::String(version) => {
+            values.insert("version".to_string(), Value::from(version.as_str()));
         }
+        Value::Object(options) => {
+            values.extend(
+                options
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone())),
+            );
+        }
+        _ => {}
     }
+
+    values
+        .into_iter()
+        .map(|(name, value)| {
+            let name = name
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_ascii_uppercase()
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            let value = match value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+            (name, value)
+        })
+        .collect()
 }
 
-// Name the project with two fallbacks
-async fn get_project_name(
-    path_to_cli: &PathBuf,
-    path: Arc<Path>,
-    remote_workspace_folder: String,
-    container_id: String,
-) -> Result<String, DevContainerError> {
-    if let Ok(dev_container_configuration) =
-        devcontainer_read_configuration(path_to_cli, path).await
-        && let Some(name) = dev_container_configuration.configuration.name
-    {
-        // Ideally, name the project after the name defined in devcontainer.json
-        Ok(name)
-    } else {
-        // Otherwise, name the project after the remote workspace folder name
-        Ok(Path::new(&remote_workspace_folder)
-            .file_name()
-            .and_then(|name| name.to_str())
-            .map(|string| string.into())
-            // Finally, name the project after the container ID as a last resort
-            .unwrap_or_else(|| container_id.clone()))
+fn copy_dir(source: &Path, destination: &Path) -> Result<()> {
+    std::fs::create_dir_all(destination)?;
+    for entry in std::fs::read_dir(source).with_context(|| format!("reading {source:?}"))? {
+        let entry = entry?;
+        let target = destination.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
     }
+    Ok(())
+}
+
+/// A feature published to an OCI registry, e.g. `ghcr.io/devcontainers/features/node:1`.
+#[derive(Debug, PartialEq)]
+struct OciReference {
+    registry: String,
+    repository: String,
+    /// A tag or a `sha256:` digest.
+    reference: String,
+}
+
+impl OciReference {
+    fn parse(id: &str) -> Option<Self> {
+        let (registry, path) = id.split_once('/')?;
+        if !registry.contains('.') && !registry.contains(':') && registry != "localhost" {
+            return None;
+        }
+        let (repository, reference) = match path.split_once('@') {
+            Some((repository, digest)) => (repository, digest),
+            None => match path.rsplit_once(':') {
+                Some((repository, tag)) if !tag.contains('/') => (repository, tag),
+                _ => (path, "latest"),
+            },
+        };
+        Some(Self {
+            registry: registry.to_string(),
+            repository: repository.to_string(),
+            reference: reference.to_string(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct RegistryToken {
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct OciManifest {
+    layers: Vec<OciLayer>,
+}
+
+#[derive(Deserialize)]
+struct OciLayer {
+    digest: String,
 }
 
-fn project_directory(cx: &mut AsyncWindowContext) -> Option<Arc<Path>> {
-    let Some(workspace) = cx.window_handle().downcast::<Workspace>() else {
-        return None;
+async fn fetch_oci_feature(
+    http_client: &dyn HttpClient,
+    reference: &OciReference,
+    destination: &Path,
+) -> Result<()> {
+    let OciReference {
+        registry,
+        repository,
+        reference,
+    } = reference;
+
+    // Even public registries require an anonymous token to pull.
+    let token_url = format!("https://{registry}/token?scope=repository:{repository}:pull");
+    let token = match http_client
+        .get(&token_url, AsyncBody::default(), true)
+        .await
+    {
+        Ok(response) => read_body(response)
+            .await
+            .ok()
+            .and_then(|body| serde_json::from_slice::<RegistryToken>(&body).ok())
+            .map(|token| token.token),
+        Err(_) => None,
     };
 
-    match workspace.update(cx, |workspace, _, cx| {
-        workspace.project().read(cx).active_project_directory(cx)
-    }) {
-        Ok(dir) => dir,
+    let request = Request::get(format!(
+        "https://{registry}/v2/{repository}/manifests/{reference}"
+    ))
+    .header("Accept", OCI_MANIFEST_MEDIA_TYPE)
+    .when_some(token.as_ref(), |builder, token| {
+        builder.header("Authorization", format!("Bearer {token}"))
+    })
+    .body(AsyncBody::default())?;
+    let manifest = read_body(http_client.send(request).await?).await?;
+    let manifest = serde_json::from_slice::<OciManifest>(&manifest)?;
+    let layer = manifest
+        .layers
+        .first()
+        .context("feature manifest has no layers")?;
+
+    let request = Request::get(format!(
+        "https://{registry}/v2/{repository}/blobs/{}",
+        layer.digest
+    ))
+    .follow_redirects(RedirectPolicy::FollowAll)
+    .when_some(token.as_ref(), |builder, token| {
+        builder.header("Authorization", format!("Bearer {token}"))
+    })
+    .body(AsyncBody::default())?;
+    let mut response = http_client.send(request).await?;
+    anyhow::ensure!(
+        response.status().is_success(),
+        "status error {}",
+        response.status().as_u16()
+    );
+    smol::fs::create_dir_all(destination).await?;
+    async_tar::Archive::new(response.body_mut())
+        .unpack(destination)
+        .await?;
+    Ok(())
+}
+
+async fn read_body(mut response: Response<AsyncBody>) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    response.body_mut().read_to_end(&mut body).await?;
+    anyhow::ensure!(
+        response.status().is_success(),
+        "status error {}, response: {:?}",
+        response.status().as_u16(),
+        String::from_utf8_lossy(&body)
+    );
+    Ok(body)
+}
+
+/// The ports to forward for a container created from `devcontainer.json`, as pairs of a host
+/// reachable from inside the container and a port.
+pub(crate) async fn container_forward_ports(container_id: &str) -> Result<Vec<(String, u16)>> {
+    let labels = remote::docker_command_output(
+        "docker",
+        &["inspect", "-f", "{{json .Config.Labels}}", container_id],
+    )
+    .await?;
+    let labels = serde_json::from_str::<Option<BTreeMap<String, String>>>(&labels)?;
+    Ok(labels
+        .as_ref()
+        .and_then(|labels| labels.get(FORWARD_PORTS_LABEL))
+        .map(|ports| parse_forward_ports(ports))
+        .unwrap_or_default())
+}
+
+fn parse_forward_ports(label: &str) -> Vec<(String, u16)> {
+    label
+        .split(',')
+        .filter_map(|port| {
+            let (host, port) = port.trim().rsplit_once(':').unwrap_or(("localhost", port));
+            Some((host.to_string(), port.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+async fn check_for_docker() -> Result<(), DevContainerError> {
+    let mut command = util::command::new_smol_command("docker");
+    command.arg("--version");
+
+    match command.output().await {
+        Ok(_) => Ok(()),
         Err(e) => {
-            log::error!("Error getting project directory from workspace: {:?}", e);
-            None
+            log::error!("Unable to find docker in $PATH: {:?}", e);
+            Err(DevContainerError::DockerNotAvailable)
         }
     }
 }
 
+/// Finds the local folder of the workspace, and the `devcontainer.json` it uses.
+///
+/// When the workspace is already connected to a dev container, these are read back from the
+/// container's labels so that it can be rebuilt.
+async fn locate_dev_container(cx: &mut AsyncWindowContext) -> Option<(PathBuf, PathBuf)> {
+    let workspace = cx.window_handle().downcast::<Workspace>()?;
+    let (connection_options, directory) = workspace
+        .update(cx, |workspace, _, cx| {
+            let project = workspace.project().read(cx);
+            (
+                project.remote_connection_options(cx),
+                project.active_project_directory(cx),
+            )
+        })
+        .map_err(|e| log::error!("Error getting project directory from workspace: {e:?}"))
+        .ok()?;
+
+    if let Some(RemoteConnectionOptions::Docker(options)) = connection_options {
+        let labels = remote::docker_command_output(
+            "docker",
+            &[
+                "inspect",
+                "-f",
+                "{{json .Config.Labels}}",
+                options.container_id.as_str(),
+            ],
+        )
+        .await
+        .map_err(|e| log::error!("Error inspecting dev container: {e:?}"))
+        .ok()?;
+        let labels = serde_json::from_str::<BTreeMap<String, String>>(&labels).ok()?;
+        return Some((
+            PathBuf::from(labels.get(LOCAL_FOLDER_LABEL)?),
+            PathBuf::from(labels.get(CONFIG_FILE_LABEL)?),
+        ));
+    }
+
+    let directory = directory?;
+    let config_path = find_config(&directory)?;
+    Some((directory.to_path_buf(), config_path))
+}
+
 pub(crate) async fn start_dev_container(
     cx: &mut AsyncWindowContext,
-    node_runtime: NodeRuntime,
+    http_client: Arc<dyn HttpClient>,
+    rebuild: bool,
 ) -> Result<(Connection, String), DevContainerError> {
     check_for_docker().await?;
 
-    let path_to_devcontainer_cli = ensure_devcontainer_cli(node_runtime).await?;
-
-    let Some(directory) = project_directory(cx) else {
+    let Some((local_folder, config_path)) = locate_dev_container(cx).await else {
         return Err(DevContainerError::DevContainerNotFound);
     };
+    let config = smol::fs::read_to_string(&config_path)
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|contents| DevContainerConfig::parse(&contents))
+        .map_err(|e| DevContainerError::DevContainerParseFailed(format!("{e:#}")))?;
 
-    if let Ok(DevContainerUp {
+    // Build output can only be shown in terminals that run locally.
+    let terminal = cx
+        .window_handle()
+        .downcast::<Workspace>()
+        .filter(|workspace| {
+            workspace
+                .update(cx, |workspace, _, cx| {
+                    workspace.project().read(cx).is_local()
+                })
+                .unwrap_or(false)
+        });
+    let docker = DockerCli {
+        program: "docker".to_string(),
+        terminal: terminal.map(|workspace| (workspace, (**cx).clone())),
+    };
+    let builder = DevContainerBuilder {
+        docker: &docker,
+        http_client: Some(http_client),
+        scratch_dir: paths::devcontainer_dir().join(container_slug(&local_folder)),
+        local_folder: &local_folder,
+        config_path: &config_path,
+        config: &config,
+    };
+    let DevContainerUp {
         container_id,
+        remote_user,
         remote_workspace_folder,
-        ..
-    }) = devcontainer_up(&path_to_devcontainer_cli, directory.clone()).await
-    {
-        let project_name = get_project_name(
-            &path_to_devcontainer_cli,
-            directory,
-            remote_workspace_folder.clone(),
-            container_id.clone(),
-        )
-        .await?;
+    } = builder.up(rebuild).await.map_err(|e| {
+        log::error!("Error starting dev container: {e:?}");
+        DevContainerError::DevContainerUpFailed(format!("{e:#}"))
+    })?;
 
-        let connection = Connection::DevContainer(DevContainerConnection {
-            name: project_name.into(),
-            container_id: container_id.into(),
-        });
+    // Name the project after the name defined in devcontainer.json, then after the remote
+    // workspace folder, and as a last resort after the container.
+    let name = config
+        .name
+        .clone()
+        .or_else(|| {
+            Path::new(&remote_workspace_folder)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| container_id.clone());
 
-        Ok((connection, remote_workspace_folder))
-    } else {
-        Err(DevContainerError::DevContainerUpFailed)
-    }
+    let connection = Connection::DevContainer(DevContainerConnection {
+        name: name.into(),
+        container_id: container_id.into(),
+        remote_user,
+    });
+
+    Ok((connection, remote_workspace_folder))
 }
 
 #[derive(Debug)]
 pub(crate) enum DevContainerError {
     DockerNotAvailable,
-    DevContainerCliNotAvailable,
-    DevContainerUpFailed,
     DevContainerNotFound,
-    DevContainerParseFailed,
+    DevContainerParseFailed(String),
+    DevContainerUpFailed(String),
+}
+
+impl fmt::Display for DevContainerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DockerNotAvailable => write!(f, "Docker is not available on this machine"),
+            Self::DevContainerNotFound => {
+                write!(f, "No devcontainer.json was found in this project")
+            }
+            Self::DevContainerParseFailed(error) => {
+                write!(f, "Failed to read devcontainer.json: {error}")
+            }
+            Self::DevContainerUpFailed(error) => {
+                write!(f, "Failed to start the dev container: {error}")
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use super::*;
+
+    #[test]
+    fn test_run_container_args() {
+        let config = DevContainerConfig::parse(
+            r#"{
+                "image": "mcr.microsoft.com/devcontainers/rust:1",
+                "mounts": ["source=${localWorkspaceFolderBasename}-target,target=/target,type=volume"],
+                "containerEnv": { "WORKSPACE": "${containerWorkspaceFolder}" },
+                "forwardPorts": [3000, "db:5432"],
+                "runArgs": ["--cap-add=SYS_PTRACE"],
+            }"#,
+        )
+        .unwrap();
+        let substitutions = Substitutions {
+            local_workspace_folder: Path::new("/home/me/project"),
+            container_workspace_folder: "/workspaces/project",
+        };
+        assert_eq!(
+            run_container_args(
+                &config,
+                "mcr.microsoft.com/devcontainers/rust:1",
+                &["devcontainer.local_folder=/home/me/project".to_string()],
+                "type=bind,source=/home/me/project,target=/workspaces/project",
+                &substitutions,
+            ),
+            args([
+                "run",
+                "-d",
+                "--label",
+                "devcontainer.local_folder=/home/me/project",
+                "--mount",
+                "type=bind,source=/home/me/project,target=/workspaces/project",
+                "--mount",
+                "source=project-target,target=/target,type=volume",
+                "-e",
+                "WORKSPACE=/workspaces/project",
+                "--cap-add=SYS_PTRACE",
+                "--entrypoint",
+                "/bin/sh",
+                "mcr.microsoft.com/devcontainers/rust:1",
+                "-c",
+                KEEP_ALIVE,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_forward_ports() {
+        assert_eq!(
+            parse_forward_ports("3000, db:5432,invalid"),
+            [("localhost".to_string(), 3000), ("db".to_string(), 5432)]
+        );
+    }
+
+    #[test]
+    fn test_feature_env() {
+        let metadata = json!({
+            "options": {
+                "version": { "type": "string", "default": "lts" },
+                "installYarn": { "type": "boolean", "default": true },
+            }
+        });
+        assert_eq!(
+            feature_env(&json!("18"), &metadata),
+            BTreeMap::from_iter([
+                ("INSTALLYARN".to_string(), "true".to_string()),
+                ("VERSION".to_string(), "18".to_string()),
+            ])
+        );
+        assert_eq!(
+            feature_env(&json!({ "installYarn": false, "node-gyp": "x" }), &metadata),
+            BTreeMap::from_iter([
+                ("INSTALLYARN".to_string(), "false".to_string()),
+                ("NODE_GYP".to_string(), "x".to_string()),
+                ("VERSION".to_string(), "lts".to_string()),
+            ])
+        );
+    }
 
-    use crate::dev_container::DevContainerUp;
+    #[test]
+    fn test_parse_oci_reference() {
+        assert_eq!(
+            OciReference::parse("ghcr.io/devcontainers/features/node:1"),
+            Some(OciReference {
+                registry: "ghcr.io".to_string(),
+                repository: "devcontainers/features/node".to_string(),
+                reference: "1".to_string(),
+            })
+        );
+        assert_eq!(
+            OciReference::parse("localhost:5000/features/rust"),
+            Some(OciReference {
+                registry: "localhost:5000".to_string(),
+                repository: "features/rust".to_string(),
+                reference: "latest".to_string(),
+            })
+        );
+        assert_eq!(OciReference::parse("./local-feature"), None);
+    }
 
+    /// Drives the builder against a fake docker binary that records its arguments.
+    #[cfg(unix)]
     #[test]
-    fn should_parse_from_devcontainer_json() {
-        let json = r#"{"outcome":"success","containerId":"826abcac45afd412abff083ab30793daff2f3c8ce2c831df728baf39933cb37a","remoteUser":"vscode","remoteWorkspaceFolder":"/workspaces/zed"}"#;
-        let up: DevContainerUp = serde_json::from_str(json).unwrap();
-        assert_eq!(up._outcome, "success");
+    fn test_dev_container_up_with_fake_docker() {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let dir = tempfile::tempdir().unwrap();
+        let log_path = dir.path().join("docker.log");
+        let docker_path = dir.path().join("docker");
+        std::fs::write(
+            &docker_path,
+            format!(
+                "#!/bin/sh\n\
+                 echo \"$@\" >> {log}\n\
+                 case \"$1\" in\n\
+                   run) echo fakecontainer ;;\n\
+                 esac\n",
+                log = log_path.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&docker_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let local_folder = dir.path().join("project");
+        let config_path = local_folder.join(".devcontainer").join("devcontainer.json");
+        std::fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+        let config = DevContainerConfig::parse(
+            r#"{
+                "build": { "dockerfile": "Dockerfile" },
+                "remoteUser": "vscode",
+                "postCreateCommand": "cargo build",
+                "overrideCommand": false,
+            }"#,
+        )
+        .unwrap();
+
+        let docker = DockerCli {
+            program: docker_path.to_string_lossy().into_owned(),
+            terminal: None,
+        };
+        let builder = DevContainerBuilder {
+            docker: &docker,
+            http_client: None,
+            scratch_dir: dir.path().join("scratch"),
+            local_folder: &local_folder,
+            config_path: &config_path,
+            config: &config,
+        };
+        let up = smol::block_on(builder.up(false)).unwrap();
+        assert_eq!(
+            up,
+            DevContainerUp {
+                container_id: "fakecontainer".to_string(),
+                remote_user: Some("vscode".to_string()),
+                remote_workspace_folder: "/workspaces/project".to_string(),
+            }
+        );
+
+        let tag = container_slug(&local_folder);
+        let local = local_folder.display();
+        let config_dir = config_path.parent().unwrap().display();
+        let log = std::fs::read_to_string(&log_path).unwrap();
         assert_eq!(
-            up.container_id,
-            "826abcac45afd412abff083ab30793daff2f3c8ce2c831df728baf39933cb37a"
+            log.lines().collect::<Vec<_>>(),
+            [
+                format!(
+                    "ps -a -q --filter label=devcontainer.local_folder={local} \
+                     --filter label=devcontainer.config_file={}",
+                    config_path.display()
+                ),
+                format!("build -t {tag} -f {config_dir}/Dockerfile {config_dir}/."),
+                format!(
+                    "run -d --label devcontainer.local_folder={local} \
+                     --label devcontainer.config_file={} \
+                     --mount type=bind,source={local},target=/workspaces/project {tag}",
+                    config_path.display()
+                ),
+                "exec -u vscode -w /workspaces/project fakecontainer /bin/sh -c cargo build"
+                    .to_string(),
+            ]
         );
-        assert_eq!(up._remote_user, "vscode");
-        assert_eq!(up.remote_workspace_folder, "/workspaces/zed");
     }
 }
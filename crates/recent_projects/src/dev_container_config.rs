@@ -0,0 +1,434 @@
+//! Parsing of `devcontainer.json`, following the [Dev Container specification].
+//!
+//! Only the properties Zed acts on are modeled; everything else is ignored.
+//!
+//! [Dev Container specification]: https://containers.dev/implementors/json_reference/
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::{BTreeMap, IndexMap};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Where `devcontainer.json` may live, relative to the workspace folder, in order of preference.
+const CONFIG_LOCATIONS: &[&str] = &[".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DevContainerConfig {
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub build: Option<BuildConfig>,
+    /// Deprecated top-level alias of `build.dockerfile`.
+    pub docker_file: Option<String>,
+    /// Deprecated top-level alias of `build.context`.
+    pub context: Option<String>,
+    pub docker_compose_file: Option<OneOrMany>,
+    pub service: Option<String>,
+    pub run_services: Option<Vec<String>>,
+    #[serde(default)]
+    pub features: IndexMap<String, Value>,
+    #[serde(default)]
+    pub mounts: Vec<MountConfig>,
+    pub workspace_mount: Option<String>,
+    pub workspace_folder: Option<String>,
+    #[serde(default)]
+    pub forward_ports: Vec<ForwardPort>,
+    pub post_create_command: Option<LifecycleCommand>,
+    pub post_start_command: Option<LifecycleCommand>,
+    pub remote_user: Option<String>,
+    pub container_user: Option<String>,
+    #[serde(default)]
+    pub container_env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub run_args: Vec<String>,
+    pub override_command: Option<bool>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BuildConfig {
+    pub dockerfile: Option<String>,
+    pub context: Option<String>,
+    #[serde(default)]
+    pub args: BTreeMap<String, String>,
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    pub fn to_vec(&self) -> Vec<String> {
+        match self {
+            OneOrMany::One(value) => vec![value.clone()],
+            OneOrMany::Many(values) => values.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum MountConfig {
+    /// A `--mount` argument, e.g. `source=cache,target=/cache,type=volume`.
+    Spec(String),
+    Object {
+        source: Option<String>,
+        target: String,
+        #[serde(rename = "type")]
+        kind: String,
+    },
+}
+
+impl MountConfig {
+    /// Returns the value to pass to `docker run --mount`.
+    pub fn to_mount_arg(&self) -> String {
+        match self {
+            MountConfig::Spec(spec) => spec.clone(),
+            MountConfig::Object {
+                source: Some(source),
+                target,
+                kind,
+            } => format!("type={kind},source={source},target={target}"),
+            MountConfig::Object {
+                source: None,
+                target,
+                kind,
+            } => format!("type={kind},target={target}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum ForwardPort {
+    Port(u16),
+    /// A `host:port` pair, referring to a port of another service.
+    HostPort(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum LifecycleCommand {
+    /// Run through a shell.
+    Shell(String),
+    /// Run directly, without a shell.
+    Exec(Vec<String>),
+    /// Several named commands. The specification runs them in parallel; Zed runs them in order.
+    Parallel(IndexMap<String, LifecycleCommand>),
+}
+
+impl LifecycleCommand {
+    /// Returns the commands to run, as argument vectors for `docker exec`.
+    pub fn to_argvs(&self) -> Vec<Vec<String>> {
+        match self {
+            LifecycleCommand::Shell(command) => {
+                vec![vec![
+                    "/bin/sh".to_string(),
+                    "-c".to_string(),
+                    command.clone(),
+                ]]
+            }
+            LifecycleCommand::Exec(argv) => vec![argv.clone()],
+            LifecycleCommand::Parallel(commands) => commands
+                .values()
+                .flat_map(|command| command.to_argvs())
+                .collect(),
+        }
+    }
+}
+
+/// How the container's image is obtained.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ContainerSource {
+    Image(String),
+    Dockerfile {
+        dockerfile: PathBuf,
+        context: PathBuf,
+        args: BTreeMap<String, String>,
+        target: Option<String>,
+    },
+    Compose {
+        files: Vec<PathBuf>,
+        service: String,
+        run_services: Vec<String>,
+    },
+}
+
+impl DevContainerConfig {
+    pub fn parse(contents: &str) -> Result<Self> {
+        serde_json_lenient::from_str(contents).context("parsing devcontainer.json")
+    }
+
+    /// Resolves the source of the container's image. Paths are relative to `config_dir`, the
+    /// directory containing `devcontainer.json`.
+    pub fn source(&self, config_dir: &Path) -> Result<ContainerSource> {
+        if let Some(files) = &self.docker_compose_file {
+            let service = self
+                .service
+                .clone()
+                .context("`service` is required when using `dockerComposeFile`")?;
+            return Ok(ContainerSource::Compose {
+                files: files
+                    .to_vec()
+                    .into_iter()
+                    .map(|file| config_dir.join(file))
+                    .collect(),
+                service,
+                run_services: self.run_services.clone().unwrap_or_default(),
+            });
+        }
+
+        let build = self.build.clone().unwrap_or_default();
+        if let Some(dockerfile) = build.dockerfile.or_else(|| self.docker_file.clone()) {
+            let context = build
+                .context
+                .or_else(|| self.context.clone())
+                .unwrap_or_else(|| ".".to_string());
+            return Ok(ContainerSource::Dockerfile {
+                dockerfile: config_dir.join(dockerfile),
+                context: config_dir.join(context),
+                args: build.args,
+                target: build.target,
+            });
+        }
+
+        match &self.image {
+            Some(image) => Ok(ContainerSource::Image(image.clone())),
+            None => Err(anyhow!(
+                "devcontainer.json must specify `image`, `build.dockerfile` or `dockerComposeFile`"
+            )),
+        }
+    }
+
+    /// The user that lifecycle commands and the remote server run as.
+    pub fn remote_user(&self) -> Option<&str> {
+        self.remote_user
+            .as_deref()
+            .or(self.container_user.as_deref())
+    }
+}
+
+/// Finds the `devcontainer.json` of a workspace folder, if any.
+pub(crate) fn find_config(workspace_folder: &Path) -> Option<PathBuf> {
+    CONFIG_LOCATIONS
+        .iter()
+        .map(|location| workspace_folder.join(location))
+        .find(|path| path.is_file())
+        .or_else(|| {
+            // Configurations may also live in subfolders, e.g. `.devcontainer/python/devcontainer.json`.
+            let mut subfolders = std::fs::read_dir(workspace_folder.join(".devcontainer"))
+                .ok()?
+                .flatten()
+                .map(|entry| entry.path().join("devcontainer.json"))
+                .filter(|path| path.is_file())
+                .collect::<Vec<_>>();
+            subfolders.sort();
+            subfolders.into_iter().next()
+        })
+}
+
+/// Values for the `${...}` variables that may appear in `devcontainer.json`.
+pub(crate) struct Substitutions<'a> {
+    pub local_workspace_folder: &'a Path,
+    pub container_workspace_folder: &'a str,
+}
+
+impl Substitutions<'_> {
+    /// Replaces the variables in `value` that are known locally. Unknown variables, such as
+    /// `${containerEnv:PATH}`, are left as is.
+    pub fn apply(&self, value: &str) -> String {
+        let mut result = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            result.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('}') else {
+                rest = &rest[start..];
+                break;
+            };
+            let variable = &rest[start + 2..start + end];
+            match self.resolve(variable) {
+                Some(resolved) => result.push_str(&resolved),
+                None => result.push_str(&rest[start..start + end + 1]),
+            }
+            rest = &rest[start + end + 1..];
+        }
+        result.push_str(rest);
+        result
+    }
+
+    fn resolve(&self, variable: &str) -> Option<String> {
+        match variable {
+            "localWorkspaceFolder" => Some(self.local_workspace_folder.display().to_string()),
+            "localWorkspaceFolderBasename" => Some(
+                self.local_workspace_folder
+                    .file_name()?
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            "containerWorkspaceFolder" => Some(self.container_workspace_folder.to_string()),
+            "containerWorkspaceFolderBasename" => Path::new(self.container_workspace_folder)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned()),
+            _ => {
+                let name = variable.strip_prefix("localEnv:")?;
+                let (name, default) = name.split_once(':').unwrap_or((name, ""));
+                Some(std::env::var(name).unwrap_or_else(|_| default.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_image_config() {
+        let config = DevContainerConfig::parse(
+            r#"{
+                // Comments and trailing commas are allowed.
+                "name": "Rust",
+                "image": "mcr.microsoft.com/devcontainers/rust:1",
+                "features": {
+                    "./local-feature": { "version": "2" },
+                    "ghcr.io/devcontainers/features/node:1": "lts",
+                },
+                "mounts": [
+                    "source=cargo-cache,target=/usr/local/cargo,type=volume",
+                    { "source": "${localEnv:HOME}/.ssh", "target": "/root/.ssh", "type": "bind" },
+                ],
+                "forwardPorts": [3000, "db:5432"],
+                "postCreateCommand": "cargo fetch",
+                "postStartCommand": ["git", "status"],
+                "remoteUser": "vscode",
+                "containerEnv": { "RUST_LOG": "info" },
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.name.as_deref(), Some("Rust"));
+        assert_eq!(
+            config.source(Path::new("/project/.devcontainer")).unwrap(),
+            ContainerSource::Image("mcr.microsoft.com/devcontainers/rust:1".to_string())
+        );
+        assert_eq!(
+            config.features.keys().collect::<Vec<_>>(),
+            ["./local-feature", "ghcr.io/devcontainers/features/node:1"]
+        );
+        assert_eq!(
+            config
+                .mounts
+                .iter()
+                .map(|mount| mount.to_mount_arg())
+                .collect::<Vec<_>>(),
+            [
+                "source=cargo-cache,target=/usr/local/cargo,type=volume",
+                "type=bind,source=${localEnv:HOME}/.ssh,target=/root/.ssh",
+            ]
+        );
+        assert_eq!(
+            config.forward_ports,
+            [
+                ForwardPort::Port(3000),
+                ForwardPort::HostPort("db:5432".to_string())
+            ]
+        );
+        assert_eq!(
+            config.post_create_command.unwrap().to_argvs(),
+            [["/bin/sh", "-c", "cargo fetch"]]
+        );
+        assert_eq!(
+            config.post_start_command.unwrap().to_argvs(),
+            [["git", "status"]]
+        );
+        assert_eq!(config.remote_user.as_deref(), Some("vscode"));
+        assert_eq!(config.container_env["RUST_LOG"], "info");
+    }
+
+    #[test]
+    fn test_container_source() {
+        let config_dir = Path::new("/project/.devcontainer");
+
+        let dockerfile = DevContainerConfig::parse(
+            r#"{ "build": { "dockerfile": "Dockerfile", "context": "..", "args": { "VARIANT": "3.12" } } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            dockerfile.source(config_dir).unwrap(),
+            ContainerSource::Dockerfile {
+                dockerfile: config_dir.join("Dockerfile"),
+                context: config_dir.join(".."),
+                args: BTreeMap::from_iter([("VARIANT".to_string(), "3.12".to_string())]),
+                target: None,
+            }
+        );
+
+        let legacy = DevContainerConfig::parse(r#"{ "dockerFile": "Dockerfile" }"#).unwrap();
+        assert!(matches!(
+            legacy.source(config_dir).unwrap(),
+            ContainerSource::Dockerfile { .. }
+        ));
+
+        let compose = DevContainerConfig::parse(
+            r#"{ "dockerComposeFile": ["../compose.yml", "compose.dev.yml"], "service": "app" }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            compose.source(config_dir).unwrap(),
+            ContainerSource::Compose {
+                files: vec![
+                    config_dir.join("../compose.yml"),
+                    config_dir.join("compose.dev.yml")
+                ],
+                service: "app".to_string(),
+                run_services: Vec::new(),
+            }
+        );
+
+        let missing_service =
+            DevContainerConfig::parse(r#"{ "dockerComposeFile": "compose.yml" }"#).unwrap();
+        assert!(missing_service.source(config_dir).is_err());
+        assert!(DevContainerConfig::default().source(config_dir).is_err());
+    }
+
+    #[test]
+    fn test_parallel_lifecycle_command() {
+        let config = DevContainerConfig::parse(
+            r#"{ "image": "debian", "postCreateCommand": { "deps": "npm ci", "build": ["make"] } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.post_create_command.unwrap().to_argvs(),
+            [vec!["/bin/sh", "-c", "npm ci"], vec!["make"],]
+        );
+    }
+
+    #[test]
+    fn test_substitutions() {
+        let substitutions = Substitutions {
+            local_workspace_folder: Path::new("/home/me/project"),
+            container_workspace_folder: "/workspaces/project",
+        };
+        assert_eq!(
+            substitutions.apply(
+                "source=${localWorkspaceFolder},target=${containerWorkspaceFolder},type=bind"
+            ),
+            "source=/home/me/project,target=/workspaces/project,type=bind"
+        );
+        assert_eq!(
+            substitutions.apply("${localWorkspaceFolderBasename}-${containerEnv:PATH}"),
+            "project-${containerEnv:PATH}"
+        );
+        assert_eq!(
+            substitutions.apply("${localEnv:ZED_SURELY_UNSET_VARIABLE:fallback}"),
+            "fallback"
+        );
+        assert_eq!(substitutions.apply("unterminated ${"), "unterminated ${");
+    }
+}
@@ -1,4 +1,6 @@
-use gpui::{ClickEvent, DismissEvent, EventEmitter, FocusHandle, Focusable, Render, WeakEntity};
+use gpui::{
+    App, ClickEvent, DismissEvent, EventEmitter, FocusHandle, Focusable, Render, WeakEntity,
+};
 use project::project_settings::ProjectSettings;
 use remote::RemoteConnectionOptions;
 use settings::Settings;
@@ -8,6 +10,7 @@ use ui::{
     ModalFooter, ModalHeader, ParentElement, Section, Styled, StyledExt, Window, div, h_flex, rems,
 };
 use workspace::{ModalView, OpenOptions, Workspace, notifications::DetachAndPromptErr};
+use zed_actions::ReconnectRemoteProject;
 
 use crate::open_remote_project;
 
@@ -51,6 +54,15 @@ impl DisconnectedOverlay {
         let Some(window) = window else {
             return;
         };
+        workspace.register_action(|workspace, _: &ReconnectRemoteProject, window, cx| {
+            let project = workspace.project().read(cx);
+            if !project.is_disconnected(cx) {
+                return;
+            }
+            if let Some(connection_options) = project.remote_connection_options(cx) {
+                reconnect_to_remote_project(workspace, connection_options, window, cx);
+            }
+        });
         cx.subscribe_in(
             workspace.project(),
             window,
@@ -86,34 +98,43 @@ impl DisconnectedOverlay {
         self.finished = true;
         cx.emit(DismissEvent);
 
-        if let Host::RemoteServerProject(ssh_connection_options) = &self.host {
-            self.reconnect_to_remote_project(ssh_connection_options.clone(), window, cx);
+        if let Host::RemoteServerProject(ssh_connection_options) = &self.host
+            && let Some(workspace) = self.workspace.upgrade()
+        {
+            workspace.update(cx, |workspace, cx| {
+                reconnect_to_remote_project(workspace, ssh_connection_options.clone(), window, cx)
+            });
         }
     }
 
-    fn reconnect_to_remote_project(
-        &self,
-        connection_options: RemoteConnectionOptions,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        let Some(workspace) = self.workspace.upgrade() else {
-            return;
-        };
-
-        let Some(window_handle) = window.window_handle().downcast::<Workspace>() else {
-            return;
-        };
-
-        let app_state = workspace.read(cx).app_state().clone();
-        let paths = workspace
-            .read(cx)
-            .root_paths(cx)
-            .iter()
-            .map(|path| path.to_path_buf())
-            .collect();
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        self.finished = true;
+        cx.emit(DismissEvent)
+    }
+}
 
-        cx.spawn_in(window, async move |_, cx| {
+/// Reopens the workspace's project over a new connection, then applies the edits that were made
+/// while it was disconnected.
+fn reconnect_to_remote_project(
+    workspace: &Workspace,
+    connection_options: RemoteConnectionOptions,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(window_handle) = window.window_handle().downcast::<Workspace>() else {
+        return;
+    };
+
+    let app_state = workspace.app_state().clone();
+    let paths = workspace
+        .root_paths(cx)
+        .iter()
+        .map(|path| path.to_path_buf())
+        .collect();
+    let offline_edits = workspace.project().read(cx).offline_edits(cx);
+
+    window
+        .spawn(cx, async move |cx| {
             open_remote_project(
                 connection_options,
                 paths,
@@ -125,15 +146,19 @@ impl DisconnectedOverlay {
                 cx,
             )
             .await?;
+
+            if !offline_edits.is_empty() {
+                let project =
+                    window_handle.update(cx, |workspace, _, _| workspace.project().clone())?;
+                project
+                    .update(cx, |project, cx| {
+                        project.replay_offline_edits(offline_edits, cx)
+                    })?
+                    .await?;
+            }
             Ok(())
         })
         .detach_and_prompt_err("Failed to reconnect", window, cx, |_, _, _| None);
-    }
-
-    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
-        self.finished = true;
-        cx.emit(DismissEvent)
-    }
 }
 
 impl Render for DisconnectedOverlay {
@@ -145,10 +170,10 @@ impl Render for DisconnectedOverlay {
                 "Your connection to the remote project has been lost.".to_string()
             }
             Host::RemoteServerProject(options) => {
-                let autosave = if ProjectSettings::get_global(cx)
-                    .session
-                    .restore_unsaved_buffers
-                {
+                let session = &ProjectSettings::get_global(cx).session;
+                let autosave = if session.edit_while_disconnected {
+                    "\nYou can keep editing. Your changes will be applied when you reconnect."
+                } else if session.restore_unsaved_buffers {
                     "\nUnsaved changes are stored locally."
                 } else {
                     ""
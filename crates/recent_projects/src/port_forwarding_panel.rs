@@ -0,0 +1,934 @@
+use std::{net::TcpListener, process::Stdio, sync::Arc, time::Duration};
+
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
+use fs::Fs;
+use futures::{AsyncWriteExt as _, FutureExt as _, StreamExt as _, stream::FuturesUnordered};
+use gpui::{
+    Action, App, AsyncWindowContext, Entity, EventEmitter, FocusHandle, Focusable, Pixels,
+    Subscription, Task, WeakEntity, actions, prelude::*, px,
+};
+use project::Project;
+use proto::REMOTE_SERVER_PROJECT_ID;
+use remote::{CommandTemplate, RemoteClient, RemoteConnectionOptions};
+use serde::{Deserialize, Serialize};
+use settings::{RegisterSetting, Settings};
+use ui::{Indicator, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+    notifications::{NotificationId, simple_message_notification::MessageNotification},
+};
+
+const PORT_FORWARDING_PANEL_KEY: &str = "PortForwardingPanel";
+
+/// How often the remote host is asked for the ports it is listening on.
+const PORT_DETECTION_INTERVAL: Duration = Duration::from_secs(3);
+
+actions!(
+    port_forwarding,
+    [
+        /// Toggles focus on the port forwarding panel.
+        ToggleFocus
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<PortForwardingPanel>(window, cx);
+        });
+    })
+    .detach();
+}
+
+#[derive(Debug, RegisterSetting)]
+pub struct PortForwardingPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_size: Pixels,
+    pub detect_ports: bool,
+}
+
+impl Settings for PortForwardingPanelSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let panel = content.port_forwarding_panel.as_ref().unwrap();
+        Self {
+            button: panel.button.unwrap(),
+            dock: panel.dock.unwrap().into(),
+            default_size: panel.default_size.map(px).unwrap(),
+            detect_ports: panel.detect_ports.unwrap(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedPortForwardingPanel {
+    size: Option<Pixels>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ForwardSource {
+    /// Established by the SSH connection itself, from the connection's settings.
+    Configured,
+    /// From the `forwardPorts` of the dev container's `devcontainer.json`.
+    DevContainer,
+    Manual,
+    Detected,
+}
+
+struct PortForward {
+    local_port: u16,
+    remote_host: String,
+    remote_port: u16,
+    source: ForwardSource,
+    /// Owns the forwarding process or relay, which is stopped when the forward is removed.
+    _process: Option<Task<()>>,
+}
+
+pub struct PortForwardingPanel {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    size: Option<Pixels>,
+    port_editor: Entity<Editor>,
+    forwards: Vec<PortForward>,
+    listening_ports: Vec<proto::ListeningPort>,
+    /// Ports that were already listening the last time the remote host was polled. `None` until
+    /// the first poll, so that ports open at connection time don't trigger prompts.
+    known_ports: Option<HashSet<u16>>,
+    pending_serialization: Task<Option<()>>,
+    detection_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl PortForwardingPanel {
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            let serialized_panel = cx
+                .background_spawn(async move {
+                    KEY_VALUE_STORE
+                        .read_kvp(PORT_FORWARDING_PANEL_KEY)
+                        .ok()
+                        .flatten()
+                        .and_then(|panel| {
+                            serde_json::from_str::<SerializedPortForwardingPanel>(&panel).ok()
+                        })
+                })
+                .await;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let panel = Self::new(workspace, window, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, _| panel.size = serialized_panel.size);
+                }
+                panel
+            })
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace_handle = cx.weak_entity();
+
+        cx.new(|cx| {
+            let port_editor = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text("Remote port, or local:remote", window, cx);
+                editor
+            });
+
+            let mut detect_ports = PortForwardingPanelSettings::get_global(cx).detect_ports;
+            let subscriptions =
+                vec![
+                    cx.observe_global::<settings::SettingsStore>(move |this: &mut Self, cx| {
+                        let new_detect_ports =
+                            PortForwardingPanelSettings::get_global(cx).detect_ports;
+                        if detect_ports != new_detect_ports {
+                            detect_ports = new_detect_ports;
+                            this.update_port_detection(cx);
+                        }
+                    }),
+                ];
+
+            let mut this = Self {
+                workspace: workspace_handle,
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                size: None,
+                port_editor,
+                forwards: Vec::new(),
+                listening_ports: Vec::new(),
+                known_ports: None,
+                pending_serialization: Task::ready(None),
+                detection_task: None,
+                _subscriptions: subscriptions,
+            };
+            this.add_configured_forwards(cx);
+            this.forward_dev_container_ports(cx);
+            this.update_port_detection(cx);
+            this
+        })
+    }
+
+    fn remote_client(&self, cx: &App) -> Option<Entity<RemoteClient>> {
+        self.project.read(cx).remote_client()
+    }
+
+    /// Lists the forwards from the SSH connection's `port_forwards` setting, which are set up
+    /// along with the connection and live as long as it does.
+    fn add_configured_forwards(&mut self, cx: &App) {
+        let Some(remote_client) = self.remote_client(cx) else {
+            return;
+        };
+        let RemoteConnectionOptions::Ssh(options) = remote_client.read(cx).connection_options()
+        else {
+            return;
+        };
+        for forward in options.port_forwards.unwrap_or_default() {
+            self.forwards.push(PortForward {
+                local_port: forward.local_port,
+                remote_host: forward
+                    .remote_host
+                    .unwrap_or_else(|| "localhost".to_string()),
+                remote_port: forward.remote_port,
+                source: ForwardSource::Configured,
+                _process: None,
+            });
+        }
+    }
+
+    /// Forwards the ports listed in `forwardPorts` when connected to a dev container.
+    fn forward_dev_container_ports(&mut self, cx: &mut Context<Self>) {
+        let Some(remote_client) = self.remote_client(cx) else {
+            return;
+        };
+        let RemoteConnectionOptions::Docker(options) = remote_client.read(cx).connection_options()
+        else {
+            return;
+        };
+        cx.spawn(async move |this, cx| {
+            let ports = crate::dev_container::container_forward_ports(&options.container_id)
+                .await
+                .context("reading the dev container's forwarded ports")
+                .log_err()
+                .unwrap_or_default();
+            this.update(cx, |this, cx| {
+                for (remote_host, remote_port) in ports {
+                    this.forward_host_port(
+                        None,
+                        remote_host,
+                        remote_port,
+                        ForwardSource::DevContainer,
+                        false,
+                        cx,
+                    );
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn update_port_detection(&mut self, cx: &mut Context<Self>) {
+        if !PortForwardingPanelSettings::get_global(cx).detect_ports {
+            self.detection_task = None;
+            self.known_ports = None;
+            self.listening_ports.clear();
+            cx.notify();
+            return;
+        }
+        if self.detection_task.is_some() {
+            return;
+        }
+        let Some(remote_client) = self.remote_client(cx) else {
+            return;
+        };
+        let remote_client = remote_client.downgrade();
+
+        self.detection_task = Some(cx.spawn(async move |this, cx| {
+            loop {
+                let Ok(proto_client) = remote_client.read_with(cx, |client, _| {
+                    (!client.is_disconnected()).then(|| client.proto_client())
+                }) else {
+                    break;
+                };
+
+                if let Some(proto_client) = proto_client {
+                    match proto_client
+                        .request(proto::GetListeningPorts {
+                            project_id: REMOTE_SERVER_PROJECT_ID,
+                        })
+                        .await
+                    {
+                        Ok(response) => {
+                            if this
+                                .update(cx, |this, cx| {
+                                    this.update_listening_ports(response.ports, cx)
+                                })
+                                .is_err()
+                            {
+                                break;
+                            }
+                        }
+                        Err(error) => log::debug!("failed to list listening ports: {error:#}"),
+                    }
+                }
+
+                cx.background_executor()
+                    .timer(PORT_DETECTION_INTERVAL)
+                    .await;
+            }
+        }));
+    }
+
+    fn update_listening_ports(&mut self, ports: Vec<proto::ListeningPort>, cx: &mut Context<Self>) {
+        let current_ports = ports
+            .iter()
+            .filter_map(|port| u16::try_from(port.port).ok())
+            .collect::<HashSet<_>>();
+
+        if let Some(known_ports) = self.known_ports.as_ref() {
+            for port in &ports {
+                let Ok(remote_port) = u16::try_from(port.port) else {
+                    continue;
+                };
+                if !known_ports.contains(&remote_port) && !self.is_forwarded(remote_port) {
+                    self.prompt_to_forward(remote_port, port.process_name.clone(), cx);
+                }
+            }
+        }
+
+        self.known_ports = Some(current_ports);
+        self.listening_ports = ports;
+        cx.notify();
+    }
+
+    fn is_forwarded(&self, remote_port: u16) -> bool {
+        self.forwards
+            .iter()
+            .any(|forward| forward.remote_port == remote_port)
+    }
+
+    fn prompt_to_forward(
+        &self,
+        remote_port: u16,
+        process_name: Option<String>,
+        cx: &mut Context<Self>,
+    ) {
+        struct DetectedPortNotification;
+
+        let message = match process_name {
+            Some(process_name) => {
+                format!("{process_name} is listening on port {remote_port} on the remote host.")
+            }
+            None => format!("A process is listening on port {remote_port} on the remote host."),
+        };
+        let panel = cx.weak_entity();
+
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_notification(
+                    NotificationId::composite::<DetectedPortNotification>(remote_port as usize),
+                    cx,
+                    |cx| {
+                        cx.new(|cx| {
+                            MessageNotification::new(message, cx)
+                                .primary_message("Forward and Open in Browser")
+                                .primary_icon(IconName::ArrowUpRight)
+                                .primary_on_click({
+                                    let panel = panel.clone();
+                                    move |_, cx| {
+                                        panel
+                                            .update(cx, |panel, cx| {
+                                                panel.forward_port(
+                                                    None,
+                                                    remote_port,
+                                                    ForwardSource::Detected,
+                                                    true,
+                                                    cx,
+                                                )
+                                            })
+                                            .ok();
+                                    }
+                                })
+                                .secondary_message("Forward")
+                                .secondary_on_click(move |_, cx| {
+                                    panel
+                                        .update(cx, |panel, cx| {
+                                            panel.forward_port(
+                                                None,
+                                                remote_port,
+                                                ForwardSource::Detected,
+                                                false,
+                                                cx,
+                                            )
+                                        })
+                                        .ok();
+                                })
+                        })
+                    },
+                );
+            })
+            .log_err();
+    }
+
+    fn forward_port(
+        &mut self,
+        local_port: Option<u16>,
+        remote_port: u16,
+        source: ForwardSource,
+        open_in_browser: bool,
+        cx: &mut Context<Self>,
+    ) {
+        self.forward_host_port(
+            local_port,
+            "localhost".to_string(),
+            remote_port,
+            source,
+            open_in_browser,
+            cx,
+        );
+    }
+
+    /// Forwards a local port to `remote_host:remote_port`, as reached from the remote host.
+    fn forward_host_port(
+        &mut self,
+        local_port: Option<u16>,
+        remote_host: String,
+        remote_port: u16,
+        source: ForwardSource,
+        open_in_browser: bool,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(forward) = self.forwards.iter().find(|forward| {
+            forward.remote_host == remote_host && forward.remote_port == remote_port
+        }) {
+            if open_in_browser {
+                cx.open_url(&format!("http://localhost:{}", forward.local_port));
+            }
+            return;
+        }
+        let Some(remote_client) = self.remote_client(cx) else {
+            return;
+        };
+
+        let mut forward = PortForward {
+            local_port: local_port.unwrap_or(remote_port),
+            remote_host: remote_host.clone(),
+            remote_port,
+            source,
+            _process: None,
+        };
+
+        // Connections that share the host's network interface (e.g. WSL) need no forwarding.
+        if !remote_client.read(cx).shares_network_interface() {
+            let forwards_each_connection = remote_client.read(cx).forwards_each_connection();
+            match available_local_port(local_port.unwrap_or(remote_port)).and_then(|local_port| {
+                let remote_client = remote_client.read(cx);
+                let command = if forwards_each_connection {
+                    remote_client.build_forward_connection_command(&remote_host, remote_port)?
+                } else {
+                    remote_client.build_forward_ports_command(vec![(
+                        local_port,
+                        remote_host,
+                        remote_port,
+                    )])?
+                };
+                anyhow::Ok((local_port, command))
+            }) {
+                Ok((local_port, command)) => {
+                    forward.local_port = local_port;
+                    forward._process = Some(if forwards_each_connection {
+                        self.spawn_connection_relay(local_port, remote_port, command, cx)
+                    } else {
+                        self.spawn_forwarding_process(local_port, remote_port, command, cx)
+                    });
+                }
+                Err(error) => {
+                    self.show_forward_error(remote_port, format!("{error:#}"), cx);
+                    return;
+                }
+            }
+        }
+
+        if open_in_browser {
+            cx.open_url(&format!("http://localhost:{}", forward.local_port));
+        }
+        self.forwards.push(forward);
+        cx.notify();
+    }
+
+    fn spawn_forwarding_process(
+        &self,
+        local_port: u16,
+        remote_port: u16,
+        command: CommandTemplate,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            let output = util::command::new_smol_command(command.program)
+                .args(command.args)
+                .envs(command.env)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true)
+                .output()
+                .await;
+
+            let message = match output {
+                Ok(output) => {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    let stderr = stderr.trim();
+                    if stderr.is_empty() {
+                        format!("Forwarding process exited with {}", output.status)
+                    } else {
+                        stderr.to_string()
+                    }
+                }
+                Err(error) => format!("Failed to spawn forwarding process: {error}"),
+            };
+            this.update(cx, |this, cx| {
+                this.forward_stopped(local_port, remote_port, message, cx)
+            })
+            .ok();
+        })
+    }
+
+    /// Listens on `local_port` and relays each incoming connection through its own run of
+    /// `command`, for connections that can't forward ports themselves (e.g. Docker). Dropping
+    /// the returned task closes the listener and the connections relayed so far.
+    fn spawn_connection_relay(
+        &self,
+        local_port: u16,
+        remote_port: u16,
+        command: CommandTemplate,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            let error = match smol::net::TcpListener::bind(("127.0.0.1", local_port)).await {
+                Ok(listener) => {
+                    let mut connections = FuturesUnordered::new();
+                    loop {
+                        let accepted = futures::select_biased! {
+                            accepted = listener.accept().fuse() => accepted,
+                            _ = connections.select_next_some() => continue,
+                        };
+                        match accepted {
+                            Ok((stream, _)) => {
+                                let command = command.clone();
+                                connections.push(cx.background_spawn(async move {
+                                    relay_connection(stream, command).await.log_err();
+                                }));
+                            }
+                            Err(error) => break error,
+                        }
+                    }
+                }
+                Err(error) => error,
+            };
+            let message = format!("Failed to listen for connections: {error}");
+            this.update(cx, |this, cx| {
+                this.forward_stopped(local_port, remote_port, message, cx)
+            })
+            .ok();
+        })
+    }
+
+    /// Removes a forward whose process or relay stopped, so that the port can be forwarded again.
+    fn forward_stopped(
+        &mut self,
+        local_port: u16,
+        remote_port: u16,
+        message: String,
+        cx: &mut Context<Self>,
+    ) {
+        log::warn!("port forward for remote port {remote_port} stopped: {message}");
+        self.forwards.retain(|forward| {
+            forward.source == ForwardSource::Configured || forward.local_port != local_port
+        });
+        self.show_forward_error(remote_port, message, cx);
+        cx.notify();
+    }
+
+    fn show_forward_error(&self, remote_port: u16, message: String, cx: &mut Context<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                struct PortForwardErrorNotification;
+                workspace.show_toast(
+                    workspace::Toast::new(
+                        NotificationId::composite::<PortForwardErrorNotification>(
+                            remote_port as usize,
+                        ),
+                        format!("Couldn't forward port {remote_port}: {message}"),
+                    ),
+                    cx,
+                );
+            })
+            .log_err();
+    }
+
+    fn remove_forward(&mut self, remote_port: u16, cx: &mut Context<Self>) {
+        self.forwards.retain(|forward| {
+            forward.source == ForwardSource::Configured || forward.remote_port != remote_port
+        });
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.port_editor.read(cx).text(cx);
+        match parse_port_spec(&text) {
+            Ok((local_port, remote_port)) => {
+                self.port_editor
+                    .update(cx, |editor, cx| editor.clear(window, cx));
+                self.forward_port(local_port, remote_port, ForwardSource::Manual, false, cx);
+            }
+            Err(error) => {
+                self.workspace
+                    .update(cx, |workspace, cx| {
+                        struct InvalidPortNotification;
+                        workspace.show_toast(
+                            workspace::Toast::new(
+                                NotificationId::unique::<InvalidPortNotification>(),
+                                format!("{error:#}"),
+                            )
+                            .autohide(),
+                            cx,
+                        );
+                    })
+                    .log_err();
+            }
+        }
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let size = self.size;
+        self.pending_serialization = cx.background_spawn(async move {
+            KEY_VALUE_STORE
+                .write_kvp(
+                    PORT_FORWARDING_PANEL_KEY.into(),
+                    serde_json::to_string(&SerializedPortForwardingPanel { size }).ok()?,
+                )
+                .await
+                .log_err()
+        });
+    }
+
+    fn render_forward(
+        &self,
+        ix: usize,
+        forward: &PortForward,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let remote_port = forward.remote_port;
+        let local_url = format!("http://localhost:{}", forward.local_port);
+        let source = match forward.source {
+            ForwardSource::Configured => "from settings",
+            ForwardSource::DevContainer => "from devcontainer.json",
+            ForwardSource::Manual => "added manually",
+            ForwardSource::Detected => "detected",
+        };
+
+        h_flex()
+            .id(("port-forward", ix))
+            .w_full()
+            .py_0p5()
+            .gap_2()
+            .child(Indicator::dot().color(Color::Success))
+            .child(
+                Label::new(format!(
+                    "{}:{} → localhost:{}",
+                    forward.remote_host, forward.remote_port, forward.local_port
+                ))
+                .buffer_font(cx),
+            )
+            .child(
+                Label::new(source)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(div().flex_1())
+            .child(
+                IconButton::new(("open-forward", ix), IconName::ArrowUpRight)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Open in Browser"))
+                    .on_click(move |_, _, cx| cx.open_url(&local_url)),
+            )
+            .when(forward.source != ForwardSource::Configured, |row| {
+                row.child(
+                    IconButton::new(("remove-forward", ix), IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Stop Forwarding"))
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.remove_forward(remote_port, cx);
+                        })),
+                )
+            })
+    }
+
+    fn render_listening_port(
+        &self,
+        ix: usize,
+        port: &proto::ListeningPort,
+        cx: &Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let remote_port = u16::try_from(port.port).ok()?;
+        if self.is_forwarded(remote_port) {
+            return None;
+        }
+        let process = match (&port.process_name, port.pid) {
+            (Some(name), Some(pid)) => format!("{name} ({pid})"),
+            (Some(name), None) => name.clone(),
+            (None, Some(pid)) => pid.to_string(),
+            (None, None) => String::new(),
+        };
+
+        Some(
+            h_flex()
+                .id(("listening-port", ix))
+                .w_full()
+                .py_0p5()
+                .gap_2()
+                .child(Label::new(remote_port.to_string()).buffer_font(cx))
+                .child(
+                    Label::new(process)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(div().flex_1())
+                .child(
+                    Button::new(("forward-port", ix), "Forward")
+                        .style(ButtonStyle::Subtle)
+                        .label_size(LabelSize::Small)
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.forward_port(None, remote_port, ForwardSource::Manual, false, cx);
+                        })),
+                ),
+        )
+    }
+}
+
+/// Copies data both ways between a local connection and a process connected to the remote port.
+async fn relay_connection(stream: smol::net::TcpStream, command: CommandTemplate) -> Result<()> {
+    let mut child = util::command::new_smol_command(command.program)
+        .args(command.args)
+        .envs(command.env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .context("spawning port forwarding process")?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("forwarding process has no stdin")?;
+    let mut stdout = child
+        .stdout
+        .take()
+        .context("forwarding process has no stdout")?;
+
+    let mut reader = stream.clone();
+    let mut writer = stream;
+    let upload = async move {
+        futures::io::copy(&mut reader, &mut stdin).await?;
+        stdin.close().await
+    };
+    let download = async move {
+        futures::io::copy(&mut stdout, &mut writer).await?;
+        writer.shutdown(std::net::Shutdown::Write)
+    };
+    futures::future::try_join(upload, download).await?;
+    Ok(())
+}
+
+/// Returns `preferred` if nothing is bound to it locally, or else a free port chosen by the OS.
+fn available_local_port(preferred: u16) -> Result<u16> {
+    if TcpListener::bind(("127.0.0.1", preferred)).is_ok() {
+        return Ok(preferred);
+    }
+    let listener = TcpListener::bind(("127.0.0.1", 0)).context("finding a free local port")?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// Parses `remote` or `local:remote` into an optional local port and a remote port.
+fn parse_port_spec(text: &str) -> Result<(Option<u16>, u16)> {
+    let text = text.trim();
+    let parse = |port: &str| {
+        port.trim()
+            .parse::<u16>()
+            .with_context(|| format!("invalid port: {port:?}"))
+    };
+    match text.split_once(':') {
+        Some((local, remote)) => Ok((Some(parse(local)?), parse(remote)?)),
+        None => Ok((None, parse(text)?)),
+    }
+}
+
+impl EventEmitter<PanelEvent> for PortForwardingPanel {}
+
+impl Focusable for PortForwardingPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for PortForwardingPanel {
+    fn persistent_name() -> &'static str {
+        "PortForwardingPanel"
+    }
+
+    fn panel_key() -> &'static str {
+        PORT_FORWARDING_PANEL_KEY
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        PortForwardingPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.port_forwarding_panel.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.size
+            .unwrap_or_else(|| PortForwardingPanelSettings::get_global(cx).default_size)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.size = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        PortForwardingPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::ArrowRightLeft)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Port Forwarding Panel")
+    }
+
+    fn icon_label(&self, _: &Window, _: &App) -> Option<String> {
+        let count = self.forwards.len();
+        (count > 0).then(|| count.to_string())
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        10
+    }
+
+    fn enabled(&self, cx: &App) -> bool {
+        self.project.read(cx).is_via_remote_server()
+    }
+}
+
+impl Render for PortForwardingPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let detect_ports = PortForwardingPanelSettings::get_global(cx).detect_ports;
+        let listening_ports = self
+            .listening_ports
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, port)| self.render_listening_port(ix, port, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id("port-forwarding-panel")
+            .key_context("PortForwardingPanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .overflow_y_scroll()
+            .child(
+                h_flex()
+                    .p_2()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new("Forward Port").size(LabelSize::Small))
+                    .child(div().flex_1().child(self.port_editor.clone())),
+            )
+            .child(
+                v_flex()
+                    .p_2()
+                    .gap_1()
+                    .child(
+                        Label::new("Forwarded Ports")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .when(self.forwards.is_empty(), |this| {
+                        this.child(Label::new("No ports are forwarded.").color(Color::Muted))
+                    })
+                    .children(
+                        self.forwards
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, forward)| self.render_forward(ix, forward, cx)),
+                    ),
+            )
+            .when(detect_ports, |this| {
+                this.child(
+                    v_flex()
+                        .p_2()
+                        .gap_1()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(
+                            Label::new("Listening on the Remote Host")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .when(listening_ports.is_empty(), |this| {
+                            this.child(
+                                Label::new("No other listening ports detected.")
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .children(listening_ports),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_port_spec() {
+        assert_eq!(parse_port_spec("3000").unwrap(), (None, 3000));
+        assert_eq!(parse_port_spec(" 8080:3000 ").unwrap(), (Some(8080), 3000));
+        assert!(parse_port_spec("").is_err());
+        assert!(parse_port_spec("http").is_err());
+        assert!(parse_port_spec("70000").is_err());
+        assert!(parse_port_spec("1:2:3").is_err());
+    }
+}
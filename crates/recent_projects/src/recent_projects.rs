@@ -1,6 +1,8 @@
 mod dev_container;
+mod dev_container_config;
 mod dev_container_suggest;
 pub mod disconnected_overlay;
+mod port_forwarding_panel;
 mod remote_connections;
 mod remote_servers;
 mod ssh_config;
@@ -24,6 +26,7 @@ use picker::{
     Picker, PickerDelegate,
     highlighted_match_with_paths::{HighlightedMatch, HighlightedMatchWithPaths},
 };
+pub use port_forwarding_panel::{PortForwardingPanel, PortForwardingPanelSettings};
 pub use remote_connections::SshSettings;
 pub use remote_servers::RemoteServerProjects;
 use settings::Settings;
@@ -35,7 +38,7 @@ use workspace::{
     WORKSPACE_DB, Workspace, WorkspaceId, notifications::DetachAndPromptErr,
     with_active_or_new_workspace,
 };
-use zed_actions::{OpenDevContainer, OpenRecent, OpenRemote};
+use zed_actions::{OpenDevContainer, OpenRecent, OpenRemote, RebuildDevContainer};
 
 pub fn init(cx: &mut App) {
     #[cfg(target_os = "windows")]
@@ -165,67 +168,10 @@ pub fn init(cx: &mut App) {
     });
 
     cx.observe_new(DisconnectedOverlay::register).detach();
+    port_forwarding_panel::init(cx);
 
-    cx.on_action(|_: &OpenDevContainer, cx| {
-        with_active_or_new_workspace(cx, move |workspace, window, cx| {
-            let app_state = workspace.app_state().clone();
-            let replace_window = window.window_handle().downcast::<Workspace>();
-
-            cx.spawn_in(window, async move |_, mut cx| {
-                let (connection, starting_dir) = match dev_container::start_dev_container(
-                    &mut cx,
-                    app_state.node_runtime.clone(),
-                )
-                .await
-                {
-                    Ok((c, s)) => (c, s),
-                    Err(e) => {
-                        log::error!("Failed to start Dev Container: {:?}", e);
-                        cx.prompt(
-                            gpui::PromptLevel::Critical,
-                            "Failed to start Dev Container",
-                            Some(&format!("{:?}", e)),
-                            &["Ok"],
-                        )
-                        .await
-                        .ok();
-                        return;
-                    }
-                };
-
-                let result = open_remote_project(
-                    connection.into(),
-                    vec![starting_dir].into_iter().map(PathBuf::from).collect(),
-                    app_state,
-                    OpenOptions {
-                        replace_window,
-                        ..OpenOptions::default()
-                    },
-                    &mut cx,
-                )
-                .await;
-
-                if let Err(e) = result {
-                    log::error!("Failed to connect: {e:#}");
-                    cx.prompt(
-                        gpui::PromptLevel::Critical,
-                        "Failed to connect",
-                        Some(&e.to_string()),
-                        &["Ok"],
-                    )
-                    .await
-                    .ok();
-                }
-            })
-            .detach();
-
-            let fs = workspace.project().read(cx).fs().clone();
-            let handle = cx.entity().downgrade();
-            workspace.toggle_modal(window, cx, |window, cx| {
-                RemoteServerProjects::new_dev_container(fs, window, handle, cx)
-            });
-        });
-    });
+    cx.on_action(|_: &OpenDevContainer, cx| open_dev_container(false, cx));
+    cx.on_action(|_: &RebuildDevContainer, cx| open_dev_container(true, cx));
 
     // Subscribe to worktree additions to suggest opening the project in a dev container
     cx.observe_new(
@@ -256,6 +202,68 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+fn open_dev_container(rebuild: bool, cx: &mut App) {
+    with_active_or_new_workspace(cx, move |workspace, window, cx| {
+        let app_state = workspace.app_state().clone();
+        let replace_window = window.window_handle().downcast::<Workspace>();
+
+        cx.spawn_in(window, async move |_, mut cx| {
+            let (connection, starting_dir) = match dev_container::start_dev_container(
+                &mut cx,
+                app_state.client.http_client(),
+                rebuild,
+            )
+            .await
+            {
+                Ok((c, s)) => (c, s),
+                Err(e) => {
+                    log::error!("Failed to start Dev Container: {:?}", e);
+                    cx.prompt(
+                        gpui::PromptLevel::Critical,
+                        "Failed to start Dev Container",
+                        Some(&e.to_string()),
+                        &["Ok"],
+                    )
+                    .await
+                    .ok();
+                    return;
+                }
+            };
+
+            let result = open_remote_project(
+                connection.into(),
+                vec![starting_dir].into_iter().map(PathBuf::from).collect(),
+                app_state,
+                OpenOptions {
+                    replace_window,
+                    ..OpenOptions::default()
+                },
+                &mut cx,
+            )
+            .await;
+
+            if let Err(e) = result {
+                log::error!("Failed to connect: {e:#}");
+                cx.prompt(
+                    gpui::PromptLevel::Critical,
+                    "Failed to connect",
+                    Some(&e.to_string()),
+                    &["Ok"],
+                )
+                .await
+                .ok();
+            }
+        })
+        .detach();
+
+        let fs = workspace.project().read(cx).fs().clone();
+        let handle = cx.entity().downgrade();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            RemoteServerProjects::new_dev_container(fs, window, handle, cx)
+        });
+    });
+}
+
 #[cfg(target_os = "windows")]
 pub fn add_wsl_distro(
     fs: Arc<dyn project::Fs>,
@@ -719,6 +727,7 @@ impl PickerDelegate for RecentProjectsDelegate {
                                         RemoteConnectionOptions::Ssh { .. } => IconName::Server,
                                         RemoteConnectionOptions::Wsl { .. } => IconName::Linux,
                                         RemoteConnectionOptions::Docker(_) => IconName::Box,
+                                        RemoteConnectionOptions::Command(_) => IconName::Terminal,
                                     })
                                     .color(Color::Muted)
                                     .into_any_element()
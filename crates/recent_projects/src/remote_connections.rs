@@ -24,7 +24,10 @@ use remote::{
 };
 use semver::Version;
 pub use settings::SshConnection;
-use settings::{DevContainerConnection, ExtendingVec, RegisterSetting, Settings, WslConnection};
+use settings::{
+    CommandConnection, DevContainerConnection, ExtendingVec, RegisterSetting, Settings,
+    WslConnection,
+};
 use theme::ThemeSettings;
 use ui::{
     ActiveTheme, Color, CommonAnimationExt, Context, InteractiveElement, IntoElement, KeyBinding,
@@ -37,6 +40,7 @@ use workspace::{AppState, ModalView, Workspace};
 pub struct SshSettings {
     pub ssh_connections: ExtendingVec<SshConnection>,
     pub wsl_connections: ExtendingVec<WslConnection>,
+    pub command_connections: ExtendingVec<CommandConnection>,
     /// Whether to read ~/.ssh/config for ssh connection sources.
     pub read_ssh_config: bool,
 }
@@ -50,6 +54,10 @@ impl SshSettings {
         self.wsl_connections.clone().0.into_iter()
     }
 
+    pub fn command_connections(&self) -> impl Iterator<Item = CommandConnection> + use<> {
+        self.command_connections.clone().0.into_iter()
+    }
+
     pub fn fill_connection_options_from_settings(&self, options: &mut SshConnectionOptions) {
         for conn in self.ssh_connections() {
             if conn.host == options.host.to_string()
@@ -87,6 +95,7 @@ pub enum Connection {
     Ssh(SshConnection),
     Wsl(WslConnection),
     DevContainer(DevContainerConnection),
+    Command(CommandConnection),
 }
 
 impl From<Connection> for RemoteConnectionOptions {
@@ -99,8 +108,10 @@ impl From<Connection> for RemoteConnectionOptions {
                     name: conn.name.to_string(),
                     container_id: conn.container_id.to_string(),
                     upload_binary_over_docker_exec: false,
+                    remote_user: conn.remote_user,
                 })
             }
+            Connection::Command(conn) => RemoteConnectionOptions::Command(conn.into()),
         }
     }
 }
@@ -117,12 +128,23 @@ impl From<WslConnection> for Connection {
     }
 }
 
+impl From<CommandConnection> for Connection {
+    fn from(val: CommandConnection) -> Self {
+        Connection::Command(val)
+    }
+}
+
 impl Settings for SshSettings {
     fn from_settings(content: &settings::SettingsContent) -> Self {
         let remote = &content.remote;
         Self {
             ssh_connections: remote.ssh_connections.clone().unwrap_or_default().into(),
             wsl_connections: remote.wsl_connections.clone().unwrap_or_default().into(),
+            command_connections: remote
+                .command_connections
+                .clone()
+                .unwrap_or_default()
+                .into(),
             read_ssh_config: remote.read_ssh_config.unwrap(),
         }
     }
@@ -309,6 +331,7 @@ impl RemoteConnectionModal {
                 (options.distro_name.clone(), None, true, false)
             }
             RemoteConnectionOptions::Docker(options) => (options.name.clone(), None, false, true),
+            RemoteConnectionOptions::Command(options) => (options.name.clone(), None, false, false),
         };
         Self {
             prompt: cx.new(|cx| {
@@ -720,6 +743,7 @@ pub async fn open_remote_project(
                                     RemoteConnectionOptions::Docker(_) => {
                                         "Failed to connect to Dev Container"
                                     }
+                                    RemoteConnectionOptions::Command(_) => "Failed to connect",
                                 },
                                 Some(&format!("{e:#}")),
                                 &["Retry", "Cancel"],
@@ -779,6 +803,7 @@ pub async fn open_remote_project(
                                 RemoteConnectionOptions::Docker(_) => {
                                     "Failed to connect to Dev Container"
                                 }
+                                RemoteConnectionOptions::Command(_) => "Failed to connect",
                             },
                             Some(&format!("{e:#}")),
                             &["Retry", "Cancel"],
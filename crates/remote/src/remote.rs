@@ -7,10 +7,11 @@ mod transport;
 #[cfg(target_os = "windows")]
 pub use remote_client::OpenWslPath;
 pub use remote_client::{
-    ConnectionIdentifier, ConnectionState, RemoteArch, RemoteClient, RemoteClientDelegate,
-    RemoteClientEvent, RemoteConnection, RemoteConnectionOptions, RemoteOs, RemotePlatform,
-    connect,
+    CommandTemplate, ConnectionIdentifier, ConnectionState, RemoteArch, RemoteClient,
+    RemoteClientDelegate, RemoteClientEvent, RemoteConnection, RemoteConnectionOptions, RemoteOs,
+    RemotePlatform, connect,
 };
-pub use transport::docker::DockerConnectionOptions;
+pub use transport::command::{CommandConnectionOptions, KubernetesPod};
+pub use transport::docker::{DockerConnectionOptions, docker_command_output};
 pub use transport::ssh::{SshConnectionOptions, SshPortForwardOption};
 pub use transport::wsl::WslConnectionOptions;
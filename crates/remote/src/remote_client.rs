@@ -3,6 +3,7 @@ use crate::{
     protocol::MessageId,
     proxy::ProxyLaunchError,
     transport::{
+        command::{CommandConnectionOptions, CommandExecConnection},
         docker::{DockerConnectionOptions, DockerExecConnection},
         ssh::SshRemoteConnection,
         wsl::{WslConnectionOptions, WslRemoteConnection},
@@ -899,6 +900,22 @@ impl RemoteClient {
         connection.build_forward_ports_command(forwards)
     }
 
+    pub fn forwards_each_connection(&self) -> bool {
+        self.remote_connection()
+            .map_or(false, |connection| connection.forwards_each_connection())
+    }
+
+    pub fn build_forward_connection_command(
+        &self,
+        remote_host: &str,
+        remote_port: u16,
+    ) -> Result<CommandTemplate> {
+        let Some(connection) = self.remote_connection() else {
+            return Err(anyhow!("no ssh connection"));
+        };
+        connection.build_forward_connection_command(remote_host, remote_port)
+    }
+
     pub fn upload_directory(
         &self,
         src_path: PathBuf,
@@ -965,6 +982,12 @@ impl RemoteClient {
         })
     }
 
+    /// Gives up on the connection, as when reconnecting to the server has failed.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn simulate_reconnect_exhausted(&mut self, cx: &mut Context<Self>) {
+        self.set_state(State::ReconnectExhausted, cx);
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn fake_server(
         client_cx: &mut gpui::TestAppContext,
@@ -1099,6 +1122,11 @@ impl ConnectionPool {
                                 .await
                                 .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
                         }
+                        RemoteConnectionOptions::Command(opts) => {
+                            CommandExecConnection::new(opts, delegate, cx)
+                                .await
+                                .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
+                        }
                     };
 
                     cx.update_global(|pool: &mut Self, _| {
@@ -1135,6 +1163,7 @@ pub enum RemoteConnectionOptions {
     Ssh(SshConnectionOptions),
     Wsl(WslConnectionOptions),
     Docker(DockerConnectionOptions),
+    Command(CommandConnectionOptions),
 }
 
 impl RemoteConnectionOptions {
@@ -1143,6 +1172,7 @@ impl RemoteConnectionOptions {
             RemoteConnectionOptions::Ssh(opts) => opts.host.to_string(),
             RemoteConnectionOptions::Wsl(opts) => opts.distro_name.clone(),
             RemoteConnectionOptions::Docker(opts) => opts.name.clone(),
+            RemoteConnectionOptions::Command(opts) => opts.name.clone(),
         }
     }
 }
@@ -1203,6 +1233,19 @@ pub trait RemoteConnection: Send + Sync {
         &self,
         forwards: Vec<(u16, String, u16)>,
     ) -> Result<CommandTemplate>;
+    /// Whether ports are forwarded by running [`Self::build_forward_connection_command`] for each
+    /// incoming connection, instead of a single [`Self::build_forward_ports_command`] process.
+    fn forwards_each_connection(&self) -> bool {
+        false
+    }
+    /// Builds a command whose stdin and stdout are connected to a port on the remote host.
+    fn build_forward_connection_command(
+        &self,
+        _remote_host: &str,
+        _remote_port: u16,
+    ) -> Result<CommandTemplate> {
+        Err(anyhow!("Not currently supported for this connection"))
+    }
     fn connection_options(&self) -> RemoteConnectionOptions;
     fn path_style(&self) -> PathStyle;
     fn shell(&self) -> String;
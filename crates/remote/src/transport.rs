@@ -12,7 +12,9 @@ use gpui::{AppContext as _, AsyncApp, Task};
 use rpc::proto::Envelope;
 use smol::process::Child;
 
+pub mod command;
 pub mod docker;
+mod server_binary;
 pub mod ssh;
 pub mod wsl;
 
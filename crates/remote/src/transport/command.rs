@@ -0,0 +1,687 @@
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender};
+use gpui::{App, AppContext as _, AsyncApp, Task};
+use parking_lot::Mutex;
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+};
+use util::{
+    paths::{PathStyle, RemotePathBuf},
+    rel_path::RelPath,
+    shell::ShellKind,
+};
+
+use super::server_binary::{self, ServerBinaryTarget};
+use crate::{
+    RemoteClientDelegate, RemoteConnection, RemoteConnectionOptions, RemotePlatform,
+    remote_client::CommandTemplate,
+};
+
+/// Placeholder in `upload` templates for the local path being uploaded.
+const LOCAL_PATH_PLACEHOLDER: &str = "{local}";
+/// Placeholder in `upload` templates for the remote destination path.
+const REMOTE_PATH_PLACEHOLDER: &str = "{remote}";
+/// Placeholder argument in `forward_ports` templates, replaced by one `local:remote` argument per
+/// forwarded port.
+const FORWARDS_PLACEHOLDER: &str = "{forwards}";
+
+/// Options for connecting to an environment that is reachable through a CLI, such as a
+/// Kubernetes pod, a podman container or a cloud shell, by running user supplied commands.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CommandConnectionOptions {
+    /// Name to use for this connection in UI.
+    pub name: String,
+    /// Runs a program in the environment with stdin and stdout attached, e.g.
+    /// `["podman", "exec", "-i", "my-container"]`. The program and its arguments are appended.
+    pub exec: Vec<String>,
+    /// Like `exec`, but allocates a TTY for terminals. Defaults to `exec`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec_tty: Option<Vec<String>>,
+    /// Copies a local file or directory into the environment, with `{local}` and `{remote}`
+    /// replaced by the source and destination paths. When unset, files are streamed over `exec`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upload: Option<Vec<String>>,
+    /// Forwards local ports to the environment, with a `{forwards}` argument replaced by one
+    /// `local_port:remote_port` argument per forwarded port.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forward_ports: Option<Vec<String>>,
+}
+
+/// A Kubernetes pod to connect to with `kubectl`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct KubernetesPod {
+    pub pod: String,
+    pub container: Option<String>,
+    pub namespace: Option<String>,
+    /// The kubeconfig context to use, instead of the current one.
+    pub context: Option<String>,
+}
+
+impl KubernetesPod {
+    pub fn connection_options(&self, name: String) -> CommandConnectionOptions {
+        let mut kubectl = vec!["kubectl".to_string()];
+        if let Some(context) = &self.context {
+            kubectl.extend(["--context".to_string(), context.clone()]);
+        }
+        if let Some(namespace) = &self.namespace {
+            kubectl.extend(["--namespace".to_string(), namespace.clone()]);
+        }
+        let container = match &self.container {
+            Some(container) => vec!["-c".to_string(), container.clone()],
+            None => Vec::new(),
+        };
+        let exec = |flags: &str| {
+            let mut exec = kubectl.clone();
+            exec.extend(["exec".to_string(), flags.to_string(), self.pod.clone()]);
+            exec.extend(container.iter().cloned());
+            exec.push("--".to_string());
+            exec
+        };
+
+        let mut upload = kubectl.clone();
+        upload.push("cp".to_string());
+        upload.extend(container.iter().cloned());
+        upload.push(LOCAL_PATH_PLACEHOLDER.to_string());
+        upload.push(format!("{}:{REMOTE_PATH_PLACEHOLDER}", self.pod));
+
+        let mut forward_ports = kubectl.clone();
+        forward_ports.extend([
+            "port-forward".to_string(),
+            format!("pod/{}", self.pod),
+            FORWARDS_PLACEHOLDER.to_string(),
+        ]);
+
+        CommandConnectionOptions {
+            name,
+            exec: exec("-i"),
+            exec_tty: Some(exec("-it")),
+            upload: Some(upload),
+            forward_ports: Some(forward_ports),
+        }
+    }
+}
+
+impl From<settings::CommandConnection> for CommandConnectionOptions {
+    fn from(val: settings::CommandConnection) -> Self {
+        let name = val.name.to_string();
+        match val.kubernetes {
+            Some(kubernetes) => KubernetesPod {
+                pod: kubernetes.pod,
+                container: kubernetes.container,
+                namespace: kubernetes.namespace,
+                context: kubernetes.context,
+            }
+            .connection_options(name),
+            None => CommandConnectionOptions {
+                name,
+                exec: val.exec,
+                exec_tty: val.exec_tty,
+                upload: val.upload,
+                forward_ports: val.forward_ports,
+            },
+        }
+    }
+}
+
+impl CommandConnectionOptions {
+    /// Builds the command that runs `program` in the environment, in `working_dir` and with `env`
+    /// set. Both are applied by a POSIX shell inside the environment, since exec commands differ
+    /// in how (and whether) they support them.
+    fn exec_command(
+        &self,
+        tty: bool,
+        program: &str,
+        args: &[impl AsRef<str>],
+        working_dir: Option<&str>,
+        env: &HashMap<String, String>,
+    ) -> Result<CommandTemplate> {
+        let template = if tty {
+            self.exec_tty.as_ref().unwrap_or(&self.exec)
+        } else {
+            &self.exec
+        };
+        let (exec_program, exec_args) = template
+            .split_first()
+            .context("the exec command of the connection is empty")?;
+        let mut command_args = exec_args.to_vec();
+
+        if working_dir.is_none() && env.is_empty() {
+            command_args.push(program.to_string());
+        } else {
+            let shell_kind = ShellKind::Posix;
+            let mut script = String::new();
+            if let Some(working_dir) = working_dir {
+                let working_dir = match working_dir.strip_prefix("~/") {
+                    Some(relative) => format!(
+                        "\"$HOME\"/{}",
+                        shell_kind.try_quote(relative).context("shell quoting")?
+                    ),
+                    None => shell_kind
+                        .try_quote(working_dir)
+                        .context("shell quoting")?
+                        .into_owned(),
+                };
+                script.push_str(&format!("cd {working_dir} && "));
+            }
+            script.push_str("exec env");
+            let mut env = env.iter().collect::<Vec<_>>();
+            env.sort();
+            for (name, value) in env {
+                let assignment = format!("{name}={value}");
+                let assignment = shell_kind.try_quote(&assignment).context("shell quoting")?;
+                script.push_str(&format!(" {assignment}"));
+            }
+            // The program and its arguments are passed as positional parameters, so that they
+            // don't need quoting.
+            script.push_str(" \"$0\" \"$@\"");
+            command_args.extend(["sh".to_string(), "-c".to_string(), script]);
+            command_args.push(program.to_string());
+        }
+        command_args.extend(args.iter().map(|arg| arg.as_ref().to_string()));
+
+        Ok(CommandTemplate {
+            program: exec_program.clone(),
+            args: command_args,
+            env: Default::default(),
+        })
+    }
+
+    fn upload_command(&self, local: &Path, remote: &str) -> Option<CommandTemplate> {
+        let (program, args) = self.upload.as_ref()?.split_first()?;
+        let local = local.to_string_lossy();
+        Some(CommandTemplate {
+            program: program.clone(),
+            args: args
+                .iter()
+                .map(|arg| {
+                    arg.replace(LOCAL_PATH_PLACEHOLDER, &local)
+                        .replace(REMOTE_PATH_PLACEHOLDER, remote)
+                })
+                .collect(),
+            env: Default::default(),
+        })
+    }
+}
+
+fn smol_command(template: &CommandTemplate) -> smol::process::Command {
+    let mut command = util::command::new_smol_command(&template.program);
+    command.args(&template.args).envs(&template.env);
+    command
+}
+
+async fn run(template: &CommandTemplate) -> Result<String> {
+    let mut command = smol_command(template);
+    let output = command.output().await?;
+    anyhow::ensure!(
+        output.status.success(),
+        "failed to run command {command:?}: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+pub(crate) struct CommandExecConnection {
+    proxy_process: Mutex<Option<u32>>,
+    remote_dir_for_server: String,
+    remote_binary_relpath: Option<Arc<RelPath>>,
+    connection_options: CommandConnectionOptions,
+    remote_platform: Option<RemotePlatform>,
+    shell: Option<String>,
+}
+
+impl CommandExecConnection {
+    pub async fn new(
+        connection_options: CommandConnectionOptions,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        let mut this = Self {
+            proxy_process: Mutex::new(None),
+            remote_dir_for_server: "/".to_string(),
+            remote_binary_relpath: None,
+            connection_options,
+            remote_platform: None,
+            shell: None,
+        };
+        let (release_channel, version, commit) = cx.update(|cx| {
+            (
+                ReleaseChannel::global(cx),
+                AppVersion::global(cx),
+                AppCommitSha::try_global(cx),
+            )
+        })?;
+
+        delegate.set_status(Some("Checking remote platform"), cx);
+        let uname = this.run_exec("uname", &["-sm"], None).await?;
+        this.remote_platform = Some(super::parse_platform(&uname)?);
+
+        let shell = this
+            .run_exec("sh", &["-c", "echo $SHELL"], None)
+            .await
+            .unwrap_or_default();
+        this.shell = Some(super::parse_shell(&shell, "sh"));
+
+        this.remote_dir_for_server = this
+            .run_exec("sh", &["-c", "echo $HOME"], None)
+            .await?
+            .trim()
+            .to_string();
+
+        this.remote_binary_relpath = Some(
+            server_binary::ensure_server_binary(
+                &this,
+                &delegate,
+                release_channel,
+                version,
+                commit,
+                cx,
+            )
+            .await?,
+        );
+
+        Ok(this)
+    }
+
+    async fn run_exec(
+        &self,
+        program: &str,
+        args: &[impl AsRef<str>],
+        working_dir: Option<&str>,
+    ) -> Result<String> {
+        let command = self.connection_options.exec_command(
+            false,
+            program,
+            args,
+            working_dir,
+            &HashMap::default(),
+        )?;
+        run(&command).await
+    }
+
+    fn kill_inner(&self) -> Result<()> {
+        if let Some(pid) = self.proxy_process.lock().take() {
+            util::command::new_smol_command("kill")
+                .arg(pid.to_string())
+                .spawn()
+                .context("Failed to kill process")?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl ServerBinaryTarget for CommandExecConnection {
+    fn platform(&self) -> Result<RemotePlatform> {
+        self.remote_platform
+            .context("No remote platform defined; cannot proceed.")
+    }
+
+    fn server_dir(&self) -> &str {
+        &self.remote_dir_for_server
+    }
+
+    async fn run(&self, program: &str, args: &[&str], working_dir: Option<&str>) -> Result<String> {
+        self.run_exec(program, args, working_dir).await
+    }
+
+    async fn upload_file(&self, src_path: &Path, dest_path: &RelPath) -> Result<()> {
+        let dest_path = format!(
+            "{}/{}",
+            self.remote_dir_for_server,
+            dest_path.display(PathStyle::Posix)
+        );
+        log::debug!("uploading file {:?} to {:?}", src_path, dest_path);
+        if let Some(command) = self.connection_options.upload_command(src_path, &dest_path) {
+            run(&command).await?;
+            return Ok(());
+        }
+
+        // Without an upload command, the file is streamed to the exec command's stdin.
+        let command = self.connection_options.exec_command(
+            false,
+            "sh",
+            &["-c", "cat > \"$0\"", &dest_path],
+            None,
+            &HashMap::default(),
+        )?;
+        let mut file = smol::fs::File::open(src_path)
+            .await
+            .with_context(|| format!("opening {src_path:?}"))?;
+        let mut child = smol_command(&command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        let mut stdin = child.stdin.take().context("upload command has no stdin")?;
+        let copied = futures::io::copy(&mut file, &mut stdin).await;
+        drop(stdin);
+        let output = child.output().await?;
+        anyhow::ensure!(
+            output.status.success(),
+            "failed to upload file {src_path:?} -> {dest_path}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        copied.with_context(|| format!("failed to upload file {src_path:?} -> {dest_path}"))?;
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for CommandExecConnection {
+    fn has_wsl_interop(&self) -> bool {
+        false
+    }
+
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<i32>> {
+        if !self.has_been_killed()
+            && let Err(e) = self.kill_inner()
+        {
+            return Task::ready(Err(e));
+        }
+
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let Some(remote_binary_relpath) = self.remote_binary_relpath.clone() else {
+            return Task::ready(Err(anyhow!("Remote binary path not set")));
+        };
+
+        let mut env = HashMap::default();
+        for env_var in ["RUST_LOG", "RUST_BACKTRACE", "ZED_GENERATE_MINIDUMPS"] {
+            if let Ok(value) = std::env::var(env_var) {
+                env.insert(env_var.to_string(), value);
+            }
+        }
+        let mut proxy_args = vec![
+            "proxy".to_string(),
+            "--identifier".to_string(),
+            unique_identifier,
+        ];
+        if reconnect {
+            proxy_args.push("--reconnect".to_string());
+        }
+        let command = match self.connection_options.exec_command(
+            false,
+            &remote_binary_relpath.display(self.path_style()),
+            &proxy_args,
+            Some(&self.remote_dir_for_server),
+            &env,
+        ) {
+            Ok(command) => command,
+            Err(e) => return Task::ready(Err(e)),
+        };
+
+        let mut command = smol_command(&command);
+        command
+            .kill_on_drop(true)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                return Task::ready(Err(
+                    anyhow!(e).context("Failed to start remote server process")
+                ));
+            }
+        };
+        *self.proxy_process.lock() = Some(child.id());
+
+        super::handle_rpc_messages_over_child_process_stdio(
+            child,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            cx,
+        )
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: RemotePathBuf,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let dest_path = dest_path.to_string();
+        if let Some(command) = self
+            .connection_options
+            .upload_command(&src_path, &dest_path)
+        {
+            return cx.background_spawn(async move {
+                run(&command).await.context("Failed to upload directory")?;
+                Ok(())
+            });
+        }
+
+        // Without an upload command, the directory is streamed as a tarball over `exec`.
+        let extract = self.connection_options.exec_command(
+            false,
+            "sh",
+            &["-c", "mkdir -p \"$0\" && tar -xf - -C \"$0\"", &dest_path],
+            None,
+            &HashMap::default(),
+        );
+        cx.background_spawn(async move {
+            let mut archive = util::command::new_smol_command("tar")
+                .arg("-C")
+                .arg(&src_path)
+                .args(["-cf", "-", "."])
+                .stdout(Stdio::piped())
+                .spawn()
+                .context("Failed to archive directory")?;
+            let archive_stdout = archive
+                .stdout
+                .take()
+                .context("Failed to archive directory")?
+                .into_stdio()
+                .await?;
+            let output = smol_command(&extract?)
+                .stdin(archive_stdout)
+                .output()
+                .await?;
+            anyhow::ensure!(
+                archive.status().await?.success() && output.status.success(),
+                "Failed to upload directory: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            Ok(())
+        })
+    }
+
+    async fn kill(&self) -> Result<()> {
+        self.kill_inner()
+    }
+
+    fn has_been_killed(&self) -> bool {
+        self.proxy_process.lock().is_none()
+    }
+
+    fn build_command(
+        &self,
+        program: Option<String>,
+        args: &[String],
+        env: &HashMap<String, String>,
+        working_dir: Option<String>,
+        _port_forward: Option<(u16, String, u16)>,
+    ) -> Result<CommandTemplate> {
+        let working_dir =
+            working_dir.map(|dir| RemotePathBuf::new(dir, self.path_style()).to_string());
+        match program {
+            Some(program) => self.connection_options.exec_command(
+                true,
+                &program,
+                args,
+                working_dir.as_deref(),
+                env,
+            ),
+            None => self.connection_options.exec_command(
+                true,
+                &self.shell(),
+                &["-l"],
+                working_dir.as_deref(),
+                env,
+            ),
+        }
+    }
+
+    fn build_forward_ports_command(
+        &self,
+        forwards: Vec<(u16, String, u16)>,
+    ) -> Result<CommandTemplate> {
+        let template = self
+            .connection_options
+            .forward_ports
+            .as_ref()
+            .context("Port forwarding is not configured for this connection")?;
+        let (program, template_args) = template
+            .split_first()
+            .context("The port forwarding command of the connection is empty")?;
+        let mut args = Vec::new();
+        for arg in template_args {
+            if arg == FORWARDS_PLACEHOLDER {
+                for (local_port, host, remote_port) in &forwards {
+                    anyhow::ensure!(
+                        host == "localhost" || host == "127.0.0.1",
+                        "Only ports on localhost can be forwarded over this connection"
+                    );
+                    args.push(format!("{local_port}:{remote_port}"));
+                }
+            } else {
+                args.push(arg.clone());
+            }
+        }
+        Ok(CommandTemplate {
+            program: program.clone(),
+            args,
+            env: Default::default(),
+        })
+    }
+
+    fn connection_options(&self) -> RemoteConnectionOptions {
+        RemoteConnectionOptions::Command(self.connection_options.clone())
+    }
+
+    fn path_style(&self) -> PathStyle {
+        PathStyle::Posix
+    }
+
+    fn shell(&self) -> String {
+        match &self.shell {
+            Some(shell) => shell.clone(),
+            None => self.default_system_shell(),
+        }
+    }
+
+    fn default_system_shell(&self) -> String {
+        String::from("/bin/sh")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pod() -> KubernetesPod {
+        KubernetesPod {
+            pod: "api-7d9f".to_string(),
+            container: Some("app".to_string()),
+            namespace: Some("staging".to_string()),
+            context: None,
+        }
+    }
+
+    #[test]
+    fn test_kubernetes_exec_command() {
+        let options = pod().connection_options("api".to_string());
+        let command = options
+            .exec_command(false, "uname", &["-sm"], None, &HashMap::default())
+            .unwrap();
+        assert_eq!(command.program, "kubectl");
+        assert_eq!(
+            command.args,
+            [
+                "--namespace",
+                "staging",
+                "exec",
+                "-i",
+                "api-7d9f",
+                "-c",
+                "app",
+                "--",
+                "uname",
+                "-sm"
+            ]
+        );
+
+        let command = options
+            .exec_command(
+                true,
+                "/bin/bash",
+                &["-l"],
+                Some("~/src"),
+                &HashMap::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            command.args,
+            [
+                "--namespace",
+                "staging",
+                "exec",
+                "-it",
+                "api-7d9f",
+                "-c",
+                "app",
+                "--",
+                "sh",
+                "-c",
+                "cd \"$HOME\"/src && exec env \"$0\" \"$@\"",
+                "/bin/bash",
+                "-l",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_upload_command() {
+        let options = pod().connection_options("api".to_string());
+        let command = options
+            .upload_command(Path::new("/tmp/server.gz"), "/root/.zed_server/server.gz")
+            .unwrap();
+        assert_eq!(
+            command.args,
+            [
+                "--namespace",
+                "staging",
+                "cp",
+                "-c",
+                "app",
+                "/tmp/server.gz",
+                "api-7d9f:/root/.zed_server/server.gz"
+            ]
+        );
+
+        let options = CommandConnectionOptions {
+            name: "podman".to_string(),
+            exec: vec!["podman".into(), "exec".into(), "-i".into(), "box".into()],
+            ..Default::default()
+        };
+        assert!(options.upload_command(Path::new("/tmp/a"), "/b").is_none());
+    }
+}
@@ -5,15 +5,11 @@ use async_trait::async_trait;
 use collections::HashMap;
 use parking_lot::Mutex;
 use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
-use semver::Version as SemanticVersion;
-use std::time::Instant;
 use std::{
     path::{Path, PathBuf},
     process::Stdio,
     sync::Arc,
 };
-use util::ResultExt;
-use util::shell::ShellKind;
 use util::{
     paths::{PathStyle, RemotePathBuf},
     rel_path::RelPath,
@@ -23,6 +19,7 @@ use futures::channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender};
 use gpui::{App, AppContext, AsyncApp, Task};
 use rpc::proto::Envelope;
 
+use super::server_binary::{self, ServerBinaryTarget};
 use crate::{
     RemoteArch, RemoteClientDelegate, RemoteConnection, RemoteConnectionOptions, RemoteOs,
     RemotePlatform, remote_client::CommandTemplate,
@@ -33,6 +30,23 @@ pub struct DockerConnectionOptions {
     pub name: String,
     pub container_id: String,
     pub upload_binary_over_docker_exec: bool,
+    /// The user to run commands as inside the container, instead of the image's default user.
+    pub remote_user: Option<String>,
+}
+
+/// Runs a docker CLI command to completion, returning its stdout.
+pub async fn docker_command_output(program: &str, args: &[impl AsRef<str>]) -> Result<String> {
+    let mut command = util::command::new_smol_command(program);
+    for arg in args {
+        command.arg(arg.as_ref());
+    }
+    let output = command.output().await?;
+    anyhow::ensure!(
+        output.status.success(),
+        "failed to run command {command:?}: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
 pub(crate) struct DockerExecConnection {
@@ -81,11 +95,11 @@ impl DockerExecConnection {
         this.remote_dir_for_server = this.docker_user_home_dir().await?.trim().to_string();
 
         this.remote_binary_relpath = Some(
-            this.ensure_server_binary(
+            server_binary::ensure_server_binary(
+                &this,
                 &delegate,
                 release_channel,
                 version,
-                &this.remote_dir_for_server,
                 commit,
                 cx,
             )
@@ -148,148 +162,6 @@ impl DockerExecConnection {
         Ok(RemotePlatform { os, arch })
     }
 
-    async fn ensure_server_binary(
-        &self,
-        delegate: &Arc<dyn RemoteClientDelegate>,
-        release_channel: ReleaseChannel,
-        version: SemanticVersion,
-        remote_dir_for_server: &str,
-        commit: Option<AppCommitSha>,
-        cx: &mut AsyncApp,
-    ) -> Result<Arc<RelPath>> {
-        let remote_platform = if self.remote_platform.is_some() {
-            self.remote_platform.unwrap()
-        } else {
-            anyhow::bail!("No remote platform defined; cannot proceed.")
-        };
-
-        let version_str = match release_channel {
-            ReleaseChannel::Nightly => {
-                let commit = commit.map(|s| s.full()).unwrap_or_default();
-                format!("{}-{}", version, commit)
-            }
-            ReleaseChannel::Dev => "build".to_string(),
-            _ => version.to_string(),
-        };
-        let binary_name = format!(
-            "zed-remote-server-{}-{}",
-            release_channel.dev_name(),
-            version_str
-        );
-        let dst_path =
-            paths::remote_server_dir_relative().join(RelPath::unix(&binary_name).unwrap());
-
-        #[cfg(debug_assertions)]
-        if let Some(remote_server_path) =
-            super::build_remote_server_from_source(&remote_platform, delegate.as_ref(), cx).await?
-        {
-            let tmp_path = paths::remote_server_dir_relative().join(
-                RelPath::unix(&format!(
-                    "download-{}-{}",
-                    std::process::id(),
-                    remote_server_path.file_name().unwrap().to_string_lossy()
-                ))
-                .unwrap(),
-            );
-            self.upload_local_server_binary(
-                &remote_server_path,
-                &tmp_path,
-                &remote_dir_for_server,
-                delegate,
-                cx,
-            )
-            .await?;
-            self.extract_server_binary(&dst_path, &tmp_path, &remote_dir_for_server, delegate, cx)
-                .await?;
-            return Ok(dst_path);
-        }
-
-        if self
-            .run_docker_exec(
-                &dst_path.display(self.path_style()),
-                Some(&remote_dir_for_server),
-                &Default::default(),
-                &["version"],
-            )
-            .await
-            .is_ok()
-        {
-            return Ok(dst_path);
-        }
-
-        let wanted_version = cx.update(|cx| match release_channel {
-            ReleaseChannel::Nightly => Ok(None),
-            ReleaseChannel::Dev => {
-                anyhow::bail!(
-                    "ZED_BUILD_REMOTE_SERVER is not set and no remote server exists at ({:?})",
-                    dst_path
-                )
-            }
-            _ => Ok(Some(AppVersion::global(cx))),
-        })??;
-
-        let tmp_path_gz = paths::remote_server_dir_relative().join(
-            RelPath::unix(&format!(
-                "{}-download-{}.gz",
-                binary_name,
-                std::process::id()
-            ))
-            .unwrap(),
-        );
-        if !self.connection_options.upload_binary_over_docker_exec
-            && let Some(url) = delegate
-                .get_download_url(remote_platform, release_channel, wanted_version.clone(), cx)
-                .await?
-        {
-            match self
-                .download_binary_on_server(&url, &tmp_path_gz, &remote_dir_for_server, delegate, cx)
-                .await
-            {
-                Ok(_) => {
-                    self.extract_server_binary(
-                        &dst_path,
-                        &tmp_path_gz,
-                        &remote_dir_for_server,
-                        delegate,
-                        cx,
-                    )
-                    .await
-                    .context("extracting server binary")?;
-                    return Ok(dst_path);
-                }
-                Err(e) => {
-                    log::error!(
-                        "Failed to download binary on server, attempting to download locally and then upload it the server: {e:#}",
-                    )
-                }
-            }
-        }
-
-        let src_path = delegate
-            .download_server_binary_locally(remote_platform, release_channel, wanted_version, cx)
-            .await
-            .context("downloading server binary locally")?;
-        self.upload_local_server_binary(
-            &src_path,
-            &tmp_path_gz,
-            &remote_dir_for_server,
-            delegate,
-            cx,
-        )
-        .await
-        .context("uploading server binary")?;
-        self.extract_server_binary(
-            &dst_path,
-            &tmp_path_gz,
-            &remote_dir_for_server,
-            delegate,
-            cx,
-        )
-        .await
-        .context("extracting server binary")?;
-        Ok(dst_path)
-    }
-
     async fn docker_user_home_dir(&self) -> Result<String> {
         let inner_program = self.shell();
         self.run_docker_exec(
@@ -301,141 +173,22 @@ impl DockerExecConnection {
         .await
     }
 
-    async fn extract_server_binary(
-        &self,
-        dst_path: &RelPath,
-        tmp_path: &RelPath,
-        remote_dir_for_server: &str,
-        delegate: &Arc<dyn RemoteClientDelegate>,
-        cx: &mut AsyncApp,
-    ) -> Result<()> {
-        delegate.set_status(Some("Extracting remote development server"), cx);
-        let server_mode = 0o755;
-
-        let shell_kind = ShellKind::Posix;
-        let orig_tmp_path = tmp_path.display(self.path_style());
-        let server_mode = format!("{:o}", server_mode);
-        let server_mode = shell_kind
-            .try_quote(&server_mode)
-            .context("shell quoting")?;
-        let dst_path = dst_path.display(self.path_style());
-        let dst_path = shell_kind.try_quote(&dst_path).context("shell quoting")?;
-        let script = if let Some(tmp_path) = orig_tmp_path.strip_suffix(".gz") {
-            let orig_tmp_path = shell_kind
-                .try_quote(&orig_tmp_path)
-                .context("shell quoting")?;
-            let tmp_path = shell_kind.try_quote(&tmp_path).context("shell quoting")?;
-            format!(
-                "gunzip -f {orig_tmp_path} && chmod {server_mode} {tmp_path} && mv {tmp_path} {dst_path}",
-            )
-        } else {
-            let orig_tmp_path = shell_kind
-                .try_quote(&orig_tmp_path)
-                .context("shell quoting")?;
-            format!("chmod {server_mode} {orig_tmp_path} && mv {orig_tmp_path} {dst_path}",)
-        };
-        let args = shell_kind.args_for_shell(false, script.to_string());
-        self.run_docker_exec(
-            "sh",
-            Some(&remote_dir_for_server),
-            &Default::default(),
-            &args,
-        )
-        .await
-        .log_err();
-        Ok(())
-    }
-
-    async fn upload_local_server_binary(
-        &self,
-        src_path: &Path,
-        tmp_path_gz: &RelPath,
-        remote_dir_for_server: &str,
-        delegate: &Arc<dyn RemoteClientDelegate>,
-        cx: &mut AsyncApp,
-    ) -> Result<()> {
-        if let Some(parent) = tmp_path_gz.parent() {
-            self.run_docker_exec(
-                "mkdir",
-                Some(remote_dir_for_server),
-                &Default::default(),
-                &["-p", parent.display(self.path_style()).as_ref()],
-            )
-            .await?;
-        }
-
-        let src_stat = smol::fs::metadata(&src_path).await?;
-        let size = src_stat.len();
-
-        let t0 = Instant::now();
-        delegate.set_status(Some("Uploading remote development server"), cx);
-        log::info!(
-            "uploading remote development server to {:?} ({}kb)",
-            tmp_path_gz,
-            size / 1024
-        );
-        self.upload_file(src_path, tmp_path_gz, remote_dir_for_server)
-            .await
-            .context("failed to upload server binary")?;
-        log::info!("uploaded remote development server in {:?}", t0.elapsed());
-        Ok(())
-    }
-
-    async fn upload_file(
-        &self,
-        src_path: &Path,
-        dest_path: &RelPath,
-        remote_dir_for_server: &str,
-    ) -> Result<()> {
-        log::debug!("uploading file {:?} to {:?}", src_path, dest_path);
-
-        let src_path_display = src_path.display().to_string();
-        let dest_path_str = dest_path.display(self.path_style());
-
-        let mut command = util::command::new_smol_command("docker");
-        command.arg("cp");
-        command.arg("-a");
-        command.arg(&src_path_display);
-        command.arg(format!(
-            "{}:{}/{}",
-            &self.connection_options.container_id, remote_dir_for_server, dest_path_str
-        ));
-
-        let output = command.output().await?;
-
-        if output.status.success() {
-            return Ok(());
-        }
-
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        log::debug!(
-            "failed to upload file via docker cp {src_path_display} -> {dest_path_str}: {stderr}",
-        );
-        anyhow::bail!(
-            "failed to upload file via docker cp {} -> {}: {}",
-            src_path_display,
-            dest_path_str,
-            stderr,
-        );
-    }
-
     async fn run_docker_command(
         &self,
         subcommand: &str,
         args: &[impl AsRef<str>],
     ) -> Result<String> {
-        let mut command = util::command::new_smol_command("docker");
-        command.arg(subcommand);
-        for arg in args {
-            command.arg(arg.as_ref());
+        let args = std::iter::once(subcommand)
+            .chain(args.iter().map(|arg| arg.as_ref()))
+            .collect::<Vec<_>>();
+        docker_command_output("docker", &args).await
+    }
+
+    fn user_args(&self) -> Vec<String> {
+        match &self.connection_options.remote_user {
+            Some(user) => vec!["-u".to_string(), user.clone()],
+            None => Vec::new(),
         }
-        let output = command.output().await?;
-        anyhow::ensure!(
-            output.status.success(),
-            "failed to run command {command:?}: {}",
-            String::from_utf8_lossy(&output.stderr)
-        );
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
     async fn run_docker_exec(
@@ -445,10 +198,11 @@ impl DockerExecConnection {
         env: &HashMap<String, String>,
         program_args: &[impl AsRef<str>],
     ) -> Result<String> {
-        let mut args = match working_directory {
-            Some(dir) => vec!["-w".to_string(), dir.to_string()],
-            None => vec![],
-        };
+        let mut args = self.user_args();
+        if let Some(dir) = working_directory {
+            args.push("-w".to_string());
+            args.push(dir.to_string());
+        }
 
         for (k, v) in env.iter() {
             args.push("-e".to_string());
@@ -465,79 +219,6 @@ impl DockerExecConnection {
         self.run_docker_command("exec", args.as_ref()).await
     }
 
-    async fn download_binary_on_server(
-        &self,
-        url: &str,
-        tmp_path_gz: &RelPath,
-        remote_dir_for_server: &str,
-        delegate: &Arc<dyn RemoteClientDelegate>,
-        cx: &mut AsyncApp,
-    ) -> Result<()> {
-        if let Some(parent) = tmp_path_gz.parent() {
-            self.run_docker_exec(
-                "mkdir",
-                Some(remote_dir_for_server),
-                &Default::default(),
-                &["-p", parent.display(self.path_style()).as_ref()],
-            )
-            .await?;
-        }
-
-        delegate.set_status(Some("Downloading remote development server on host"), cx);
-
-        match self
-            .run_docker_exec(
-                "curl",
-                Some(remote_dir_for_server),
-                &Default::default(),
-                &[
-                    "-f",
-                    "-L",
-                    url,
-                    "-o",
-                    &tmp_path_gz.display(self.path_style()),
-                ],
-            )
-            .await
-        {
-            Ok(_) => {}
-            Err(e) => {
-                if self
-                    .run_docker_exec("which", None, &Default::default(), &["curl"])
-                    .await
-                    .is_ok()
-                {
-                    return Err(e);
-                }
-
-                log::info!("curl is not available, trying wget");
-                match self
-                    .run_docker_exec(
-                        "wget",
-                        Some(remote_dir_for_server),
-                        &Default::default(),
-                        &[url, "-O", &tmp_path_gz.display(self.path_style())],
-                    )
-                    .await
-                {
-                    Ok(_) => {}
-                    Err(e) => {
-                        if self
-                            .run_docker_exec("which", None, &Default::default(), &["wget"])
-                            .await
-                            .is_ok()
-                        {
-                            return Err(e);
-                        } else {
-                            anyhow::bail!("Neither curl nor wget is available");
-                        }
-                    }
-                }
-            }
-        }
-        Ok(())
-    }
-
     fn kill_inner(&self) -> Result<()> {
         if let Some(pid) = self.proxy_process.lock().take() {
             if let Ok(_) = util::command::new_smol_command("kill")
@@ -554,6 +235,60 @@ impl DockerExecConnection {
     }
 }
 
+#[async_trait(?Send)]
+impl ServerBinaryTarget for DockerExecConnection {
+    fn platform(&self) -> Result<RemotePlatform> {
+        self.remote_platform
+            .context("No remote platform defined; cannot proceed.")
+    }
+
+    fn server_dir(&self) -> &str {
+        &self.remote_dir_for_server
+    }
+
+    async fn run(&self, program: &str, args: &[&str], working_dir: Option<&str>) -> Result<String> {
+        self.run_docker_exec(program, working_dir, &Default::default(), args)
+            .await
+    }
+
+    async fn upload_file(&self, src_path: &Path, dest_path: &RelPath) -> Result<()> {
+        log::debug!("uploading file {:?} to {:?}", src_path, dest_path);
+
+        let src_path_display = src_path.display().to_string();
+        let dest_path_str = dest_path.display(self.path_style());
+
+        let mut command = util::command::new_smol_command("docker");
+        command.arg("cp");
+        command.arg("-a");
+        command.arg(&src_path_display);
+        command.arg(format!(
+            "{}:{}/{}",
+            &self.connection_options.container_id, self.remote_dir_for_server, dest_path_str
+        ));
+
+        let output = command.output().await?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::debug!(
+            "failed to upload file via docker cp {src_path_display} -> {dest_path_str}: {stderr}",
+        );
+        anyhow::bail!(
+            "failed to upload file via docker cp {} -> {}: {}",
+            src_path_display,
+            dest_path_str,
+            stderr,
+        );
+    }
+
+    fn can_download_binary(&self) -> bool {
+        !self.connection_options.upload_binary_over_docker_exec
+    }
+}
+
 #[async_trait(?Send)]
 impl RemoteConnection for DockerExecConnection {
     fn has_wsl_interop(&self) -> bool {
@@ -590,6 +325,7 @@ impl RemoteConnection for DockerExecConnection {
             }
         }
 
+        docker_args.extend(self.user_args());
         docker_args.extend([
             "-w".to_string(),
             self.remote_dir_for_server.clone(),
@@ -707,6 +443,7 @@ impl RemoteConnection for DockerExecConnection {
         };
 
         let mut docker_args = vec!["exec".to_string()];
+        docker_args.extend(self.user_args());
 
         if let Some(parsed_working_dir) = parsed_working_dir {
             docker_args.push("-w".to_string());
@@ -735,7 +472,49 @@ impl RemoteConnection for DockerExecConnection {
         &self,
         _forwards: Vec<(u16, String, u16)>,
     ) -> Result<CommandTemplate> {
-        Err(anyhow::anyhow!("Not currently supported for docker_exec"))
+        Err(anyhow::anyhow!(
+            "docker_exec forwards each connection separately"
+        ))
+    }
+
+    fn forwards_each_connection(&self) -> bool {
+        true
+    }
+
+    // `docker exec` can't publish ports of a running container, so each connection is relayed
+    // through the remote server's stdin and stdout instead.
+    fn build_forward_connection_command(
+        &self,
+        remote_host: &str,
+        remote_port: u16,
+    ) -> Result<CommandTemplate> {
+        let remote_binary_relpath = self
+            .remote_binary_relpath
+            .as_ref()
+            .context("Remote binary path not set")?;
+
+        let mut docker_args = vec!["exec".to_string()];
+        docker_args.extend(self.user_args());
+        docker_args.extend([
+            "-w".to_string(),
+            self.remote_dir_for_server.clone(),
+            "-i".to_string(),
+            self.connection_options.container_id.to_string(),
+            remote_binary_relpath
+                .display(self.path_style())
+                .into_owned(),
+            "forward-port".to_string(),
+            "--host".to_string(),
+            remote_host.to_string(),
+            "--port".to_string(),
+            remote_port.to_string(),
+        ]);
+
+        Ok(CommandTemplate {
+            program: "docker".to_string(),
+            args: docker_args,
+            env: Default::default(),
+        })
     }
 
     fn connection_options(&self) -> RemoteConnectionOptions {
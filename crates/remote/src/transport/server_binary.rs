@@ -0,0 +1,235 @@
+//! Installs the remote server in environments that are only reachable by running commands in
+//! them, such as Docker containers and the environments of command connections.
+
+use std::{path::Path, sync::Arc, time::Instant};
+
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use gpui::AsyncApp;
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use semver::Version as SemanticVersion;
+use util::{paths::PathStyle, rel_path::RelPath, shell::ShellKind};
+
+use crate::{RemoteClientDelegate, RemotePlatform};
+
+/// An environment that the remote server can be installed in. Its paths are POSIX paths, and it
+/// provides `sh`.
+#[async_trait(?Send)]
+pub(super) trait ServerBinaryTarget {
+    fn platform(&self) -> Result<RemotePlatform>;
+
+    /// The directory that the relative paths of the server binary are resolved against.
+    fn server_dir(&self) -> &str;
+
+    /// Runs `program` in the environment, in `working_dir` when given, returning its stdout.
+    async fn run(&self, program: &str, args: &[&str], working_dir: Option<&str>) -> Result<String>;
+
+    /// Copies the local file at `src_path` to `dest_path`, relative to [`Self::server_dir`].
+    async fn upload_file(&self, src_path: &Path, dest_path: &RelPath) -> Result<()>;
+
+    /// Whether the environment may download the server itself, rather than having it uploaded.
+    fn can_download_binary(&self) -> bool {
+        true
+    }
+}
+
+/// Makes sure that the server binary matching this version of Zed is installed, returning its
+/// path relative to [`ServerBinaryTarget::server_dir`].
+pub(super) async fn ensure_server_binary(
+    target: &impl ServerBinaryTarget,
+    delegate: &Arc<dyn RemoteClientDelegate>,
+    release_channel: ReleaseChannel,
+    version: SemanticVersion,
+    commit: Option<AppCommitSha>,
+    cx: &mut AsyncApp,
+) -> Result<Arc<RelPath>> {
+    let remote_platform = target.platform()?;
+
+    let version_str = match release_channel {
+        ReleaseChannel::Nightly => {
+            let commit = commit.map(|s| s.full()).unwrap_or_default();
+            format!("{}-{}", version, commit)
+        }
+        ReleaseChannel::Dev => "build".to_string(),
+        _ => version.to_string(),
+    };
+    let binary_name = format!(
+        "zed-remote-server-{}-{}",
+        release_channel.dev_name(),
+        version_str
+    );
+    let dst_path = paths::remote_server_dir_relative().join(RelPath::unix(&binary_name).unwrap());
+
+    #[cfg(debug_assertions)]
+    if let Some(remote_server_path) =
+        super::build_remote_server_from_source(&remote_platform, delegate.as_ref(), cx).await?
+    {
+        let tmp_path = paths::remote_server_dir_relative().join(
+            RelPath::unix(&format!(
+                "download-{}-{}",
+                std::process::id(),
+                remote_server_path.file_name().unwrap().to_string_lossy()
+            ))
+            .unwrap(),
+        );
+        upload_local_server_binary(target, &remote_server_path, &tmp_path, delegate, cx).await?;
+        extract_server_binary(target, &dst_path, &tmp_path, delegate, cx).await?;
+        return Ok(dst_path);
+    }
+
+    if target
+        .run(
+            &dst_path.display(PathStyle::Posix),
+            &["version"],
+            Some(target.server_dir()),
+        )
+        .await
+        .is_ok()
+    {
+        return Ok(dst_path);
+    }
+
+    let wanted_version = cx.update(|cx| match release_channel {
+        ReleaseChannel::Nightly => Ok(None),
+        ReleaseChannel::Dev => {
+            anyhow::bail!(
+                "ZED_BUILD_REMOTE_SERVER is not set and no remote server exists at ({:?})",
+                dst_path
+            )
+        }
+        _ => Ok(Some(AppVersion::global(cx))),
+    })??;
+
+    let tmp_path_gz = paths::remote_server_dir_relative().join(
+        RelPath::unix(&format!(
+            "{}-download-{}.gz",
+            binary_name,
+            std::process::id()
+        ))
+        .unwrap(),
+    );
+    if target.can_download_binary()
+        && let Some(url) = delegate
+            .get_download_url(remote_platform, release_channel, wanted_version.clone(), cx)
+            .await?
+    {
+        match download_binary_on_server(target, &url, &tmp_path_gz, delegate, cx).await {
+            Ok(()) => {
+                extract_server_binary(target, &dst_path, &tmp_path_gz, delegate, cx)
+                    .await
+                    .context("extracting server binary")?;
+                return Ok(dst_path);
+            }
+            Err(e) => {
+                log::error!(
+                    "Failed to download binary on server, attempting to download locally and then upload it the server: {e:#}",
+                )
+            }
+        }
+    }
+
+    let src_path = delegate
+        .download_server_binary_locally(remote_platform, release_channel, wanted_version, cx)
+        .await
+        .context("downloading server binary locally")?;
+    upload_local_server_binary(target, &src_path, &tmp_path_gz, delegate, cx)
+        .await
+        .context("uploading server binary")?;
+    extract_server_binary(target, &dst_path, &tmp_path_gz, delegate, cx)
+        .await
+        .context("extracting server binary")?;
+    Ok(dst_path)
+}
+
+async fn download_binary_on_server(
+    target: &impl ServerBinaryTarget,
+    url: &str,
+    tmp_path_gz: &RelPath,
+    delegate: &Arc<dyn RemoteClientDelegate>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    create_parent_dir(target, tmp_path_gz).await?;
+    delegate.set_status(Some("Downloading remote development server on host"), cx);
+
+    let shell_kind = ShellKind::Posix;
+    let url = shell_kind.try_quote(url).context("shell quoting")?;
+    let tmp_path = tmp_path_gz.display(PathStyle::Posix);
+    let tmp_path = shell_kind.try_quote(&tmp_path).context("shell quoting")?;
+    let script = format!(
+        "if command -v curl >/dev/null; then curl -f -L {url} -o {tmp_path}; \
+         elif command -v wget >/dev/null; then wget {url} -O {tmp_path}; \
+         else echo 'Neither curl nor wget is available' >&2; exit 1; fi"
+    );
+    target
+        .run("sh", &["-c", &script], Some(target.server_dir()))
+        .await?;
+    Ok(())
+}
+
+async fn upload_local_server_binary(
+    target: &impl ServerBinaryTarget,
+    src_path: &Path,
+    tmp_path_gz: &RelPath,
+    delegate: &Arc<dyn RemoteClientDelegate>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    create_parent_dir(target, tmp_path_gz).await?;
+
+    let size = smol::fs::metadata(&src_path).await?.len();
+    let t0 = Instant::now();
+    delegate.set_status(Some("Uploading remote development server"), cx);
+    log::info!(
+        "uploading remote development server to {:?} ({}kb)",
+        tmp_path_gz,
+        size / 1024
+    );
+    target
+        .upload_file(src_path, tmp_path_gz)
+        .await
+        .context("failed to upload server binary")?;
+    log::info!("uploaded remote development server in {:?}", t0.elapsed());
+    Ok(())
+}
+
+async fn create_parent_dir(target: &impl ServerBinaryTarget, path: &RelPath) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        target
+            .run(
+                "mkdir",
+                &["-p", parent.display(PathStyle::Posix).as_ref()],
+                Some(target.server_dir()),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+async fn extract_server_binary(
+    target: &impl ServerBinaryTarget,
+    dst_path: &RelPath,
+    tmp_path: &RelPath,
+    delegate: &Arc<dyn RemoteClientDelegate>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    delegate.set_status(Some("Extracting remote development server"), cx);
+
+    let shell_kind = ShellKind::Posix;
+    let orig_tmp_path = tmp_path.display(PathStyle::Posix);
+    let dst_path = dst_path.display(PathStyle::Posix);
+    let dst_path = shell_kind.try_quote(&dst_path).context("shell quoting")?;
+    let orig_tmp_path_quoted = shell_kind
+        .try_quote(&orig_tmp_path)
+        .context("shell quoting")?;
+    let script = if let Some(tmp_path) = orig_tmp_path.strip_suffix(".gz") {
+        let tmp_path = shell_kind.try_quote(tmp_path).context("shell quoting")?;
+        format!(
+            "gunzip -f {orig_tmp_path_quoted} && chmod 755 {tmp_path} && mv {tmp_path} {dst_path}"
+        )
+    } else {
+        format!("chmod 755 {orig_tmp_path_quoted} && mv {orig_tmp_path_quoted} {dst_path}")
+    };
+    target
+        .run("sh", &["-c", &script], Some(target.server_dir()))
+        .await?;
+    Ok(())
+}
@@ -274,6 +274,7 @@ impl HeadlessProject {
         session.add_request_handler(cx.weak_entity(), Self::handle_shutdown_remote_server);
         session.add_request_handler(cx.weak_entity(), Self::handle_ping);
         session.add_request_handler(cx.weak_entity(), Self::handle_get_processes);
+        session.add_request_handler(cx.weak_entity(), Self::handle_get_listening_ports);
 
         session.add_entity_request_handler(Self::handle_add_worktree);
         session.add_request_handler(cx.weak_entity(), Self::handle_remove_worktree);
@@ -926,6 +927,17 @@ impl HeadlessProject {
         Ok(proto::GetProcessesResponse { processes })
     }
 
+    async fn handle_get_listening_ports(
+        _this: Entity<Self>,
+        _envelope: TypedEnvelope<proto::GetListeningPorts>,
+        cx: AsyncApp,
+    ) -> Result<proto::GetListeningPortsResponse> {
+        let ports = cx
+            .background_spawn(async move { crate::listening_ports::listening_ports() })
+            .await;
+        Ok(proto::GetListeningPortsResponse { ports })
+    }
+
     async fn handle_get_directory_environment(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GetDirectoryEnvironment>,
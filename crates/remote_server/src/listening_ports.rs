@@ -0,0 +1,194 @@
+//! Detection of the TCP ports that processes on the remote host are listening on, so that the
+//! client can offer to forward them.
+
+use rpc::proto;
+
+/// The `st` value of a socket in the `LISTEN` state in `/proc/net/tcp`.
+#[cfg(any(test, target_os = "linux"))]
+const TCP_LISTEN: &str = "0A";
+
+#[cfg(any(test, target_os = "linux"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ListeningSocket {
+    port: u16,
+    inode: u64,
+}
+
+/// Parses the contents of `/proc/net/tcp` or `/proc/net/tcp6`, returning the sockets in the
+/// `LISTEN` state.
+#[cfg(any(test, target_os = "linux"))]
+fn parse_proc_net_tcp(contents: &str) -> Vec<ListeningSocket> {
+    contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let local_address = columns.nth(1)?;
+            let state = columns.nth(1)?;
+            if state != TCP_LISTEN {
+                return None;
+            }
+            let inode = columns.nth(5)?.parse().ok()?;
+            let (_, port) = local_address.rsplit_once(':')?;
+            let port = u16::from_str_radix(port, 16).ok()?;
+            Some(ListeningSocket { port, inode })
+        })
+        .collect()
+}
+
+/// Maps socket inodes to the pid of a process holding them, by scanning `/proc/<pid>/fd`.
+///
+/// Processes owned by other users can't be inspected and are silently skipped.
+#[cfg(target_os = "linux")]
+fn socket_owners() -> collections::HashMap<u64, u32> {
+    let mut owners = collections::HashMap::default();
+    let Ok(processes) = std::fs::read_dir("/proc") else {
+        return owners;
+    };
+    for process in processes.flatten() {
+        let Some(pid) = process
+            .file_name()
+            .to_str()
+            .and_then(|pid| pid.parse().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = std::fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            let Ok(target) = std::fs::read_link(fd.path()) else {
+                continue;
+            };
+            if let Some(inode) = target
+                .to_str()
+                .and_then(|target| target.strip_prefix("socket:["))
+                .and_then(|target| target.strip_suffix(']'))
+                .and_then(|inode| inode.parse().ok())
+            {
+                owners.insert(inode, pid);
+            }
+        }
+    }
+    owners
+}
+
+#[cfg(target_os = "linux")]
+pub fn listening_ports() -> Vec<proto::ListeningPort> {
+    let mut sockets = Vec::new();
+    for path in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        if let Ok(contents) = std::fs::read_to_string(path) {
+            sockets.extend(parse_proc_net_tcp(&contents));
+        }
+    }
+    let owners = socket_owners();
+    collect_ports(sockets, |inode| {
+        let pid = *owners.get(&inode)?;
+        let name = std::fs::read_to_string(format!("/proc/{pid}/comm")).ok();
+        Some((pid, name.map(|name| name.trim().to_string())))
+    })
+}
+
+/// Port detection relies on procfs, so no ports are reported on other platforms.
+#[cfg(not(target_os = "linux"))]
+pub fn listening_ports() -> Vec<proto::ListeningPort> {
+    Vec::new()
+}
+
+/// Deduplicates sockets listening on the same port (e.g. on both IPv4 and IPv6), sorted by port.
+#[cfg(any(test, target_os = "linux"))]
+fn collect_ports(
+    sockets: Vec<ListeningSocket>,
+    mut owner: impl FnMut(u64) -> Option<(u32, Option<String>)>,
+) -> Vec<proto::ListeningPort> {
+    let mut ports = collections::BTreeMap::<u16, proto::ListeningPort>::default();
+    for socket in sockets {
+        let entry = ports
+            .entry(socket.port)
+            .or_insert_with(|| proto::ListeningPort {
+                port: socket.port as u32,
+                pid: None,
+                process_name: None,
+            });
+        if entry.pid.is_none()
+            && let Some((pid, process_name)) = owner(socket.inode)
+        {
+            entry.pid = Some(pid);
+            entry.process_name = process_name;
+        }
+    }
+    ports.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_net_tcp() {
+        let contents = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41234 1 0000000000000000 100 0 0 10 0
+   1: 00000000:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1711 1 0000000000000000 100 0 0 10 0
+   2: 0100007F:1F90 0100007F:D2A6 01 00000000:00000000 00:00000000 00000000  1000        0 41300 1 0000000000000000 20 4 30 10 -1
+";
+        assert_eq!(
+            parse_proc_net_tcp(contents),
+            vec![
+                ListeningSocket {
+                    port: 8080,
+                    inode: 41234
+                },
+                ListeningSocket {
+                    port: 22,
+                    inode: 1711
+                },
+            ]
+        );
+
+        let contents6 = "\
+  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000000000000:1F90 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41235 1 0000000000000000 100 0 0 10 0
+";
+        assert_eq!(
+            parse_proc_net_tcp(contents6),
+            vec![ListeningSocket {
+                port: 8080,
+                inode: 41235
+            }]
+        );
+    }
+
+    #[test]
+    fn test_collect_ports() {
+        let sockets = vec![
+            ListeningSocket {
+                port: 8080,
+                inode: 1,
+            },
+            ListeningSocket { port: 22, inode: 2 },
+            ListeningSocket {
+                port: 8080,
+                inode: 3,
+            },
+        ];
+        let ports = collect_ports(sockets, |inode| {
+            (inode == 3).then(|| (42, Some("node".to_string())))
+        });
+        assert_eq!(
+            ports,
+            vec![
+                proto::ListeningPort {
+                    port: 22,
+                    pid: None,
+                    process_name: None,
+                },
+                proto::ListeningPort {
+                    port: 8080,
+                    pid: Some(42),
+                    process_name: Some("node".to_string()),
+                },
+            ]
+        );
+    }
+}
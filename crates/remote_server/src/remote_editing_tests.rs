@@ -29,7 +29,9 @@ use serde_json::json;
 use settings::{Settings, SettingsLocation, SettingsStore, initial_server_settings_content};
 use smol::stream::StreamExt;
 use std::{
+    cell::RefCell,
     path::{Path, PathBuf},
+    rc::Rc,
     sync::Arc,
 };
 use unindent::Unindent as _;
@@ -168,6 +170,37 @@ async fn test_basic_remote_editing(cx: &mut TestAppContext, server_cx: &mut Test
     });
 }
 
+#[gpui::test]
+async fn test_remote_load_file_range(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                "server.log": "first line\nsecond line\nthird line\n",
+            },
+        }),
+    )
+    .await;
+
+    let (project, _headless) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    // Large files are read in ranges from the server, rather than loaded into a buffer.
+    let reader = worktree.read_with(cx, |worktree, _| {
+        worktree.file_range_reader(rel_path("server.log"))
+    });
+    assert_eq!(reader.read(11..22).await.unwrap(), b"second line");
+    assert_eq!(reader.read(23..100).await.unwrap(), b"third line\n");
+    assert_eq!(reader.read(100..200).await.unwrap(), b"");
+}
+
 #[gpui::test]
 async fn test_remote_project_search(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
@@ -1189,6 +1222,141 @@ async fn test_reconnect(cx: &mut TestAppContext, server_cx: &mut TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_edit_while_disconnected(
+    cx: &mut TestAppContext,
+    server_cx: &mut TestAppContext,
+    reconnected_server_cx: &mut TestAppContext,
+) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                "clean.txt": "one\ntwo\nthree\n",
+                "conflicted.txt": "alpha\nbeta\n",
+            },
+        }),
+    )
+    .await;
+
+    let (project, _headless) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = worktree.read_with(cx, |worktree, _| worktree.id());
+    let clean = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, rel_path("clean.txt")), cx)
+        })
+        .await
+        .unwrap();
+    let conflicted = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, rel_path("conflicted.txt")), cx)
+        })
+        .await
+        .unwrap();
+
+    // Edit while connected, so that the buffer's history goes beyond its base text.
+    clean.update(cx, |buffer, cx| buffer.edit([(0..3, "ONE")], None, cx));
+    project
+        .update(cx, |project, cx| project.save_buffer(clean.clone(), cx))
+        .await
+        .unwrap();
+
+    let client = project.read_with(cx, |project, _| project.remote_client().unwrap());
+    client.update(cx, |client, cx| client.simulate_reconnect_exhausted(cx));
+    cx.run_until_parked();
+    assert!(project.read_with(cx, |project, cx| project.is_disconnected(cx)));
+
+    // Keep editing and saving while disconnected. Nothing reaches the server.
+    clean.update(cx, |buffer, cx| buffer.edit([(4..7, "TWO")], None, cx));
+    conflicted.update(cx, |buffer, cx| buffer.edit([(0..0, "local\n")], None, cx));
+    for buffer in [&clean, &conflicted] {
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+    }
+    assert!(project.read_with(cx, |project, _| project.has_queued_offline_saves()));
+    server_cx.run_until_parked();
+    assert_eq!(
+        fs.load(path!("/code/project1/clean.txt").as_ref())
+            .await
+            .unwrap(),
+        "ONE\ntwo\nthree\n"
+    );
+
+    // Meanwhile, one of the files is changed on the server.
+    fs.save(
+        path!("/code/project1/conflicted.txt").as_ref(),
+        &"alpha\nremote\n".into(),
+        LineEnding::Unix,
+    )
+    .await
+    .unwrap();
+
+    let offline_edits = project.read_with(cx, |project, cx| project.offline_edits(cx));
+    assert_eq!(offline_edits.paths().count(), 2);
+
+    // Reopen the project over a new connection, and replay the offline edits.
+    let (reopened, _reopened_headless) = init_test(&fs, cx, reconnected_server_cx).await;
+    reopened
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    let conflicts = Rc::new(RefCell::new(Vec::new()));
+    cx.update(|cx| {
+        let conflicts = conflicts.clone();
+        cx.subscribe(&reopened, move |_, event, _| {
+            if let project::Event::OfflineEditConflict {
+                buffer,
+                remote_text,
+            } = event
+            {
+                conflicts
+                    .borrow_mut()
+                    .push((buffer.clone(), remote_text.to_string()));
+            }
+        })
+        .detach();
+    });
+    reopened
+        .update(cx, |project, cx| {
+            project.replay_offline_edits(offline_edits, cx)
+        })
+        .await
+        .unwrap();
+    reconnected_server_cx.run_until_parked();
+
+    assert_eq!(
+        fs.load(path!("/code/project1/clean.txt").as_ref())
+            .await
+            .unwrap(),
+        "ONE\nTWO\nthree\n"
+    );
+    assert_eq!(
+        fs.load(path!("/code/project1/conflicted.txt").as_ref())
+            .await
+            .unwrap(),
+        "alpha\nremote\n"
+    );
+    let conflicts = conflicts.borrow();
+    assert_eq!(conflicts.len(), 1);
+    let (buffer, remote_text) = &conflicts[0];
+    assert_eq!(remote_text, "alpha\nremote\n");
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "local\nalpha\nbeta\n");
+        assert!(buffer.is_dirty());
+    });
+}
+
 #[gpui::test]
 async fn test_remote_root_rename(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
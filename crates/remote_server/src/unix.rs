@@ -576,6 +576,23 @@ pub(crate) enum ExecuteProxyError {
     StderrTask(#[source] anyhow::Error),
 }
 
+pub(crate) fn execute_forward_port(host: &str, port: u16) -> Result<()> {
+    use std::os::fd::AsFd as _;
+
+    let stream = std::net::TcpStream::connect((host, port))
+        .with_context(|| format!("failed to connect to {host}:{port}"))?;
+    let mut outgoing = stream.try_clone()?;
+    std::thread::spawn(move || {
+        std::io::copy(&mut std::io::stdin().lock(), &mut outgoing).log_err();
+        outgoing.shutdown(std::net::Shutdown::Write).ok();
+    });
+
+    // Write to the stdout file descriptor directly, as `Stdout` buffers until a newline.
+    let mut stdout = File::from(std::io::stdout().as_fd().try_clone_to_owned()?);
+    std::io::copy(&mut &stream, &mut stdout)?;
+    Ok(())
+}
+
 pub(crate) fn execute_proxy(
     identifier: String,
     is_reconnecting: bool,
@@ -1,5 +1,7 @@
+mod jupyter_token_modal;
 mod kernel_list_item;
 mod kernel_options;
 
+pub use jupyter_token_modal::*;
 pub use kernel_list_item::*;
 pub use kernel_options::*;
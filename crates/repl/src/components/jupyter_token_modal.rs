@@ -0,0 +1,109 @@
+use editor::Editor;
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable};
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::repl_store::ReplStore;
+
+/// Prompts for the token of a Jupyter server and stores it in the system keychain.
+pub struct JupyterTokenModal {
+    server_name: SharedString,
+    server_url: String,
+    editor: Entity<Editor>,
+}
+
+impl EventEmitter<DismissEvent> for JupyterTokenModal {}
+impl ModalView for JupyterTokenModal {}
+
+impl Focusable for JupyterTokenModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl JupyterTokenModal {
+    pub fn new(
+        server_name: SharedString,
+        server_url: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_masked(true, cx);
+            editor.set_placeholder_text("Paste the server token", window, cx);
+            editor
+        });
+
+        Self {
+            server_name,
+            server_url,
+            editor,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let token = self.editor.update(cx, |editor, cx| {
+            let text = editor.text(cx);
+            editor.clear(window, cx);
+            text
+        });
+        let token = token.trim();
+        let token = (!token.is_empty()).then(|| token.to_string());
+
+        ReplStore::global(cx).update(cx, |store, cx| {
+            store
+                .set_jupyter_server_token(self.server_url.clone(), token, cx)
+                .detach_and_log_err(cx);
+        });
+
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for JupyterTokenModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("JupyterTokenModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                v_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .gap_0p5()
+                    .child(
+                        Headline::new(format!("Token for {}", self.server_name))
+                            .size(HeadlineSize::XSmall),
+                    )
+                    .child(
+                        Label::new(self.server_url.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                div()
+                    .py_2()
+                    .px_3()
+                    .bg(cx.theme().colors().editor_background)
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.editor.clone()),
+            )
+            .child(
+                div().px_3().py_1().child(
+                    Label::new("Leave empty and confirm to remove the stored token.")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            )
+    }
+}
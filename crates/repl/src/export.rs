@@ -0,0 +1,467 @@
+//! Exports a script split into `# %%` cells, along with the outputs captured by the REPL,
+//! to a Jupyter notebook or a Markdown document.
+
+use std::ops::Range;
+
+use base64::Engine as _;
+use serde_json::{Value, json};
+
+use crate::repl_editor::jupytext_prefixes;
+
+/// An output captured from a kernel, kept independently of how it is rendered so that it can
+/// be exported.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum CapturedOutput {
+    Stream {
+        name: String,
+        text: String,
+    },
+    DisplayData {
+        data: serde_json::Map<String, Value>,
+        display_id: Option<String>,
+    },
+    ExecuteResult {
+        data: serde_json::Map<String, Value>,
+        execution_count: usize,
+    },
+    Error {
+        ename: String,
+        evalue: String,
+        traceback: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Notebook,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Notebook => "ipynb",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExportCellKind {
+    Code,
+    Markdown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ExportCell {
+    pub kind: ExportCellKind,
+    pub source: String,
+    /// The rows of the cell's source in the buffer, excluding the `# %%` marker.
+    pub rows: Range<u32>,
+    pub outputs: Vec<CapturedOutput>,
+    pub execution_count: Option<usize>,
+}
+
+/// Splits a script into cells on the same `# %%` markers that the REPL runs cells by (see
+/// [`jupytext_prefixes`]).
+///
+/// Cells marked with `[markdown]` have their comment prefixes stripped. Text before the first
+/// marker becomes a code cell of its own, unless it is blank.
+pub(crate) fn split_cells(text: &str, comment_prefixes: &[String]) -> Vec<ExportCell> {
+    let markers = jupytext_prefixes(comment_prefixes);
+
+    let mut cells = Vec::new();
+    let mut current_kind = ExportCellKind::Code;
+    let mut current_lines: Vec<&str> = Vec::new();
+    let mut current_start = 0;
+
+    let mut push_cell = |kind: ExportCellKind, lines: &mut Vec<&str>, start: u32, end: u32| {
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        let first_content = lines
+            .iter()
+            .position(|line| !line.trim().is_empty())
+            .unwrap_or(lines.len());
+        if first_content == lines.len() && kind == ExportCellKind::Code {
+            lines.clear();
+            return;
+        }
+
+        let source = lines[first_content..]
+            .iter()
+            .map(|line| match kind {
+                ExportCellKind::Code => line.to_string(),
+                ExportCellKind::Markdown => strip_comment_prefix(line, comment_prefixes),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        lines.clear();
+
+        cells.push(ExportCell {
+            kind,
+            source,
+            rows: start + first_content as u32..end,
+            outputs: Vec::new(),
+            execution_count: None,
+        });
+    };
+
+    let mut row = 0;
+    for line in text.lines() {
+        if let Some(marker_rest) = markers
+            .iter()
+            .find_map(|marker| line.strip_prefix(marker.as_str()))
+        {
+            push_cell(current_kind, &mut current_lines, current_start, row);
+            current_kind = if marker_rest.contains("[markdown]") || marker_rest.contains("[md]") {
+                ExportCellKind::Markdown
+            } else {
+                ExportCellKind::Code
+            };
+            current_start = row + 1;
+        } else {
+            current_lines.push(line);
+        }
+        row += 1;
+    }
+    push_cell(current_kind, &mut current_lines, current_start, row);
+
+    cells
+}
+
+fn strip_comment_prefix(line: &str, comment_prefixes: &[String]) -> String {
+    let trimmed = line.trim_start();
+    comment_prefixes
+        .iter()
+        .find_map(|prefix| {
+            trimmed
+                .strip_prefix(prefix.as_str())
+                .or_else(|| trimmed.strip_prefix(prefix.trim_end()))
+        })
+        .unwrap_or(line)
+        .to_string()
+}
+
+/// Splits text into lines that keep their line endings, as nbformat stores multiline strings.
+fn notebook_lines(text: &str) -> Value {
+    Value::Array(
+        text.split_inclusive('\n')
+            .map(|line| Value::String(line.to_string()))
+            .collect(),
+    )
+}
+
+fn output_to_notebook(output: &CapturedOutput) -> Value {
+    match output {
+        CapturedOutput::Stream { name, text } => json!({
+            "output_type": "stream",
+            "name": name,
+            "text": notebook_lines(text),
+        }),
+        CapturedOutput::DisplayData { data, .. } => json!({
+            "output_type": "display_data",
+            "data": data,
+            "metadata": {},
+        }),
+        CapturedOutput::ExecuteResult {
+            data,
+            execution_count,
+        } => json!({
+            "output_type": "execute_result",
+            "execution_count": execution_count,
+            "data": data,
+            "metadata": {},
+        }),
+        CapturedOutput::Error {
+            ename,
+            evalue,
+            traceback,
+        } => json!({
+            "output_type": "error",
+            "ename": ename,
+            "evalue": evalue,
+            "traceback": traceback,
+        }),
+    }
+}
+
+pub(crate) fn to_notebook(
+    cells: &[ExportCell],
+    language: &str,
+    kernelspec: Option<(&str, &str)>,
+) -> String {
+    let cells = cells
+        .iter()
+        .enumerate()
+        .map(|(ix, cell)| match cell.kind {
+            ExportCellKind::Markdown => json!({
+                "cell_type": "markdown",
+                "id": format!("cell-{ix}"),
+                "metadata": {},
+                "source": notebook_lines(&cell.source),
+            }),
+            ExportCellKind::Code => json!({
+                "cell_type": "code",
+                "id": format!("cell-{ix}"),
+                "metadata": {},
+                "execution_count": cell.execution_count,
+                "source": notebook_lines(&cell.source),
+                "outputs": cell.outputs.iter().map(output_to_notebook).collect::<Vec<_>>(),
+            }),
+        })
+        .collect::<Vec<_>>();
+
+    let mut metadata = json!({
+        "language_info": { "name": language.to_lowercase() },
+    });
+    if let Some((name, display_name)) = kernelspec {
+        metadata["kernelspec"] = json!({
+            "name": name,
+            "display_name": display_name,
+            "language": language.to_lowercase(),
+        });
+    }
+
+    let notebook = json!({
+        "nbformat": 4,
+        "nbformat_minor": 5,
+        "metadata": metadata,
+        "cells": cells,
+    });
+
+    let mut text = serde_json::to_string_pretty(&notebook).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+/// Mime types in the order they are preferred when exporting to Markdown.
+const MARKDOWN_MIME_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/svg+xml",
+    "text/markdown",
+    "text/plain",
+];
+
+fn data_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(lines) => lines
+            .iter()
+            .filter_map(|line| line.as_str())
+            .collect::<String>(),
+        other => other.to_string(),
+    }
+}
+
+fn fenced(text: &str, language: &str) -> String {
+    let fence = if text.contains("```") { "````" } else { "```" };
+    format!(
+        "{fence}{language}\n{}\n{fence}\n",
+        text.trim_end_matches('\n')
+    )
+}
+
+fn data_to_markdown(data: &serde_json::Map<String, Value>) -> Option<String> {
+    let (mime_type, value) = MARKDOWN_MIME_TYPES
+        .iter()
+        .find_map(|mime_type| Some((*mime_type, data.get(*mime_type)?)))?;
+
+    Some(match mime_type {
+        "image/svg+xml" => {
+            let svg = base64::engine::general_purpose::STANDARD.encode(data_text(value));
+            format!("![output](data:{mime_type};base64,{svg})\n")
+        }
+        "image/png" | "image/jpeg" => {
+            let image = data_text(value).replace(['\n', '\r'], "");
+            format!("![output](data:{mime_type};base64,{image})\n")
+        }
+        "text/markdown" => format!("{}\n", data_text(value).trim_end()),
+        _ => fenced(&data_text(value), "text"),
+    })
+}
+
+pub(crate) fn to_markdown(cells: &[ExportCell], language: &str) -> String {
+    let language = language.to_lowercase();
+    let mut sections = Vec::new();
+
+    for cell in cells {
+        match cell.kind {
+            ExportCellKind::Markdown => sections.push(format!("{}\n", cell.source.trim_end())),
+            ExportCellKind::Code => {
+                sections.push(fenced(&cell.source, &language));
+                for output in &cell.outputs {
+                    let section = match output {
+                        CapturedOutput::Stream { text, .. } => Some(fenced(text, "text")),
+                        CapturedOutput::DisplayData { data, .. }
+                        | CapturedOutput::ExecuteResult { data, .. } => data_to_markdown(data),
+                        CapturedOutput::Error {
+                            ename,
+                            evalue,
+                            traceback,
+                        } => {
+                            let traceback = traceback
+                                .iter()
+                                .map(|line| strip_ansi_escapes(line))
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                            Some(fenced(&format!("{ename}: {evalue}\n{traceback}"), "text"))
+                        }
+                    };
+                    sections.extend(section);
+                }
+            }
+        }
+    }
+
+    sections.join("\n")
+}
+
+/// Removes the terminal color codes that kernels include in tracebacks.
+fn strip_ansi_escapes(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '\u{1b}' && chars.peek() == Some(&'[') {
+            chars.next();
+            for ch in chars.by_ref() {
+                if ch.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(ch);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn python_cells() -> Vec<ExportCell> {
+        split_cells(
+            indoc! {r#"
+                import math
+
+                # %% [markdown]
+                # # Title
+                # Some *prose*.
+
+                # %%
+                print(math.pi)
+                x = 2
+
+                # %% Final cell
+                x * 3
+            "#},
+            &["# ".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_split_cells() {
+        let cells = python_cells();
+
+        assert_eq!(
+            cells
+                .iter()
+                .map(|cell| (cell.kind, cell.source.as_str(), cell.rows.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (ExportCellKind::Code, "import math", 0..2),
+                (ExportCellKind::Markdown, "# Title\nSome *prose*.", 3..6),
+                (ExportCellKind::Code, "print(math.pi)\nx = 2", 7..10),
+                (ExportCellKind::Code, "x * 3", 11..12),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_notebook() {
+        let mut cells = python_cells();
+        cells[2].execution_count = Some(1);
+        cells[2].outputs = vec![CapturedOutput::Stream {
+            name: "stdout".into(),
+            text: "3.14\n".into(),
+        }];
+        cells[3].execution_count = Some(2);
+        cells[3].outputs = vec![CapturedOutput::ExecuteResult {
+            data: serde_json::from_value(json!({ "text/plain": "6" })).unwrap(),
+            execution_count: 2,
+        }];
+
+        let notebook: Value = serde_json::from_str(&to_notebook(
+            &cells,
+            "Python",
+            Some(("python3", "Python 3")),
+        ))
+        .unwrap();
+
+        assert_eq!(notebook["nbformat"], 4);
+        assert_eq!(notebook["metadata"]["kernelspec"]["name"], "python3");
+
+        let cells = notebook["cells"].as_array().unwrap();
+        assert_eq!(cells.len(), 4);
+        assert_eq!(cells[1]["cell_type"], "markdown");
+        assert_eq!(cells[1]["source"], json!(["# Title\n", "Some *prose*."]));
+        assert_eq!(cells[2]["execution_count"], 1);
+        assert_eq!(
+            cells[2]["outputs"],
+            json!([{ "output_type": "stream", "name": "stdout", "text": ["3.14\n"] }])
+        );
+        assert_eq!(cells[3]["outputs"][0]["output_type"], "execute_result");
+        assert_eq!(cells[3]["outputs"][0]["data"]["text/plain"], "6");
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let mut cells = python_cells();
+        cells[2].outputs = vec![
+            CapturedOutput::DisplayData {
+                data: serde_json::from_value(json!({
+                    "image/png": "aGVsbG8=\n",
+                    "text/plain": "<Figure>",
+                }))
+                .unwrap(),
+                display_id: None,
+            },
+            CapturedOutput::Error {
+                ename: "NameError".into(),
+                evalue: "name 'y' is not defined".into(),
+                traceback: vec!["\u{1b}[0;31mNameError\u{1b}[0m: boom".into()],
+            },
+        ];
+
+        assert_eq!(
+            to_markdown(&cells, "Python"),
+            indoc! {r#"
+                ```python
+                import math
+                ```
+
+                # Title
+                Some *prose*.
+
+                ```python
+                print(math.pi)
+                x = 2
+                ```
+
+                ![output](data:image/png;base64,aGVsbG8=)
+
+                ```text
+                NameError: name 'y' is not defined
+                NameError: boom
+                ```
+
+                ```python
+                x * 3
+                ```
+            "#}
+        );
+    }
+}
@@ -34,7 +34,13 @@ impl KernelSpecification {
         match self {
             Self::Jupyter(spec) => spec.name.clone().into(),
             Self::PythonEnv(spec) => spec.name.clone().into(),
-            Self::Remote(spec) => spec.name.clone().into(),
+            Self::Remote(spec) => match &spec.kernel_id {
+                Some(kernel_id) => {
+                    let short_id = kernel_id.get(..8).unwrap_or(kernel_id);
+                    format!("{} ({short_id})", spec.name).into()
+                }
+                None => spec.name.clone().into(),
+            },
         }
     }
 
@@ -42,6 +48,7 @@ impl KernelSpecification {
         match self {
             Self::Jupyter(_) => "Jupyter".into(),
             Self::PythonEnv(_) => "Python Environment".into(),
+            Self::Remote(spec) if spec.kernel_id.is_some() => "Running Remote Kernel".into(),
             Self::Remote(_) => "Remote".into(),
         }
     }
@@ -62,6 +69,14 @@ impl KernelSpecification {
         })
     }
 
+    pub fn kernelspec(&self) -> &JupyterKernelspec {
+        match self {
+            Self::Jupyter(spec) => &spec.kernelspec,
+            Self::PythonEnv(spec) => &spec.kernelspec,
+            Self::Remote(spec) => &spec.kernelspec,
+        }
+    }
+
     pub fn icon(&self, cx: &App) -> Icon {
         let lang_name = match self {
             Self::Jupyter(spec) => spec.kernelspec.language.clone(),
@@ -164,6 +179,22 @@ pub trait RunningKernel: Send + Debug {
     fn kernel_info(&self) -> Option<&KernelInfoReply>;
     fn set_kernel_info(&mut self, info: KernelInfoReply);
     fn force_shutdown(&mut self, window: &mut Window, cx: &mut App) -> Task<anyhow::Result<()>>;
+
+    /// Whether the kernel was already running when the session attached to it, in which case
+    /// other clients may depend on it and the session must not shut it down.
+    fn is_attached(&self) -> bool {
+        false
+    }
+
+    /// Restarts an attached kernel in place. Returns `None` for kernels the session launched,
+    /// which are shut down and launched again instead.
+    fn restart_attached(
+        &mut self,
+        _window: &mut Window,
+        _cx: &mut App,
+    ) -> Option<Task<anyhow::Result<()>>> {
+        None
+    }
 }
 
 #[derive(Debug, Clone)]
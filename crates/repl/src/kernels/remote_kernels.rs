@@ -12,7 +12,7 @@ use smol::io::AsyncReadExt as _;
 use crate::Session;
 
 use super::RunningKernel;
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use jupyter_websocket_client::{
     JupyterWebSocket, JupyterWebSocketReader, JupyterWebSocketWriter, KernelLaunchRequest,
     KernelSpecsResponse, RemoteServer,
@@ -25,6 +25,43 @@ pub struct RemoteKernelSpecification {
     pub url: String,
     pub token: String,
     pub kernelspec: JupyterKernelspec,
+    /// An already running kernel on the server to attach to, instead of launching a new one.
+    pub kernel_id: Option<String>,
+}
+
+impl RemoteKernelSpecification {
+    pub fn remote_server(&self) -> RemoteServer {
+        RemoteServer {
+            base_url: self.url.clone(),
+            token: self.token.clone(),
+        }
+    }
+}
+
+async fn send_remote_request(
+    remote_server: &RemoteServer,
+    http_client: &Arc<dyn HttpClient>,
+    method: &str,
+    path: &str,
+    body: AsyncBody,
+) -> Result<String> {
+    let request = Request::builder()
+        .method(method)
+        .uri(&remote_server.api_url(path))
+        .header("Authorization", format!("token {}", remote_server.token))
+        .body(body)?;
+
+    let response = http_client.send(request).await?;
+    let status = response.status();
+
+    let mut body = String::new();
+    response.into_body().read_to_string(&mut body).await?;
+
+    anyhow::ensure!(
+        status.is_success(),
+        "{method} {path} failed with {status}: {body}"
+    );
+    Ok(body)
 }
 
 pub async fn launch_remote_kernel(
@@ -65,6 +102,88 @@ pub async fn launch_remote_kernel(
     Ok(response.id)
 }
 
+/// A kernel that is already running on a Jupyter server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunningRemoteKernel {
+    pub id: String,
+    pub name: String,
+    pub execution_state: String,
+    pub connections: u64,
+}
+
+/// Lists the kernels that are already running on the server.
+pub async fn list_running_remote_kernels(
+    remote_server: &RemoteServer,
+    http_client: Arc<dyn HttpClient>,
+) -> Result<Vec<RunningRemoteKernel>> {
+    let body = send_remote_request(
+        remote_server,
+        &http_client,
+        "GET",
+        "/kernels",
+        AsyncBody::default(),
+    )
+    .await?;
+    let kernels: Vec<jupyter_websocket_client::Kernel> = serde_json::from_str(&body)?;
+    Ok(kernels
+        .into_iter()
+        .map(|kernel| RunningRemoteKernel {
+            id: kernel.id,
+            name: kernel.name,
+            execution_state: kernel.execution_state,
+            connections: kernel.connections,
+        })
+        .collect())
+}
+
+pub async fn interrupt_remote_kernel(
+    remote_server: &RemoteServer,
+    http_client: Arc<dyn HttpClient>,
+    kernel_id: &str,
+) -> Result<()> {
+    send_remote_request(
+        remote_server,
+        &http_client,
+        "POST",
+        &format!("/kernels/{kernel_id}/interrupt"),
+        AsyncBody::default(),
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn restart_remote_kernel(
+    remote_server: &RemoteServer,
+    http_client: Arc<dyn HttpClient>,
+    kernel_id: &str,
+) -> Result<()> {
+    send_remote_request(
+        remote_server,
+        &http_client,
+        "POST",
+        &format!("/kernels/{kernel_id}/restart"),
+        AsyncBody::default(),
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn shutdown_remote_kernel(
+    remote_server: &RemoteServer,
+    http_client: Arc<dyn HttpClient>,
+    kernel_id: &str,
+) -> Result<()> {
+    send_remote_request(
+        remote_server,
+        &http_client,
+        "DELETE",
+        &format!("/kernels/{kernel_id}"),
+        AsyncBody::default(),
+    )
+    .await?;
+    Ok(())
+}
+
 pub async fn list_remote_kernelspecs(
     remote_server: RemoteServer,
     http_client: Arc<dyn HttpClient>,
@@ -99,6 +218,7 @@ pub async fn list_remote_kernelspecs(
             url: remote_server.base_url.clone(),
             token: remote_server.token.clone(),
             kernelspec: spec.spec,
+            kernel_id: None,
         })
         .collect::<Vec<RemoteKernelSpecification>>();
 
@@ -106,9 +226,35 @@ pub async fn list_remote_kernelspecs(
     Ok(remote_kernelspecs)
 }
 
+/// Lists the kernelspecs available on a Jupyter server, along with the kernels already running on
+/// it. Running kernels are returned as specifications that attach to the existing kernel.
+pub async fn list_remote_kernels(
+    remote_server: RemoteServer,
+    http_client: Arc<dyn HttpClient>,
+) -> Result<(Vec<RemoteKernelSpecification>, Vec<RunningRemoteKernel>)> {
+    let running_kernels = list_running_remote_kernels(&remote_server, http_client.clone()).await?;
+    let kernelspecs = list_remote_kernelspecs(remote_server, http_client).await?;
+
+    let attachable_kernels = running_kernels
+        .iter()
+        .filter_map(|kernel| {
+            let spec = kernelspecs.iter().find(|spec| spec.name == kernel.name)?;
+            Some(RemoteKernelSpecification {
+                kernel_id: Some(kernel.id.clone()),
+                ..spec.clone()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok((
+        kernelspecs.into_iter().chain(attachable_kernels).collect(),
+        running_kernels,
+    ))
+}
+
 impl PartialEq for RemoteKernelSpecification {
     fn eq(&self, other: &Self) -> bool {
-        self.name == other.name && self.url == other.url
+        self.name == other.name && self.url == other.url && self.kernel_id == other.kernel_id
     }
 }
 
@@ -124,6 +270,7 @@ pub struct RemoteRunningKernel {
     pub execution_state: ExecutionState,
     pub kernel_info: Option<KernelInfoReply>,
     pub kernel_id: String,
+    attached: bool,
 }
 
 impl RemoteRunningKernel {
@@ -134,21 +281,25 @@ impl RemoteRunningKernel {
         window: &mut Window,
         cx: &mut App,
     ) -> Task<Result<Box<dyn RunningKernel>>> {
-        let remote_server = RemoteServer {
-            base_url: kernelspec.url,
-            token: kernelspec.token,
-        };
+        let remote_server = kernelspec.remote_server();
 
         let http_client = cx.http_client();
 
+        let attached = kernelspec.kernel_id.is_some();
+
         window.spawn(cx, async move |cx| {
-            let kernel_id = launch_remote_kernel(
-                &remote_server,
-                http_client.clone(),
-                &kernelspec.name,
-                working_directory.to_str().unwrap_or_default(),
-            )
-            .await?;
+            let kernel_id = match kernelspec.kernel_id {
+                Some(kernel_id) => kernel_id,
+                None => {
+                    launch_remote_kernel(
+                        &remote_server,
+                        http_client.clone(),
+                        &kernelspec.name,
+                        working_directory.to_str().unwrap_or_default(),
+                    )
+                    .await?
+                }
+            };
 
             let ws_url = format!(
                 "{}/api/kernels/{}/channels?token={}",
@@ -223,6 +374,7 @@ impl RemoteRunningKernel {
                 execution_state: ExecutionState::Idle,
                 kernel_info: None,
                 kernel_id,
+                attached,
                 http_client: http_client.clone(),
             }) as Box<dyn RunningKernel>)
         })
@@ -268,27 +420,179 @@ impl RunningKernel for RemoteRunningKernel {
     }
 
     fn force_shutdown(&mut self, window: &mut Window, cx: &mut App) -> Task<anyhow::Result<()>> {
-        let url = self
-            .remote_server
-            .api_url(&format!("/kernels/{}", self.kernel_id));
-        let token = self.remote_server.token.clone();
+        // Other clients of an attached kernel still use it, so only disconnect from it.
+        if self.attached {
+            return Task::ready(Ok(()));
+        }
+
+        let remote_server = RemoteServer {
+            base_url: self.remote_server.base_url.clone(),
+            token: self.remote_server.token.clone(),
+        };
+        let kernel_id = self.kernel_id.clone();
         let http_client = self.http_client.clone();
 
         window.spawn(cx, async move |_| {
-            let request = Request::builder()
-                .method("DELETE")
-                .uri(&url)
-                .header("Authorization", format!("token {}", token))
-                .body(AsyncBody::default())?;
-
-            let response = http_client.send(request).await?;
-
-            anyhow::ensure!(
-                response.status().is_success(),
-                "Failed to shutdown kernel: {}",
-                response.status()
-            );
-            Ok(())
+            shutdown_remote_kernel(&remote_server, http_client, &kernel_id)
+                .await
+                .context("shutting down kernel")
+        })
+    }
+
+    fn is_attached(&self) -> bool {
+        self.attached
+    }
+
+    fn restart_attached(
+        &mut self,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<()>>> {
+        if !self.attached {
+            return None;
+        }
+
+        let remote_server = RemoteServer {
+            base_url: self.remote_server.base_url.clone(),
+            token: self.remote_server.token.clone(),
+        };
+        let kernel_id = self.kernel_id.clone();
+        let http_client = self.http_client.clone();
+
+        Some(window.spawn(cx, async move |_| {
+            restart_remote_kernel(&remote_server, http_client, &kernel_id)
+                .await
+                .context("restarting kernel")
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use collections::HashSet;
+    use gpui::TestAppContext;
+    use http_client::{FakeHttpClient, Response};
+    use std::sync::Mutex;
+
+    /// Serves the subset of the Jupyter Server REST API used by Zed.
+    fn fake_jupyter_server(shut_down: Arc<Mutex<HashSet<String>>>) -> Arc<dyn HttpClient> {
+        FakeHttpClient::create(move |request| {
+            let shut_down = shut_down.clone();
+            async move {
+                let authorized = request
+                    .headers()
+                    .get("Authorization")
+                    .is_some_and(|value| value == "token secret");
+                if !authorized {
+                    return Ok(Response::builder().status(403).body(AsyncBody::default())?);
+                }
+
+                let body = match (request.method().as_str(), request.uri().path()) {
+                    ("GET", "/api/kernelspecs") => serde_json::json!({
+                        "default": "python3",
+                        "kernelspecs": {
+                            "python3": {
+                                "name": "python3",
+                                "spec": {
+                                    "argv": ["python", "-m", "ipykernel_launcher", "-f", "{connection_file}"],
+                                    "display_name": "Python 3",
+                                    "language": "python",
+                                },
+                                "resources": {},
+                            },
+                        },
+                    }),
+                    ("GET", "/api/kernels") => serde_json::json!([
+                        {
+                            "id": "0123456789abcdef",
+                            "name": "python3",
+                            "last_activity": "2024-01-01T00:00:00Z",
+                            "execution_state": "idle",
+                            "connections": 1,
+                        },
+                        {
+                            "id": "fedcba9876543210",
+                            "name": "missing-kernelspec",
+                            "last_activity": "2024-01-01T00:00:00Z",
+                            "execution_state": "busy",
+                            "connections": 0,
+                        },
+                    ]),
+                    ("DELETE", path) if path.starts_with("/api/kernels/") => {
+                        let kernel_id = path.trim_start_matches("/api/kernels/");
+                        shut_down.lock().unwrap().insert(kernel_id.to_string());
+                        return Ok(Response::builder().status(204).body(AsyncBody::default())?);
+                    }
+                    ("POST", path)
+                        if path.ends_with("/interrupt") || path.ends_with("/restart") =>
+                    {
+                        return Ok(Response::builder().status(204).body(AsyncBody::default())?);
+                    }
+                    _ => return Ok(Response::builder().status(404).body(AsyncBody::default())?),
+                };
+
+                Ok(Response::builder()
+                    .status(200)
+                    .body(AsyncBody::from(body.to_string()))?)
+            }
         })
     }
+
+    fn remote_server(token: &str) -> RemoteServer {
+        RemoteServer {
+            base_url: "http://localhost:8888".into(),
+            token: token.into(),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_list_remote_kernels(_cx: &mut TestAppContext) {
+        let http_client = fake_jupyter_server(Default::default());
+
+        let (specs, running_kernels) = list_remote_kernels(remote_server("secret"), http_client)
+            .await
+            .unwrap();
+
+        assert_eq!(running_kernels.len(), 2);
+        assert_eq!(running_kernels[0].execution_state, "idle");
+
+        // The kernelspec itself, plus an attachable entry for the running python3 kernel. The
+        // running kernel without a known kernelspec can't be attached to.
+        assert_eq!(specs.len(), 2);
+        assert_eq!(specs[0].name, "python3");
+        assert_eq!(specs[0].kernel_id, None);
+        assert_eq!(specs[1].kernel_id.as_deref(), Some("0123456789abcdef"));
+        assert_eq!(specs[1].kernelspec.language, "python");
+        assert_eq!(specs[1].token, "secret");
+    }
+
+    #[gpui::test]
+    async fn test_remote_kernels_require_token(_cx: &mut TestAppContext) {
+        let http_client = fake_jupyter_server(Default::default());
+
+        let error = list_remote_kernels(remote_server("wrong"), http_client)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("403"), "{error}");
+    }
+
+    #[gpui::test]
+    async fn test_manage_remote_kernel(_cx: &mut TestAppContext) {
+        let shut_down = Arc::new(Mutex::new(HashSet::default()));
+        let http_client = fake_jupyter_server(shut_down.clone());
+        let server = remote_server("secret");
+
+        interrupt_remote_kernel(&server, http_client.clone(), "0123456789abcdef")
+            .await
+            .unwrap();
+        restart_remote_kernel(&server, http_client.clone(), "0123456789abcdef")
+            .await
+            .unwrap();
+        shutdown_remote_kernel(&server, http_client, "0123456789abcdef")
+            .await
+            .unwrap();
+
+        assert!(shut_down.lock().unwrap().contains("0123456789abcdef"));
+    }
 }
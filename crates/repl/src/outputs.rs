@@ -45,7 +45,7 @@ use image::ImageView;
 mod markdown;
 use markdown::MarkdownView;
 
-mod table;
+pub(crate) mod table;
 use table::TableView;
 
 pub mod plain;
@@ -55,6 +55,8 @@ pub(crate) mod user_error;
 use user_error::ErrorView;
 use workspace::Workspace;
 
+use crate::export::CapturedOutput;
+
 /// When deciding what to render from a collection of mediatypes, we need to rank them in order of importance
 fn rank_mime_type(mimetype: &MimeType) -> usize {
     match mimetype {
@@ -367,6 +369,23 @@ pub struct ExecutionView {
     workspace: WeakEntity<Workspace>,
     pub outputs: Vec<Output>,
     pub status: ExecutionStatus,
+    captured_outputs: Vec<CapturedOutput>,
+    clear_captured_on_next_output: bool,
+    execution_count: Option<usize>,
+}
+
+fn media_to_json(data: &MimeBundle) -> serde_json::Map<String, serde_json::Value> {
+    match serde_json::to_value(data) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => Default::default(),
+    }
+}
+
+fn execution_count_to_usize(count: impl serde::Serialize) -> Option<usize> {
+    serde_json::to_value(count)
+        .ok()?
+        .as_u64()
+        .map(|count| count as usize)
 }
 
 impl ExecutionView {
@@ -379,7 +398,81 @@ impl ExecutionView {
             workspace,
             outputs: Default::default(),
             status,
+            captured_outputs: Vec::new(),
+            clear_captured_on_next_output: false,
+            execution_count: None,
+        }
+    }
+
+    /// The outputs of this execution, in a form suitable for exporting.
+    pub(crate) fn captured_outputs(&self) -> &[CapturedOutput] {
+        &self.captured_outputs
+    }
+
+    pub(crate) fn execution_count(&self) -> Option<usize> {
+        self.execution_count
+    }
+
+    fn capture_message(&mut self, message: &JupyterMessageContent) {
+        let captured = match message {
+            JupyterMessageContent::ExecuteResult(result) => {
+                let execution_count = execution_count_to_usize(result.execution_count);
+                self.execution_count = self.execution_count.or(execution_count);
+                CapturedOutput::ExecuteResult {
+                    data: media_to_json(&result.data),
+                    execution_count: execution_count.unwrap_or_default(),
+                }
+            }
+            JupyterMessageContent::DisplayData(result) => CapturedOutput::DisplayData {
+                data: media_to_json(&result.data),
+                display_id: result.transient.as_ref().and_then(|t| t.display_id.clone()),
+            },
+            JupyterMessageContent::StreamContent(result) => {
+                let name = serde_json::to_value(&result.name)
+                    .ok()
+                    .and_then(|name| name.as_str().map(ToString::to_string))
+                    .unwrap_or_else(|| "stdout".to_string());
+
+                if !self.clear_captured_on_next_output
+                    && let Some(CapturedOutput::Stream {
+                        name: last_name,
+                        text,
+                    }) = self.captured_outputs.last_mut()
+                    && *last_name == name
+                {
+                    text.push_str(&result.text);
+                    return;
+                }
+
+                CapturedOutput::Stream {
+                    name,
+                    text: result.text.clone(),
+                }
+            }
+            JupyterMessageContent::ErrorOutput(result) => CapturedOutput::Error {
+                ename: result.ename.clone(),
+                evalue: result.evalue.clone(),
+                traceback: result.traceback.clone(),
+            },
+            JupyterMessageContent::ExecuteReply(reply) => {
+                self.execution_count = execution_count_to_usize(reply.execution_count);
+                return;
+            }
+            JupyterMessageContent::ClearOutput(options) => {
+                if options.wait {
+                    self.clear_captured_on_next_output = true;
+                } else {
+                    self.captured_outputs.clear();
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        if std::mem::take(&mut self.clear_captured_on_next_output) {
+            self.captured_outputs.clear();
         }
+        self.captured_outputs.push(captured);
     }
 
     /// Accept a Jupyter message belonging to this execution
@@ -389,6 +482,8 @@ impl ExecutionView {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        self.capture_message(message);
+
         let output: Output = match message {
             JupyterMessageContent::ExecuteResult(result) => Output::new(
                 &result.data,
@@ -483,6 +578,17 @@ impl ExecutionView {
     ) {
         let mut any = false;
 
+        for captured in &mut self.captured_outputs {
+            if let CapturedOutput::DisplayData {
+                data: captured_data,
+                display_id: Some(captured_display_id),
+            } = captured
+                && captured_display_id == display_id
+            {
+                *captured_data = media_to_json(data);
+            }
+        }
+
         self.outputs.iter_mut().for_each(|output| {
             if let Some(other_display_id) = output.display_id().as_ref()
                 && other_display_id == display_id
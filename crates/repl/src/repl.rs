@@ -1,4 +1,5 @@
 pub mod components;
+mod export;
 mod jupyter_settings;
 pub mod kernels;
 pub mod notebook;
@@ -8,6 +9,7 @@ mod repl_sessions_ui;
 mod repl_settings;
 mod repl_store;
 mod session;
+mod variable_explorer;
 
 use std::{sync::Arc, time::Duration};
 
@@ -16,15 +18,18 @@ use gpui::{App, PlatformDispatcher, Priority, RunnableVariant};
 use project::Fs;
 pub use runtimelib::ExecutionState;
 
+pub use crate::export::ExportFormat;
 pub use crate::jupyter_settings::JupyterSettings;
 pub use crate::kernels::{Kernel, KernelSpecification, KernelStatus};
 pub use crate::repl_editor::*;
 pub use crate::repl_sessions_ui::{
-    ClearOutputs, Interrupt, ReplSessionsPage, Restart, Run, Sessions, Shutdown,
+    ClearOutputs, ExportToMarkdown, ExportToNotebook, Interrupt, OpenVariableExplorer,
+    ReplSessionsPage, Restart, Run, Sessions, Shutdown,
 };
 pub use crate::repl_settings::ReplSettings;
 use crate::repl_store::ReplStore;
 pub use crate::session::Session;
+pub use crate::variable_explorer::{KernelVariable, VariableExplorer};
 
 pub const KERNEL_DOCS_URL: &str = "https://zed.dev/docs/repl#changing-kernels";
 
@@ -8,11 +8,15 @@ use editor::{Editor, MultiBufferOffset};
 use gpui::{App, Entity, WeakEntity, Window, prelude::*};
 use language::{BufferSnapshot, Language, LanguageName, Point};
 use project::{ProjectItem as _, WorktreeId};
+use util::ResultExt as _;
+use workspace::{Toast, notifications::NotificationId};
 
+use crate::export::{self, ExportCellKind, ExportFormat};
 use crate::repl_store::ReplStore;
 use crate::session::SessionEvent;
 use crate::{
-    ClearOutputs, Interrupt, JupyterSettings, KernelSpecification, Restart, Session, Shutdown,
+    ClearOutputs, ExportToMarkdown, ExportToNotebook, Interrupt, JupyterSettings,
+    KernelSpecification, OpenVariableExplorer, Restart, Session, Shutdown, VariableExplorer,
 };
 
 pub fn assign_kernelspec(
@@ -288,6 +292,142 @@ pub fn restart(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
     });
 }
 
+pub fn open_variable_explorer(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut App) {
+    let Some(editor) = editor.upgrade() else {
+        return;
+    };
+
+    let Some(session) = ReplStore::global(cx)
+        .read(cx)
+        .get_session(editor.entity_id())
+        .cloned()
+    else {
+        return;
+    };
+
+    let Some(workspace) = editor.read(cx).workspace() else {
+        return;
+    };
+
+    workspace.update(cx, |workspace, cx| {
+        if !session.read(cx).supports_variable_inspection() {
+            struct VariableInspectionUnsupported;
+            let language = session.read(cx).kernel_specification.language();
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<VariableInspectionUnsupported>(),
+                    format!("The variable explorer doesn't support {language} kernels yet."),
+                ),
+                cx,
+            );
+            return;
+        }
+
+        let existing = workspace
+            .active_pane()
+            .read(cx)
+            .items()
+            .filter_map(|item| item.downcast::<VariableExplorer>())
+            .find(|explorer| explorer.read(cx).session().entity_id() == session.entity_id());
+
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+        } else {
+            let explorer = VariableExplorer::new(session, cx);
+            workspace.add_item_to_active_pane(Box::new(explorer), None, true, window, cx);
+        }
+    });
+}
+
+/// Exports the cells of the editor's file, along with the outputs they produced in the
+/// current session, to a file chosen by the user.
+pub fn export_outputs(
+    editor: WeakEntity<Editor>,
+    format: ExportFormat,
+    cx: &mut App,
+) -> Result<()> {
+    let editor = editor.upgrade().context("editor was dropped")?;
+    let buffer = editor
+        .read(cx)
+        .buffer()
+        .read(cx)
+        .as_singleton()
+        .context("cannot export outputs of a multibuffer")?;
+    let snapshot = buffer.read(cx).snapshot();
+    let language = snapshot.language().context("buffer has no language")?;
+
+    let comment_prefixes = language
+        .default_scope()
+        .line_comment_prefixes()
+        .iter()
+        .map(|prefix| prefix.to_string())
+        .collect::<Vec<_>>();
+    let mut cells = export::split_cells(&snapshot.text(), &comment_prefixes);
+
+    let store = ReplStore::global(cx);
+    let session = store.read(cx).get_session(editor.entity_id()).cloned();
+    if let Some(session) = session.as_ref() {
+        let session = session.read(cx);
+        for cell in &mut cells {
+            if cell.kind == ExportCellKind::Code
+                && let Some((outputs, execution_count)) =
+                    session.outputs_for_rows(cell.rows.clone(), cx)
+            {
+                cell.outputs = outputs;
+                cell.execution_count = execution_count;
+            }
+        }
+    }
+
+    let language_name = language.code_fence_block_name();
+    let content = match format {
+        ExportFormat::Notebook => {
+            let kernelspec = session.as_ref().map(|session| {
+                let specification = &session.read(cx).kernel_specification;
+                (
+                    specification.name().to_string(),
+                    specification.kernelspec().display_name.clone(),
+                )
+            });
+            export::to_notebook(
+                &cells,
+                &language_name,
+                kernelspec
+                    .as_ref()
+                    .map(|(name, display_name)| (name.as_str(), display_name.as_str())),
+            )
+        }
+        ExportFormat::Markdown => export::to_markdown(&cells, &language_name),
+    };
+
+    let source_path = buffer
+        .read(cx)
+        .file()
+        .and_then(|file| file.as_local())
+        .map(|file| file.abs_path(cx))
+        .context("buffer is not a local file")?;
+    let directory = source_path
+        .parent()
+        .map(|parent| parent.to_path_buf())
+        .unwrap_or_default();
+    let suggested_name = source_path
+        .file_stem()
+        .map(|stem| format!("{}.{}", stem.to_string_lossy(), format.extension()));
+
+    let fs = store.read(cx).fs().clone();
+    let path = cx.prompt_for_new_path(&directory, suggested_name.as_deref());
+
+    cx.background_spawn(async move {
+        let Some(path) = path.await?? else {
+            return Ok(());
+        };
+        fs.atomic_write(path, content).await
+    })
+    .detach_and_log_err(cx);
+
+    Ok(())
+}
+
 pub fn setup_editor_session_actions(editor: &mut Editor, editor_handle: WeakEntity<Editor>) {
     editor
         .register_action({
@@ -330,7 +470,7 @@ pub fn setup_editor_session_actions(editor: &mut Editor, editor_handle: WeakEnti
 
     editor
         .register_action({
-            let editor_handle = editor_handle;
+            let editor_handle = editor_handle.clone();
             move |_: &Restart, window, cx| {
                 if !JupyterSettings::enabled(cx) {
                     return;
@@ -340,6 +480,45 @@ pub fn setup_editor_session_actions(editor: &mut Editor, editor_handle: WeakEnti
             }
         })
         .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &ExportToNotebook, _, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::export_outputs(editor_handle.clone(), ExportFormat::Notebook, cx).log_err();
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &ExportToMarkdown, _, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::export_outputs(editor_handle.clone(), ExportFormat::Markdown, cx).log_err();
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle;
+            move |_: &OpenVariableExplorer, window, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::open_variable_explorer(editor_handle.clone(), window, cx);
+            }
+        })
+        .detach();
 }
 
 fn cell_range(buffer: &BufferSnapshot, start_row: u32, end_row: u32) -> Range<Point> {
@@ -350,6 +529,15 @@ fn cell_range(buffer: &BufferSnapshot, start_row: u32, end_row: u32) -> Range<Po
     Point::new(start_row, 0)..Point::new(snippet_end_row, buffer.line_len(snippet_end_row))
 }
 
+/// Returns the markers that start a jupytext cell (e.g. `# %%`) for the given line comment
+/// prefixes, which include their trailing whitespace.
+pub(crate) fn jupytext_prefixes(comment_prefixes: &[impl AsRef<str>]) -> Vec<String> {
+    comment_prefixes
+        .iter()
+        .map(|comment_prefix| format!("{}%%", comment_prefix.as_ref()))
+        .collect()
+}
+
 // Returns the ranges of the snippets in the buffer and the next point for moving the cursor to
 fn jupytext_cells(
     buffer: &BufferSnapshot,
@@ -367,10 +555,7 @@ fn jupytext_cells(
         return (Vec::new(), None);
     }
 
-    let jupytext_prefixes = comment_prefixes
-        .iter()
-        .map(|comment_prefix| format!("{comment_prefix}%%"))
-        .collect::<Vec<_>>();
+    let jupytext_prefixes = jupytext_prefixes(comment_prefixes);
 
     let mut snippet_start_row = None;
     loop {
@@ -1,16 +1,17 @@
 use editor::Editor;
 use gpui::{
-    AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, Subscription, actions,
-    prelude::*,
+    AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, Subscription, WeakEntity,
+    actions, prelude::*,
 };
 use project::ProjectItem as _;
-use ui::{ButtonLike, ElevationIndex, KeyBinding, prelude::*};
+use ui::{ButtonLike, ElevationIndex, Indicator, KeyBinding, Tooltip, prelude::*};
 use util::ResultExt as _;
 use workspace::item::ItemEvent;
 use workspace::{Workspace, item::Item};
 
+use crate::components::JupyterTokenModal;
 use crate::jupyter_settings::JupyterSettings;
-use crate::repl_store::ReplStore;
+use crate::repl_store::{JupyterServer, JupyterServerStatus, RemoteKernelAction, ReplStore};
 
 actions!(
     repl,
@@ -29,6 +30,12 @@ actions!(
         Shutdown,
         /// Restarts the current kernel.
         Restart,
+        /// Opens the variable explorer for the current kernel.
+        OpenVariableExplorer,
+        /// Exports the current file's cells and their outputs to a Jupyter notebook.
+        ExportToNotebook,
+        /// Exports the current file's cells and their outputs to a Markdown document.
+        ExportToMarkdown,
         /// Refreshes the list of available kernelspecs.
         RefreshKernelspecs
     ]
@@ -145,11 +152,13 @@ pub fn init(cx: &mut App) {
 
 pub struct ReplSessionsPage {
     focus_handle: FocusHandle,
+    workspace: WeakEntity<Workspace>,
     _subscriptions: Vec<Subscription>,
 }
 
 impl ReplSessionsPage {
     pub fn new(window: &mut Window, cx: &mut Context<Workspace>) -> Entity<Self> {
+        let workspace = cx.weak_entity();
         cx.new(|cx| {
             let focus_handle = cx.focus_handle();
 
@@ -162,10 +171,152 @@ impl ReplSessionsPage {
 
             Self {
                 focus_handle,
+                workspace,
                 _subscriptions: subscriptions,
             }
         })
     }
+
+    fn render_jupyter_server(
+        &self,
+        ix: usize,
+        server: &JupyterServer,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let (indicator_color, status_text): (Color, SharedString) = match &server.status {
+            JupyterServerStatus::Connecting => (Color::Modified, "Connecting".into()),
+            JupyterServerStatus::Connected => (Color::Success, "Connected".into()),
+            JupyterServerStatus::MissingToken => (Color::Warning, "No token".into()),
+            JupyterServerStatus::Error(error) => (Color::Error, error.clone()),
+        };
+
+        let set_token_button = (!server.token_from_env()).then(|| {
+            let workspace = self.workspace.clone();
+            let server_name = server.name.clone();
+            let server_url = server.url.clone();
+            Button::new(("set-jupyter-token", ix), "Set Token")
+                .style(ButtonStyle::Subtle)
+                .on_click(move |_, window, cx| {
+                    let server_name = server_name.clone();
+                    let server_url = server_url.clone();
+                    workspace
+                        .update(cx, |workspace, cx| {
+                            workspace.toggle_modal(window, cx, |window, cx| {
+                                JupyterTokenModal::new(server_name, server_url, window, cx)
+                            });
+                        })
+                        .ok();
+                })
+        });
+
+        let running_kernels = server
+            .running_kernels
+            .iter()
+            .enumerate()
+            .map(|(kernel_ix, kernel)| {
+                let action_button = |id: &'static str, label: &'static str, action| {
+                    let server_url = server.url.clone();
+                    let kernel_id = kernel.id.clone();
+                    Button::new((id, ix * 1000 + kernel_ix), label)
+                        .style(ButtonStyle::Subtle)
+                        .on_click(move |_, _, cx| {
+                            let kernel_id = kernel_id.clone();
+                            ReplStore::global(cx).update(cx, |store, cx| {
+                                store
+                                    .manage_remote_kernel(&server_url, kernel_id, action, cx)
+                                    .detach_and_log_err(cx);
+                            });
+                        })
+                };
+
+                h_flex()
+                    .pl_6()
+                    .gap_2()
+                    .child(Label::new(kernel.name.clone()))
+                    .child(
+                        Label::new(format!(
+                            "{} ({}, {} connections)",
+                            kernel.id, kernel.execution_state, kernel.connections
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .child(div().flex_1())
+                    .child(action_button(
+                        "interrupt-remote-kernel",
+                        "Interrupt",
+                        RemoteKernelAction::Interrupt,
+                    ))
+                    .child(action_button(
+                        "restart-remote-kernel",
+                        "Restart",
+                        RemoteKernelAction::Restart,
+                    ))
+                    .child(action_button(
+                        "shutdown-remote-kernel",
+                        "Shut Down",
+                        RemoteKernelAction::Shutdown,
+                    ))
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .w_full()
+            .gap_1()
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(Indicator::dot().color(indicator_color))
+                    .child(Label::new(server.name.clone()))
+                    .child(
+                        Label::new(server.url.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        div().flex_1().min_w_0().child(
+                            Label::new(status_text.clone())
+                                .size(LabelSize::Small)
+                                .color(indicator_color)
+                                .truncate(),
+                        ),
+                    )
+                    .children(set_token_button)
+                    .child(
+                        IconButton::new(("refresh-jupyter-server", ix), IconName::RotateCw)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Reconnect"))
+                            .on_click(|_, _, cx| {
+                                ReplStore::global(cx).update(cx, |store, cx| {
+                                    store.refresh_kernelspecs(cx).detach_and_log_err(cx);
+                                });
+                            }),
+                    ),
+            )
+            .children(running_kernels)
+    }
+
+    fn render_jupyter_servers(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let servers = ReplStore::global(cx).read(cx).jupyter_servers().to_vec();
+        if servers.is_empty() {
+            return None;
+        }
+
+        Some(
+            v_flex()
+                .pt_4()
+                .gap_2()
+                .child(Label::new("Jupyter Servers").size(LabelSize::Large))
+                .children(
+                    servers
+                        .iter()
+                        .enumerate()
+                        .map(|(ix, server)| self.render_jupyter_server(ix, server, cx)),
+                )
+                .into_any_element(),
+        )
+    }
 }
 
 impl EventEmitter<ItemEvent> for ReplSessionsPage {}
@@ -231,21 +382,26 @@ impl Render for ReplSessionsPage {
                                 )
                             }),
                     ),
-                );
+                )
+                .children(self.render_jupyter_servers(cx));
         }
 
         // When there are no sessions, show the command to run code in an editor
         if sessions.is_empty() {
             let instructions = "To run code in a Jupyter kernel, select some code and use the 'repl::Run' command.";
 
-            return ReplSessionsContainer::new("No Jupyter Kernel Sessions").child(
-                v_flex()
-                    .child(Label::new(instructions))
-                    .child(KeyBinding::for_action(&Run, cx)),
-            );
+            return ReplSessionsContainer::new("No Jupyter Kernel Sessions")
+                .child(
+                    v_flex()
+                        .child(Label::new(instructions))
+                        .child(KeyBinding::for_action(&Run, cx)),
+                )
+                .children(self.render_jupyter_servers(cx));
         }
 
-        ReplSessionsContainer::new("Jupyter Kernel Sessions").children(sessions)
+        ReplSessionsContainer::new("Jupyter Kernel Sessions")
+            .children(sessions)
+            .children(self.render_jupyter_servers(cx))
     }
 }
 
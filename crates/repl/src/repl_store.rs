@@ -3,19 +3,62 @@ use std::sync::Arc;
 use anyhow::{Context as _, Result};
 use collections::HashMap;
 use command_palette_hooks::CommandPaletteFilter;
-use gpui::{App, Context, Entity, EntityId, Global, Subscription, Task, prelude::*};
+use credentials_provider::CredentialsProvider;
+use gpui::{App, Context, Entity, EntityId, Global, SharedString, Subscription, Task, prelude::*};
 use jupyter_websocket_client::RemoteServer;
 use language::Language;
 use project::{Fs, Project, WorktreeId};
-use settings::{Settings, SettingsStore};
+use settings::{JupyterServerContent, Settings, SettingsStore};
+use util::ResultExt as _;
 
 use crate::kernels::{
-    list_remote_kernelspecs, local_kernel_specifications, python_env_kernel_specifications,
+    RunningRemoteKernel, interrupt_remote_kernel, list_remote_kernels, local_kernel_specifications,
+    python_env_kernel_specifications, restart_remote_kernel, shutdown_remote_kernel,
 };
 use crate::{JupyterSettings, KernelSpecification, Session};
 
 struct GlobalReplStore(Entity<ReplStore>);
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JupyterServerStatus {
+    Connecting,
+    Connected,
+    MissingToken,
+    Error(SharedString),
+}
+
+/// A Jupyter server configured in the `jupyter.servers` setting, or through the
+/// `JUPYTER_SERVER` and `JUPYTER_TOKEN` environment variables.
+#[derive(Debug, Clone)]
+pub struct JupyterServer {
+    pub name: SharedString,
+    pub url: String,
+    pub status: JupyterServerStatus,
+    pub running_kernels: Vec<RunningRemoteKernel>,
+    token: Option<String>,
+    token_from_env: bool,
+}
+
+impl JupyterServer {
+    pub fn token_from_env(&self) -> bool {
+        self.token_from_env
+    }
+
+    fn remote_server(&self) -> Option<RemoteServer> {
+        Some(RemoteServer {
+            base_url: self.url.clone(),
+            token: self.token.clone()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteKernelAction {
+    Interrupt,
+    Restart,
+    Shutdown,
+}
+
 impl Global for GlobalReplStore {}
 
 pub struct ReplStore {
@@ -25,6 +68,8 @@ pub struct ReplStore {
     kernel_specifications: Vec<KernelSpecification>,
     selected_kernel_for_worktree: HashMap<WorktreeId, KernelSpecification>,
     kernel_specifications_for_worktree: HashMap<WorktreeId, Vec<KernelSpecification>>,
+    configured_servers: Vec<JupyterServerContent>,
+    jupyter_servers: Vec<JupyterServer>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -48,6 +93,12 @@ impl ReplStore {
     pub fn new(fs: Arc<dyn Fs>, cx: &mut Context<Self>) -> Self {
         let subscriptions = vec![cx.observe_global::<SettingsStore>(move |this, cx| {
             this.set_enabled(JupyterSettings::enabled(cx), cx);
+
+            let servers = &JupyterSettings::get_global(cx).servers;
+            if *servers != this.configured_servers {
+                this.configured_servers = servers.clone();
+                this.refresh_kernelspecs(cx).detach_and_log_err(cx);
+            }
         })];
 
         let this = Self {
@@ -58,6 +109,8 @@ impl ReplStore {
             _subscriptions: subscriptions,
             kernel_specifications_for_worktree: HashMap::default(),
             selected_kernel_for_worktree: HashMap::default(),
+            configured_servers: JupyterSettings::get_global(cx).servers.clone(),
+            jupyter_servers: Vec::new(),
         };
         this.on_enabled_changed(cx);
         this
@@ -90,6 +143,10 @@ impl ReplStore {
         self.sessions.values()
     }
 
+    pub fn jupyter_servers(&self) -> &[JupyterServer] {
+        &self.jupyter_servers
+    }
+
     fn set_enabled(&mut self, enabled: bool, cx: &mut Context<Self>) {
         if self.enabled == enabled {
             return;
@@ -135,34 +192,105 @@ impl ReplStore {
         })
     }
 
-    fn get_remote_kernel_specifications(
-        &self,
-        cx: &mut Context<Self>,
-    ) -> Option<Task<Result<Vec<KernelSpecification>>>> {
-        match (
+    fn configured_jupyter_servers(&self) -> Vec<JupyterServer> {
+        let mut servers = self
+            .configured_servers
+            .iter()
+            .map(|server| JupyterServer {
+                name: server.name.clone().into(),
+                url: server.url.trim_end_matches('/').to_string(),
+                status: JupyterServerStatus::Connecting,
+                running_kernels: Vec::new(),
+                token: None,
+                token_from_env: false,
+            })
+            .collect::<Vec<_>>();
+
+        if let (Ok(url), Ok(token)) = (
             std::env::var("JUPYTER_SERVER"),
             std::env::var("JUPYTER_TOKEN"),
         ) {
-            (Ok(server), Ok(token)) => {
-                let remote_server = RemoteServer {
-                    base_url: server,
-                    token,
-                };
-                let http_client = cx.http_client();
-                Some(cx.spawn(async move |_, _| {
-                    list_remote_kernelspecs(remote_server, http_client)
-                        .await
-                        .map(|specs| specs.into_iter().map(KernelSpecification::Remote).collect())
-                }))
+            let url = url.trim_end_matches('/').to_string();
+            if let Some(server) = servers.iter_mut().find(|server| server.url == url) {
+                server.token = Some(token);
+                server.token_from_env = true;
+            } else {
+                servers.push(JupyterServer {
+                    name: url.clone().into(),
+                    url,
+                    status: JupyterServerStatus::Connecting,
+                    running_kernels: Vec::new(),
+                    token: Some(token),
+                    token_from_env: true,
+                });
             }
-            _ => None,
         }
+
+        servers
+    }
+
+    fn refresh_jupyter_servers(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<KernelSpecification>> {
+        self.jupyter_servers = self.configured_jupyter_servers();
+        let servers = self.jupyter_servers.clone();
+        let http_client = cx.http_client();
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+
+        cx.spawn(async move |this, cx| {
+            let mut kernel_specifications = Vec::new();
+
+            for mut server in servers {
+                if server.token.is_none() {
+                    server.token = credentials_provider
+                        .read_credentials(&server.url, cx)
+                        .await
+                        .log_err()
+                        .flatten()
+                        .and_then(|(_, token)| String::from_utf8(token).ok());
+                }
+
+                match server.remote_server() {
+                    Some(remote_server) => {
+                        match list_remote_kernels(remote_server, http_client.clone()).await {
+                            Ok((specs, running_kernels)) => {
+                                kernel_specifications
+                                    .extend(specs.into_iter().map(KernelSpecification::Remote));
+                                server.running_kernels = running_kernels;
+                                server.status = JupyterServerStatus::Connected;
+                            }
+                            Err(error) => {
+                                log::error!("failed to connect to {}: {error:#}", server.url);
+                                server.status =
+                                    JupyterServerStatus::Error(format!("{error:#}").into());
+                            }
+                        }
+                    }
+                    None => server.status = JupyterServerStatus::MissingToken,
+                }
+
+                this.update(cx, |this, cx| {
+                    if let Some(existing) = this
+                        .jupyter_servers
+                        .iter_mut()
+                        .find(|existing| existing.url == server.url)
+                    {
+                        *existing = server;
+                    }
+                    cx.notify();
+                })
+                .ok();
+            }
+
+            kernel_specifications
+        })
     }
 
     pub fn refresh_kernelspecs(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
         let local_kernel_specifications = local_kernel_specifications(self.fs.clone());
 
-        let remote_kernel_specifications = self.get_remote_kernel_specifications(cx);
+        let remote_kernel_specifications = self.refresh_jupyter_servers(cx);
 
         let all_specs = cx.background_spawn(async move {
             let mut all_specs = local_kernel_specifications
@@ -171,11 +299,7 @@ impl ReplStore {
                 .map(KernelSpecification::Jupyter)
                 .collect::<Vec<_>>();
 
-            if let Some(remote_task) = remote_kernel_specifications
-                && let Ok(remote_specs) = remote_task.await
-            {
-                all_specs.extend(remote_specs);
-            }
+            all_specs.extend(remote_kernel_specifications.await);
 
             anyhow::Ok(all_specs)
         });
@@ -195,6 +319,69 @@ impl ReplStore {
         })
     }
 
+    /// Stores the token for a Jupyter server in the system keychain, or removes it when `token`
+    /// is `None`, then reconnects to the configured servers.
+    pub fn set_jupyter_server_token(
+        &mut self,
+        url: String,
+        token: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        cx.spawn(async move |this, cx| {
+            match token {
+                Some(token) => {
+                    credentials_provider
+                        .write_credentials(&url, "token", token.as_bytes(), cx)
+                        .await?
+                }
+                None => credentials_provider.delete_credentials(&url, cx).await?,
+            }
+
+            this.update(cx, |this, cx| this.refresh_kernelspecs(cx))?
+                .await
+        })
+    }
+
+    /// Interrupts, restarts or shuts down a kernel running on a Jupyter server, whether or not
+    /// it is attached to a session in Zed.
+    pub fn manage_remote_kernel(
+        &mut self,
+        server_url: &str,
+        kernel_id: String,
+        action: RemoteKernelAction,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(remote_server) = self
+            .jupyter_servers
+            .iter()
+            .find(|server| server.url == server_url)
+            .and_then(|server| server.remote_server())
+        else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "not connected to Jupyter server {server_url}"
+            )));
+        };
+        let http_client = cx.http_client();
+
+        cx.spawn(async move |this, cx| {
+            match action {
+                RemoteKernelAction::Interrupt => {
+                    interrupt_remote_kernel(&remote_server, http_client, &kernel_id).await?
+                }
+                RemoteKernelAction::Restart => {
+                    restart_remote_kernel(&remote_server, http_client, &kernel_id).await?
+                }
+                RemoteKernelAction::Shutdown => {
+                    shutdown_remote_kernel(&remote_server, http_client, &kernel_id).await?
+                }
+            }
+
+            this.update(cx, |this, cx| this.refresh_kernelspecs(cx))?
+                .await
+        })
+    }
+
     pub fn set_active_kernelspec(
         &mut self,
         worktree_id: WorktreeId,
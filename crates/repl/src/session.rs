@@ -1,6 +1,8 @@
 use crate::components::KernelListItem;
+use crate::export::CapturedOutput;
 use crate::kernels::RemoteRunningKernel;
 use crate::setup_editor_session_actions;
+use crate::variable_explorer::{self, KernelVariable};
 use crate::{
     KernelStatus,
     kernels::{Kernel, KernelSpecification, NativeRunningKernel},
@@ -25,7 +27,7 @@ use language::Point;
 use project::Fs;
 use runtimelib::{
     ExecuteRequest, ExecutionState, InterruptRequest, JupyterMessage, JupyterMessageContent,
-    ShutdownRequest,
+    ShutdownRequest, Stdio,
 };
 use std::{env::temp_dir, ops::Range, sync::Arc, time::Duration};
 use theme::ActiveTheme;
@@ -38,9 +40,19 @@ pub struct Session {
     pub kernel: Kernel,
     blocks: HashMap<String, EditorBlock>,
     pub kernel_specification: KernelSpecification,
+    variables: Vec<KernelVariable>,
+    variables_error: Option<SharedString>,
+    pending_variables_request: Option<PendingVariablesRequest>,
+    watching_variables: bool,
     _buffer_subscription: Subscription,
 }
 
+/// An in-flight execution of the variable introspection snippet.
+struct PendingVariablesRequest {
+    msg_id: String,
+    stdout: String,
+}
+
 struct EditorBlock {
     code_range: Range<Anchor>,
     invalidation_anchor: Anchor,
@@ -221,6 +233,10 @@ impl Session {
             kernel: Kernel::StartingKernel(Task::ready(()).shared()),
             blocks: HashMap::default(),
             kernel_specification,
+            variables: Vec::new(),
+            variables_error: None,
+            pending_variables_request: None,
+            watching_variables: false,
             _buffer_subscription: subscription,
         };
 
@@ -350,6 +366,142 @@ impl Session {
         anyhow::Ok(())
     }
 
+    pub fn variables(&self) -> &[KernelVariable] {
+        &self.variables
+    }
+
+    pub fn variables_error(&self) -> Option<SharedString> {
+        self.variables_error.clone()
+    }
+
+    pub fn is_refreshing_variables(&self) -> bool {
+        self.pending_variables_request.is_some()
+    }
+
+    /// Whether the variables of this session's kernel can be inspected.
+    pub fn supports_variable_inspection(&self) -> bool {
+        variable_explorer::introspection_code(&self.kernel_specification.language()).is_some()
+    }
+
+    /// Refreshes the variable list now and after every subsequent execution, until the kernel
+    /// restarts or shuts down.
+    pub fn watch_variables(&mut self, cx: &mut Context<Self>) {
+        if self.watching_variables
+            || !self.supports_variable_inspection()
+            || !matches!(self.kernel, Kernel::RunningKernel(_))
+        {
+            return;
+        }
+        self.watching_variables = true;
+        self.refresh_variables(cx);
+    }
+
+    /// Stops refreshing the variable list after each execution, once nothing shows it.
+    pub fn stop_watching_variables(&mut self) {
+        self.watching_variables = false;
+    }
+
+    pub fn is_watching_variables(&self) -> bool {
+        self.watching_variables
+    }
+
+    /// Forgets the variables of the previous kernel, which can no longer answer requests.
+    fn reset_variables(&mut self) {
+        self.variables.clear();
+        self.variables_error = None;
+        self.pending_variables_request = None;
+        self.watching_variables = false;
+    }
+
+    pub fn refresh_variables(&mut self, cx: &mut Context<Self>) {
+        if self.pending_variables_request.is_some() {
+            return;
+        }
+
+        let Some(code) =
+            variable_explorer::introspection_code(&self.kernel_specification.language())
+        else {
+            return;
+        };
+
+        if !matches!(self.kernel, Kernel::RunningKernel(_)) {
+            return;
+        }
+
+        let message: JupyterMessage = ExecuteRequest {
+            code,
+            store_history: false,
+            ..ExecuteRequest::default()
+        }
+        .into();
+
+        self.pending_variables_request = Some(PendingVariablesRequest {
+            msg_id: message.header.msg_id.clone(),
+            stdout: String::new(),
+        });
+        self.send(message, cx).ok();
+        cx.notify();
+    }
+
+    fn handle_variables_message(&mut self, message: &JupyterMessage, cx: &mut Context<Self>) {
+        let Some(request) = self.pending_variables_request.as_mut() else {
+            return;
+        };
+
+        match &message.content {
+            JupyterMessageContent::StreamContent(stream)
+                if matches!(stream.name, Stdio::Stdout) =>
+            {
+                request.stdout.push_str(&stream.text);
+            }
+            JupyterMessageContent::ErrorOutput(error) => {
+                self.variables_error = Some(format!("{}: {}", error.ename, error.evalue).into());
+            }
+            JupyterMessageContent::Status(status)
+                if status.execution_state == ExecutionState::Idle =>
+            {
+                let request = self.pending_variables_request.take();
+                if let Some(request) = request {
+                    match variable_explorer::parse_variables(&request.stdout) {
+                        Ok(variables) => {
+                            self.variables = variables;
+                            self.variables_error = None;
+                        }
+                        Err(error) => {
+                            if self.variables_error.is_none() {
+                                self.variables_error = Some(error.to_string().into());
+                            }
+                        }
+                    }
+                }
+                cx.notify();
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the captured outputs and execution count of the execution whose code ends within
+    /// the given buffer rows.
+    pub(crate) fn outputs_for_rows(
+        &self,
+        rows: Range<u32>,
+        cx: &App,
+    ) -> Option<(Vec<CapturedOutput>, Option<usize>)> {
+        let editor = self.editor.upgrade()?;
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+
+        self.blocks
+            .values()
+            .find(|block| rows.contains(&block.code_range.end.to_point(&snapshot).row))
+            .map(|block| {
+                let execution_view = block.execution_view.read(cx);
+                (
+                    execution_view.captured_outputs().to_vec(),
+                    execution_view.execution_count(),
+                )
+            })
+    }
+
     pub fn clear_outputs(&mut self, cx: &mut Context<Self>) {
         let blocks_to_remove: HashSet<CustomBlockId> =
             self.blocks.values().map(|block| block.block_id).collect();
@@ -527,8 +679,24 @@ impl Session {
             _ => {}
         }
 
+        if self
+            .pending_variables_request
+            .as_ref()
+            .is_some_and(|request| &request.msg_id == parent_message_id)
+        {
+            self.handle_variables_message(message, cx);
+            return;
+        }
+
         if let Some(block) = self.blocks.get_mut(parent_message_id) {
             block.handle_message(message, window, cx);
+
+            if self.watching_variables
+                && let JupyterMessageContent::Status(status) = &message.content
+                && status.execution_state == ExecutionState::Idle
+            {
+                self.refresh_variables(cx);
+            }
         }
     }
 
@@ -559,21 +727,28 @@ impl Session {
             repl_session_id = cx.entity_id().to_string(),
         );
 
+        self.reset_variables();
         self.kernel = kernel;
+        cx.notify();
     }
 
     pub fn shutdown(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.reset_variables();
         let kernel = std::mem::replace(&mut self.kernel, Kernel::ShuttingDown);
 
         match kernel {
             Kernel::RunningKernel(mut kernel) => {
                 let mut request_tx = kernel.request_tx();
+                let attached = kernel.is_attached();
 
                 let forced = kernel.force_shutdown(window, cx);
 
                 cx.spawn(async move |this, cx| {
-                    let message: JupyterMessage = ShutdownRequest { restart: false }.into();
-                    request_tx.try_send(message).ok();
+                    // An attached kernel keeps running for its other clients.
+                    if !attached {
+                        let message: JupyterMessage = ShutdownRequest { restart: false }.into();
+                        request_tx.try_send(message).ok();
+                    }
 
                     forced.await.log_err();
 
@@ -597,12 +772,32 @@ impl Session {
     }
 
     pub fn restart(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.reset_variables();
         let kernel = std::mem::replace(&mut self.kernel, Kernel::Restarting);
 
         match kernel {
             Kernel::Restarting => {
                 // Do nothing if already restarting
             }
+            Kernel::RunningKernel(mut kernel) if kernel.is_attached() => {
+                // Restart the attached kernel on its server instead of shutting it down, as
+                // other clients depend on it.
+                let Some(restarted) = kernel.restart_attached(window, cx) else {
+                    return;
+                };
+                cx.spawn_in(window, async move |this, cx| {
+                    let result = restarted.await;
+                    this.update(cx, |session, cx| {
+                        session.clear_outputs(cx);
+                        match result {
+                            Ok(()) => session.kernel(Kernel::RunningKernel(kernel), cx),
+                            Err(error) => session.kernel_errored(format!("{error:#}"), cx),
+                        }
+                    })
+                    .ok();
+                })
+                .detach();
+            }
             Kernel::RunningKernel(mut kernel) => {
                 let mut request_tx = kernel.request_tx();
 
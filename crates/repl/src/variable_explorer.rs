@@ -0,0 +1,466 @@
+//! # Variable Explorer
+//!
+//! Lists the variables defined in a running kernel, along with their types,
+//! shapes and a short preview of their values.
+//!
+//! Kernel state is gathered by running a small, language-specific
+//! introspection snippet with `store_history` disabled. The snippet prints a
+//! single line to stdout, prefixed with [`VARIABLES_MARKER`], containing a
+//! JSON array of [`KernelVariable`]s. Tabular values (pandas DataFrames and
+//! numpy arrays) additionally carry a [Tabular Data Resource] so they can be
+//! opened in the same table renderer used for cell outputs.
+//!
+//! [Tabular Data Resource]: https://specs.frictionlessdata.io/tabular-data-resource/
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use gpui::{
+    App, Entity, EventEmitter, FocusHandle, Focusable, Subscription, WeakEntity, prelude::*,
+};
+use runtimelib::media::datatable::TabularDataResource;
+use serde::Deserialize;
+use ui::{Tooltip, prelude::*};
+use workspace::item::{Item, ItemEvent};
+
+use crate::Session;
+use crate::outputs::table::TableView;
+
+/// Prefix of the stdout line emitted by the introspection snippet.
+pub(crate) const VARIABLES_MARKER: &str = "__zed_repl_variables__";
+
+/// Number of rows sent back by the kernel for tabular previews.
+const TABLE_PREVIEW_ROWS: usize = 100;
+
+const PYTHON_INTROSPECTION: &str = r#"
+def __zed_list_variables(max_rows):
+    import json as _json
+    import types as _types
+
+    hidden = {"In", "Out", "exit", "quit", "get_ipython", "open"}
+    result = []
+    for name, value in list(get_ipython().user_ns.items()):
+        if name.startswith("_") or name in hidden:
+            continue
+        if isinstance(value, (_types.ModuleType, _types.FunctionType, _types.BuiltinFunctionType, type)):
+            continue
+        entry = {"name": name, "type": type(value).__name__}
+        shape = getattr(value, "shape", None)
+        if isinstance(shape, tuple):
+            entry["shape"] = " x ".join(str(dim) for dim in shape)
+        elif hasattr(value, "__len__") and not isinstance(value, str):
+            try:
+                entry["shape"] = str(len(value))
+            except Exception:
+                pass
+        try:
+            preview = repr(value)
+        except Exception as error:
+            preview = "<repr failed: %s>" % error
+        entry["preview"] = " ".join(preview.split())[:200]
+        try:
+            module = type(value).__module__
+            if module.startswith("pandas") and hasattr(value, "to_json"):
+                frame = value if hasattr(value, "columns") else value.to_frame()
+                entry["table"] = _json.loads(frame.head(max_rows).to_json(orient="table"))
+            elif module == "numpy" and getattr(value, "ndim", 0) in (1, 2):
+                import pandas as _pd
+                frame = _pd.DataFrame(value[:max_rows])
+                frame.columns = [str(column) for column in frame.columns]
+                entry["table"] = _json.loads(frame.to_json(orient="table"))
+        except Exception:
+            pass
+        result.append(entry)
+    print("__zed_repl_variables__" + _json.dumps(result, default=str))
+
+__zed_list_variables(__ZED_MAX_ROWS__)
+del __zed_list_variables
+"#;
+
+/// Returns the code to run in a kernel of the given language to list its
+/// variables, if the language is supported.
+pub(crate) fn introspection_code(kernel_language: &str) -> Option<String> {
+    match kernel_language.to_lowercase().as_str() {
+        "python" => {
+            Some(PYTHON_INTROSPECTION.replace("__ZED_MAX_ROWS__", &TABLE_PREVIEW_ROWS.to_string()))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KernelVariable {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    #[serde(default)]
+    pub shape: Option<String>,
+    #[serde(default)]
+    pub preview: String,
+    #[serde(default)]
+    pub table: Option<TabularDataResource>,
+}
+
+/// Extracts the variables from the stdout of the introspection snippet.
+pub(crate) fn parse_variables(stdout: &str) -> Result<Vec<KernelVariable>> {
+    let payload = stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(VARIABLES_MARKER))
+        .context("kernel did not report any variables")?;
+    let mut variables: Vec<KernelVariable> =
+        serde_json::from_str(payload).context("parsing kernel variables")?;
+    variables.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(variables)
+}
+
+pub struct VariableExplorer {
+    session: WeakEntity<Session>,
+    focus_handle: FocusHandle,
+    open_tables: HashMap<String, Entity<TableView>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl VariableExplorer {
+    pub fn new(session: Entity<Session>, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| {
+            session.update(cx, |session, cx| session.watch_variables(cx));
+
+            let subscriptions = vec![
+                // Watching stops when the kernel restarts, so resume it once the new kernel runs.
+                cx.observe(&session, |_, session, cx| {
+                    session.update(cx, |session, cx| session.watch_variables(cx));
+                    cx.notify();
+                }),
+                // Without an explorer, nothing shows the variables, so stop refreshing them.
+                cx.on_release(|this, cx| {
+                    this.session
+                        .update(cx, |session, _| session.stop_watching_variables())
+                        .ok();
+                }),
+            ];
+
+            Self {
+                session: session.downgrade(),
+                focus_handle: cx.focus_handle(),
+                open_tables: HashMap::default(),
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    pub fn session(&self) -> &WeakEntity<Session> {
+        &self.session
+    }
+
+    fn toggle_table(
+        &mut self,
+        variable: &KernelVariable,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.open_tables.remove(&variable.name).is_some() {
+            cx.notify();
+            return;
+        }
+
+        if let Some(table) = variable.table.as_ref() {
+            let view = cx.new(|cx| TableView::new(table, window, cx));
+            self.open_tables.insert(variable.name.clone(), view);
+            cx.notify();
+        }
+    }
+
+    fn render_variable(
+        &self,
+        ix: usize,
+        variable: &KernelVariable,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let open_table = self.open_tables.get(&variable.name).cloned();
+        let is_open = open_table.is_some();
+
+        v_flex()
+            .w_full()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .w_full()
+                    .py_1()
+                    .gap_2()
+                    .child(
+                        div()
+                            .w(rems(10.))
+                            .flex_none()
+                            .child(Label::new(variable.name.clone()).buffer_font(cx)),
+                    )
+                    .child(
+                        div().w(rems(8.)).flex_none().child(
+                            Label::new(variable.type_name.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                    .child(
+                        div().w(rems(6.)).flex_none().child(
+                            Label::new(variable.shape.clone().unwrap_or_default())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                    .child(
+                        div().flex_1().min_w_0().child(
+                            Label::new(variable.preview.clone())
+                                .size(LabelSize::Small)
+                                .buffer_font(cx)
+                                .truncate(),
+                        ),
+                    )
+                    .when(variable.table.is_some(), |row| {
+                        let variable = variable.clone();
+                        row.child(
+                            IconButton::new(("open-table", ix), IconName::FileTextOutlined)
+                                .icon_size(IconSize::Small)
+                                .toggle_state(is_open)
+                                .tooltip(Tooltip::text(if is_open {
+                                    "Close Table"
+                                } else {
+                                    "Open as Table"
+                                }))
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.toggle_table(&variable, window, cx);
+                                })),
+                        )
+                    }),
+            )
+            .children(open_table.map(|table| div().w_full().pb_2().child(table)))
+    }
+}
+
+impl EventEmitter<ItemEvent> for VariableExplorer {}
+
+impl Focusable for VariableExplorer {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for VariableExplorer {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Variables".into()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("REPL Variable Explorer Opened")
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(workspace::item::ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for VariableExplorer {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(session) = self.session.upgrade() else {
+            return v_flex()
+                .p_4()
+                .size_full()
+                .child(Label::new("The REPL session has ended.").color(Color::Muted))
+                .into_any_element();
+        };
+
+        let session = session.read(cx);
+        let kernel_name = session.kernel_specification.name();
+        let supported = session.supports_variable_inspection();
+        let variables = session.variables().to_vec();
+        let error = session.variables_error();
+        let is_refreshing = session.is_refreshing_variables();
+
+        let weak_session = self.session.clone();
+
+        v_flex()
+            .id("repl-variable-explorer")
+            .track_focus(&self.focus_handle)
+            .p_4()
+            .gap_2()
+            .size_full()
+            .overflow_y_scroll()
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .child(Label::new(format!("Variables: {kernel_name}")).size(LabelSize::Large))
+                    .child(
+                        Button::new("refresh-variables", "Refresh")
+                            .style(ButtonStyle::Subtle)
+                            .disabled(!supported || is_refreshing)
+                            .on_click(move |_, _, cx| {
+                                weak_session
+                                    .update(cx, |session, cx| session.refresh_variables(cx))
+                                    .ok();
+                            }),
+                    ),
+            )
+            .when(!supported, |this| {
+                this.child(
+                    Label::new("Variable inspection is not supported for this kernel's language.")
+                        .color(Color::Muted),
+                )
+            })
+            .children(error.map(|error| Label::new(error).color(Color::Error)))
+            .when(supported && variables.is_empty(), |this| {
+                this.child(Label::new("No variables defined.").color(Color::Muted))
+            })
+            .children(
+                variables
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, variable)| self.render_variable(ix, variable, cx)),
+            )
+            .into_any_element()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernels::{Kernel, KernelSpecification, RemoteKernelSpecification, RunningKernel};
+    use futures::channel::mpsc;
+    use gpui::{Task, TestAppContext, VisualTestContext, Window};
+    use project::FakeFs;
+    use runtimelib::{ExecutionState, JupyterMessage, KernelInfoReply};
+    use settings::SettingsStore;
+    use std::path::PathBuf;
+
+    #[derive(Debug)]
+    struct FakeKernel {
+        request_tx: mpsc::Sender<JupyterMessage>,
+        working_directory: PathBuf,
+        execution_state: ExecutionState,
+    }
+
+    impl RunningKernel for FakeKernel {
+        fn request_tx(&self) -> mpsc::Sender<JupyterMessage> {
+            self.request_tx.clone()
+        }
+
+        fn working_directory(&self) -> &PathBuf {
+            &self.working_directory
+        }
+
+        fn execution_state(&self) -> &ExecutionState {
+            &self.execution_state
+        }
+
+        fn set_execution_state(&mut self, state: ExecutionState) {
+            self.execution_state = state;
+        }
+
+        fn kernel_info(&self) -> Option<&KernelInfoReply> {
+            None
+        }
+
+        fn set_kernel_info(&mut self, _: KernelInfoReply) {}
+
+        fn force_shutdown(&mut self, _: &mut Window, _: &mut App) -> Task<anyhow::Result<()>> {
+            Task::ready(Ok(()))
+        }
+    }
+
+    #[gpui::test]
+    async fn test_variables_are_watched_while_the_explorer_is_open(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        let kernel_specification = KernelSpecification::Remote(RemoteKernelSpecification {
+            name: "python3".to_string(),
+            url: "http://localhost:8888".to_string(),
+            token: String::new(),
+            kernelspec: serde_json::from_value(serde_json::json!({
+                "argv": [],
+                "display_name": "Python 3",
+                "language": "python",
+            }))
+            .unwrap(),
+            kernel_id: None,
+        });
+        let session = cx.add_window(|window, cx| {
+            Session::new(
+                WeakEntity::new_invalid(),
+                fs,
+                kernel_specification,
+                window,
+                cx,
+            )
+        });
+        let window = session;
+        let session = window.root(cx).unwrap();
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        // The test server can't launch kernels, so replace the failed launch with a fake kernel.
+        cx.run_until_parked();
+        let (request_tx, _request_rx) = mpsc::channel(16);
+        session.update(cx, |session, cx| {
+            session.kernel(
+                Kernel::RunningKernel(Box::new(FakeKernel {
+                    request_tx,
+                    working_directory: PathBuf::new(),
+                    execution_state: ExecutionState::Idle,
+                })),
+                cx,
+            )
+        });
+
+        let explorer = cx.update(|_, cx| VariableExplorer::new(session.clone(), cx));
+        assert!(session.read_with(cx, |session, _| session.is_watching_variables()));
+
+        drop(explorer);
+        cx.run_until_parked();
+        assert!(!session.read_with(cx, |session, _| session.is_watching_variables()));
+    }
+
+    #[test]
+    fn test_parse_variables() {
+        let stdout = concat!(
+            "some unrelated output\n",
+            "__zed_repl_variables__[",
+            r#"{"name": "x", "type": "int", "preview": "42"}, "#,
+            r#"{"name": "df", "type": "DataFrame", "shape": "3 x 2", "preview": "a b", "#,
+            r#""table": {"schema": {"fields": [{"name": "a", "type": "integer"}]}, "data": [{"a": 1}]}}"#,
+            "]\n",
+        );
+
+        let variables = parse_variables(stdout).unwrap();
+        assert_eq!(variables.len(), 2);
+
+        assert_eq!(variables[0].name, "df");
+        assert_eq!(variables[0].type_name, "DataFrame");
+        assert_eq!(variables[0].shape.as_deref(), Some("3 x 2"));
+        assert!(variables[0].table.is_some());
+
+        assert_eq!(variables[1].name, "x");
+        assert_eq!(variables[1].preview, "42");
+        assert_eq!(variables[1].shape, None);
+        assert!(variables[1].table.is_none());
+    }
+
+    #[test]
+    fn test_parse_variables_without_marker() {
+        assert!(parse_variables("Traceback (most recent call last):\n").is_err());
+    }
+
+    #[test]
+    fn test_introspection_code() {
+        let code = introspection_code("Python").unwrap();
+        assert!(code.contains(VARIABLES_MARKER));
+        assert!(!code.contains("__ZED_MAX_ROWS__"));
+        assert!(introspection_code("typescript").is_none());
+    }
+}
@@ -2,7 +2,7 @@ use bitflags::bitflags;
 pub use buffer_search::BufferSearchBar;
 use editor::SearchSettings;
 use gpui::{Action, App, ClickEvent, FocusHandle, IntoElement, actions};
-use project::search::SearchQuery;
+use project::search::{SearchQuery, SyntaxScope};
 pub use project_search::ProjectSearchView;
 use ui::{ButtonStyle, IconButton, IconButtonShape};
 use ui::{Tooltip, prelude::*};
@@ -37,6 +37,14 @@ actions!(
         ToggleIncludeIgnored,
         /// Toggles regular expression mode.
         ToggleRegex,
+        /// Toggles structural search mode, which matches code patterns against syntax trees.
+        ToggleStructural,
+        /// Toggles restricting matches to code, outside of comments and strings.
+        ToggleCodeOnly,
+        /// Toggles restricting matches to comments.
+        ToggleCommentsOnly,
+        /// Toggles restricting matches to string literals.
+        ToggleStringsOnly,
         /// Toggles the replace interface.
         ToggleReplace,
         /// Toggles searching within selection only.
@@ -62,7 +70,7 @@ actions!(
 
 bitflags! {
     #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
-    pub struct SearchOptions: u8 {
+    pub struct SearchOptions: u16 {
         const NONE = 0;
         const WHOLE_WORD = 1 << SearchOption::WholeWord as u8;
         const CASE_SENSITIVE = 1 << SearchOption::CaseSensitive as u8;
@@ -71,6 +79,10 @@ bitflags! {
         const ONE_MATCH_PER_LINE = 1 << SearchOption::OneMatchPerLine as u8;
         /// If set, reverse direction when finding the active match
         const BACKWARDS = 1 << SearchOption::Backwards as u8;
+        const STRUCTURAL = 1 << SearchOption::Structural as u8;
+        const CODE_ONLY = 1 << SearchOption::CodeOnly as u8;
+        const COMMENTS_ONLY = 1 << SearchOption::CommentsOnly as u8;
+        const STRINGS_ONLY = 1 << SearchOption::StringsOnly as u8;
     }
 }
 
@@ -83,6 +95,10 @@ pub enum SearchOption {
     Regex,
     OneMatchPerLine,
     Backwards,
+    Structural,
+    CodeOnly,
+    CommentsOnly,
+    StringsOnly,
 }
 
 pub(crate) enum SearchSource<'a, 'b> {
@@ -103,6 +119,10 @@ impl SearchOption {
             SearchOption::Regex => "Use Regular Expressions",
             SearchOption::OneMatchPerLine => "One Match Per Line",
             SearchOption::Backwards => "Search Backwards",
+            SearchOption::Structural => "Use Structural Search",
+            SearchOption::CodeOnly => "Only Search in Code",
+            SearchOption::CommentsOnly => "Only Search in Comments",
+            SearchOption::StringsOnly => "Only Search in Strings",
         }
     }
 
@@ -112,6 +132,10 @@ impl SearchOption {
             SearchOption::CaseSensitive => ui::IconName::CaseSensitive,
             SearchOption::IncludeIgnored => ui::IconName::Sliders,
             SearchOption::Regex => ui::IconName::Regex,
+            SearchOption::Structural => ui::IconName::ListTree,
+            SearchOption::CodeOnly => ui::IconName::Code,
+            SearchOption::CommentsOnly => ui::IconName::Chat,
+            SearchOption::StringsOnly => ui::IconName::Quote,
             _ => panic!("{self:?} is not a named SearchOption"),
         }
     }
@@ -122,6 +146,10 @@ impl SearchOption {
             SearchOption::CaseSensitive => &ToggleCaseSensitive,
             SearchOption::IncludeIgnored => &ToggleIncludeIgnored,
             SearchOption::Regex => &ToggleRegex,
+            SearchOption::Structural => &ToggleStructural,
+            SearchOption::CodeOnly => &ToggleCodeOnly,
+            SearchOption::CommentsOnly => &ToggleCommentsOnly,
+            SearchOption::StringsOnly => &ToggleStringsOnly,
             _ => panic!("{self:?} is not a toggle action"),
         }
     }
@@ -173,9 +201,35 @@ impl SearchOptions {
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
         options.set(SearchOptions::REGEX, query.is_regex());
+        options.set(SearchOptions::STRUCTURAL, query.is_structural());
+        options.set(
+            SearchOptions::CODE_ONLY,
+            query.scope() == Some(SyntaxScope::Code),
+        );
+        options.set(
+            SearchOptions::COMMENTS_ONLY,
+            query.scope() == Some(SyntaxScope::Comments),
+        );
+        options.set(
+            SearchOptions::STRINGS_ONLY,
+            query.scope() == Some(SyntaxScope::Strings),
+        );
         options
     }
 
+    /// The syntactic scope these options restrict matches to, if any.
+    pub fn scope(&self) -> Option<SyntaxScope> {
+        if self.contains(SearchOptions::CODE_ONLY) {
+            Some(SyntaxScope::Code)
+        } else if self.contains(SearchOptions::COMMENTS_ONLY) {
+            Some(SyntaxScope::Comments)
+        } else if self.contains(SearchOptions::STRINGS_ONLY) {
+            Some(SyntaxScope::Strings)
+        } else {
+            None
+        }
+    }
+
     pub fn from_settings(settings: &SearchSettings) -> SearchOptions {
         let mut options = SearchOptions::NONE;
         options.set(SearchOptions::WHOLE_WORD, settings.whole_word);
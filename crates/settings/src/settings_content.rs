@@ -130,6 +130,9 @@ pub struct SettingsContent {
     /// Configuration for the Notification Panel
     pub notification_panel: Option<NotificationPanelSettingsContent>,
 
+    /// Configuration for the Port Forwarding Panel
+    pub port_forwarding_panel: Option<PortForwardingPanelSettingsContent>,
+
     pub proxy: Option<String>,
 
     /// The URL of the Zed server to connect to.
@@ -397,6 +400,10 @@ pub struct DebuggerSettingsContent {
     ///
     /// Default: Bottom
     pub dock: Option<DockPosition>,
+    /// Directories searched for core dumps when debugging a crashed program.
+    ///
+    /// Default: []
+    pub core_dump_directories: Option<Vec<String>>,
 }
 
 /// The granularity of one 'step' in the stepping requests `next`, `stepIn`, `stepOut`, and `stepBack`.
@@ -572,6 +579,28 @@ pub struct NotificationPanelSettingsContent {
     pub default_width: Option<f32>,
 }
 
+#[with_fallible_options]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
+pub struct PortForwardingPanelSettingsContent {
+    /// Whether to show the panel button in the status bar of remote projects.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Where to dock the panel.
+    ///
+    /// Default: bottom
+    pub dock: Option<DockPosition>,
+    /// Default width or height of the panel in pixels, depending on where it is docked.
+    ///
+    /// Default: 240
+    #[serde(serialize_with = "crate::serialize_optional_f32_with_two_decimal_places")]
+    pub default_size: Option<f32>,
+    /// Whether to watch the remote host for newly opened ports and offer to forward them.
+    ///
+    /// Default: true
+    pub detect_ports: Option<bool>,
+}
+
 #[with_fallible_options]
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
 pub struct PanelSettingsContent {
@@ -902,6 +931,7 @@ pub struct RemoteSettingsContent {
     pub ssh_connections: Option<Vec<SshConnection>>,
     pub wsl_connections: Option<Vec<WslConnection>>,
     pub dev_container_connections: Option<Vec<DevContainerConnection>>,
+    pub command_connections: Option<Vec<CommandConnection>>,
     pub read_ssh_config: Option<bool>,
 }
 
@@ -912,6 +942,7 @@ pub struct RemoteSettingsContent {
 pub struct DevContainerConnection {
     pub name: SharedString,
     pub container_id: SharedString,
+    pub remote_user: Option<String>,
 }
 
 #[with_fallible_options]
@@ -938,6 +969,41 @@ pub struct SshConnection {
     pub connection_timeout: Option<u16>,
 }
 
+/// A connection to an environment that is reachable through a CLI, such as a Kubernetes pod or
+/// a podman container.
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom)]
+pub struct CommandConnection {
+    /// Name to use for this connection in UI.
+    pub name: SharedString,
+    /// Connects to a Kubernetes pod with `kubectl`, instead of using the commands below.
+    pub kubernetes: Option<KubernetesPodConnection>,
+    /// Runs a program in the environment with stdin and stdout attached, e.g.
+    /// `["podman", "exec", "-i", "my-container"]`. The program and its arguments are appended.
+    #[serde(default)]
+    pub exec: Vec<String>,
+    /// Like `exec`, but allocates a TTY for terminals. Defaults to `exec`.
+    pub exec_tty: Option<Vec<String>>,
+    /// Copies a local file or directory into the environment, with `{local}` and `{remote}`
+    /// replaced by the source and destination paths. When unset, files are streamed over `exec`.
+    pub upload: Option<Vec<String>>,
+    /// Forwards local ports to the environment, with a `{forwards}` argument replaced by one
+    /// `local_port:remote_port` argument per forwarded port.
+    pub forward_ports: Option<Vec<String>>,
+    #[serde(default)]
+    pub projects: BTreeSet<RemoteProject>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
+pub struct KubernetesPodConnection {
+    pub pod: String,
+    pub container: Option<String>,
+    pub namespace: Option<String>,
+    /// The kubeconfig context to use, instead of the current one.
+    pub context: Option<String>,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom, Debug)]
 pub struct WslConnection {
     pub distro_name: SharedString,
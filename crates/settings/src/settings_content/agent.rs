@@ -74,6 +74,16 @@ pub struct AgentSettingsContent {
     ///
     /// Default: false
     pub always_allow_tool_actions: Option<bool>,
+    /// Rules deciding whether a tool call is allowed, needs confirmation, or is denied, keyed by
+    /// tool name. They are checked before `always_allow_tool_actions`. When several rules match a
+    /// tool call, `deny` takes precedence over `ask`, which takes precedence over `allow`.
+    ///
+    /// Rules for `terminal` match commands, with `*` matching any text, e.g. `cargo test*`.
+    /// Rules for `edit_file`, `delete_path` and `move_path` are globs matching paths, e.g.
+    /// `**/*.lock`.
+    ///
+    /// Default: {}
+    pub tool_permissions: Option<IndexMap<Arc<str>, Vec<ToolPermissionRuleContent>>>,
     /// Where to show a popup notification when the agent is waiting for user input.
     ///
     /// Default: "primary_screen"
@@ -119,6 +129,21 @@ pub struct AgentSettingsContent {
     ///
     /// Default: 4
     pub message_editor_min_lines: Option<usize>,
+    /// Whether to compact threads that approach the model's context window, replacing older
+    /// messages and tool outputs with a summary.
+    ///
+    /// Default: true
+    pub auto_compact: Option<bool>,
+    /// The fraction of the model's context window a thread must use before it is compacted
+    /// automatically.
+    ///
+    /// Default: 0.8
+    #[serde(serialize_with = "crate::serialize_optional_f32_with_two_decimal_places")]
+    pub auto_compact_threshold: Option<f32>,
+    /// How many of the most recent turns are kept verbatim when compacting a thread.
+    ///
+    /// Default: 2
+    pub compaction_preserved_turns: Option<usize>,
 }
 
 impl AgentSettingsContent {
@@ -164,6 +189,17 @@ impl AgentSettingsContent {
         self.always_allow_tool_actions = Some(allow);
     }
 
+    pub fn add_tool_permission_rule(&mut self, tool: Arc<str>, rule: ToolPermissionRuleContent) {
+        let rules = self
+            .tool_permissions
+            .get_or_insert_default()
+            .entry(tool)
+            .or_default();
+        if !rules.contains(&rule) {
+            rules.push(rule);
+        }
+    }
+
     pub fn set_play_sound_when_agent_done(&mut self, allow: bool) {
         self.play_sound_when_agent_done = Some(allow);
     }
@@ -205,6 +241,26 @@ pub struct AgentProfileContent {
     pub default_model: Option<LanguageModelSelection>,
 }
 
+#[with_fallible_options]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct ToolPermissionRuleContent {
+    /// The command or path pattern the rule applies to.
+    pub pattern: String,
+    /// What to do with tool calls matching the pattern.
+    pub action: ToolPermissionAction,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolPermissionAction {
+    /// Run the tool without asking.
+    Allow,
+    /// Ask for confirmation, even if `always_allow_tool_actions` is enabled.
+    Ask,
+    /// Refuse to run the tool.
+    Deny,
+}
+
 #[with_fallible_options]
 #[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
 pub struct ContextServerPresetContent {
@@ -809,6 +809,22 @@ pub struct JupyterContent {
     ///
     /// Default: `{}`
     pub kernel_selections: Option<HashMap<String, String>>,
+
+    /// Jupyter servers whose kernels can be used in addition to locally installed ones.
+    ///
+    /// Tokens for these servers are stored in the system keychain.
+    ///
+    /// Default: `[]`
+    pub servers: Option<Vec<JupyterServerContent>>,
+}
+
+/// A Jupyter server to connect to.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct JupyterServerContent {
+    /// The name to show for this server.
+    pub name: String,
+    /// The base URL of the server, e.g. `http://localhost:8888`.
+    pub url: String,
 }
 
 /// Whether to allow drag and drop text selection in buffer.
@@ -76,6 +76,7 @@ pub enum EditPredictionProvider {
     Supermaven,
     Zed,
     Codestral,
+    Local,
     Experimental(&'static str),
 }
 
@@ -96,6 +97,7 @@ impl<'de> Deserialize<'de> for EditPredictionProvider {
             Supermaven,
             Zed,
             Codestral,
+            Local,
             Experimental(String),
         }
 
@@ -105,6 +107,7 @@ impl<'de> Deserialize<'de> for EditPredictionProvider {
             Content::Supermaven => EditPredictionProvider::Supermaven,
             Content::Zed => EditPredictionProvider::Zed,
             Content::Codestral => EditPredictionProvider::Codestral,
+            Content::Local => EditPredictionProvider::Local,
             Content::Experimental(name)
                 if name == EXPERIMENTAL_SWEEP_EDIT_PREDICTION_PROVIDER_NAME =>
             {
@@ -144,6 +147,7 @@ impl EditPredictionProvider {
             | EditPredictionProvider::Copilot
             | EditPredictionProvider::Supermaven
             | EditPredictionProvider::Codestral
+            | EditPredictionProvider::Local
             | EditPredictionProvider::Experimental(_) => false,
         }
     }
@@ -164,6 +168,8 @@ pub struct EditPredictionSettingsContent {
     pub copilot: Option<CopilotSettingsContent>,
     /// Settings specific to Codestral.
     pub codestral: Option<CodestralSettingsContent>,
+    /// Settings specific to locally hosted fill-in-the-middle models.
+    pub local: Option<LocalEditPredictionSettingsContent>,
     /// Whether edit predictions are enabled in the assistant prompt editor.
     /// This has no effect if globally disabled.
     pub enabled_in_text_threads: Option<bool>,
@@ -207,6 +213,81 @@ pub struct CodestralSettingsContent {
     pub api_url: Option<String>,
 }
 
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct LocalEditPredictionSettingsContent {
+    /// The kind of API served at `api_url`.
+    ///
+    /// Default: "ollama"
+    pub api: Option<LocalEditPredictionApi>,
+    /// Api URL of the local model server.
+    ///
+    /// Default: "http://localhost:11434"
+    pub api_url: Option<String>,
+    /// Model to use for completions.
+    ///
+    /// Default: "qwen2.5-coder:1.5b"
+    pub model: Option<String>,
+    /// The fill-in-the-middle prompt template the model was trained with.
+    ///
+    /// Default: "qwen_coder"
+    pub prompt_format: Option<FimPromptFormat>,
+    /// Maximum tokens to generate.
+    ///
+    /// Default: 128
+    pub max_tokens: Option<u32>,
+}
+
+/// The API used to request completions from a local model server.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalEditPredictionApi {
+    /// Ollama's `/api/generate` endpoint.
+    #[default]
+    Ollama,
+    /// An OpenAI-compatible `/completions` endpoint, as served by llama.cpp, vLLM or LM Studio.
+    OpenAiCompatible,
+}
+
+/// A fill-in-the-middle prompt template.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum FimPromptFormat {
+    /// The template used by Qwen2.5-Coder and Qwen3-Coder.
+    #[default]
+    QwenCoder,
+    /// The template used by StarCoder2 and its derivatives.
+    StarCoder,
+    /// The template used by DeepSeek-Coder.
+    DeepseekCoder,
+}
+
 /// The mode in which edit predictions should be displayed.
 #[derive(
     Copy,
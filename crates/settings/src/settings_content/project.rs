@@ -62,6 +62,32 @@ pub struct ProjectSettingsContent {
 
     /// The list of custom Git hosting providers.
     pub git_hosting_providers: Option<ExtendingVec<GitHostingProviderConfig>>,
+
+    /// Settings for checking the spelling of comments, strings and prose.
+    pub spell_check: Option<SpellCheckSettingsContent>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct SpellCheckSettingsContent {
+    /// Whether to underline misspelled words in comments, strings and prose.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+
+    /// The Hunspell dictionaries to check words against, either by name (e.g. "en_US")
+    /// or by the path to a `.dic` file with its `.aff` file next to it. Dictionaries are
+    /// looked up by name in Zed's `dictionaries` config directory, then in the system's
+    /// Hunspell directories.
+    ///
+    /// Default: ["en_US"]
+    pub dictionaries: Option<Vec<String>>,
+
+    /// Words that are always considered correctly spelled. Words listed in project
+    /// settings are added to the ones in user settings.
+    ///
+    /// Default: []
+    pub words: Option<ExtendingVec<String>>,
 }
 
 #[with_fallible_options]
@@ -116,6 +142,19 @@ pub struct WorktreeSettingsContent {
     /// external dependencies that should not be modified directly.
     /// Default: []
     pub read_only_files: Option<Vec<String>>,
+
+    /// Files larger than this many megabytes are opened in a read-only large-file view,
+    /// which pages the file from disk instead of loading it into an editor buffer.
+    /// Syntax highlighting, language servers and git diffs are not available for them.
+    /// Default: 256
+    pub large_file_threshold_mb: Option<u64>,
+
+    /// Character encodings to use for files matching the given globs, instead of detecting
+    /// the encoding from the file's contents. Keys are encoding labels such as "shift_jis"
+    /// or "windows-1252". A byte order mark at the start of a file still takes precedence.
+    ///
+    /// Default: {}
+    pub file_encodings: Option<HashMap<String, Vec<String>>>,
 }
 
 #[with_fallible_options]
@@ -212,6 +251,11 @@ pub struct SessionSettingsContent {
     ///
     /// Default: false
     pub trust_all_worktrees: Option<bool>,
+    /// Whether to keep the buffers of a remote project editable after its connection is lost.
+    /// Saves are queued, and the edits are applied when the project is reconnected.
+    ///
+    /// Default: true
+    pub edit_while_disconnected: Option<bool>,
 }
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema, MergeFrom, Debug)]
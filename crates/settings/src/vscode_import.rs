@@ -197,6 +197,7 @@ impl VsCodeSettings {
             node: self.node_binary_settings(),
             notification_panel: None,
             outline_panel: self.outline_panel_settings_content(),
+            port_forwarding_panel: None,
             preview_tabs: self.preview_tabs_settings_content(),
             project: self.project_settings_content(),
             project_panel: self.project_panel_settings_content(),
@@ -406,6 +407,7 @@ impl VsCodeSettings {
             load_direnv: None,
             slash_commands: None,
             git_hosting_providers: None,
+            spell_check: self.spell_check_settings_content(),
         }
     }
 
@@ -654,6 +656,7 @@ impl VsCodeSettings {
             active_language_button: None,
             cursor_position_button: None,
             line_endings_button: None,
+            encoding_button: self.read_bool("workbench.statusBar.encoding.visible"),
         })
     }
 
@@ -907,6 +910,8 @@ impl VsCodeSettings {
                 .filter(|r| !r.is_empty()),
             private_files: None,
             hidden_files: None,
+            large_file_threshold_mb: None,
+            file_encodings: None,
             read_only_files: self
                 .read_value("files.readonlyExclude")
                 .and_then(|v| v.as_object())
@@ -924,6 +929,23 @@ impl VsCodeSettings {
                 .filter(|r| !r.is_empty()),
         }
     }
+
+    fn spell_check_settings_content(&self) -> Option<SpellCheckSettingsContent> {
+        skip_default(SpellCheckSettingsContent {
+            enabled: self.read_bool("cSpell.enabled"),
+            dictionaries: None,
+            words: self
+                .read_value("cSpell.words")
+                .and_then(|v| v.as_array())
+                .map(|v| {
+                    v.iter()
+                        .filter_map(|n| n.as_str().map(str::to_owned))
+                        .collect::<Vec<_>>()
+                })
+                .filter(|r| !r.is_empty())
+                .map(ExtendingVec),
+        })
+    }
 }
 
 fn skip_default<T: Default + PartialEq>(value: T) -> Option<T> {
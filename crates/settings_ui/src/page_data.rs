@@ -182,6 +182,27 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                     metadata: None,
                     files: USER,
                 }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Edit While Disconnected",
+                    description: "Whether to keep remote buffers editable after the connection is lost, applying the edits when reconnected.",
+                    field: Box::new(SettingField {
+                        json_path: Some("session.edit_while_disconnected"),
+                        pick: |settings_content| {
+                            settings_content
+                                .session
+                                .as_ref()
+                                .and_then(|session| session.edit_while_disconnected.as_ref())
+                        },
+                        write: |settings_content, value| {
+                            settings_content
+                                .session
+                                .get_or_insert_default()
+                                .edit_while_disconnected = value;
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
                 SettingsPageItem::SettingItem(SettingItem {
                     title: "Restore On Startup",
                     description: "What to restore from the previous session when opening Zed.",
@@ -6195,6 +6216,43 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                         metadata: None,
                         files: USER,
                     }),
+                    SettingsPageItem::SettingItem(SettingItem {
+                        title: "Auto Compact",
+                        description: "Whether to summarize older messages when a thread approaches the model's context window.",
+                        field: Box::new(SettingField {
+                            json_path: Some("agent.auto_compact"),
+                            pick: |settings_content| {
+                                settings_content.agent.as_ref()?.auto_compact.as_ref()
+                            },
+                            write: |settings_content, value| {
+                                settings_content.agent.get_or_insert_default().auto_compact = value;
+                            },
+                        }),
+                        metadata: None,
+                        files: USER,
+                    }),
+                    SettingsPageItem::SettingItem(SettingItem {
+                        title: "Compaction Preserved Turns",
+                        description: "How many of the most recent turns are kept verbatim when compacting a thread.",
+                        field: Box::new(SettingField {
+                            json_path: Some("agent.compaction_preserved_turns"),
+                            pick: |settings_content| {
+                                settings_content
+                                    .agent
+                                    .as_ref()?
+                                    .compaction_preserved_turns
+                                    .as_ref()
+                            },
+                            write: |settings_content, value| {
+                                settings_content
+                                    .agent
+                                    .get_or_insert_default()
+                                    .compaction_preserved_turns = value;
+                            },
+                        }),
+                        metadata: None,
+                        files: USER,
+                    }),
                 ];
                 items.extend(edit_prediction_language_settings_section());
                 items.extend(
@@ -0,0 +1,237 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use editor::{CodeActionProvider, ExcerptId};
+use fs::Fs;
+use gpui::{App, AppContext as _, Entity, Task, WeakEntity, Window};
+use language::{Buffer, Point, ToOffset as _};
+use project::{CodeAction, LspAction, Project, ProjectTransaction};
+use serde::{Deserialize, Serialize};
+use settings::SettingsContent;
+
+use crate::spell_checker::WordChecker;
+
+const SPELL_CHECK_CODE_ACTION_PROVIDER_ID: &str = "spell_check";
+
+/// The maximum number of replacements offered for a misspelled word.
+const MAX_SUGGESTIONS: usize = 5;
+
+/// What a spell check code action does, stored in the `data` of its LSP code action.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum SpellCheckAction {
+    Replace { text: String },
+    AddToProjectDictionary { word: String },
+    AddToUserDictionary { word: String },
+}
+
+/// Offers replacements for the misspelled word under the cursor, and adds it to the project or
+/// user dictionary.
+pub(crate) struct SpellCheckCodeActionProvider {
+    project: Option<WeakEntity<Project>>,
+}
+
+impl SpellCheckCodeActionProvider {
+    pub fn new(project: Option<WeakEntity<Project>>) -> Self {
+        Self { project }
+    }
+}
+
+impl CodeActionProvider for SpellCheckCodeActionProvider {
+    fn id(&self) -> Arc<str> {
+        SPELL_CHECK_CODE_ACTION_PROVIDER_ID.into()
+    }
+
+    fn code_actions(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        let Some(checker) = WordChecker::for_buffer(buffer, cx) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let can_add_to_project = buffer.read(cx).file().is_some()
+            && self
+                .project
+                .as_ref()
+                .and_then(|project| project.upgrade())
+                .is_some_and(|project| project.read(cx).is_local());
+        let snapshot = buffer.read(cx).snapshot();
+        let cursor = range.start.to_offset(&snapshot);
+
+        cx.background_spawn(async move {
+            let row = snapshot.offset_to_point(cursor).row;
+            let line = snapshot.point_to_offset(Point::new(row, 0))
+                ..snapshot.point_to_offset(Point::new(row, snapshot.line_len(row)));
+            let Some(misspelling) = checker
+                .misspellings(&snapshot, line)
+                .into_iter()
+                .find(|misspelling| misspelling.start <= cursor && cursor <= misspelling.end)
+            else {
+                return Ok(Vec::new());
+            };
+
+            let word = snapshot
+                .text_for_range(misspelling.clone())
+                .collect::<String>();
+            let mut actions = checker
+                .suggest(&word, MAX_SUGGESTIONS)
+                .into_iter()
+                .map(|suggestion| {
+                    (
+                        format!("Change to \"{suggestion}\""),
+                        SpellCheckAction::Replace { text: suggestion },
+                    )
+                })
+                .collect::<Vec<_>>();
+            if can_add_to_project {
+                actions.push((
+                    format!("Add \"{word}\" to Project Dictionary"),
+                    SpellCheckAction::AddToProjectDictionary { word: word.clone() },
+                ));
+            }
+            actions.push((
+                format!("Add \"{word}\" to User Dictionary"),
+                SpellCheckAction::AddToUserDictionary { word },
+            ));
+
+            let range =
+                snapshot.anchor_before(misspelling.start)..snapshot.anchor_after(misspelling.end);
+            actions
+                .into_iter()
+                .map(|(title, action)| {
+                    anyhow::Ok(CodeAction {
+                        server_id: language::LanguageServerId(0),
+                        range: range.clone(),
+                        lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+                            title,
+                            kind: Some(lsp::CodeActionKind::QUICKFIX),
+                            data: Some(serde_json::to_value(action)?),
+                            ..Default::default()
+                        })),
+                        resolved: true,
+                    })
+                })
+                .collect()
+        })
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer: Entity<Buffer>,
+        action: CodeAction,
+        _excerpt_id: ExcerptId,
+        push_to_history: bool,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<ProjectTransaction>> {
+        let spell_check_action = match &action.lsp_action {
+            LspAction::Action(lsp_action) => lsp_action
+                .data
+                .clone()
+                .context("spell check code action has no data")
+                .and_then(|data| Ok(serde_json::from_value::<SpellCheckAction>(data)?)),
+            _ => Err(anyhow::anyhow!("not a spell check code action")),
+        };
+        let spell_check_action = match spell_check_action {
+            Ok(spell_check_action) => spell_check_action,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        match spell_check_action {
+            SpellCheckAction::Replace { text } => {
+                let transaction = buffer.update(cx, |buffer, cx| {
+                    buffer.finalize_last_transaction();
+                    buffer.start_transaction();
+                    buffer.edit([(action.range, text)], None, cx);
+                    buffer.end_transaction(cx)?;
+                    let transaction = buffer.finalize_last_transaction()?.clone();
+                    if !push_to_history {
+                        buffer.forget_transaction(transaction.id);
+                    }
+                    Some(transaction)
+                });
+                let mut project_transaction = ProjectTransaction::default();
+                if let Some(transaction) = transaction {
+                    project_transaction.0.insert(buffer, transaction);
+                }
+                Task::ready(Ok(project_transaction))
+            }
+            SpellCheckAction::AddToProjectDictionary { word } => {
+                let result = add_to_project_dictionary(self.project.as_ref(), &buffer, word, cx);
+                Task::ready(result.map(|_| ProjectTransaction::default()))
+            }
+            SpellCheckAction::AddToUserDictionary { word } => {
+                settings::update_settings_file(<dyn Fs>::global(cx), cx, move |content, _| {
+                    add_word(content, word)
+                });
+                Task::ready(Ok(ProjectTransaction::default()))
+            }
+        }
+    }
+}
+
+/// Adds a word to the settings file at the root of the buffer's worktree, creating the file if
+/// needed.
+fn add_to_project_dictionary(
+    project: Option<&WeakEntity<Project>>,
+    buffer: &Entity<Buffer>,
+    word: String,
+    cx: &mut App,
+) -> Result<()> {
+    let project = project
+        .and_then(|project| project.upgrade())
+        .context("project was released")?;
+    let worktree_id = buffer
+        .read(cx)
+        .file()
+        .context("buffer has no file")?
+        .worktree_id(cx);
+    let worktree = project
+        .read(cx)
+        .worktree_for_id(worktree_id, cx)
+        .context("worktree was removed")?;
+    let rel_path = paths::local_settings_file_relative_path();
+
+    project.update(cx, |project, cx| {
+        let create_settings_file =
+            if project.contains_local_settings_file(worktree_id, rel_path, cx) {
+                None
+            } else {
+                Some(worktree.update(cx, |worktree, cx| {
+                    worktree.create_entry(rel_path.into(), false, None, cx)
+                }))
+            };
+
+        cx.spawn(async move |project, cx| {
+            if let Some(create_settings_file) = create_settings_file {
+                create_settings_file.await?;
+            }
+            project.update(cx, |project, cx| {
+                project.update_local_settings_file(
+                    worktree_id,
+                    rel_path.into(),
+                    cx,
+                    move |content, _| add_word(content, word),
+                );
+            })
+        })
+        .detach_and_log_err(cx);
+    });
+    Ok(())
+}
+
+fn add_word(content: &mut SettingsContent, word: String) {
+    let words = &mut content
+        .project
+        .spell_check
+        .get_or_insert_default()
+        .words
+        .get_or_insert_default()
+        .0;
+    if !words.contains(&word) {
+        words.push(word);
+    }
+}
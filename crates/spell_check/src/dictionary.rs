@@ -0,0 +1,714 @@
+//! A reader for Hunspell dictionaries.
+//!
+//! Every word of the `.dic` file is expanded with the prefix and suffix rules of the `.aff`
+//! file when the dictionary is loaded, so that checking a word is a set lookup. Compounding
+//! and morphological analysis aren't supported.
+
+use std::ops::Range;
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use encoding_rs::Encoding;
+
+type Flag = u32;
+
+/// The maximum number of words generated from a single dictionary entry, which guards against
+/// runaway expansion with very large affix classes.
+const MAX_FORMS_PER_ENTRY: usize = 4096;
+
+pub struct Dictionary {
+    words: HashSet<String>,
+    no_suggest: HashSet<String>,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FlagMode {
+    Char,
+    Long,
+    Numeric,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum CharClass {
+    Any,
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl CharClass {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            CharClass::Any => true,
+            CharClass::OneOf(chars) => chars.contains(&c),
+            CharClass::NoneOf(chars) => !chars.contains(&c),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct AffixRule {
+    strip: String,
+    add: String,
+    continuation: Vec<Flag>,
+    condition: Vec<CharClass>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct AffixClass {
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+#[derive(Default)]
+struct Affixes {
+    flag_mode: Option<FlagMode>,
+    aliases: Vec<Vec<Flag>>,
+    prefixes: HashMap<Flag, AffixClass>,
+    suffixes: HashMap<Flag, AffixClass>,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+    need_affix: Option<Flag>,
+    forbidden: Option<Flag>,
+    only_in_compound: Option<Flag>,
+    no_suggest: Option<Flag>,
+}
+
+impl Affixes {
+    fn parse(text: &str) -> Result<Self> {
+        let mut affixes = Affixes::default();
+        let mut lines = text.lines().enumerate();
+        while let Some((line_ix, line)) = lines.next() {
+            let mut fields = line.split_whitespace();
+            let Some(keyword) = fields.next() else {
+                continue;
+            };
+            match keyword {
+                "FLAG" => {
+                    affixes.flag_mode = match fields.next() {
+                        Some("long") => Some(FlagMode::Long),
+                        Some("num") => Some(FlagMode::Numeric),
+                        _ => Some(FlagMode::Char),
+                    }
+                }
+                "AF" => {
+                    // The first `AF` line holds the number of aliases that follow.
+                    if let Some(flags) = fields.next()
+                        && flags.parse::<usize>().is_err()
+                    {
+                        let flags = affixes.parse_flags(flags);
+                        affixes.aliases.push(flags);
+                    }
+                }
+                "TRY" => {
+                    affixes.try_chars = fields.next().unwrap_or_default().chars().collect();
+                }
+                "REP" => {
+                    if let (Some(from), Some(to)) = (fields.next(), fields.next()) {
+                        affixes
+                            .replacements
+                            .push((from.replace('_', " "), to.replace('_', " ")));
+                    }
+                }
+                "NEEDAFFIX" | "PSEUDOROOT" => {
+                    affixes.need_affix = fields.next().and_then(|flag| affixes.parse_flag(flag))
+                }
+                "FORBIDDENWORD" => {
+                    affixes.forbidden = fields.next().and_then(|flag| affixes.parse_flag(flag))
+                }
+                "ONLYINCOMPOUND" => {
+                    affixes.only_in_compound =
+                        fields.next().and_then(|flag| affixes.parse_flag(flag))
+                }
+                "NOSUGGEST" => {
+                    affixes.no_suggest = fields.next().and_then(|flag| affixes.parse_flag(flag))
+                }
+                "PFX" | "SFX" => {
+                    let (Some(flag), Some(cross_product), Some(count)) =
+                        (fields.next(), fields.next(), fields.next())
+                    else {
+                        continue;
+                    };
+                    let flag = affixes
+                        .parse_flag(flag)
+                        .with_context(|| format!("invalid affix flag on line {}", line_ix + 1))?;
+                    let count = count.parse::<usize>().with_context(|| {
+                        format!("invalid affix rule count on line {}", line_ix + 1)
+                    })?;
+                    let mut class = AffixClass {
+                        cross_product: cross_product == "Y",
+                        rules: Vec::with_capacity(count),
+                    };
+                    for _ in 0..count {
+                        let Some((_, rule_line)) = lines.next() else {
+                            break;
+                        };
+                        if let Some(rule) = affixes.parse_rule(rule_line, keyword == "PFX") {
+                            class.rules.push(rule);
+                        }
+                    }
+                    let classes = if keyword == "PFX" {
+                        &mut affixes.prefixes
+                    } else {
+                        &mut affixes.suffixes
+                    };
+                    classes.entry(flag).or_default().rules.extend(class.rules);
+                    classes.entry(flag).or_default().cross_product = class.cross_product;
+                }
+                _ => {}
+            }
+        }
+        Ok(affixes)
+    }
+
+    fn parse_rule(&self, line: &str, is_prefix: bool) -> Option<AffixRule> {
+        let mut fields = line.split_whitespace();
+        let _keyword = fields.next()?;
+        let _flag = fields.next()?;
+        let strip = fields.next()?;
+        let add = fields.next()?;
+        let condition = fields.next().unwrap_or(".");
+
+        let strip = if strip == "0" { "" } else { strip };
+        let (add, continuation) = match add.split_once('/') {
+            Some((add, flags)) => (add, self.parse_flag_field(flags)),
+            None => (add, Vec::new()),
+        };
+        let add = if add == "0" { "" } else { add };
+
+        let mut condition = parse_condition(condition)?;
+        // Conditions are written in reading order but only ever compared against the start of
+        // the word for prefixes, or its end for suffixes.
+        if !is_prefix {
+            condition.reverse();
+        }
+        Some(AffixRule {
+            strip: strip.to_string(),
+            add: add.to_string(),
+            continuation,
+            condition,
+        })
+    }
+
+    fn flag_mode(&self) -> FlagMode {
+        self.flag_mode.unwrap_or(FlagMode::Char)
+    }
+
+    fn parse_flag(&self, text: &str) -> Option<Flag> {
+        self.parse_flags(text).first().copied()
+    }
+
+    fn parse_flags(&self, text: &str) -> Vec<Flag> {
+        match self.flag_mode() {
+            FlagMode::Char => text.chars().map(|c| c as Flag).collect(),
+            FlagMode::Long => {
+                let chars = text.chars().collect::<Vec<_>>();
+                chars
+                    .chunks(2)
+                    .map(|pair| {
+                        let high = pair[0] as Flag;
+                        let low = pair.get(1).map_or(0, |c| *c as Flag);
+                        (high << 16) | (low & 0xffff)
+                    })
+                    .collect()
+            }
+            FlagMode::Numeric => text
+                .split(',')
+                .filter_map(|flag| flag.trim().parse().ok())
+                .collect(),
+        }
+    }
+
+    /// Parses the flags of a word or an affix, which may be a reference to an `AF` alias.
+    fn parse_flag_field(&self, text: &str) -> Vec<Flag> {
+        if !self.aliases.is_empty()
+            && let Ok(alias) = text.parse::<usize>()
+        {
+            return self
+                .aliases
+                .get(alias.wrapping_sub(1))
+                .cloned()
+                .unwrap_or_default();
+        }
+        self.parse_flags(text)
+    }
+}
+
+fn parse_condition(condition: &str) -> Option<Vec<CharClass>> {
+    let mut classes = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => classes.push(CharClass::Any),
+            '[' => {
+                let mut negated = false;
+                let mut set = Vec::new();
+                loop {
+                    match chars.next()? {
+                        ']' => break,
+                        '^' if set.is_empty() && !negated => negated = true,
+                        c => set.push(c),
+                    }
+                }
+                classes.push(if negated {
+                    CharClass::NoneOf(set)
+                } else {
+                    CharClass::OneOf(set)
+                });
+            }
+            c => classes.push(CharClass::OneOf(vec![c])),
+        }
+    }
+    Some(classes)
+}
+
+/// Applies a suffix rule to a word, returning the suffixed word.
+fn apply_suffix(word: &str, rule: &AffixRule) -> Option<String> {
+    let mut end = word.chars().rev();
+    for class in &rule.condition {
+        if !class.matches(end.next()?) {
+            return None;
+        }
+    }
+    let stem = word.strip_suffix(rule.strip.as_str())?;
+    if stem.is_empty() && rule.add.is_empty() {
+        return None;
+    }
+    Some(format!("{stem}{}", rule.add))
+}
+
+/// Applies a prefix rule to a word, returning the prefixed word.
+fn apply_prefix(word: &str, rule: &AffixRule) -> Option<String> {
+    let mut start = word.chars();
+    for class in &rule.condition {
+        if !class.matches(start.next()?) {
+            return None;
+        }
+    }
+    let stem = word.strip_prefix(rule.strip.as_str())?;
+    if stem.is_empty() && rule.add.is_empty() {
+        return None;
+    }
+    Some(format!("{}{stem}", rule.add))
+}
+
+impl Dictionary {
+    /// Parses a dictionary from the contents of its `.aff` and `.dic` files.
+    pub fn from_bytes(aff: &[u8], dic: &[u8]) -> Result<Self> {
+        let aff = decode(aff, None);
+        let encoding = aff
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("SET "))
+            .and_then(|label| Encoding::for_label(label.trim().as_bytes()));
+        let dic = decode(dic, encoding);
+        Self::parse(&aff, &dic)
+    }
+
+    pub fn parse(aff: &str, dic: &str) -> Result<Self> {
+        let affixes = Affixes::parse(aff)?;
+        let mut words = HashSet::default();
+        let mut no_suggest = HashSet::default();
+        let mut forms = Vec::new();
+
+        // The first line holds the approximate number of entries.
+        for line in dic.lines().skip(1) {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('\t') || line.starts_with('#') {
+                continue;
+            }
+            let (word, flags) = split_entry(line);
+            let flags = flags
+                .map(|flags| affixes.parse_flag_field(flags))
+                .unwrap_or_default();
+            let has_flag = |flag: Option<Flag>| flag.is_some_and(|flag| flags.contains(&flag));
+            if has_flag(affixes.forbidden) || has_flag(affixes.only_in_compound) {
+                continue;
+            }
+
+            forms.clear();
+            expand_entry(&word, &flags, &affixes, &mut forms);
+            if has_flag(affixes.need_affix) {
+                forms.retain(|form| *form != word);
+            }
+            if has_flag(affixes.no_suggest) {
+                no_suggest.extend(forms.iter().cloned());
+            }
+            words.extend(forms.drain(..));
+        }
+
+        Ok(Self {
+            words,
+            no_suggest,
+            try_chars: affixes.try_chars,
+            replacements: affixes.replacements,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns whether the word is spelled correctly.
+    ///
+    /// Capitalized and upper-case spellings of lower-case words are accepted, as are upper-case
+    /// spellings of capitalized words, but words that are capitalized in the dictionary must be
+    /// capitalized.
+    pub fn check(&self, word: &str) -> bool {
+        let word = normalize_apostrophes(word);
+        if self.words.contains(word.as_str()) {
+            return true;
+        }
+        match Casing::of(&word) {
+            Casing::Capitalized => self.words.contains(&word.to_lowercase()),
+            Casing::Upper => {
+                let lowercase = word.to_lowercase();
+                self.words.contains(&lowercase) || self.words.contains(&capitalize(&lowercase))
+            }
+            Casing::Lower | Casing::Mixed => false,
+        }
+    }
+
+    /// Returns up to `limit` correctly spelled words that are similar to the given one, most
+    /// likely first.
+    pub fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let word = normalize_apostrophes(word);
+        let casing = Casing::of(&word);
+        let lowercase = word.to_lowercase();
+
+        let mut suggestions = Vec::new();
+        let push = |candidate: String, suggestions: &mut Vec<String>| {
+            if suggestions.len() < limit
+                && candidate != word
+                && !self.no_suggest.contains(&candidate)
+                && candidate.split(' ').all(|word| self.check(word))
+                && !suggestions.contains(&candidate)
+            {
+                suggestions.push(candidate);
+            }
+        };
+
+        for (from, to) in &self.replacements {
+            for (ix, _) in lowercase.match_indices(from.as_str()) {
+                let mut candidate = lowercase.clone();
+                candidate.replace_range(ix..ix + from.len(), to);
+                push(casing.apply(&candidate), &mut suggestions);
+            }
+        }
+
+        // Proper nouns written in lower case.
+        push(capitalize(&lowercase), &mut suggestions);
+
+        let alphabet = self.alphabet();
+        let edits = single_edits(&lowercase, &alphabet);
+        for candidate in &edits {
+            if self.check(candidate) {
+                push(casing.apply(candidate), &mut suggestions);
+            } else {
+                // Proper nouns must keep the dictionary's capitalization.
+                push(capitalize(candidate), &mut suggestions);
+            }
+        }
+
+        // Missing spaces between two words.
+        for (ix, _) in lowercase.char_indices().skip(1) {
+            let (first, second) = lowercase.split_at(ix);
+            if first.chars().count() > 1 && second.chars().count() > 1 {
+                push(casing.apply(&format!("{first} {second}")), &mut suggestions);
+            }
+        }
+
+        // Only look further when nothing closer is found, as there are many more candidates.
+        if suggestions.is_empty() && lowercase.chars().count() <= 16 {
+            for edit in &edits {
+                for candidate in single_edits(edit, &alphabet) {
+                    push(casing.apply(&candidate), &mut suggestions);
+                    if suggestions.len() >= limit {
+                        return suggestions;
+                    }
+                }
+            }
+        }
+
+        suggestions
+    }
+
+    fn alphabet(&self) -> Vec<char> {
+        if self.try_chars.is_empty() {
+            ('a'..='z').chain(['\'']).collect()
+        } else {
+            self.try_chars
+                .iter()
+                .copied()
+                .filter(|c| !c.is_uppercase())
+                .collect()
+        }
+    }
+}
+
+fn decode(bytes: &[u8], encoding: Option<&'static Encoding>) -> String {
+    let encoding = encoding.unwrap_or(encoding_rs::UTF_8);
+    let (text, _, _) = encoding.decode(bytes);
+    text.into_owned()
+}
+
+/// Splits a `.dic` line into its word and its flags, if any. Slashes in words are escaped
+/// with a backslash, and any morphological fields after the word are ignored.
+fn split_entry(line: &str) -> (String, Option<&str>) {
+    let line = line.split(['\t', ' ']).next().unwrap_or(line);
+    let mut word = String::new();
+    let mut chars = line.char_indices();
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some((_, escaped)) = chars.next() {
+                    word.push(escaped);
+                }
+            }
+            '/' => return (word, Some(&line[ix + 1..])),
+            c => word.push(c),
+        }
+    }
+    (word, None)
+}
+
+fn expand_entry(word: &str, flags: &[Flag], affixes: &Affixes, forms: &mut Vec<String>) {
+    forms.push(word.to_string());
+
+    let mut suffixed = Vec::new();
+    for flag in flags {
+        let Some(class) = affixes.suffixes.get(flag) else {
+            continue;
+        };
+        for rule in &class.rules {
+            let Some(form) = apply_suffix(word, rule) else {
+                continue;
+            };
+            // Suffixes may allow a second suffix, or a prefix, to be applied on top of them.
+            for continuation in &rule.continuation {
+                if let Some(class) = affixes.suffixes.get(continuation) {
+                    for rule in &class.rules {
+                        forms.extend(apply_suffix(&form, rule));
+                    }
+                }
+                if let Some(class) = affixes.prefixes.get(continuation) {
+                    for rule in &class.rules {
+                        forms.extend(apply_prefix(&form, rule));
+                    }
+                }
+            }
+            if class.cross_product {
+                suffixed.push(form.clone());
+            }
+            forms.push(form);
+            if forms.len() >= MAX_FORMS_PER_ENTRY {
+                return;
+            }
+        }
+    }
+
+    for flag in flags {
+        let Some(class) = affixes.prefixes.get(flag) else {
+            continue;
+        };
+        for rule in &class.rules {
+            forms.extend(apply_prefix(word, rule));
+            if class.cross_product {
+                for form in &suffixed {
+                    forms.extend(apply_prefix(form, rule));
+                }
+            }
+            if forms.len() >= MAX_FORMS_PER_ENTRY {
+                return;
+            }
+        }
+    }
+}
+
+/// Returns all the strings one deletion, transposition, replacement or insertion away from
+/// the word.
+fn single_edits(word: &str, alphabet: &[char]) -> Vec<String> {
+    let chars = word.chars().collect::<Vec<_>>();
+    let mut edits = Vec::new();
+    let splice = |range: Range<usize>, replacement: &[char]| -> String {
+        chars[..range.start]
+            .iter()
+            .chain(replacement)
+            .chain(&chars[range.end..])
+            .collect()
+    };
+
+    for ix in 0..chars.len().saturating_sub(1) {
+        edits.push(splice(ix..ix + 2, &[chars[ix + 1], chars[ix]]));
+    }
+    for (ix, &current) in chars.iter().enumerate() {
+        for &c in alphabet {
+            if c != current {
+                edits.push(splice(ix..ix + 1, &[c]));
+            }
+        }
+    }
+    for ix in 0..chars.len() {
+        edits.push(splice(ix..ix + 1, &[]));
+    }
+    for ix in 0..=chars.len() {
+        for &c in alphabet {
+            edits.push(splice(ix..ix, &[c]));
+        }
+    }
+    edits
+}
+
+fn normalize_apostrophes(word: &str) -> String {
+    word.replace('\u{2019}', "'")
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Casing {
+    Lower,
+    Capitalized,
+    Upper,
+    Mixed,
+}
+
+impl Casing {
+    fn of(word: &str) -> Self {
+        let mut letters = word.chars().filter(|c| c.is_alphabetic());
+        let Some(first) = letters.next() else {
+            return Casing::Lower;
+        };
+        let (mut has_upper, mut has_lower) = (false, false);
+        for c in letters {
+            has_upper |= c.is_uppercase();
+            has_lower |= c.is_lowercase();
+        }
+        match (first.is_uppercase(), has_upper, has_lower) {
+            (false, false, _) => Casing::Lower,
+            (true, false, _) => Casing::Capitalized,
+            (true, true, false) => Casing::Upper,
+            _ => Casing::Mixed,
+        }
+    }
+
+    fn apply(self, lowercase: &str) -> String {
+        match self {
+            Casing::Lower | Casing::Mixed => lowercase.to_string(),
+            Casing::Capitalized => capitalize(lowercase),
+            Casing::Upper => lowercase.to_uppercase(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "\
+SET UTF-8
+TRY esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'
+REP 2
+REP alot a_lot
+REP f ph
+
+PFX U Y 1
+PFX U   0     un         .
+
+SFX S Y 4
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [aeiou]y
+SFX S   0     es         [sxzh]
+SFX S   0     s          [^sxzhy]
+
+SFX D Y 2
+SFX D   0     d          e
+SFX D   0     ed         [^e]
+
+NEEDAFFIX X
+";
+
+    const DIC: &str = "\
+8
+hello/S
+city/S
+box/S
+play/SD
+tie/DU
+Paris
+don't
+walk/SXD
+";
+
+    fn dictionary() -> Dictionary {
+        Dictionary::parse(AFF, DIC).unwrap()
+    }
+
+    #[test]
+    fn test_affix_expansion() {
+        let dictionary = dictionary();
+        for word in [
+            "hello", "hellos", "city", "cities", "box", "boxes", "play", "plays", "played", "tie",
+            "tied", "untie", "untied", "walks", "walked",
+        ] {
+            assert!(dictionary.check(word), "{word} should be spelled correctly");
+        }
+        for word in ["citys", "boxs", "untieed", "unplay", "walk", "helo"] {
+            assert!(!dictionary.check(word), "{word} should be misspelled");
+        }
+    }
+
+    #[test]
+    fn test_casing() {
+        let dictionary = dictionary();
+        assert!(dictionary.check("Hello"));
+        assert!(dictionary.check("HELLO"));
+        assert!(!dictionary.check("hELLO"));
+        assert!(dictionary.check("Paris"));
+        assert!(dictionary.check("PARIS"));
+        assert!(!dictionary.check("paris"));
+        assert!(dictionary.check("don't"));
+        assert!(dictionary.check("don\u{2019}t"));
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let dictionary = dictionary();
+        assert_eq!(dictionary.suggest("helo", 3), vec!["hello"]);
+        assert_eq!(dictionary.suggest("Citeis", 3), vec!["Cities"]);
+        assert_eq!(dictionary.suggest("paris", 3), vec!["Paris"]);
+        assert_eq!(dictionary.suggest("boxcity", 3), vec!["box city"]);
+        assert_eq!(dictionary.suggest("xyzzyq", 3), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_flag_modes() {
+        let aff = "FLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n";
+        let dic = "1\ncat/AaBb\n";
+        let dictionary = Dictionary::parse(aff, dic).unwrap();
+        assert!(dictionary.check("cats"));
+
+        let aff = "FLAG num\nAF 1\nAF 12,7\nSFX 12 Y 1\nSFX 12 0 s .\n";
+        let dic = "1\ndog/1\n";
+        let dictionary = Dictionary::parse(aff, dic).unwrap();
+        assert!(dictionary.check("dogs"));
+    }
+
+    #[test]
+    fn test_legacy_encoding() {
+        let aff = b"SET ISO8859-1\nSFX S Y 1\nSFX S 0 s .\n";
+        let dic = b"1\ncaf\xe9/S\n";
+        let dictionary = Dictionary::from_bytes(aff, dic).unwrap();
+        assert!(dictionary.check("caf\u{e9}s"));
+    }
+}
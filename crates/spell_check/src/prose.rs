@@ -0,0 +1,145 @@
+use std::ops::Range;
+
+use language::BufferSnapshot;
+
+/// Highlight captures whose text is checked.
+const PROSE_SCOPES: &[&str] = &[
+    "comment",
+    "string",
+    "title",
+    "emphasis",
+    "link_text",
+    "strikethrough",
+];
+
+/// Highlight captures within prose whose text isn't checked, such as escape sequences, or code
+/// spans and URLs in Markdown.
+const EXCLUDED_SCOPES: &[&str] = &[
+    "text.literal",
+    "link_uri",
+    "punctuation.embedded",
+    "string.escape",
+    "string.regex",
+    "string.special",
+    "embedded",
+];
+
+/// Returns the ranges of the buffer that contain prose, as opposed to code: comments, strings,
+/// and the text of markup languages such as Markdown.
+///
+/// Buffers without a grammar, such as plain text, are prose throughout, while buffers whose
+/// grammar has no highlights query contain none.
+pub fn prose_ranges(buffer: &BufferSnapshot, range: Range<usize>) -> Vec<Range<usize>> {
+    let Some(grammar) = buffer.language().and_then(|language| language.grammar()) else {
+        return vec![range];
+    };
+    if grammar.highlights_config.is_none() {
+        return Vec::new();
+    }
+
+    let mut included = Vec::new();
+    let mut excluded = Vec::new();
+    let mut captures = buffer
+        .syntax
+        .captures(range.clone(), &buffer.text, |grammar| {
+            grammar
+                .highlights_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+    while let Some(capture) = captures.peek() {
+        if let Some(config) = captures.grammars()[capture.grammar_index]
+            .highlights_config
+            .as_ref()
+        {
+            let name = config.query.capture_names()[capture.index as usize];
+            let is_scope = |scope: &str| {
+                name.strip_prefix(scope)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+            };
+            let node_range = capture.node.byte_range();
+            if EXCLUDED_SCOPES.iter().any(|scope| is_scope(scope)) {
+                excluded.push(node_range);
+            } else if name == "text" || PROSE_SCOPES.iter().any(|scope| is_scope(scope)) {
+                included.push(node_range);
+            }
+        }
+        captures.advance();
+    }
+
+    let included = merge(included);
+    let excluded = merge(excluded);
+    subtract(&included, &excluded)
+        .into_iter()
+        .filter_map(|prose| {
+            let start = prose.start.max(range.start);
+            let end = prose.end.min(range.end);
+            (start < end).then_some(start..end)
+        })
+        .collect()
+}
+
+fn merge(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_unstable_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        if let Some(last) = merged.last_mut()
+            && range.start <= last.end
+        {
+            last.end = last.end.max(range.end);
+        } else {
+            merged.push(range);
+        }
+    }
+    merged
+}
+
+/// Removes the excluded ranges from the included ones. Both must be sorted and disjoint.
+fn subtract(included: &[Range<usize>], excluded: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut result = Vec::new();
+    let mut excluded = excluded.iter().peekable();
+    for range in included {
+        let mut start = range.start;
+        while let Some(exclusion) = excluded.peek() {
+            if exclusion.end <= start {
+                excluded.next();
+                continue;
+            }
+            if exclusion.start >= range.end {
+                break;
+            }
+            if exclusion.start > start {
+                result.push(start..exclusion.start);
+            }
+            start = start.max(exclusion.end);
+            if exclusion.end > range.end {
+                break;
+            }
+            excluded.next();
+        }
+        if start < range.end {
+            result.push(start..range.end);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subtract() {
+        assert_eq!(
+            subtract(&[0..10, 20..30], &[2..4, 8..22, 25..26]),
+            vec![0..2, 4..8, 22..25, 26..30]
+        );
+        assert_eq!(subtract(&[0..10], &[]), vec![0..10]);
+        assert_eq!(subtract(&[5..10], &[0..20]), Vec::<Range<usize>>::new());
+    }
+
+    #[test]
+    fn test_merge() {
+        assert_eq!(merge(vec![4..8, 0..2, 1..3, 8..9]), vec![0..3, 4..9]);
+    }
+}
@@ -0,0 +1,48 @@
+mod code_actions;
+mod dictionary;
+mod prose;
+mod spell_check_addon;
+#[cfg(test)]
+mod spell_check_tests;
+mod spell_checker;
+mod words;
+
+use editor::Editor;
+use fs::Fs;
+use gpui::App;
+use settings::{RegisterSetting, Settings};
+
+pub use dictionary::Dictionary;
+pub use prose::prose_ranges;
+pub use spell_checker::SpellChecker;
+pub use words::words;
+
+pub fn init(cx: &mut App) {
+    SpellChecker::init(<dyn Fs>::global(cx), cx);
+    cx.observe_new(|editor: &mut Editor, window, cx| {
+        if let Some(window) = window
+            && editor.mode().is_full()
+        {
+            spell_check_addon::register(editor, window, cx);
+        }
+    })
+    .detach();
+}
+
+#[derive(Clone, Debug, RegisterSetting)]
+pub struct SpellCheckSettings {
+    pub enabled: bool,
+    pub dictionaries: Vec<String>,
+    pub words: Vec<String>,
+}
+
+impl Settings for SpellCheckSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let spell_check = content.project.spell_check.clone().unwrap();
+        Self {
+            enabled: spell_check.enabled.unwrap(),
+            dictionaries: spell_check.dictionaries.unwrap(),
+            words: spell_check.words.unwrap().0,
+        }
+    }
+}
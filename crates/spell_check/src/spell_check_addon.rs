@@ -0,0 +1,117 @@
+use std::{any::Any, rc::Rc, time::Duration};
+
+use editor::{Editor, EditorEvent};
+use gpui::{
+    AppContext as _, Context, HighlightStyle, Subscription, Task, UnderlineStyle, Window, px,
+};
+use settings::SettingsStore;
+use theme::ActiveTheme as _;
+
+use crate::{SpellChecker, code_actions::SpellCheckCodeActionProvider, spell_checker::WordChecker};
+
+/// How long to wait after the last edit or scroll before checking the visible text again.
+pub(crate) const CHECK_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// The type under which misspelled words are highlighted.
+pub(crate) enum Misspelling {}
+
+pub(crate) struct SpellCheckAddon {
+    check_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl editor::Addon for SpellCheckAddon {
+    fn to_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn to_any_mut(&mut self) -> Option<&mut dyn Any> {
+        Some(self)
+    }
+}
+
+pub(crate) fn register(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+    let spell_checker = SpellChecker::global(cx);
+    let subscriptions = vec![
+        cx.subscribe_self(|editor, event: &EditorEvent, cx| match event {
+            EditorEvent::BufferEdited
+            | EditorEvent::Reparsed(_)
+            | EditorEvent::ExcerptsAdded { .. }
+            | EditorEvent::ExcerptsExpanded { .. }
+            | EditorEvent::ScrollPositionChanged { .. } => schedule_check(editor, cx),
+            _ => {}
+        }),
+        cx.observe_global::<SettingsStore>(schedule_check),
+        cx.observe(&spell_checker, |editor, _, cx| schedule_check(editor, cx)),
+    ];
+    editor.register_addon(SpellCheckAddon {
+        check_task: Task::ready(()),
+        _subscriptions: subscriptions,
+    });
+
+    let provider =
+        SpellCheckCodeActionProvider::new(editor.project().map(|project| project.downgrade()));
+    editor.add_code_action_provider(Rc::new(provider), window, cx);
+    schedule_check(editor, cx);
+}
+
+fn schedule_check(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let Some(addon) = editor.addon_mut::<SpellCheckAddon>() else {
+        return;
+    };
+    addon.check_task = cx.spawn(async move |editor, cx| {
+        cx.background_executor().timer(CHECK_DEBOUNCE).await;
+
+        let Ok(checks) = editor.update(cx, |editor, cx| {
+            editor
+                .visible_excerpts(false, cx)
+                .into_iter()
+                .filter_map(|(excerpt_id, (buffer, _, range))| {
+                    let checker = WordChecker::for_buffer(&buffer, cx)?;
+                    Some((excerpt_id, buffer.read(cx).snapshot(), range, checker))
+                })
+                .collect::<Vec<_>>()
+        }) else {
+            return;
+        };
+
+        let misspellings = cx
+            .background_spawn(async move {
+                let mut misspellings = Vec::new();
+                for (excerpt_id, buffer, range, checker) in checks {
+                    for misspelling in checker.misspellings(&buffer, range) {
+                        let range = buffer.anchor_after(misspelling.start)
+                            ..buffer.anchor_before(misspelling.end);
+                        misspellings.push((excerpt_id, range));
+                    }
+                }
+                misspellings
+            })
+            .await;
+
+        editor
+            .update(cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let ranges = misspellings
+                    .into_iter()
+                    .filter_map(|(excerpt_id, range)| {
+                        snapshot.anchor_range_in_excerpt(excerpt_id, range)
+                    })
+                    .collect::<Vec<_>>();
+                if ranges.is_empty() {
+                    editor.clear_highlights::<Misspelling>(cx);
+                } else {
+                    let style = HighlightStyle {
+                        underline: Some(UnderlineStyle {
+                            color: Some(cx.theme().status().info),
+                            thickness: px(1.),
+                            wavy: true,
+                        }),
+                        ..Default::default()
+                    };
+                    editor.highlight_text::<Misspelling>(ranges, style, cx);
+                }
+            })
+            .ok();
+    });
+}
@@ -0,0 +1,265 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use editor::{AnchorRangeExt as _, Editor, ExcerptId};
+use fs::{FakeFs, Fs};
+use gpui::{Entity, TestAppContext, VisualTestContext};
+use language::Buffer;
+use project::{CodeAction, Project};
+use serde_json::json;
+use settings::SettingsStore;
+use util::path;
+
+use crate::{
+    code_actions::SpellCheckCodeActionProvider,
+    spell_check_addon::{CHECK_DEBOUNCE, Misspelling},
+};
+
+const AFF: &str = "\
+SET UTF-8
+TRY esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'
+
+SFX S Y 1
+SFX S   0     s          .
+";
+
+const DIC: &str = "\
+2
+hello/S
+world/S
+";
+
+const TEXT: &str = "Hello wrold, helo worlds.\n";
+
+#[gpui::test]
+async fn test_misspellings_are_highlighted(cx: &mut TestAppContext) {
+    let fs = init_test(cx);
+    install_dictionary(&fs).await;
+    let (_, editor, buffer, cx) = open_editor(fs, cx).await;
+
+    wait_for_check(cx);
+    assert_eq!(misspellings(&editor, cx), vec![6..11, 13..17]);
+
+    buffer.update(cx, |buffer, cx| buffer.edit([(6..11, "world")], None, cx));
+    wait_for_check(cx);
+    assert_eq!(misspellings(&editor, cx), vec![13..17]);
+
+    cx.update(|_, cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings
+                    .project
+                    .spell_check
+                    .get_or_insert_default()
+                    .words
+                    .get_or_insert_default()
+                    .0 = vec!["helo".to_string()];
+            });
+        });
+    });
+    wait_for_check(cx);
+    assert_eq!(misspellings(&editor, cx), Vec::<Range<usize>>::new());
+}
+
+#[gpui::test]
+async fn test_dictionaries_are_loaded_once_installed(cx: &mut TestAppContext) {
+    let fs = init_test(cx);
+    let (_, editor, _, cx) = open_editor(fs.clone(), cx).await;
+
+    wait_for_check(cx);
+    assert_eq!(misspellings(&editor, cx), Vec::<Range<usize>>::new());
+
+    install_dictionary(&fs).await;
+    wait_for_check(cx);
+    assert_eq!(misspellings(&editor, cx), vec![6..11, 13..17]);
+}
+
+#[gpui::test]
+async fn test_replace_code_action(cx: &mut TestAppContext) {
+    let fs = init_test(cx);
+    install_dictionary(&fs).await;
+    let (project, _, buffer, cx) = open_editor(fs, cx).await;
+    wait_for_check(cx);
+
+    let provider = SpellCheckCodeActionProvider::new(Some(project.downgrade()));
+    let actions = code_actions(&provider, &buffer, 15, cx).await;
+    assert_eq!(
+        titles(&actions),
+        [
+            "Change to \"hello\"",
+            "Add \"helo\" to Project Dictionary",
+            "Add \"helo\" to User Dictionary",
+        ]
+    );
+
+    apply_code_action(&provider, &buffer, actions[0].clone(), cx).await;
+    assert_eq!(
+        buffer.read_with(cx, |buffer, _| buffer.text()),
+        "Hello wrold, hello worlds.\n"
+    );
+
+    let actions = code_actions(&provider, &buffer, 3, cx).await;
+    assert_eq!(titles(&actions), Vec::<&str>::new());
+}
+
+#[gpui::test]
+async fn test_add_to_dictionary_code_actions(cx: &mut TestAppContext) {
+    let fs = init_test(cx);
+    install_dictionary(&fs).await;
+    fs.insert_tree(
+        paths::config_dir(),
+        json!({
+            "settings.json": "{}",
+        }),
+    )
+    .await;
+    let (project, _, buffer, cx) = open_editor(fs.clone(), cx).await;
+    wait_for_check(cx);
+
+    let provider = SpellCheckCodeActionProvider::new(Some(project.downgrade()));
+    let actions = code_actions(&provider, &buffer, 8, cx).await;
+    assert_eq!(
+        titles(&actions),
+        [
+            "Change to \"world\"",
+            "Add \"wrold\" to Project Dictionary",
+            "Add \"wrold\" to User Dictionary",
+        ]
+    );
+
+    apply_code_action(&provider, &buffer, actions[1].clone(), cx).await;
+    cx.run_until_parked();
+    let project_settings = fs
+        .load(Path::new(path!("/project/.zed/settings.json")))
+        .await
+        .unwrap();
+    let project_settings: serde_json::Value = serde_json::from_str(&project_settings).unwrap();
+    assert_eq!(project_settings["spell_check"]["words"], json!(["wrold"]));
+
+    apply_code_action(&provider, &buffer, actions[2].clone(), cx).await;
+    cx.run_until_parked();
+    let user_settings = fs.load(paths::settings_file()).await.unwrap();
+    let user_settings: serde_json::Value = serde_json::from_str(&user_settings).unwrap();
+    assert_eq!(user_settings["spell_check"]["words"], json!(["wrold"]));
+}
+
+fn init_test(cx: &mut TestAppContext) -> Arc<FakeFs> {
+    let fs = FakeFs::new(cx.executor());
+    cx.update(|cx| {
+        let settings_store = SettingsStore::test(cx);
+        cx.set_global(settings_store);
+        theme::init(theme::LoadThemes::JustBase, cx);
+        editor::init(cx);
+        <dyn Fs>::set_global(fs.clone(), cx);
+        crate::init(cx);
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                let spell_check = settings.project.spell_check.get_or_insert_default();
+                spell_check.enabled = Some(true);
+                spell_check.dictionaries = Some(vec!["en_US".to_string()]);
+            });
+        });
+    });
+    fs
+}
+
+async fn install_dictionary(fs: &FakeFs) {
+    fs.insert_tree(
+        paths::dictionaries_dir(),
+        json!({
+            "en_US.aff": AFF,
+            "en_US.dic": DIC,
+        }),
+    )
+    .await;
+}
+
+async fn open_editor(
+    fs: Arc<FakeFs>,
+    cx: &mut TestAppContext,
+) -> (
+    Entity<Project>,
+    Entity<Editor>,
+    Entity<Buffer>,
+    &mut VisualTestContext,
+) {
+    fs.insert_tree(path!("/project"), json!({ "notes.txt": TEXT }))
+        .await;
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/project/notes.txt"), cx)
+        })
+        .await
+        .unwrap();
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx)
+    });
+    (project, editor, buffer, cx)
+}
+
+/// Waits for the visible text to be checked, letting the dictionaries load first if needed.
+fn wait_for_check(cx: &mut VisualTestContext) {
+    for _ in 0..2 {
+        cx.run_until_parked();
+        cx.executor().advance_clock(CHECK_DEBOUNCE);
+        cx.run_until_parked();
+    }
+}
+
+fn misspellings(editor: &Entity<Editor>, cx: &mut VisualTestContext) -> Vec<Range<usize>> {
+    editor.update_in(cx, |editor, window, cx| {
+        let snapshot = editor.snapshot(window, cx);
+        let mut ranges = snapshot
+            .text_highlight_ranges::<Misspelling>()
+            .map(|ranges| ranges.as_ref().clone().1)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|range| range.to_offset(&snapshot.buffer_snapshot()))
+            .map(|range| range.start.0..range.end.0)
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|range| range.start);
+        ranges
+    })
+}
+
+async fn code_actions(
+    provider: &SpellCheckCodeActionProvider,
+    buffer: &Entity<Buffer>,
+    offset: usize,
+    cx: &mut VisualTestContext,
+) -> Vec<CodeAction> {
+    cx.update(|window, cx| {
+        let anchor = buffer.read(cx).anchor_before(offset);
+        editor::CodeActionProvider::code_actions(provider, buffer, anchor..anchor, window, cx)
+    })
+    .await
+    .unwrap()
+}
+
+async fn apply_code_action(
+    provider: &SpellCheckCodeActionProvider,
+    buffer: &Entity<Buffer>,
+    action: CodeAction,
+    cx: &mut VisualTestContext,
+) {
+    cx.update(|window, cx| {
+        editor::CodeActionProvider::apply_code_action(
+            provider,
+            buffer.clone(),
+            action,
+            ExcerptId::min(),
+            true,
+            window,
+            cx,
+        )
+    })
+    .await
+    .unwrap();
+}
+
+fn titles(actions: &[CodeAction]) -> Vec<&str> {
+    actions
+        .iter()
+        .map(|action| action.lsp_action.title())
+        .collect()
+}
@@ -0,0 +1,271 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use fs::Fs;
+use futures::StreamExt as _;
+use gpui::{App, AppContext as _, Context, Entity, Global, Subscription, Task};
+use language::{Buffer, BufferSnapshot};
+use settings::{Settings as _, SettingsLocation, SettingsStore};
+
+use crate::{SpellCheckSettings, dictionary::Dictionary, prose::prose_ranges, words::words};
+
+/// How long to wait for changes in the dictionaries directory to settle before reloading.
+const DICTIONARIES_WATCH_LATENCY: Duration = Duration::from_millis(100);
+
+struct GlobalSpellChecker(Entity<SpellChecker>);
+
+impl Global for GlobalSpellChecker {}
+
+enum DictionaryState {
+    Loading,
+    Loaded(Arc<Dictionary>),
+    Failed,
+}
+
+/// Loads and caches the dictionaries that are shared by all editors.
+///
+/// Dictionaries that failed to load are retried once the settings or the contents of Zed's
+/// dictionaries directory change, and loaded dictionaries are reloaded when that directory
+/// changes.
+pub struct SpellChecker {
+    fs: Arc<dyn Fs>,
+    dictionaries: HashMap<String, DictionaryState>,
+    _settings_subscription: Subscription,
+    _watch_dictionaries: Task<()>,
+}
+
+impl SpellChecker {
+    pub(crate) fn init(fs: Arc<dyn Fs>, cx: &mut App) {
+        let spell_checker = cx.new(|cx| Self {
+            fs: fs.clone(),
+            dictionaries: HashMap::default(),
+            _settings_subscription: cx.observe_global::<SettingsStore>(|this, _| {
+                this.forget_failed_dictionaries();
+            }),
+            _watch_dictionaries: cx.spawn(async move |this, cx| {
+                let (mut events, _watcher) = fs
+                    .watch(paths::dictionaries_dir(), DICTIONARIES_WATCH_LATENCY)
+                    .await;
+                while events.next().await.is_some() {
+                    if this
+                        .update(cx, |this, cx| this.reload_dictionaries(cx))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            }),
+        });
+        cx.set_global(GlobalSpellChecker(spell_checker));
+    }
+
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalSpellChecker>().0.clone()
+    }
+
+    /// Returns the given dictionaries that are loaded, and starts loading the others. The
+    /// spell checker notifies its observers once a dictionary has loaded.
+    pub fn dictionaries(
+        &mut self,
+        names: &[String],
+        cx: &mut Context<Self>,
+    ) -> Vec<Arc<Dictionary>> {
+        let mut dictionaries = Vec::new();
+        for name in names {
+            match self.dictionaries.get(name) {
+                Some(DictionaryState::Loaded(dictionary)) => dictionaries.push(dictionary.clone()),
+                Some(DictionaryState::Loading | DictionaryState::Failed) => {}
+                None => {
+                    self.dictionaries
+                        .insert(name.clone(), DictionaryState::Loading);
+                    self.load(name.clone(), cx);
+                }
+            }
+        }
+        dictionaries
+    }
+
+    /// Forgets the dictionaries that failed to load, so that they're loaded again the next time
+    /// they're needed.
+    fn forget_failed_dictionaries(&mut self) {
+        self.dictionaries
+            .retain(|_, state| !matches!(state, DictionaryState::Failed));
+    }
+
+    /// Reloads the dictionaries that are loaded, keeping them in use until their replacements
+    /// have loaded, and retries the ones that failed to load.
+    fn reload_dictionaries(&mut self, cx: &mut Context<Self>) {
+        self.forget_failed_dictionaries();
+        let loaded = self
+            .dictionaries
+            .iter()
+            .filter(|(_, state)| matches!(state, DictionaryState::Loaded(_)))
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        for name in loaded {
+            self.load(name, cx);
+        }
+        cx.notify();
+    }
+
+    fn load(&mut self, name: String, cx: &mut Context<Self>) {
+        let fs = self.fs.clone();
+        cx.spawn(async move |this, cx| {
+            let state = match load_dictionary(fs, &name, cx).await {
+                Ok(dictionary) => {
+                    log::info!("loaded dictionary {name:?} with {} words", dictionary.len());
+                    DictionaryState::Loaded(Arc::new(dictionary))
+                }
+                Err(error) => {
+                    log::error!("failed to load dictionary {name:?}: {error:#}");
+                    DictionaryState::Failed
+                }
+            };
+            this.update(cx, |this, cx| {
+                this.dictionaries.insert(name, state);
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+async fn load_dictionary(
+    fs: Arc<dyn Fs>,
+    name: &str,
+    cx: &mut gpui::AsyncApp,
+) -> Result<Dictionary> {
+    let dic_path = find_dictionary(fs.as_ref(), name)
+        .await
+        .with_context(|| format!("no dictionary named {name:?} was found"))?;
+    let aff_path = dic_path.with_extension("aff");
+    let dic = fs.load_bytes(&dic_path).await?;
+    let aff = fs
+        .load_bytes(&aff_path)
+        .await
+        .with_context(|| format!("failed to load {aff_path:?}"))?;
+    cx.background_spawn(async move { Dictionary::from_bytes(&aff, &dic) })
+        .await
+}
+
+/// Returns the path to a dictionary's `.dic` file. Dictionaries given by name are looked up in
+/// Zed's dictionaries directory, then in the system's Hunspell directories.
+async fn find_dictionary(fs: &dyn Fs, name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    if path.extension().is_some_and(|extension| extension == "dic")
+        || name.contains(std::path::MAIN_SEPARATOR)
+    {
+        let path = match path.strip_prefix("~") {
+            Ok(relative) => paths::home_dir().join(relative),
+            Err(_) => path.to_path_buf(),
+        };
+        return fs.is_file(&path).await.then_some(path);
+    }
+
+    let file_name = format!("{name}.dic");
+    for dir in dictionary_dirs() {
+        let path = dir.join(&file_name);
+        if fs.is_file(&path).await {
+            return Some(path);
+        }
+    }
+    None
+}
+
+fn dictionary_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![paths::dictionaries_dir().clone()];
+    if cfg!(target_os = "macos") {
+        dirs.extend([
+            paths::home_dir().join("Library/Spelling"),
+            PathBuf::from("/Library/Spelling"),
+            PathBuf::from("/opt/homebrew/share/hunspell"),
+            PathBuf::from("/usr/local/share/hunspell"),
+        ]);
+    } else if cfg!(any(target_os = "linux", target_os = "freebsd")) {
+        dirs.extend([
+            PathBuf::from("/usr/share/hunspell"),
+            PathBuf::from("/usr/share/myspell"),
+            PathBuf::from("/usr/share/myspell/dicts"),
+            PathBuf::from("/usr/local/share/hunspell"),
+        ]);
+    }
+    dirs
+}
+
+/// Checks a word against a set of dictionaries and a list of additional words.
+pub(crate) struct WordChecker {
+    pub dictionaries: Vec<Arc<Dictionary>>,
+    pub words: HashSet<String>,
+}
+
+impl WordChecker {
+    /// Returns the checker for a buffer, or `None` when spell checking is disabled for it or
+    /// none of its dictionaries are loaded yet.
+    pub fn for_buffer(buffer: &Entity<Buffer>, cx: &mut App) -> Option<Self> {
+        let file = buffer.read(cx).file();
+        let location = file.map(|file| SettingsLocation {
+            worktree_id: file.worktree_id(cx),
+            path: file.path().as_ref(),
+        });
+        let settings = SpellCheckSettings::get(location, cx).clone();
+        if !settings.enabled {
+            return None;
+        }
+
+        let dictionaries = SpellChecker::global(cx).update(cx, |spell_checker, cx| {
+            spell_checker.dictionaries(&settings.dictionaries, cx)
+        });
+        if dictionaries.is_empty() {
+            return None;
+        }
+        Some(Self {
+            dictionaries,
+            words: settings.words.into_iter().collect(),
+        })
+    }
+
+    pub fn check(&self, word: &str) -> bool {
+        // Words added in lower case may also be capitalized, like dictionary words.
+        self.words.contains(word)
+            || self.words.contains(&word.to_lowercase())
+            || self
+                .dictionaries
+                .iter()
+                .any(|dictionary| dictionary.check(word))
+    }
+
+    /// Returns the ranges of the misspelled words in the prose within the given range.
+    pub fn misspellings(&self, buffer: &BufferSnapshot, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut misspellings = Vec::new();
+        for prose_range in prose_ranges(buffer, range) {
+            let text = buffer
+                .text_for_range(prose_range.clone())
+                .collect::<String>();
+            for word in words(&text) {
+                if !self.check(&text[word.clone()]) {
+                    misspellings.push(prose_range.start + word.start..prose_range.start + word.end);
+                }
+            }
+        }
+        misspellings
+    }
+
+    pub fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        for dictionary in &self.dictionaries {
+            for suggestion in dictionary.suggest(word, limit) {
+                if !suggestions.contains(&suggestion) {
+                    suggestions.push(suggestion);
+                }
+            }
+        }
+        suggestions.truncate(limit);
+        suggestions
+    }
+}
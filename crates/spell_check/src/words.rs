@@ -0,0 +1,138 @@
+use std::ops::Range;
+
+/// Words shorter than this aren't checked, as they are mostly abbreviations.
+const MIN_WORD_LEN: usize = 3;
+
+/// Returns the byte ranges of the words to check in a piece of prose.
+///
+/// URLs, email addresses and words containing digits are skipped. Identifiers are split into
+/// their parts on underscores and at camel case boundaries, and upper-case parts are skipped,
+/// since they are usually acronyms.
+pub fn words(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    for chunk in chunks(text, |c| c.is_whitespace()) {
+        let chunk_text = &text[chunk.clone()];
+        if chunk_text.contains("://") || chunk_text.contains('@') {
+            continue;
+        }
+        for token in chunks(chunk_text, |c| !is_word_char(c)) {
+            let token_text = &chunk_text[token.clone()];
+            if token_text.chars().any(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            let offset = chunk.start + token.start;
+            for identifier_part in chunks(token_text, |c| c == '_') {
+                for part in split_camel_case(&token_text[identifier_part.clone()]) {
+                    let start = offset + identifier_part.start + part.start;
+                    let range = trim_apostrophes(text, start..start + part.len());
+                    let word = &text[range.clone()];
+                    if word.chars().count() >= MIN_WORD_LEN && !is_upper_case(word) {
+                        words.push(range);
+                    }
+                }
+            }
+        }
+    }
+    words
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '\'' || c == '\u{2019}'
+}
+
+fn is_upper_case(word: &str) -> bool {
+    word.chars().all(|c| !c.is_lowercase())
+}
+
+/// Returns the ranges of the non-empty runs of characters between separators.
+fn chunks(text: &str, is_separator: impl Fn(char) -> bool) -> Vec<Range<usize>> {
+    let mut chunks = Vec::new();
+    let mut start = None;
+    for (ix, c) in text.char_indices() {
+        if is_separator(c) {
+            if let Some(start) = start.take() {
+                chunks.push(start..ix);
+            }
+        } else if start.is_none() {
+            start = Some(ix);
+        }
+    }
+    if let Some(start) = start {
+        chunks.push(start..text.len());
+    }
+    chunks
+}
+
+/// Splits an identifier before each upper-case letter that follows a lower-case one, and
+/// before the last letter of a run of upper-case letters followed by a lower-case one, as in
+/// `parseHTTPResponse`.
+fn split_camel_case(text: &str) -> Vec<Range<usize>> {
+    let chars = text.char_indices().collect::<Vec<_>>();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for (ix, window) in chars.windows(2).enumerate() {
+        let ((_, prev), (offset, c)) = (window[0], window[1]);
+        let next = chars.get(ix + 2).map(|(_, c)| *c);
+        let is_boundary = c.is_uppercase()
+            && (prev.is_lowercase()
+                || (prev.is_uppercase() && next.is_some_and(|next| next.is_lowercase())));
+        if is_boundary {
+            parts.push(start..offset);
+            start = offset;
+        }
+    }
+    parts.push(start..text.len());
+    parts
+}
+
+fn trim_apostrophes(text: &str, mut range: Range<usize>) -> Range<usize> {
+    while let Some(c) = text[range.clone()].chars().next()
+        && (c == '\'' || c == '\u{2019}')
+    {
+        range.start += c.len_utf8();
+    }
+    while let Some(c) = text[range.clone()].chars().next_back()
+        && (c == '\'' || c == '\u{2019}')
+    {
+        range.end -= c.len_utf8();
+    }
+    range
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word_texts(text: &str) -> Vec<&str> {
+        words(text).into_iter().map(|range| &text[range]).collect()
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            word_texts("Teh quick, brown fox doesn't jump."),
+            ["Teh", "quick", "brown", "fox", "doesn't", "jump"]
+        );
+        assert_eq!(
+            word_texts("'quoted' words and \u{2019}curly\u{2019} ones"),
+            ["quoted", "words", "and", "curly", "ones"]
+        );
+        assert_eq!(
+            word_texts("See https://zed.dev/docs or mail hi@zed.dev"),
+            ["See", "mail"]
+        );
+        assert_eq!(
+            word_texts("utf8 and v2 are skipped"),
+            ["and", "are", "skipped"]
+        );
+    }
+
+    #[test]
+    fn test_identifiers() {
+        assert_eq!(
+            word_texts("parseHTTPResponse snake_case_name an HTML tag"),
+            ["parse", "Response", "snake", "case", "name", "tag"]
+        );
+        assert_eq!(word_texts("naïveCafé"), ["naïve", "Café"]);
+    }
+}
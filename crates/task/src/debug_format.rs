@@ -280,10 +280,48 @@ pub struct DebugScenario {
     pub tcp_connection: Option<TcpArgumentsTemplate>,
 }
 
+/// A set of debug scenarios that are started together, in order, and stopped or restarted as a group.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct DebugCompound {
+    /// Name of the compound debug task
+    pub label: SharedString,
+    /// Labels of the debug scenarios to start, in the order they should be started
+    pub configurations: Vec<SharedString>,
+    /// Whether the remaining sessions should be stopped when any session of the compound ends
+    #[serde(default)]
+    pub stop_all: bool,
+}
+
+/// A single entry of a debug.json file: either a debug scenario or a compound of scenarios.
+#[derive(Serialize, PartialEq, Eq, Clone, Debug, JsonSchema)]
+#[serde(untagged)]
+pub enum DebugTaskEntry {
+    Scenario(DebugScenario),
+    Compound(DebugCompound),
+}
+
+impl<'de> Deserialize<'de> for DebugTaskEntry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // A scenario accepts arbitrary adapter-specific fields, so compounds are matched first to
+        // keep scenario errors intact rather than reporting an opaque untagged enum mismatch.
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if let Ok(compound) = DebugCompound::deserialize(&value) {
+            return Ok(Self::Compound(compound));
+        }
+        DebugScenario::deserialize(value)
+            .map(Self::Scenario)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 /// A group of Debug Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
-pub struct DebugTaskFile(pub Vec<DebugScenario>);
+pub struct DebugTaskFile(pub Vec<DebugTaskEntry>);
 
 impl DebugTaskFile {
     pub fn generate_json_schema(schemas: &AdapterSchemas) -> serde_json::Value {
@@ -353,6 +391,71 @@ impl DebugTaskFile {
             .expect("meta_schema should be present in schemars settings")
             .to_string();
 
+        let scenario_schema = serde_json::json!({
+            "type": "object",
+            "required": ["adapter", "label"],
+            // TODO: Uncommenting this will cause json-language-server to provide warnings for
+            // unrecognized properties. It should be enabled if/when there's an adapter JSON
+            // schema that's comprehensive. In order to not get warnings for the other schemas,
+            // `additionalProperties` or `unevaluatedProperties` (to handle "allOf" etc style
+            // schema combinations) could be set to `true` for that schema.
+            //
+            // "unevaluatedProperties": false,
+            "properties": {
+                "adapter": {
+                    "type": "string",
+                    "description": "The name of the debug adapter"
+                },
+                "label": {
+                    "type": "string",
+                    "description": "The name of the debug configuration"
+                },
+                "build": build_task_definition_ref,
+                "tcp_connection": {
+                    "type": "object",
+                    "description": "Optional TCP connection information for connecting to an already running debug adapter",
+                    "properties": {
+                        "port": {
+                            "type": "integer",
+                            "description": "The port that the debug adapter is listening on (default: auto-find open port)"
+                        },
+                        "host": {
+                            "type": "string",
+                            "pattern": "^((25[0-5]|(2[0-4]|1\\d|[1-9]|)\\d)\\.?\\b){4}$",
+                            "description": "The host that the debug adapter is listening to (default: 127.0.0.1)"
+                        },
+                        "timeout": {
+                            "type": "integer",
+                            "description": "The max amount of time in milliseconds to connect to a tcp DAP before returning an error (default: 2000ms)"
+                        }
+                    }
+                }
+            },
+            "allOf": adapter_conditions
+        });
+
+        let compound_schema = serde_json::json!({
+            "type": "object",
+            "required": ["label", "configurations"],
+            "additionalProperties": false,
+            "properties": {
+                "label": {
+                    "type": "string",
+                    "description": "The name of the compound debug configuration"
+                },
+                "configurations": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Labels of the debug configurations to start, in the order they should be started"
+                },
+                "stop_all": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Whether the remaining sessions should be stopped when any session of the compound ends"
+                }
+            }
+        });
+
         serde_json::json!({
             "$schema": meta_schema,
             "title": "Debug Configurations",
@@ -360,46 +463,7 @@ impl DebugTaskFile {
             "allowTrailingCommas": true,
             "type": "array",
             "items": {
-                "type": "object",
-                "required": ["adapter", "label"],
-                // TODO: Uncommenting this will cause json-language-server to provide warnings for
-                // unrecognized properties. It should be enabled if/when there's an adapter JSON
-                // schema that's comprehensive. In order to not get warnings for the other schemas,
-                // `additionalProperties` or `unevaluatedProperties` (to handle "allOf" etc style
-                // schema combinations) could be set to `true` for that schema.
-                //
-                // "unevaluatedProperties": false,
-                "properties": {
-                    "adapter": {
-                        "type": "string",
-                        "description": "The name of the debug adapter"
-                    },
-                    "label": {
-                        "type": "string",
-                        "description": "The name of the debug configuration"
-                    },
-                    "build": build_task_definition_ref,
-                    "tcp_connection": {
-                        "type": "object",
-                        "description": "Optional TCP connection information for connecting to an already running debug adapter",
-                        "properties": {
-                            "port": {
-                                "type": "integer",
-                                "description": "The port that the debug adapter is listening on (default: auto-find open port)"
-                            },
-                            "host": {
-                                "type": "string",
-                                "pattern": "^((25[0-5]|(2[0-4]|1\\d|[1-9]|)\\d)\\.?\\b){4}$",
-                                "description": "The host that the debug adapter is listening to (default: 127.0.0.1)"
-                            },
-                            "timeout": {
-                                "type": "integer",
-                                "description": "The max amount of time in milliseconds to connect to a tcp DAP before returning an error (default: 2000ms)"
-                            }
-                        }
-                    }
-                },
-                "allOf": adapter_conditions
+                "anyOf": [scenario_schema, compound_schema]
             },
             "$defs": generator.take_definitions(true),
         })
@@ -531,4 +595,59 @@ mod tests {
             _ => panic!("Expected Template variant"),
         }
     }
+    #[test]
+    fn test_debug_task_file_with_compound() {
+        use crate::{DebugCompound, DebugTaskEntry, DebugTaskFile};
+
+        let json = r#"[
+            {
+                "label": "Backend",
+                "adapter": "CodeLLDB",
+                "request": "launch",
+                "program": "target/debug/server"
+            },
+            {
+                "label": "Full stack",
+                "configurations": ["Backend", "Frontend"],
+                "stop_all": true
+            }
+        ]"#;
+
+        let deserialized: DebugTaskFile = serde_json::from_str(json).unwrap();
+        assert_eq!(2, deserialized.0.len());
+        match &deserialized.0[0] {
+            DebugTaskEntry::Scenario(scenario) => {
+                assert_eq!("Backend", scenario.label.as_ref());
+                assert_eq!("CodeLLDB", scenario.adapter.as_ref());
+            }
+            _ => panic!("Expected Scenario variant"),
+        }
+        assert_eq!(
+            DebugTaskEntry::Compound(DebugCompound {
+                label: "Full stack".into(),
+                configurations: vec!["Backend".into(), "Frontend".into()],
+                stop_all: true,
+            }),
+            deserialized.0[1]
+        );
+
+        let serialized = serde_json::to_value(&deserialized).unwrap();
+        assert_eq!(
+            json!({
+                "label": "Full stack",
+                "configurations": ["Backend", "Frontend"],
+                "stop_all": true,
+            }),
+            serialized[1]
+        );
+    }
+
+    #[test]
+    fn test_invalid_scenario_reports_scenario_error() {
+        use crate::DebugTaskEntry;
+
+        let json = r#"{ "label": "Missing adapter", "request": "launch" }"#;
+        let error = serde_json::from_str::<DebugTaskEntry>(json).unwrap_err();
+        assert!(error.to_string().contains("adapter"), "{error}");
+    }
 }
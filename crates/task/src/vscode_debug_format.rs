@@ -3,7 +3,8 @@ use serde::Deserialize;
 use util::ResultExt as _;
 
 use crate::{
-    DebugScenario, DebugTaskFile, EnvVariableReplacer, TcpArgumentsTemplate, VariableName,
+    DebugCompound, DebugScenario, DebugTaskEntry, DebugTaskFile, EnvVariableReplacer,
+    TcpArgumentsTemplate, VariableName,
 };
 
 // TODO support preLaunchTask linkage with other tasks
@@ -46,12 +47,51 @@ impl VsCodeDebugTaskDefinition {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeDebugCompound {
+    name: String,
+    configurations: Vec<VsCodeCompoundConfiguration>,
+    #[serde(default)]
+    stop_all: bool,
+}
+
+/// Compound members are usually plain configuration names, but may also name the folder of a
+/// multi-root workspace the configuration comes from, which Zed does not need.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeCompoundConfiguration {
+    Name(String),
+    WithFolder { name: String },
+}
+
+impl VsCodeDebugCompound {
+    fn into_zed(self, replacer: &EnvVariableReplacer) -> DebugCompound {
+        DebugCompound {
+            label: replacer.replace(&self.name).into(),
+            configurations: self
+                .configurations
+                .into_iter()
+                .map(|configuration| match configuration {
+                    VsCodeCompoundConfiguration::Name(name)
+                    | VsCodeCompoundConfiguration::WithFolder { name } => {
+                        replacer.replace(&name).into()
+                    }
+                })
+                .collect(),
+            stop_all: self.stop_all,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct VsCodeDebugTaskFile {
     #[serde(default)]
     version: Option<String>,
     configurations: Vec<VsCodeDebugTaskDefinition>,
+    #[serde(default)]
+    compounds: Vec<VsCodeDebugCompound>,
 }
 
 impl TryFrom<VsCodeDebugTaskFile> for DebugTaskFile {
@@ -77,6 +117,12 @@ impl TryFrom<VsCodeDebugTaskFile> for DebugTaskFile {
             .configurations
             .into_iter()
             .filter_map(|config| config.try_to_zed(&replacer).log_err())
+            .map(DebugTaskEntry::Scenario)
+            .chain(
+                file.compounds
+                    .into_iter()
+                    .map(|compound| DebugTaskEntry::Compound(compound.into_zed(&replacer))),
+            )
             .collect::<Vec<_>>();
         Ok(DebugTaskFile(templates))
     }
@@ -100,7 +146,7 @@ fn task_type_to_adapter_name(task_type: &str) -> String {
 mod tests {
     use serde_json::json;
 
-    use crate::{DebugScenario, DebugTaskFile, VariableName};
+    use crate::{DebugCompound, DebugScenario, DebugTaskEntry, DebugTaskFile, VariableName};
 
     use super::VsCodeDebugTaskFile;
 
@@ -132,7 +178,7 @@ mod tests {
         let zed = DebugTaskFile::try_from(parsed).expect("converting to Zed debug templates");
         pretty_assertions::assert_eq!(
             zed,
-            DebugTaskFile(vec![DebugScenario {
+            DebugTaskFile(vec![DebugTaskEntry::Scenario(DebugScenario {
                 label: "Debug my JS app".into(),
                 adapter: "JavaScript".into(),
                 config: json!({
@@ -153,7 +199,7 @@ mod tests {
                 }),
                 tcp_connection: None,
                 build: None
-            }])
+            })])
         );
     }
 
@@ -179,7 +225,7 @@ mod tests {
         let expected_placeholder = format!("${{{}}}", VariableName::PickProcessId);
         pretty_assertions::assert_eq!(
             zed,
-            DebugTaskFile(vec![DebugScenario {
+            DebugTaskFile(vec![DebugTaskEntry::Scenario(DebugScenario {
                 label: "Attach to Process".into(),
                 adapter: "CodeLLDB".into(),
                 config: json!({
@@ -188,7 +234,50 @@ mod tests {
                 }),
                 tcp_connection: None,
                 build: None
-            }])
+            })])
+        );
+    }
+
+    #[test]
+    fn test_parsing_vscode_launch_json_compounds() {
+        let raw = r#"
+            {
+                "version": "0.2.0",
+                "configurations": [
+                    {
+                        "name": "Server",
+                        "request": "launch",
+                        "type": "lldb",
+                        "program": "${workspaceFolder}/target/debug/server"
+                    },
+                    {
+                        "name": "Client",
+                        "request": "launch",
+                        "type": "chrome",
+                        "url": "http://localhost:8080"
+                    }
+                ],
+                "compounds": [
+                    {
+                        "name": "Server/Client",
+                        "configurations": ["Server", { "name": "Client", "folder": "web" }],
+                        "stopAll": true
+                    }
+                ]
+            }
+        "#;
+        let parsed: VsCodeDebugTaskFile =
+            serde_json_lenient::from_str(raw).expect("deserializing launch.json");
+        let zed = DebugTaskFile::try_from(parsed).expect("converting to Zed debug templates");
+
+        assert_eq!(zed.0.len(), 3);
+        pretty_assertions::assert_eq!(
+            zed.0[2],
+            DebugTaskEntry::Compound(DebugCompound {
+                label: "Server/Client".into(),
+                configurations: vec!["Server".into(), "Client".into()],
+                stop_all: true,
+            })
         );
     }
 }
@@ -366,6 +366,7 @@ impl TitleBar {
             RemoteConnectionOptions::Docker(_dev_container_connection) => {
                 (None, "Dev Container", IconName::Box)
             }
+            RemoteConnectionOptions::Command(_) => (None, "Remote Project", IconName::Terminal),
         };
 
         let nickname = nickname.unwrap_or_else(|| host.clone());
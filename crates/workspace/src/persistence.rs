@@ -881,6 +881,9 @@ impl Domain for WorkspaceDb {
             DROP TABLE user_toolchains;
             ALTER TABLE user_toolchains2 RENAME TO user_toolchains;
         ),
+        sql!(
+            ALTER TABLE remote_connections ADD COLUMN command TEXT;
+        ),
     ];
 
     // Allow recovering from bad migration that was initially shipped to nightly
@@ -1308,6 +1311,7 @@ impl WorkspaceDb {
         let mut distro = None;
         let mut name = None;
         let mut container_id = None;
+        let mut command = None;
         match options {
             RemoteConnectionOptions::Ssh(options) => {
                 kind = RemoteConnectionKind::Ssh;
@@ -1324,6 +1328,12 @@ impl WorkspaceDb {
                 kind = RemoteConnectionKind::Docker;
                 container_id = Some(options.container_id);
                 name = Some(options.name);
+                user = options.remote_user;
+            }
+            RemoteConnectionOptions::Command(options) => {
+                kind = RemoteConnectionKind::Command;
+                name = Some(options.name.clone());
+                command = Some(serde_json::to_string(&options)?);
             }
         }
         Self::get_or_create_remote_connection_query(
@@ -1335,6 +1345,7 @@ impl WorkspaceDb {
             distro,
             name,
             container_id,
+            command,
         )
     }
 
@@ -1347,6 +1358,7 @@ impl WorkspaceDb {
         distro: Option<String>,
         name: Option<String>,
         container_id: Option<String>,
+        command: Option<String>,
     ) -> Result<RemoteConnectionId> {
         if let Some(id) = this.select_row_bound(sql!(
             SELECT id
@@ -1358,7 +1370,8 @@ impl WorkspaceDb {
                 user IS ? AND
                 distro IS ? AND
                 name IS ? AND
-                container_id IS ?
+                container_id IS ? AND
+                command IS ?
             LIMIT 1
         ))?((
             kind.serialize(),
@@ -1368,6 +1381,7 @@ impl WorkspaceDb {
             distro.clone(),
             name.clone(),
             container_id.clone(),
+            command.clone(),
         ))? {
             Ok(RemoteConnectionId(id))
         } else {
@@ -1379,8 +1393,9 @@ impl WorkspaceDb {
                     user,
                     distro,
                     name,
-                    container_id
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                    container_id,
+                    command
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
                 RETURNING id
             ))?((
                 kind.serialize(),
@@ -1390,6 +1405,7 @@ impl WorkspaceDb {
                 distro,
                 name,
                 container_id,
+                command,
             ))?
             .context("failed to insert remote project")?;
             Ok(RemoteConnectionId(id))
@@ -1473,25 +1489,28 @@ impl WorkspaceDb {
     fn remote_connections(&self) -> Result<HashMap<RemoteConnectionId, RemoteConnectionOptions>> {
         Ok(self.select(sql!(
             SELECT
-                id, kind, host, port, user, distro, container_id, name
+                id, kind, host, port, user, distro, container_id, name, command
             FROM
                 remote_connections
         ))?()?
         .into_iter()
-        .filter_map(|(id, kind, host, port, user, distro, container_id, name)| {
-            Some((
-                RemoteConnectionId(id),
-                Self::remote_connection_from_row(
-                    kind,
-                    host,
-                    port,
-                    user,
-                    distro,
-                    container_id,
-                    name,
-                )?,
-            ))
-        })
+        .filter_map(
+            |(id, kind, host, port, user, distro, container_id, name, command)| {
+                Some((
+                    RemoteConnectionId(id),
+                    Self::remote_connection_from_row(
+                        kind,
+                        host,
+                        port,
+                        user,
+                        distro,
+                        container_id,
+                        name,
+                        command,
+                    )?,
+                ))
+            },
+        )
         .collect())
     }
 
@@ -1499,14 +1518,24 @@ impl WorkspaceDb {
         &self,
         id: RemoteConnectionId,
     ) -> Result<RemoteConnectionOptions> {
-        let (kind, host, port, user, distro, container_id, name) = self.select_row_bound(sql!(
-            SELECT kind, host, port, user, distro, container_id, name
-            FROM remote_connections
-            WHERE id = ?
-        ))?(id.0)?
+        let (kind, host, port, user, distro, container_id, name, command) = self
+            .select_row_bound(sql!(
+                SELECT kind, host, port, user, distro, container_id, name, command
+                FROM remote_connections
+                WHERE id = ?
+            ))?(id.0)?
         .context("no such remote connection")?;
-        Self::remote_connection_from_row(kind, host, port, user, distro, container_id, name)
-            .context("invalid remote_connection row")
+        Self::remote_connection_from_row(
+            kind,
+            host,
+            port,
+            user,
+            distro,
+            container_id,
+            name,
+            command,
+        )
+        .context("invalid remote_connection row")
     }
 
     fn remote_connection_from_row(
@@ -1517,6 +1546,7 @@ impl WorkspaceDb {
         distro: Option<String>,
         container_id: Option<String>,
         name: Option<String>,
+        command: Option<String>,
     ) -> Option<RemoteConnectionOptions> {
         match RemoteConnectionKind::deserialize(&kind)? {
             RemoteConnectionKind::Wsl => Some(RemoteConnectionOptions::Wsl(WslConnectionOptions {
@@ -1534,8 +1564,12 @@ impl WorkspaceDb {
                     container_id: container_id?,
                     name: name?,
                     upload_binary_over_docker_exec: false,
+                    remote_user: user,
                 }))
             }
+            RemoteConnectionKind::Command => Some(RemoteConnectionOptions::Command(
+                serde_json::from_str(&command?).log_err()?,
+            )),
         }
     }
 
@@ -116,6 +116,51 @@ pub struct LoadedBinaryFile {
     pub content: Vec<u8>,
 }
 
+/// The longest range that [`FileRangeReader::read`] can read from a remote worktree at once.
+/// The host holds the whole range in memory while answering.
+pub const MAX_FILE_RANGE_LEN: u64 = 4 * 1024 * 1024;
+
+/// Reads ranges of bytes from a file of a worktree, without loading all of it.
+#[derive(Clone)]
+pub enum FileRangeReader {
+    Local {
+        fs: Arc<dyn Fs>,
+        abs_path: PathBuf,
+    },
+    Remote {
+        client: AnyProtoClient,
+        project_id: u64,
+        worktree_id: WorktreeId,
+        path: Arc<RelPath>,
+    },
+}
+
+impl FileRangeReader {
+    /// Reads the bytes within `range`, fewer if the file ends before it.
+    pub async fn read(&self, range: Range<u64>) -> Result<Vec<u8>> {
+        match self {
+            Self::Local { fs, abs_path } => fs.load_range(abs_path, range).await,
+            Self::Remote {
+                client,
+                project_id,
+                worktree_id,
+                path,
+            } => {
+                let response = client
+                    .request(proto::LoadFileRange {
+                        project_id: *project_id,
+                        worktree_id: worktree_id.to_proto(),
+                        path: path.to_proto(),
+                        start: range.start,
+                        end: range.end,
+                    })
+                    .await?;
+                Ok(response.data)
+            }
+        }
+    }
+}
+
 impl fmt::Debug for LoadedBinaryFile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("LoadedBinaryFile")
@@ -740,6 +785,21 @@ impl Worktree {
         }
     }
 
+    pub fn file_range_reader(&self, path: &RelPath) -> FileRangeReader {
+        match self {
+            Worktree::Local(this) => FileRangeReader::Local {
+                fs: this.fs.clone(),
+                abs_path: this.absolutize(path),
+            },
+            Worktree::Remote(this) => FileRangeReader::Remote {
+                client: this.client.clone(),
+                project_id: this.project_id,
+                worktree_id: this.id(),
+                path: path.into(),
+            },
+        }
+    }
+
     pub fn write_file(
         &self,
         path: Arc<RelPath>,
@@ -993,6 +1053,33 @@ impl Worktree {
         })
     }
 
+    pub async fn handle_load_file_range(
+        this: Entity<Self>,
+        request: proto::LoadFileRange,
+        cx: AsyncApp,
+    ) -> Result<proto::LoadFileRangeResponse> {
+        let path = RelPath::from_proto(&request.path)?;
+        let len = request
+            .end
+            .checked_sub(request.start)
+            .context("invalid range")?;
+        anyhow::ensure!(
+            len <= MAX_FILE_RANGE_LEN,
+            "cannot read {len} bytes at once, the limit is {MAX_FILE_RANGE_LEN}"
+        );
+        let reader = this.read_with(&cx, |this, _| {
+            anyhow::ensure!(
+                !this
+                    .as_local()
+                    .is_some_and(|this| this.is_path_private(&path)),
+                "{path:?} is private"
+            );
+            Ok(this.file_range_reader(&path))
+        })??;
+        let data = reader.read(request.start..request.end).await?;
+        Ok(proto::LoadFileRangeResponse { data })
+    }
+
     pub fn is_single_file(&self) -> bool {
         self.root_dir().is_none()
     }
@@ -1342,6 +1429,7 @@ impl LocalWorktree {
         let fs = self.fs.clone();
         let entry = self.refresh_entry(path.clone(), None, cx);
         let is_private = self.is_path_private(path.as_ref());
+        let configured_encoding = self.settings.encoding_for_path(&path);
 
         let this = cx.weak_entity();
         cx.background_spawn(async move {
@@ -1359,7 +1447,8 @@ impl LocalWorktree {
                     anyhow::bail!("File is too large to load");
                 }
             }
-            let (text, encoding, has_bom) = decode_file_text(fs.as_ref(), &abs_path).await?;
+            let (text, encoding, has_bom) =
+                decode_file_text(fs.as_ref(), &abs_path, configured_encoding).await?;
 
             let worktree = this.upgrade().context("worktree was dropped")?;
             let file = match entry.await? {
@@ -5875,6 +5964,7 @@ const FILE_ANALYSIS_BYTES: usize = 1024;
 async fn decode_file_text(
     fs: &dyn Fs,
     abs_path: &Path,
+    configured_encoding: Option<&'static Encoding>,
 ) -> Result<(String, &'static Encoding, bool)> {
     let mut file = fs
         .open_sync(&abs_path)
@@ -5919,6 +6009,12 @@ async fn decode_file_text(
             content.extend_from_slice(&buf[..n]);
         }
     }
+    if bom_encoding.is_none()
+        && let Some(encoding) = configured_encoding
+    {
+        let (cow, _) = encoding.decode_without_bom_handling(&content);
+        return Ok((cow.into_owned(), encoding, false));
+    }
     decode_byte_full(content, bom_encoding, byte_content)
 }
 
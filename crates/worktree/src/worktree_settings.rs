@@ -1,6 +1,8 @@
-use std::path::Path;
+use std::{cmp::Reverse, path::Path};
 
 use anyhow::Context as _;
+use collections::HashMap;
+use encoding_rs::Encoding;
 use settings::{RegisterSetting, Settings};
 use util::{
     ResultExt,
@@ -21,6 +23,10 @@ pub struct WorktreeSettings {
     pub private_files: PathMatcher,
     pub hidden_files: PathMatcher,
     pub read_only_files: PathMatcher,
+    /// Size in bytes above which files are opened in the large-file view.
+    pub large_file_threshold: u64,
+    /// Encodings to decode files with, and the globs of the files they apply to.
+    pub file_encodings: Vec<(&'static Encoding, PathMatcher)>,
 }
 
 impl WorktreeSettings {
@@ -54,6 +60,17 @@ impl WorktreeSettings {
     pub fn is_std_path_read_only(&self, path: &Path) -> bool {
         self.read_only_files.is_match_std_path(path)
     }
+
+    pub fn is_large_file(&self, size: u64) -> bool {
+        size >= self.large_file_threshold
+    }
+
+    pub fn encoding_for_path(&self, path: &RelPath) -> Option<&'static Encoding> {
+        self.file_encodings
+            .iter()
+            .find(|(_, matcher)| matcher.is_match(path))
+            .map(|(encoding, _)| *encoding)
+    }
 }
 
 impl Settings for WorktreeSettings {
@@ -64,6 +81,7 @@ impl Settings for WorktreeSettings {
         let private_files = worktree.private_files.unwrap().0;
         let hidden_files = worktree.hidden_files.unwrap();
         let read_only_files = worktree.read_only_files.unwrap_or_default();
+        let file_encodings = file_encodings(worktree.file_encodings.unwrap());
         let parsed_file_scan_inclusions: Vec<String> = file_scan_inclusions
             .iter()
             .flat_map(|glob| {
@@ -97,10 +115,52 @@ impl Settings for WorktreeSettings {
             read_only_files: path_matchers(read_only_files, "read_only_files")
                 .log_err()
                 .unwrap_or_default(),
+            large_file_threshold: worktree
+                .large_file_threshold_mb
+                .unwrap()
+                .saturating_mul(1024 * 1024),
+            file_encodings,
         }
     }
 }
 
+/// Parses the `file_encodings` setting into one matcher per glob, ordered so that when several
+/// globs match a path, the most specific one wins.
+fn file_encodings(setting: HashMap<String, Vec<String>>) -> Vec<(&'static Encoding, PathMatcher)> {
+    let mut globs = setting
+        .into_iter()
+        .filter_map(|(label, globs)| {
+            let Some(encoding) = Encoding::for_label(label.trim().as_bytes()) else {
+                log::error!("Unknown encoding {label:?} in file_encodings");
+                return None;
+            };
+            Some(globs.into_iter().map(move |glob| (encoding, glob)))
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+    globs.sort_by_cached_key(|(encoding, glob)| {
+        (
+            Reverse(glob_specificity(glob)),
+            encoding.name(),
+            glob.clone(),
+        )
+    });
+    globs
+        .into_iter()
+        .filter_map(|(encoding, glob)| {
+            let matcher = path_matchers(vec![glob], "file_encodings").log_err()?;
+            Some((encoding, matcher))
+        })
+        .collect()
+}
+
+/// How specific a glob is, measured by the number of its characters that aren't wildcards.
+fn glob_specificity(glob: &str) -> usize {
+    glob.chars()
+        .filter(|char| !matches!(char, '*' | '?' | '[' | ']' | '{' | '}'))
+        .count()
+}
+
 fn path_matchers(mut values: Vec<String>, context: &'static str) -> anyhow::Result<PathMatcher> {
     values.sort();
     PathMatcher::new(values, PathStyle::local())
@@ -126,6 +186,8 @@ mod tests {
                 PathStyle::local(),
             )
             .unwrap(),
+            large_file_threshold: 256 * 1024 * 1024,
+            file_encodings: Vec::new(),
         }
     }
 
@@ -228,4 +290,57 @@ mod tests {
             "Regular JS files should not be read-only"
         );
     }
+
+    #[test]
+    fn test_encoding_for_path() {
+        let mut settings = make_settings_with_read_only(&[]);
+        settings.file_encodings = vec![
+            (
+                encoding_rs::SHIFT_JIS,
+                PathMatcher::new(["legacy/**/*.c"], PathStyle::local()).unwrap(),
+            ),
+            (
+                encoding_rs::WINDOWS_1252,
+                PathMatcher::new(["**/*.bas"], PathStyle::local()).unwrap(),
+            ),
+        ];
+
+        let legacy_file = RelPath::new(Path::new("legacy/src/main.c"), PathStyle::local()).unwrap();
+        assert_eq!(
+            settings.encoding_for_path(&legacy_file),
+            Some(encoding_rs::SHIFT_JIS)
+        );
+
+        let basic_file = RelPath::new(Path::new("tools/report.bas"), PathStyle::local()).unwrap();
+        assert_eq!(
+            settings.encoding_for_path(&basic_file),
+            Some(encoding_rs::WINDOWS_1252)
+        );
+
+        let other_file = RelPath::new(Path::new("src/main.c"), PathStyle::local()).unwrap();
+        assert_eq!(settings.encoding_for_path(&other_file), None);
+    }
+
+    #[test]
+    fn test_most_specific_file_encoding_glob_wins() {
+        let mut settings = make_settings_with_read_only(&[]);
+        settings.file_encodings = file_encodings(HashMap::from_iter([
+            ("latin1".to_string(), vec!["**/*.txt".to_string()]),
+            (
+                "shift_jis".to_string(),
+                vec!["legacy/**/*.txt".to_string(), "legacy/README".to_string()],
+            ),
+            ("bogus".to_string(), vec!["**/*.md".to_string()]),
+        ]));
+
+        let encoding = |path: &str| {
+            settings
+                .encoding_for_path(&RelPath::new(Path::new(path), PathStyle::local()).unwrap())
+                .map(|encoding| encoding.name())
+        };
+        assert_eq!(encoding("legacy/src/notes.txt"), Some("Shift_JIS"));
+        assert_eq!(encoding("legacy/README"), Some("Shift_JIS"));
+        assert_eq!(encoding("docs/notes.txt"), Some("windows-1252"));
+        assert_eq!(encoding("docs/notes.md"), None);
+    }
 }
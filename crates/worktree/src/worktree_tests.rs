@@ -1,4 +1,6 @@
-use crate::{Entry, EntryKind, Event, PathChange, Worktree, WorktreeModelHandle};
+use crate::{
+    Entry, EntryKind, Event, MAX_FILE_RANGE_LEN, PathChange, Worktree, WorktreeModelHandle,
+};
 use anyhow::Result;
 use encoding_rs;
 use fs::{FakeFs, Fs, RealFs, RemoveOptions};
@@ -8,6 +10,7 @@ use parking_lot::Mutex;
 use postage::stream::Stream;
 use pretty_assertions::assert_eq;
 use rand::prelude::*;
+use rpc::proto;
 
 use serde_json::json;
 use settings::SettingsStore;
@@ -2250,6 +2253,54 @@ async fn test_private_single_file_worktree(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_load_file_range_is_limited(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree("/root", json!({"server.log": "first line\nsecond line\n"}))
+        .await;
+    let tree = Worktree::local(
+        Path::new("/root"),
+        true,
+        fs,
+        Default::default(),
+        true,
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+
+    let request = |start, end| proto::LoadFileRange {
+        project_id: 0,
+        worktree_id: 0,
+        path: rel_path("server.log").to_proto(),
+        start,
+        end,
+    };
+    let response = Worktree::handle_load_file_range(tree.clone(), request(11, 22), cx.to_async())
+        .await
+        .unwrap();
+    assert_eq!(response.data, b"second line");
+
+    // The whole range is read into memory, so hosts refuse to read too much at once.
+    assert!(
+        Worktree::handle_load_file_range(
+            tree.clone(),
+            request(0, MAX_FILE_RANGE_LEN + 1),
+            cx.to_async()
+        )
+        .await
+        .is_err()
+    );
+    assert!(
+        Worktree::handle_load_file_range(tree, request(22, 11), cx.to_async())
+            .await
+            .is_err()
+    );
+}
+
 #[gpui::test]
 async fn test_repository_above_root(executor: BackgroundExecutor, cx: &mut TestAppContext) {
     init_test(cx);
@@ -2716,6 +2767,77 @@ async fn test_load_file_encoding(cx: &mut TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_load_file_with_configured_encoding(cx: &mut TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.project.worktree.file_encodings = Some(
+                    [("windows-1252".to_string(), vec!["legacy/**".to_string()])]
+                        .into_iter()
+                        .collect(),
+                );
+            });
+        });
+    });
+
+    // "こんにちは" in Shift_JIS, which would be detected as such without the setting.
+    let sjis_bytes = vec![0x82, 0xb1, 0x82, 0xf1, 0x82, 0xc9, 0x82, 0xbf, 0x82, 0xcd];
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree("/root", json!({ "legacy": {}, "src": {} }))
+        .await;
+    fs.write(Path::new("/root/legacy/a.txt"), &sjis_bytes)
+        .await
+        .unwrap();
+    fs.write(Path::new("/root/src/a.txt"), &sjis_bytes)
+        .await
+        .unwrap();
+    let mut utf8_bom = vec![0xEF, 0xBB, 0xBF];
+    utf8_bom.extend_from_slice("Café".as_bytes());
+    fs.write(Path::new("/root/legacy/bom.txt"), &utf8_bom)
+        .await
+        .unwrap();
+
+    let tree = Worktree::local(
+        Path::new("/root"),
+        true,
+        fs,
+        Default::default(),
+        true,
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+
+    let legacy = tree
+        .update(cx, |tree, cx| tree.load_file(rel_path("legacy/a.txt"), cx))
+        .await
+        .unwrap();
+    assert_eq!(legacy.encoding, encoding_rs::WINDOWS_1252);
+    assert_eq!(legacy.text, "‚±‚ñ‚É‚¿‚Í");
+
+    let detected = tree
+        .update(cx, |tree, cx| tree.load_file(rel_path("src/a.txt"), cx))
+        .await
+        .unwrap();
+    assert_eq!(detected.encoding, encoding_rs::SHIFT_JIS);
+    assert_eq!(detected.text, "こんにちは");
+
+    // A byte order mark takes precedence over the configured encoding.
+    let bom = tree
+        .update(cx, |tree, cx| {
+            tree.load_file(rel_path("legacy/bom.txt"), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(bom.encoding, encoding_rs::UTF_8);
+    assert!(bom.has_bom);
+    assert_eq!(bom.text, "Café");
+}
+
 #[gpui::test]
 async fn test_write_file_encoding(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -448,6 +448,7 @@ pub fn initialize_workspace(
             cx.new(|_| go_to_line::cursor_position::CursorPosition::new(workspace));
         let line_ending_indicator =
             cx.new(|_| line_ending_selector::LineEndingIndicator::default());
+        let encoding_indicator = cx.new(|_| encoding_selector::EncodingIndicator::default());
         workspace.status_bar().update(cx, |status_bar, cx| {
             status_bar.add_left_item(search_button, window, cx);
             status_bar.add_left_item(lsp_button, window, cx);
@@ -457,6 +458,7 @@ pub fn initialize_workspace(
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
             status_bar.add_right_item(line_ending_indicator, window, cx);
+            status_bar.add_right_item(encoding_indicator, window, cx);
             status_bar.add_right_item(vim_mode_indicator, window, cx);
             status_bar.add_right_item(cursor_position, window, cx);
             status_bar.add_right_item(image_info, window, cx);
@@ -657,6 +659,10 @@ fn initialize_panels(
             workspace_handle.clone(),
             cx.clone(),
         );
+        let port_forwarding_panel = recent_projects::PortForwardingPanel::load(
+            workspace_handle.clone(),
+            cx.clone(),
+        );
         let debug_panel = DebugPanel::load(workspace_handle.clone(), cx);
 
         async fn add_panel_when_ready(
@@ -681,6 +687,7 @@ fn initialize_panels(
             add_panel_when_ready(git_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(channels_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(notification_panel, workspace_handle.clone(), cx.clone()),
+            add_panel_when_ready(port_forwarding_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(debug_panel, workspace_handle.clone(), cx.clone()),
             initialize_agent_panel(workspace_handle.clone(), prompt_builder, cx.clone()).map(|r| r.log_err()),
             initialize_agents_panel(workspace_handle, cx.clone()).map(|r| r.log_err())
@@ -4802,18 +4809,21 @@ mod tests {
                 "diagnostics",
                 "edit_prediction",
                 "editor",
+                "encoding_selector",
                 "feedback",
                 "file_finder",
                 "git",
                 "git_onboarding",
                 "git_panel",
                 "go_to_line",
+                "hex_editor",
                 "icon_theme_selector",
                 "inline_assistant",
                 "journal",
                 "keymap_editor",
                 "keystroke_input",
                 "language_selector",
+                "large_file_view",
                 "welcome",
                 "line_ending_selector",
                 "lsp_tool",
@@ -4827,6 +4837,7 @@ mod tests {
                 "pane",
                 "panel",
                 "picker",
+                "port_forwarding",
                 "project_panel",
                 "project_search",
                 "project_symbols",
@@ -4993,6 +5004,8 @@ mod tests {
                 cx,
             );
             image_viewer::init(cx);
+            hex_editor::init(cx);
+            large_file_view::init(cx);
             language_model::init(app_state.client.clone(), cx);
             language_models::init(app_state.user_store.clone(), app_state.client.clone(), cx);
             web_search::init(cx);
@@ -186,7 +186,9 @@ fn assign_edit_prediction_provider(
             let provider = cx.new(|_| CodestralEditPredictionDelegate::new(http_client));
             editor.set_edit_prediction_provider(Some(provider), window, cx);
         }
-        value @ (EditPredictionProvider::Experimental(_) | EditPredictionProvider::Zed) => {
+        value @ (EditPredictionProvider::Experimental(_)
+        | EditPredictionProvider::Local
+        | EditPredictionProvider::Zed) => {
             let ep_store = edit_prediction::EditPredictionStore::global(client, &user_store, cx);
 
             if let Some(project) = editor.project()
@@ -210,6 +212,8 @@ fn assign_edit_prediction_provider(
                         } else {
                             return false;
                         }
+                    } else if value == EditPredictionProvider::Local {
+                        edit_prediction::EditPredictionModel::Local
                     } else if user_store.read(cx).current_user().is_some() {
                         edit_prediction::EditPredictionModel::Zeta1
                     } else {
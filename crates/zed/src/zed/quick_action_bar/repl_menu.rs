@@ -172,6 +172,58 @@ impl QuickActionBar {
                             }
                         },
                     )
+                    .custom_entry(
+                        move |_window, _cx| {
+                            Label::new("Show Variables")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |window, cx| {
+                                repl::open_variable_explorer(editor.clone(), window, cx);
+                            }
+                        },
+                    )
+                    .custom_entry(
+                        move |_window, _cx| {
+                            Label::new("Export to Notebook")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |_, cx| {
+                                repl::export_outputs(
+                                    editor.clone(),
+                                    repl::ExportFormat::Notebook,
+                                    cx,
+                                )
+                                .log_err();
+                            }
+                        },
+                    )
+                    .custom_entry(
+                        move |_window, _cx| {
+                            Label::new("Export to Markdown")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |_, cx| {
+                                repl::export_outputs(
+                                    editor.clone(),
+                                    repl::ExportFormat::Markdown,
+                                    cx,
+                                )
+                                .log_err();
+                            }
+                        },
+                    )
                     .separator()
                     .custom_entry(
                         move |_window, _cx| {